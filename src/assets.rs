@@ -0,0 +1,177 @@
+//! Local images referenced in doc comments.
+//!
+//! Rustdoc keeps the relative image paths of doc comments as they are, so they
+//! only resolve if the images happen to be deployed next to the pages. Images
+//! found relative to the crate directory are copied to an `_assets` folder of the
+//! crate output instead, and their links point there.
+
+use crate::converter::OutputFlavor;
+use crate::error::{Error, Result};
+use crate::plain::{normalize, relative_path};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Folder of the crate output that images are copied to (Docusaurus makes no
+/// pages of `_`-prefixed folders, so it cannot clash with a module's).
+pub(crate) const ASSETS_DIR: &str = "_assets";
+
+/// Whether an image path is relative to the document (not a URL or absolute path).
+fn is_relative(target: &str) -> bool {
+  !(target.contains("://")
+    || target.starts_with('/')
+    || target.starts_with('#')
+    || target.starts_with("data:"))
+}
+
+/// Path of `target` relative to the directory `from`, starting with `./` or `../`.
+pub(crate) fn relative_link(from: &Path, target: &Path) -> String {
+  let path = relative_path(from, target)
+    .to_string_lossy()
+    .replace('\\', "/");
+  match path.starts_with("../") {
+    true => path,
+    false => format!("./{}", path),
+  }
+}
+
+/// Rewrite the relative paths of Markdown images and `<img>` tags.
+///
+/// `rewrite` returns the new path of an image, or `None` to keep it. Docusaurus
+/// only bundles images of `<img>` tags that are `require`d, so their new paths
+/// are wrapped in `require` for that flavor.
+pub(crate) fn rewrite_image_paths(
+  content: &str,
+  flavor: OutputFlavor,
+  mut rewrite: impl FnMut(&str) -> Option<String>,
+) -> String {
+  let mut image_path = |target: &str| is_relative(target).then(|| rewrite(target)).flatten();
+
+  // Markdown images: `![alt](path "title")`
+  let mut output = String::new();
+  let mut rest = content;
+  while let Some(start) = rest.find("![") {
+    let Some(target_start) = rest[start..].find("](").map(|i| start + i + 2) else {
+      break;
+    };
+    let Some(target_end) = rest[target_start..]
+      .find([')', ' '])
+      .map(|i| target_start + i)
+    else {
+      break;
+    };
+    output.push_str(&rest[..target_start]);
+    let target = &rest[target_start..target_end];
+    output.push_str(&image_path(target).unwrap_or_else(|| target.to_string()));
+    rest = &rest[target_end..];
+  }
+  output.push_str(rest);
+
+  // HTML images: `<img src="path">`
+  let content = output;
+  let mut output = String::new();
+  let mut rest = content.as_str();
+  while let Some(start) = rest.find("<img") {
+    let tag_end = rest[start..].find('>').map_or(rest.len(), |i| start + i);
+    let Some(src_start) = rest[start..tag_end].find("src=\"").map(|i| start + i) else {
+      output.push_str(&rest[..tag_end]);
+      rest = &rest[tag_end..];
+      continue;
+    };
+    let target_start = src_start + "src=\"".len();
+    let Some(target_end) = rest[target_start..].find('"').map(|i| target_start + i) else {
+      break;
+    };
+    output.push_str(&rest[..src_start]);
+    let target = &rest[target_start..target_end];
+    match image_path(target) {
+      Some(path) if flavor == OutputFlavor::Docusaurus => {
+        output.push_str(&format!("src={{require('{}').default}}", path))
+      }
+      Some(path) => output.push_str(&format!("src=\"{}\"", path)),
+      None => output.push_str(&rest[src_start..=target_end]),
+    }
+    rest = &rest[target_end + 1..];
+  }
+  output.push_str(rest);
+  output
+}
+
+/// Point the local images of the pages in `files` to their copies in the assets
+/// folder, returning the images to copy (keyed by their path in the folder).
+///
+/// Image paths are resolved against `crate_dir`; missing images are left alone.
+pub(crate) fn collect_doc_images(
+  files: &mut BTreeMap<String, String>,
+  crate_dir: &Path,
+  extension: &str,
+  flavor: OutputFlavor,
+) -> BTreeMap<PathBuf, PathBuf> {
+  let page_extension = format!(".{}", extension);
+  let mut images = BTreeMap::new();
+  let mut missing = BTreeSet::new();
+  for (path, content) in files.iter_mut() {
+    if !path.ends_with(&page_extension) {
+      continue;
+    }
+    let page_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    *content = rewrite_image_paths(content, flavor, |target| {
+      let source = crate_dir.join(target);
+      if !source.is_file() {
+        missing.insert(target.to_string());
+        return None;
+      }
+      let asset = normalize(Path::new(target));
+      let link = relative_link(page_dir, &Path::new(ASSETS_DIR).join(&asset));
+      images.insert(asset, source);
+      Some(link)
+    });
+  }
+  for target in missing {
+    log::warn!(
+      "Image `{}` of the docs was not found in {}",
+      target,
+      crate_dir.display()
+    );
+  }
+  images
+}
+
+/// Copy the images returned by [`collect_doc_images`] to the assets folder of
+/// `crate_output_dir`.
+pub(crate) fn copy_doc_images(
+  images: &BTreeMap<PathBuf, PathBuf>,
+  crate_output_dir: &Path,
+) -> Result<()> {
+  for (asset, source) in images {
+    let destination = crate_output_dir.join(ASSETS_DIR).join(asset);
+    if let Some(parent) = destination.parent() {
+      std::fs::create_dir_all(parent).map_err(Error::write(parent))?;
+    }
+    std::fs::copy(source, &destination).map_err(Error::write(&destination))?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rewrite_image_paths() {
+    let content = "![Diagram](docs/diagram.png \"Flow\") ![CI](https://ci/badge.svg)\n<img width=\"20\" src=\"docs/logo.svg\"> <img alt=\"x\">";
+    let rewrite = |flavor| {
+      rewrite_image_paths(content, flavor, |target| {
+        Some(format!("../_assets/{}", target))
+      })
+    };
+
+    assert_eq!(
+      rewrite(OutputFlavor::Docusaurus),
+      "![Diagram](../_assets/docs/diagram.png \"Flow\") ![CI](https://ci/badge.svg)\n<img width=\"20\" src={require('../_assets/docs/logo.svg').default}> <img alt=\"x\">"
+    );
+    assert_eq!(
+      rewrite(OutputFlavor::PlainMarkdown),
+      "![Diagram](../_assets/docs/diagram.png \"Flow\") ![CI](https://ci/badge.svg)\n<img width=\"20\" src=\"../_assets/docs/logo.svg\"> <img alt=\"x\">"
+    );
+  }
+}
@@ -4,6 +4,9 @@
 //! and CSS files needed for Rust documentation in Docusaurus.
 
 use anyhow::{Context, Result, bail};
+use cargo_doc_docusaurus::converter::DocusaurusMajor;
+use cargo_doc_docusaurus::site_config::detect_docusaurus_major;
+use std::borrow::Cow;
 use std::fs;
 use std::path::Path;
 
@@ -71,13 +74,91 @@ const TEMPLATES: &[Template] = &[
   },
 ];
 
+/// Changes turning the templates (written for Docusaurus 3) into Docusaurus 2
+/// components: prism-react-renderer 1, React 17 types and the v2 theme internals.
+const DOCUSAURUS_2_CHANGES: &[(&str, &str)] = &[
+  (
+    "import { Highlight, themes } from \"prism-react-renderer\";",
+    "import Highlight, { defaultProps } from \"prism-react-renderer\";\n\
+     import githubTheme from \"prism-react-renderer/themes/github\";\n\
+     import draculaTheme from \"prism-react-renderer/themes/dracula\";",
+  ),
+  (
+    "themes.dracula : themes.github",
+    "draculaTheme : githubTheme",
+  ),
+  (
+    "<Highlight code={code}",
+    "<Highlight {...defaultProps} code={code}",
+  ),
+  ("React.JSX.Element", "JSX.Element"),
+  (
+    "'@docusaurus/plugin-content-docs/client'",
+    "'@docusaurus/theme-common/internal'",
+  ),
+];
+
+impl Template {
+  /// Content of the template for a Docusaurus major version.
+  fn content(&self, docusaurus_major: DocusaurusMajor) -> Cow<'static, str> {
+    match docusaurus_major {
+      DocusaurusMajor::V3 => Cow::Borrowed(self.content),
+      DocusaurusMajor::V2 => Cow::Owned(
+        DOCUSAURUS_2_CHANGES
+          .iter()
+          .fold(self.content.to_string(), |content, (from, to)| {
+            content.replace(from, to)
+          }),
+      ),
+    }
+  }
+}
+
+/// The given Docusaurus major version, or the one of the project at `docusaurus_path`
+/// (Docusaurus 3 when its `package.json` does not tell).
+fn resolve_docusaurus_major(
+  docusaurus_path: &Path,
+  docusaurus_major: Option<DocusaurusMajor>,
+) -> DocusaurusMajor {
+  let major = docusaurus_major
+    .or_else(|| detect_docusaurus_major(docusaurus_path))
+    .unwrap_or_default();
+  println!("🦖 Docusaurus {}\n", u8::from(major));
+  major
+}
+
 /// Initialize Docusaurus project with all Rust documentation components
-pub fn init_components(docusaurus_path: &Path) -> Result<()> {
+pub fn init_components(
+  docusaurus_path: &Path,
+  docusaurus_major: Option<DocusaurusMajor>,
+) -> Result<()> {
   println!("🚀 Installing Rust documentation components...\n");
 
   // Verify it's a Docusaurus project
   verify_docusaurus_project(docusaurus_path)?;
+  let docusaurus_major = resolve_docusaurus_major(docusaurus_path, docusaurus_major);
+
+  let (installed, skipped) = install_templates(docusaurus_path, docusaurus_major)?;
+
+  println!("\n📊 Summary:");
+  println!("  ✅ Installed: {}", installed);
+  if skipped > 0 {
+    println!("  ⚠️  Skipped: {} (already exist)", skipped);
+  }
 
+  if installed > 0 {
+    print_post_install_instructions();
+  }
+
+  Ok(())
+}
+
+/// Install the templates that are not in a Docusaurus project yet, returning the
+/// number of installed and skipped (already existing) files.
+pub(crate) fn install_templates(
+  docusaurus_path: &Path,
+  docusaurus_major: DocusaurusMajor,
+) -> Result<(usize, usize)> {
   let mut installed = 0;
   let mut skipped = 0;
 
@@ -98,31 +179,25 @@ pub fn init_components(docusaurus_path: &Path) -> Result<()> {
     }
 
     // Write file
-    fs::write(&target_path, template.content)
+    fs::write(&target_path, template.content(docusaurus_major).as_ref())
       .with_context(|| format!("Failed to write file: {}", target_path.display()))?;
 
     println!("✅ Installed: {}", template.description);
     installed += 1;
   }
 
-  println!("\n📊 Summary:");
-  println!("  ✅ Installed: {}", installed);
-  if skipped > 0 {
-    println!("  ⚠️  Skipped: {} (already exist)", skipped);
-  }
-
-  if installed > 0 {
-    print_post_install_instructions();
-  }
-
-  Ok(())
+  Ok((installed, skipped))
 }
 
 /// Synchronize (update) existing components
-pub fn sync_components(docusaurus_path: &Path) -> Result<()> {
+pub fn sync_components(
+  docusaurus_path: &Path,
+  docusaurus_major: Option<DocusaurusMajor>,
+) -> Result<()> {
   println!("🔄 Synchronizing Rust documentation components...\n");
 
   verify_docusaurus_project(docusaurus_path)?;
+  let docusaurus_major = resolve_docusaurus_major(docusaurus_path, docusaurus_major);
 
   let mut updated = 0;
   let mut skipped = 0;
@@ -141,13 +216,14 @@ pub fn sync_components(docusaurus_path: &Path) -> Result<()> {
     let current_content = fs::read_to_string(&target_path)
       .with_context(|| format!("Failed to read file: {}", target_path.display()))?;
 
-    if current_content == template.content {
+    let content = template.content(docusaurus_major);
+    if current_content == content {
       println!("✓ Up to date: {}", template.description);
       continue;
     }
 
     // Update file
-    fs::write(&target_path, template.content)
+    fs::write(&target_path, content.as_ref())
       .with_context(|| format!("Failed to update file: {}", target_path.display()))?;
 
     println!("✅ Updated: {}", template.description);
@@ -428,3 +504,28 @@ pub fn print_css_variables(css_only: bool) {
     println!("   See templates/CSS_GUIDE.md for complete variable reference");
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_docusaurus_2_templates() {
+    // Every change applies to a template, so template updates can't silently skip one
+    for (from, _) in DOCUSAURUS_2_CHANGES {
+      assert!(
+        TEMPLATES
+          .iter()
+          .any(|template| template.content.contains(from)),
+        "no template contains {}",
+        from
+      );
+    }
+
+    let rust_code = &TEMPLATES[0];
+    assert_eq!(rust_code.content(DocusaurusMajor::V3), rust_code.content);
+    let content = rust_code.content(DocusaurusMajor::V2);
+    assert!(content.contains("import Highlight, { defaultProps } from \"prism-react-renderer\";"));
+    assert!(!content.contains("themes."));
+  }
+}
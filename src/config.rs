@@ -0,0 +1,1131 @@
+//! Configuration file support.
+//!
+//! Options can be provided in a `doc-docusaurus.toml` (or `.json`) file, or in the
+//! `[package.metadata.doc-docusaurus]` / `[workspace.metadata.doc-docusaurus]` table
+//! of a `Cargo.toml`. CLI flags always take precedence over the configuration file.
+//! Relative paths are resolved against the directory containing the configuration file.
+//!
+//! ```toml
+//! inputs = ["target/doc/core.json", "target/doc/utils.json"]
+//! output = "docs/api"
+//! base-path = "/docs/api"
+//! workspace-crates = ["core", "utils"]
+//!
+//! # Per-crate overrides, keyed by crate name
+//! [crates.utils]
+//! include-private = true
+//! ```
+
+use crate::error::{Error, Result};
+use crate::parser;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ConversionOptions;
+use crate::converter::{
+  DocAdmonitions, DocusaurusMajor, ExternUrls, ExternVersions, MethodOrder, OutputExtension,
+  OutputFlavor, OutputLayout, SignatureStyle, SitemapOptions,
+};
+use crate::sidebar::{SidebarCollapse, SidebarFormat, SidebarIcons};
+
+/// Default configuration file name looked up in the current directory.
+pub const CONFIG_FILE_NAME: &str = "doc-docusaurus.toml";
+
+/// Default output directory when neither the CLI nor the config file sets one.
+pub const DEFAULT_OUTPUT_DIR: &str = "target/doc-md";
+
+/// Name of the metadata table used in `Cargo.toml`.
+const CARGO_METADATA_KEY: &str = "doc-docusaurus";
+
+/// A set of conversion options where every field is optional.
+///
+/// Used both for the top-level configuration and for per-crate overrides, so that
+/// multiple layers (CLI, crate override, file defaults) can be merged together.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct OptionsLayer {
+  /// Directory where markdown files will be written
+  pub output: Option<PathBuf>,
+  /// Whether to include private items in the output
+  pub include_private: Option<bool>,
+  /// Base path for links
+  pub base_path: Option<String>,
+  /// List of workspace crate names
+  pub workspace_crates: Option<Vec<String>>,
+  /// Whether to generate sidebar categories as collapsed
+  pub sidebarconfig_collapsed: Option<bool>,
+  /// Custom path for the sidebar configuration file
+  pub sidebar_output: Option<PathBuf>,
+  /// Map of item paths to the version that introduced them, shown as "Since" badges
+  pub since_map: Option<PathBuf>,
+  /// JSON file mapping old item paths to their new paths, written as client redirects
+  pub redirect_map: Option<PathBuf>,
+  /// JSON file to write the normalized model of the crate to (see [`crate::model`])
+  pub emit_model: Option<PathBuf>,
+  /// URL for the 'Go back' link in root crate sidebars
+  pub sidebar_root_link: Option<String>,
+  /// Maximum module depth that gets its own pages
+  pub max_depth: Option<usize>,
+  /// Sidebar levels to collapse, overriding `sidebarconfig_collapsed` (which collapses all levels)
+  pub sidebar_collapse: Option<SidebarCollapse>,
+  /// Only write changed files and prune stale ones
+  pub incremental: Option<bool>,
+  /// Sitemap front matter for generated pages
+  pub sitemap: Option<SitemapOptions>,
+  /// Namespace prefixed to output directories, doc IDs and sidebar keys
+  pub namespace: Option<String>,
+  /// Route of the docs plugin instance serving the pages (e.g. "/rust"), the part of `base_path` that is not a docs folder
+  pub route_base_path: Option<String>,
+  /// Id of the docs plugin instance, naming its versioned and translated docs directories
+  pub docs_plugin_id: Option<String>,
+  /// File extension of generated pages
+  pub extension: Option<OutputExtension>,
+  /// Markup flavor of generated pages
+  pub flavor: Option<OutputFlavor>,
+  /// Format of the sidebar file (TypeScript, JSON or CommonJS)
+  pub sidebar_format: Option<SidebarFormat>,
+  /// Order of the methods listed on type and trait pages
+  pub method_order: Option<MethodOrder>,
+  /// Line wrapping of function signatures: `threshold` (parameter count and width limits) or `rustfmt`
+  pub signature_style: Option<SignatureStyle>,
+  /// Width above which the parameters of a function signature go on their own lines (80, or 100 with the `rustfmt` style)
+  pub signature_width: Option<usize>,
+  /// Parameter count above which the parameters of a function signature go on their own lines (3, ignored by the `rustfmt` style)
+  pub signature_max_params: Option<usize>,
+  /// Layout of the generated pages
+  pub layout: Option<OutputLayout>,
+  /// Generate an index page of error types
+  pub error_index: Option<bool>,
+  /// Generate an index page of the async API
+  pub async_index: Option<bool>,
+  /// Generate a `feature-matrix` page showing which items each Cargo feature enables
+  pub feature_matrix: Option<bool>,
+  /// Generate a `features` page listing the crate's Cargo features and what they enable
+  pub features_page: Option<bool>,
+  /// Generate a glossary page from `# Terminology` sections and `<!-- glossary: term -->` markers in doc comments
+  pub glossary: Option<bool>,
+  /// Generate an `all` page listing every item of the crate by kind
+  pub all_items: Option<bool>,
+  /// Generate a page per item kind (e.g. `all-structs`) listing every item of that kind
+  pub kind_indexes: Option<bool>,
+  /// Extract the `JSON schema` blocks of doc comments (as generated for schemars types) to downloadable `.schema.json` files
+  pub json_schema_assets: Option<bool>,
+  /// Render `math`/`latex` fenced code blocks of doc comments as `$$` blocks for remark-math and KaTeX
+  pub math_blocks: Option<bool>,
+  /// Copy local images referenced in doc comments to an `_assets` folder of the crate output
+  pub copy_assets: Option<bool>,
+  /// Show `PhantomData` marker fields of structs (hidden by default)
+  pub show_phantom_data: Option<bool>,
+  /// Render a placeholder page for items that fail to render instead of aborting
+  pub keep_going: Option<bool>,
+  /// Fail the conversion when it has warnings (unresolved links, placeholder pages), after writing the output
+  pub deny_warnings: Option<bool>,
+  /// Write `_category_.json` files to module directories instead of a sidebar file
+  pub category_files: Option<bool>,
+  /// Generate no sidebars: pages get no `displayed_sidebar` and no sidebar file is written or merged
+  pub no_sidebar: Option<bool>,
+  /// Record hashes of the rustdoc JSON input and of all generated files in the output manifest, for attesting reproducible builds
+  pub reproducible: Option<bool>,
+  /// Wire the generated sidebars into the site's sidebars file and Docusaurus config (keeping `.bak` backups)
+  pub patch_config: Option<bool>,
+  /// Document items re-exported from other crates on this crate's pages, when their rustdoc JSON is next to the input
+  pub inline_reexports: Option<bool>,
+  /// Docusaurus version label (e.g. "1.2") like `docs_version`, also registered in `versions.json` and `versioned_sidebars`
+  pub docusaurus_version: Option<String>,
+  /// Docusaurus major version to generate pages and sidebars for, detected from the site's `package.json` when unset
+  pub docusaurus_major: Option<DocusaurusMajor>,
+  /// Docusaurus docs version (e.g. "1.2") to write versioned docs for
+  pub docs_version: Option<String>,
+  /// Module path prefix (e.g. "my_crate::api::v1") stripped from displayed module paths
+  pub strip_prefix: Option<String>,
+  /// Separator between item names and their doc summaries in listings (default `—`)
+  pub summary_separator: Option<String>,
+  /// Marker after the names of items with restricted visibility in listings (default `🔒`, empty to hide)
+  pub restricted_marker: Option<String>,
+  /// Image (URL or path of the site's static files) set as `image` in the front matter of generated pages, for social cards
+  pub social_card: Option<String>,
+  /// Show only the first line of the docs of struct fields instead of their complete docs
+  pub field_summaries: Option<bool>,
+  /// Collapse the docs of methods below their first paragraph in a `<details>` block
+  pub collapse_method_docs: Option<bool>,
+  /// Add Parameters and Returns tables to functions and methods, with parameter docs from their `# Arguments` section
+  pub parameter_tables: Option<bool>,
+  /// Docusaurus i18n locale (e.g. "fr") to write translation sources for
+  pub locale: Option<String>,
+  /// Translated docs content directory replacing `i18n/<LOCALE>/docusaurus-plugin-content-docs` (e.g. for a docs plugin instance with an id); the locale defaults to the directory below `i18n`
+  pub locale_dir: Option<PathBuf>,
+  /// Git range (e.g. "origin/main...HEAD") whose changed source files are the only ones to get pages
+  pub changed_only: Option<String>,
+  /// Production site URL (e.g. `https://docs.example.com`) linked to for pages outside a `changed_only` preview
+  pub production_url: Option<String>,
+  /// Icons (`customProps.icon`) of sidebar entries, keyed by item kind
+  pub sidebar_icons: Option<SidebarIcons>,
+  /// Admonition kinds (or `heading`) of doc comment sections, keyed by heading (e.g. "Safety")
+  pub doc_admonitions: Option<DocAdmonitions>,
+  /// Cargo.lock whose dependency versions pin docs.rs links (instead of `latest`)
+  pub cargo_lock: Option<PathBuf>,
+  /// Cargo.toml of the crate, read by `features_page` and `copy_assets` (found from the input's location if unset)
+  pub cargo_manifest: Option<PathBuf>,
+  /// README whose content is added to the crate index page, with relative image paths rewritten
+  pub include_readme: Option<PathBuf>,
+  /// Directory of page templates (`item.md`, `module.md`, `crate.md`) overriding the built-in ones
+  pub template_dir: Option<PathBuf>,
+  /// docs.rs versions of dependencies, keyed by crate name, overriding `cargo_lock`
+  pub extern_versions: Option<ExternVersions>,
+  /// Documentation URL prefixes of dependencies (e.g. internal mirrors), keyed by crate name, replacing docs.rs
+  pub extern_urls: Option<ExternUrls>,
+}
+
+impl OptionsLayer {
+  /// Fill every unset field of `self` with the value from `fallback`.
+  pub fn or(self, fallback: &OptionsLayer) -> OptionsLayer {
+    OptionsLayer {
+      output: self.output.or_else(|| fallback.output.clone()),
+      include_private: self.include_private.or(fallback.include_private),
+      base_path: self.base_path.or_else(|| fallback.base_path.clone()),
+      workspace_crates: self
+        .workspace_crates
+        .or_else(|| fallback.workspace_crates.clone()),
+      sidebarconfig_collapsed: self
+        .sidebarconfig_collapsed
+        .or(fallback.sidebarconfig_collapsed),
+      sidebar_output: self
+        .sidebar_output
+        .or_else(|| fallback.sidebar_output.clone()),
+      since_map: self.since_map.or_else(|| fallback.since_map.clone()),
+      redirect_map: self.redirect_map.or_else(|| fallback.redirect_map.clone()),
+      emit_model: self.emit_model.or_else(|| fallback.emit_model.clone()),
+      sidebar_root_link: self
+        .sidebar_root_link
+        .or_else(|| fallback.sidebar_root_link.clone()),
+      max_depth: self.max_depth.or(fallback.max_depth),
+      sidebar_collapse: self.sidebar_collapse.or(fallback.sidebar_collapse),
+      incremental: self.incremental.or(fallback.incremental),
+      sitemap: match (self.sitemap, &fallback.sitemap) {
+        (Some(sitemap), Some(fallback)) => Some(sitemap.or(fallback)),
+        (sitemap, fallback) => sitemap.or_else(|| fallback.clone()),
+      },
+      namespace: self.namespace.or_else(|| fallback.namespace.clone()),
+      route_base_path: self
+        .route_base_path
+        .or_else(|| fallback.route_base_path.clone()),
+      docs_plugin_id: self
+        .docs_plugin_id
+        .or_else(|| fallback.docs_plugin_id.clone()),
+      extension: self.extension.or(fallback.extension),
+      flavor: self.flavor.or(fallback.flavor),
+      sidebar_format: self.sidebar_format.or(fallback.sidebar_format),
+      method_order: self.method_order.or(fallback.method_order),
+      signature_style: self.signature_style.or(fallback.signature_style),
+      signature_width: self.signature_width.or(fallback.signature_width),
+      signature_max_params: self.signature_max_params.or(fallback.signature_max_params),
+      layout: self.layout.or(fallback.layout),
+      error_index: self.error_index.or(fallback.error_index),
+      async_index: self.async_index.or(fallback.async_index),
+      feature_matrix: self.feature_matrix.or(fallback.feature_matrix),
+      features_page: self.features_page.or(fallback.features_page),
+      glossary: self.glossary.or(fallback.glossary),
+      all_items: self.all_items.or(fallback.all_items),
+      kind_indexes: self.kind_indexes.or(fallback.kind_indexes),
+      json_schema_assets: self.json_schema_assets.or(fallback.json_schema_assets),
+      math_blocks: self.math_blocks.or(fallback.math_blocks),
+      copy_assets: self.copy_assets.or(fallback.copy_assets),
+      show_phantom_data: self.show_phantom_data.or(fallback.show_phantom_data),
+      keep_going: self.keep_going.or(fallback.keep_going),
+      deny_warnings: self.deny_warnings.or(fallback.deny_warnings),
+      category_files: self.category_files.or(fallback.category_files),
+      no_sidebar: self.no_sidebar.or(fallback.no_sidebar),
+      reproducible: self.reproducible.or(fallback.reproducible),
+      patch_config: self.patch_config.or(fallback.patch_config),
+      inline_reexports: self.inline_reexports.or(fallback.inline_reexports),
+      docusaurus_version: self
+        .docusaurus_version
+        .or_else(|| fallback.docusaurus_version.clone()),
+      docusaurus_major: self.docusaurus_major.or(fallback.docusaurus_major),
+      docs_version: self.docs_version.or_else(|| fallback.docs_version.clone()),
+      strip_prefix: self.strip_prefix.or_else(|| fallback.strip_prefix.clone()),
+      summary_separator: self
+        .summary_separator
+        .or_else(|| fallback.summary_separator.clone()),
+      restricted_marker: self
+        .restricted_marker
+        .or_else(|| fallback.restricted_marker.clone()),
+      social_card: self.social_card.or_else(|| fallback.social_card.clone()),
+      field_summaries: self.field_summaries.or(fallback.field_summaries),
+      collapse_method_docs: self.collapse_method_docs.or(fallback.collapse_method_docs),
+      parameter_tables: self.parameter_tables.or(fallback.parameter_tables),
+      locale: self.locale.or_else(|| fallback.locale.clone()),
+      locale_dir: self.locale_dir.or_else(|| fallback.locale_dir.clone()),
+      changed_only: self.changed_only.or_else(|| fallback.changed_only.clone()),
+      production_url: self
+        .production_url
+        .or_else(|| fallback.production_url.clone()),
+      sidebar_icons: match (self.sidebar_icons, &fallback.sidebar_icons) {
+        (Some(icons), Some(fallback)) => {
+          let mut merged = fallback.clone();
+          merged.extend(icons);
+          Some(merged)
+        }
+        (icons, fallback) => icons.or_else(|| fallback.clone()),
+      },
+      doc_admonitions: match (self.doc_admonitions, &fallback.doc_admonitions) {
+        (Some(admonitions), Some(fallback)) => {
+          let mut merged = fallback.clone();
+          merged.extend(admonitions);
+          Some(merged)
+        }
+        (admonitions, fallback) => admonitions.or_else(|| fallback.clone()),
+      },
+      cargo_lock: self.cargo_lock.or_else(|| fallback.cargo_lock.clone()),
+      cargo_manifest: self
+        .cargo_manifest
+        .or_else(|| fallback.cargo_manifest.clone()),
+      include_readme: self
+        .include_readme
+        .or_else(|| fallback.include_readme.clone()),
+      template_dir: self.template_dir.or_else(|| fallback.template_dir.clone()),
+      extern_versions: match (self.extern_versions, &fallback.extern_versions) {
+        (Some(versions), Some(fallback)) => {
+          let mut merged = fallback.clone();
+          merged.extend(versions);
+          Some(merged)
+        }
+        (versions, fallback) => versions.or_else(|| fallback.clone()),
+      },
+      extern_urls: match (self.extern_urls, &fallback.extern_urls) {
+        (Some(urls), Some(fallback)) => {
+          let mut merged = fallback.clone();
+          merged.extend(urls);
+          Some(merged)
+        }
+        (urls, fallback) => urls.or_else(|| fallback.clone()),
+      },
+    }
+  }
+
+  /// Rebase relative output and input file paths onto `base_dir`.
+  fn rebase_paths(&mut self, base_dir: &Path) {
+    for path in [
+      &mut self.output,
+      &mut self.sidebar_output,
+      &mut self.since_map,
+      &mut self.redirect_map,
+      &mut self.emit_model,
+      &mut self.locale_dir,
+      &mut self.cargo_lock,
+      &mut self.cargo_manifest,
+      &mut self.include_readme,
+      &mut self.template_dir,
+    ]
+    .into_iter()
+    .flatten()
+    {
+      *path = base_dir.join(&*path);
+    }
+  }
+
+  /// Resolve the layer into concrete settings, applying the built-in defaults.
+  pub fn resolve(self) -> ResolvedOptions {
+    ResolvedOptions {
+      output: self
+        .output
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR)),
+      include_private: self.include_private.unwrap_or(false),
+      base_path: self.base_path.unwrap_or_default(),
+      workspace_crates: self.workspace_crates.unwrap_or_default(),
+      sidebarconfig_collapsed: self.sidebarconfig_collapsed.unwrap_or(true),
+      sidebar_output: self.sidebar_output,
+      since_map: self.since_map,
+      redirect_map: self.redirect_map,
+      emit_model: self.emit_model,
+      sidebar_root_link: self.sidebar_root_link,
+      max_depth: self.max_depth,
+      sidebar_collapse: self.sidebar_collapse,
+      incremental: self.incremental.unwrap_or(false),
+      sitemap: self.sitemap,
+      namespace: self.namespace,
+      route_base_path: self.route_base_path,
+      docs_plugin_id: self.docs_plugin_id,
+      extension: self.extension.unwrap_or_default(),
+      flavor: self.flavor.unwrap_or_default(),
+      sidebar_format: self.sidebar_format.unwrap_or_default(),
+      method_order: self.method_order.unwrap_or_default(),
+      signature_style: self.signature_style.unwrap_or_default(),
+      signature_width: self.signature_width,
+      signature_max_params: self.signature_max_params,
+      layout: self.layout.unwrap_or_default(),
+      error_index: self.error_index.unwrap_or(false),
+      async_index: self.async_index.unwrap_or(false),
+      feature_matrix: self.feature_matrix.unwrap_or(false),
+      features_page: self.features_page.unwrap_or(false),
+      glossary: self.glossary.unwrap_or(false),
+      all_items: self.all_items.unwrap_or(false),
+      kind_indexes: self.kind_indexes.unwrap_or(false),
+      json_schema_assets: self.json_schema_assets.unwrap_or(false),
+      math_blocks: self.math_blocks.unwrap_or(false),
+      copy_assets: self.copy_assets.unwrap_or(false),
+      show_phantom_data: self.show_phantom_data.unwrap_or(false),
+      keep_going: self.keep_going.unwrap_or(false),
+      deny_warnings: self.deny_warnings.unwrap_or(false),
+      category_files: self.category_files.unwrap_or(false),
+      no_sidebar: self.no_sidebar.unwrap_or(false),
+      reproducible: self.reproducible.unwrap_or(false),
+      patch_config: self.patch_config.unwrap_or(false),
+      inline_reexports: self.inline_reexports.unwrap_or(false),
+      docusaurus_version: self.docusaurus_version,
+      docusaurus_major: self.docusaurus_major,
+      docs_version: self.docs_version,
+      strip_prefix: self.strip_prefix,
+      summary_separator: self.summary_separator,
+      restricted_marker: self.restricted_marker,
+      social_card: self.social_card,
+      field_summaries: self.field_summaries.unwrap_or(false),
+      collapse_method_docs: self.collapse_method_docs.unwrap_or(false),
+      parameter_tables: self.parameter_tables.unwrap_or(false),
+      locale: self.locale,
+      locale_dir: self.locale_dir,
+      changed_only: self.changed_only,
+      production_url: self.production_url,
+      sidebar_icons: self.sidebar_icons,
+      doc_admonitions: self.doc_admonitions,
+      cargo_lock: self.cargo_lock,
+      cargo_manifest: self.cargo_manifest,
+      include_readme: self.include_readme,
+      template_dir: self.template_dir,
+      extern_versions: self.extern_versions,
+      extern_urls: self.extern_urls,
+    }
+  }
+}
+
+/// Contents of a configuration file.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(from = "RawConfig")]
+pub struct Config {
+  /// Rustdoc JSON files to convert when no input is given on the command line
+  pub inputs: Vec<PathBuf>,
+  /// Options shared by all crates
+  pub options: OptionsLayer,
+  /// Per-crate overrides, keyed by crate name
+  pub crates: BTreeMap<String, OptionsLayer>,
+}
+
+/// On-disk layout of [`Config`]: the shared options live at the top level of the file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct RawConfig {
+  inputs: Vec<PathBuf>,
+  #[serde(flatten)]
+  options: OptionsLayer,
+  crates: BTreeMap<String, OptionsLayer>,
+}
+
+impl From<RawConfig> for Config {
+  fn from(raw: RawConfig) -> Self {
+    Config {
+      inputs: raw.inputs,
+      options: raw.options,
+      crates: raw.crates,
+    }
+  }
+}
+
+impl Config {
+  /// Load a configuration file.
+  ///
+  /// Files ending in `.json` are parsed as JSON, `Cargo.toml` files are read from
+  /// their `metadata.doc-docusaurus` table, and everything else is parsed as TOML.
+  pub fn load(path: &Path) -> Result<Config> {
+    let contents = fs::read_to_string(path).map_err(Error::read(path))?;
+
+    let is_json = path.extension().is_some_and(|ext| ext == "json");
+    let is_cargo_manifest = path.file_name().is_some_and(|name| name == "Cargo.toml");
+
+    let config: Config = if is_json {
+      serde_json::from_str(&contents).map_err(Error::json(path))?
+    } else if is_cargo_manifest {
+      parse_cargo_metadata(&contents, path)?
+        .ok_or_else(|| Error::MissingConfig(path.to_path_buf()))?
+    } else {
+      toml::from_str(&contents).map_err(Error::toml(path))?
+    };
+
+    Ok(config.relative_to(path.parent().unwrap_or(Path::new(""))))
+  }
+
+  /// Look for a configuration in `dir`.
+  ///
+  /// `doc-docusaurus.toml` is preferred; otherwise the metadata table of
+  /// `Cargo.toml` is used if present. Returns `None` when nothing is configured.
+  pub fn discover(dir: &Path) -> Result<Option<Config>> {
+    let config_file = dir.join(CONFIG_FILE_NAME);
+    if config_file.exists() {
+      return Config::load(&config_file).map(Some);
+    }
+
+    let manifest = dir.join("Cargo.toml");
+    if manifest.exists() {
+      let contents = fs::read_to_string(&manifest).map_err(Error::read(&manifest))?;
+      let config = parse_cargo_metadata(&contents, &manifest)?;
+      return Ok(config.map(|config| config.relative_to(dir)));
+    }
+
+    Ok(None)
+  }
+
+  /// Compute the effective options for one crate.
+  ///
+  /// Precedence (highest first): `cli`, the `[crates.<name>]` override, the
+  /// top-level file options, built-in defaults.
+  pub fn options_for_crate(&self, crate_name: &str, cli: &OptionsLayer) -> ResolvedOptions {
+    let normalized = crate_name.replace('-', "_");
+    let crate_layer = self
+      .crates
+      .iter()
+      .find(|(name, _)| name.replace('-', "_") == normalized)
+      .map(|(_, layer)| layer.clone())
+      .unwrap_or_default();
+
+    cli.clone().or(&crate_layer.or(&self.options)).resolve()
+  }
+
+  /// Rebase relative paths in the configuration onto `base_dir`.
+  fn relative_to(mut self, base_dir: &Path) -> Config {
+    for input in &mut self.inputs {
+      *input = base_dir.join(&*input);
+    }
+    self.options.rebase_paths(base_dir);
+    for layer in self.crates.values_mut() {
+      layer.rebase_paths(base_dir);
+    }
+    self
+  }
+}
+
+/// Fully resolved, owned conversion settings for a single crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedOptions {
+  /// Directory where markdown files will be written
+  pub output: PathBuf,
+  /// Whether to include private items in the output
+  pub include_private: bool,
+  /// Base path for links
+  pub base_path: String,
+  /// List of workspace crate names
+  pub workspace_crates: Vec<String>,
+  /// Whether to generate sidebar categories as collapsed
+  pub sidebarconfig_collapsed: bool,
+  /// Custom path for the sidebar configuration file
+  pub sidebar_output: Option<PathBuf>,
+  /// Map of item paths to the version that introduced them, shown as "Since" badges
+  pub since_map: Option<PathBuf>,
+  /// JSON file mapping old item paths to their new paths, written as client redirects
+  pub redirect_map: Option<PathBuf>,
+  /// JSON file to write the normalized model of the crate to (see [`crate::model`])
+  pub emit_model: Option<PathBuf>,
+  /// URL for the 'Go back' link in root crate sidebars
+  pub sidebar_root_link: Option<String>,
+  /// Maximum module depth that gets its own pages
+  pub max_depth: Option<usize>,
+  /// Sidebar levels to collapse, overriding `sidebarconfig_collapsed` (which collapses all levels)
+  pub sidebar_collapse: Option<SidebarCollapse>,
+  /// Only write changed files and prune stale ones
+  pub incremental: bool,
+  /// Sitemap front matter for generated pages
+  pub sitemap: Option<SitemapOptions>,
+  /// Namespace prefixed to output directories, doc IDs and sidebar keys
+  pub namespace: Option<String>,
+  /// Route of the docs plugin instance serving the pages (e.g. "/rust"), the part of `base_path` that is not a docs folder
+  pub route_base_path: Option<String>,
+  /// Id of the docs plugin instance, naming its versioned and translated docs directories
+  pub docs_plugin_id: Option<String>,
+  /// File extension of generated pages
+  pub extension: OutputExtension,
+  /// Markup flavor of generated pages
+  pub flavor: OutputFlavor,
+  /// Format of the sidebar file (TypeScript, JSON or CommonJS)
+  pub sidebar_format: SidebarFormat,
+  /// Order of the methods listed on type and trait pages
+  pub method_order: MethodOrder,
+  /// Line wrapping of function signatures: `threshold` (parameter count and width limits) or `rustfmt`
+  pub signature_style: SignatureStyle,
+  /// Width above which the parameters of a function signature go on their own lines (80, or 100 with the `rustfmt` style)
+  pub signature_width: Option<usize>,
+  /// Parameter count above which the parameters of a function signature go on their own lines (3, ignored by the `rustfmt` style)
+  pub signature_max_params: Option<usize>,
+  /// Layout of the generated pages
+  pub layout: OutputLayout,
+  /// Generate an index page of error types
+  pub error_index: bool,
+  /// Generate an index page of the async API
+  pub async_index: bool,
+  /// Generate a `feature-matrix` page showing which items each Cargo feature enables
+  pub feature_matrix: bool,
+  /// Generate a `features` page listing the crate's Cargo features and what they enable
+  pub features_page: bool,
+  /// Generate a glossary page from `# Terminology` sections and `<!-- glossary: term -->` markers in doc comments
+  pub glossary: bool,
+  /// Generate an `all` page listing every item of the crate by kind
+  pub all_items: bool,
+  /// Generate a page per item kind (e.g. `all-structs`) listing every item of that kind
+  pub kind_indexes: bool,
+  /// Extract the `JSON schema` blocks of doc comments (as generated for schemars types) to downloadable `.schema.json` files
+  pub json_schema_assets: bool,
+  /// Render `math`/`latex` fenced code blocks of doc comments as `$$` blocks for remark-math and KaTeX
+  pub math_blocks: bool,
+  /// Copy local images referenced in doc comments to an `_assets` folder of the crate output
+  pub copy_assets: bool,
+  /// Show `PhantomData` marker fields of structs (hidden by default)
+  pub show_phantom_data: bool,
+  /// Render a placeholder page for items that fail to render instead of aborting
+  pub keep_going: bool,
+  /// Fail the conversion when it has warnings (unresolved links, placeholder pages), after writing the output
+  pub deny_warnings: bool,
+  /// Write `_category_.json` files to module directories instead of a sidebar file
+  pub category_files: bool,
+  /// Generate no sidebars: pages get no `displayed_sidebar` and no sidebar file is written or merged
+  pub no_sidebar: bool,
+  /// Record hashes of the rustdoc JSON input and of all generated files in the output manifest, for attesting reproducible builds
+  pub reproducible: bool,
+  /// Wire the generated sidebars into the site's sidebars file and Docusaurus config (keeping `.bak` backups)
+  pub patch_config: bool,
+  /// Document items re-exported from other crates on this crate's pages, when their rustdoc JSON is next to the input
+  pub inline_reexports: bool,
+  /// Docusaurus version label (e.g. "1.2") like `docs_version`, also registered in `versions.json` and `versioned_sidebars`
+  pub docusaurus_version: Option<String>,
+  /// Docusaurus major version to generate pages and sidebars for, detected from the site's `package.json` when unset
+  pub docusaurus_major: Option<DocusaurusMajor>,
+  /// Docusaurus docs version (e.g. "1.2") to write versioned docs for
+  pub docs_version: Option<String>,
+  /// Module path prefix (e.g. "my_crate::api::v1") stripped from displayed module paths
+  pub strip_prefix: Option<String>,
+  /// Separator between item names and their doc summaries in listings (default `—`)
+  pub summary_separator: Option<String>,
+  /// Marker after the names of items with restricted visibility in listings (default `🔒`, empty to hide)
+  pub restricted_marker: Option<String>,
+  /// Image (URL or path of the site's static files) set as `image` in the front matter of generated pages, for social cards
+  pub social_card: Option<String>,
+  /// Show only the first line of the docs of struct fields instead of their complete docs
+  pub field_summaries: bool,
+  /// Collapse the docs of methods below their first paragraph in a `<details>` block
+  pub collapse_method_docs: bool,
+  /// Add Parameters and Returns tables to functions and methods, with parameter docs from their `# Arguments` section
+  pub parameter_tables: bool,
+  /// Docusaurus i18n locale (e.g. "fr") to write translation sources for
+  pub locale: Option<String>,
+  /// Translated docs content directory replacing `i18n/<LOCALE>/docusaurus-plugin-content-docs` (e.g. for a docs plugin instance with an id); the locale defaults to the directory below `i18n`
+  pub locale_dir: Option<PathBuf>,
+  /// Git range (e.g. "origin/main...HEAD") whose changed source files are the only ones to get pages
+  pub changed_only: Option<String>,
+  /// Production site URL (e.g. `https://docs.example.com`) linked to for pages outside a `changed_only` preview
+  pub production_url: Option<String>,
+  /// Icons (`customProps.icon`) of sidebar entries, keyed by item kind
+  pub sidebar_icons: Option<SidebarIcons>,
+  /// Admonition kinds (or `heading`) of doc comment sections, keyed by heading (e.g. "Safety")
+  pub doc_admonitions: Option<DocAdmonitions>,
+  /// Cargo.lock whose dependency versions pin docs.rs links (instead of `latest`)
+  pub cargo_lock: Option<PathBuf>,
+  /// Cargo.toml of the crate, read by `features_page` and `copy_assets` (found from the input's location if unset)
+  pub cargo_manifest: Option<PathBuf>,
+  /// README whose content is added to the crate index page, with relative image paths rewritten
+  pub include_readme: Option<PathBuf>,
+  /// Directory of page templates (`item.md`, `module.md`, `crate.md`) overriding the built-in ones
+  pub template_dir: Option<PathBuf>,
+  /// docs.rs versions of dependencies, keyed by crate name, overriding `cargo_lock`
+  pub extern_versions: Option<ExternVersions>,
+  /// Documentation URL prefixes of dependencies (e.g. internal mirrors), keyed by crate name, replacing docs.rs
+  pub extern_urls: Option<ExternUrls>,
+}
+
+impl ResolvedOptions {
+  /// Borrow these settings as [`ConversionOptions`] for the given input file.
+  pub fn as_conversion_options<'a>(&'a self, input_path: &'a Path) -> ConversionOptions<'a> {
+    ConversionOptions {
+      input_path,
+      output_dir: &self.output,
+      include_private: self.include_private,
+      base_path: &self.base_path,
+      workspace_crates: &self.workspace_crates,
+      sidebarconfig_collapsed: self.sidebarconfig_collapsed,
+      sidebar_output: self.sidebar_output.as_deref(),
+      since_map: self.since_map.as_deref(),
+      redirect_map: self.redirect_map.as_deref(),
+      emit_model: self.emit_model.as_deref(),
+      sidebar_root_link: self.sidebar_root_link.as_deref(),
+      max_depth: self.max_depth,
+      sidebar_collapse: self.sidebar_collapse,
+      incremental: self.incremental,
+      sitemap: self.sitemap.as_ref(),
+      namespace: self.namespace.as_deref(),
+      route_base_path: self.route_base_path.as_deref(),
+      docs_plugin_id: self.docs_plugin_id.as_deref(),
+      extension: self.extension,
+      flavor: self.flavor,
+      sidebar_format: self.sidebar_format,
+      method_order: self.method_order,
+      signature_style: self.signature_style,
+      signature_width: self.signature_width,
+      signature_max_params: self.signature_max_params,
+      layout: self.layout,
+      error_index: self.error_index,
+      async_index: self.async_index,
+      feature_matrix: self.feature_matrix,
+      features_page: self.features_page,
+      glossary: self.glossary,
+      all_items: self.all_items,
+      kind_indexes: self.kind_indexes,
+      json_schema_assets: self.json_schema_assets,
+      math_blocks: self.math_blocks,
+      copy_assets: self.copy_assets,
+      show_phantom_data: self.show_phantom_data,
+      keep_going: self.keep_going,
+      deny_warnings: self.deny_warnings,
+      category_files: self.category_files,
+      no_sidebar: self.no_sidebar,
+      reproducible: self.reproducible,
+      patch_config: self.patch_config,
+      inline_reexports: self.inline_reexports,
+      docusaurus_version: self.docusaurus_version.as_deref(),
+      docusaurus_major: self.docusaurus_major,
+      docs_version: self.docs_version.as_deref(),
+      strip_prefix: self.strip_prefix.as_deref(),
+      summary_separator: self.summary_separator.as_deref(),
+      restricted_marker: self.restricted_marker.as_deref(),
+      social_card: self.social_card.as_deref(),
+      field_summaries: self.field_summaries,
+      collapse_method_docs: self.collapse_method_docs,
+      parameter_tables: self.parameter_tables,
+      locale: self.locale.as_deref(),
+      locale_dir: self.locale_dir.as_deref(),
+      changed_only: self.changed_only.as_deref(),
+      production_url: self.production_url.as_deref(),
+      sidebar_icons: self.sidebar_icons.as_ref(),
+      doc_admonitions: self.doc_admonitions.as_ref(),
+      cargo_lock: self.cargo_lock.as_deref(),
+      cargo_manifest: self.cargo_manifest.as_deref(),
+      include_readme: self.include_readme.as_deref(),
+      template_dir: self.template_dir.as_deref(),
+      extern_versions: self.extern_versions.as_ref(),
+      extern_urls: self.extern_urls.as_ref(),
+      sidebar_hook: None,
+      item_renderer: None,
+    }
+  }
+}
+
+/// Infer the crate name from a rustdoc JSON path (`target/doc/my_crate.json` or
+/// `my_crate.json.gz` -> `my_crate`).
+pub fn crate_name_from_input(input: &Path) -> String {
+  let file_name = input
+    .file_name()
+    .and_then(|name| name.to_str())
+    .unwrap_or_default();
+  [".json.gz", ".json.zst"]
+    .iter()
+    .find_map(|extension| file_name.strip_suffix(extension))
+    .or_else(|| input.file_stem().and_then(|stem| stem.to_str()))
+    .unwrap_or_default()
+    .replace('-', "_")
+}
+
+/// Crate names of the workspace members around `dir` (e.g. `target/doc`), from
+/// `cargo metadata`, or `None` if they cannot be determined.
+///
+/// Used to skip auxiliary rustdoc JSON (doctests, build scripts, documented
+/// dependencies) found next to the workspace crates.
+pub fn workspace_member_crates(dir: &Path) -> Option<BTreeSet<String>> {
+  let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+  let output = std::process::Command::new(cargo)
+    .args(["metadata", "--no-deps", "--format-version", "1"])
+    .current_dir(dir)
+    .output()
+    .ok()
+    .filter(|output| output.status.success())?;
+  let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+  Some(member_crate_names(&metadata))
+}
+
+/// Names of the documentable targets (libraries and binaries) of `cargo metadata` packages.
+fn member_crate_names(metadata: &serde_json::Value) -> BTreeSet<String> {
+  metadata["packages"]
+    .as_array()
+    .into_iter()
+    .flatten()
+    .flat_map(|package| package["targets"].as_array().into_iter().flatten())
+    .filter(|target| {
+      target["kind"].as_array().is_some_and(|kinds| {
+        kinds.iter().any(|kind| {
+          matches!(
+            kind.as_str(),
+            Some("lib" | "rlib" | "dylib" | "proc-macro" | "bin")
+          )
+        })
+      })
+    })
+    .filter_map(|target| target["name"].as_str())
+    .map(|name| name.replace('-', "_"))
+    .collect()
+}
+
+/// Rustdoc JSON files in a directory (e.g. `target/doc`), sorted by file name.
+pub fn rustdoc_json_files(dir: &Path) -> Result<Vec<PathBuf>> {
+  let mut files: Vec<PathBuf> = fs::read_dir(dir)
+    .map_err(Error::read(dir))?
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| path.is_file() && parser::is_rustdoc_json_path(path))
+    .collect();
+  files.sort();
+  if files.is_empty() {
+    return Err(Error::NoInputFiles(dir.to_path_buf()));
+  }
+  Ok(files)
+}
+
+/// Extract `[package.metadata.doc-docusaurus]` (or the workspace equivalent) from a manifest.
+fn parse_cargo_metadata(contents: &str, path: &Path) -> Result<Option<Config>> {
+  let manifest: toml::Value = toml::from_str(contents).map_err(Error::toml(path))?;
+
+  let metadata = ["package", "workspace"].iter().find_map(|section| {
+    manifest
+      .get(section)
+      .and_then(|s| s.get("metadata"))
+      .and_then(|m| m.get(CARGO_METADATA_KEY))
+  });
+
+  match metadata {
+    Some(value) => {
+      let config = value.clone().try_into().map_err(Error::toml(path))?;
+      Ok(Some(config))
+    }
+    None => Ok(None),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::path::PathBuf;
+
+  fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, contents).unwrap();
+    path
+  }
+
+  #[test]
+  fn test_options_precedence() {
+    let config: Config = toml::from_str(
+      r#"
+      base-path = "/docs/top"
+      include-private = true
+      sidebar-root-link = "https://example.com"
+
+      [crates.my-crate]
+      base-path = "/docs/crate"
+      include-private = false
+      "#,
+    )
+    .unwrap();
+
+    let cli = OptionsLayer {
+      include_private: Some(true),
+      ..Default::default()
+    };
+    let resolved = config.options_for_crate("my_crate", &cli);
+
+    // CLI beats crate override
+    assert!(resolved.include_private);
+    // Crate override beats top-level options
+    assert_eq!(resolved.base_path, "/docs/crate");
+    // Top-level options beat defaults
+    assert_eq!(
+      resolved.sidebar_root_link.as_deref(),
+      Some("https://example.com")
+    );
+    // Defaults fill the rest
+    assert_eq!(resolved.output, PathBuf::from(DEFAULT_OUTPUT_DIR));
+    assert!(resolved.sidebarconfig_collapsed);
+  }
+
+  #[test]
+  fn test_crate_override_fallback() {
+    let config: Config = toml::from_str(
+      r#"
+      base-path = "/docs/top"
+
+      [crates.other]
+      base-path = "/docs/other"
+      "#,
+    )
+    .unwrap();
+
+    let resolved = config.options_for_crate("my_crate", &OptionsLayer::default());
+    assert_eq!(resolved.base_path, "/docs/top");
+    assert!(!resolved.include_private);
+  }
+
+  #[test]
+  fn test_sidebar_collapse_levels() {
+    let config: Config = toml::from_str(r#"sidebar-collapse = ["crates", "items"]"#).unwrap();
+    let resolved = config.options_for_crate("my_crate", &OptionsLayer::default());
+    assert_eq!(
+      resolved.sidebar_collapse,
+      Some(SidebarCollapse {
+        crates: true,
+        modules: false,
+        items: true,
+      })
+    );
+
+    assert!(toml::from_str::<Config>(r#"sidebar-collapse = ["structs"]"#).is_err());
+  }
+
+  #[test]
+  fn test_crate_name_matching_normalizes_dashes() {
+    let config: Config = toml::from_str(
+      r#"
+      [crates.my_crate]
+      base-path = "/docs/underscore"
+      "#,
+    )
+    .unwrap();
+
+    let resolved = config.options_for_crate("my-crate", &OptionsLayer::default());
+    assert_eq!(resolved.base_path, "/docs/underscore");
+  }
+
+  #[test]
+  fn test_unknown_keys_are_rejected() {
+    assert!(toml::from_str::<Config>("base_path = \"/docs\"").is_err());
+    assert!(toml::from_str::<Config>("[crates.foo]\ninclude_private = true").is_err());
+    let error = toml::from_str::<Config>("inputs = []\nbase_path = \"/docs\"").unwrap_err();
+    assert!(error.to_string().contains("base_path"));
+    // Shared options are still type checked at the top level
+    assert!(toml::from_str::<Config>("max-depth = \"deep\"").is_err());
+  }
+
+  #[test]
+  fn test_extension_option() {
+    let config: Config = toml::from_str("extension = \"mdx\"").unwrap();
+    let resolved = config.options_for_crate("my_crate", &OptionsLayer::default());
+    assert_eq!(resolved.extension, OutputExtension::Mdx);
+
+    let resolved = Config::default().options_for_crate("my_crate", &OptionsLayer::default());
+    assert_eq!(resolved.extension, OutputExtension::Md);
+
+    assert!(toml::from_str::<Config>("extension = \"txt\"").is_err());
+  }
+
+  #[test]
+  fn test_docusaurus_major_option() {
+    let config: Config = toml::from_str("docusaurus-major = 2").unwrap();
+    let resolved = config.options_for_crate("my_crate", &OptionsLayer::default());
+    assert_eq!(resolved.docusaurus_major, Some(DocusaurusMajor::V2));
+
+    assert!(toml::from_str::<Config>("docusaurus-major = 1").is_err());
+  }
+
+  #[test]
+  fn test_sidebar_icons_merge_per_kind() {
+    let config: Config = toml::from_str(
+      r#"
+      [sidebar-icons]
+      struct = "S"
+      trait = "T"
+
+      [crates.utils.sidebar-icons]
+      trait = "🅣"
+      "#,
+    )
+    .unwrap();
+    let cli = OptionsLayer {
+      sidebar_icons: Some(SidebarIcons::from([("fn".to_string(), "F".to_string())])),
+      ..Default::default()
+    };
+
+    let icons = config
+      .options_for_crate("utils", &cli)
+      .sidebar_icons
+      .unwrap();
+    assert_eq!(icons["struct"], "S");
+    assert_eq!(icons["trait"], "🅣");
+    assert_eq!(icons["fn"], "F");
+  }
+
+  #[test]
+  fn test_extern_urls_config() {
+    let config: Config = toml::from_str(
+      r#"
+      cargo-lock = "Cargo.lock"
+
+      [extern-urls]
+      internal_rpc = "https://docs.internal/internal_rpc/2.1"
+      "#,
+    )
+    .unwrap();
+    let cli = OptionsLayer {
+      extern_urls: Some(ExternUrls::from([(
+        "serde".to_string(),
+        "https://mirror/serde".to_string(),
+      )])),
+      ..Default::default()
+    };
+
+    let options = config.options_for_crate("core", &cli);
+    assert_eq!(options.cargo_lock, Some(PathBuf::from("Cargo.lock")));
+    let urls = options.extern_urls.unwrap();
+    assert_eq!(
+      urls["internal_rpc"],
+      "https://docs.internal/internal_rpc/2.1"
+    );
+    assert_eq!(urls["serde"], "https://mirror/serde");
+  }
+
+  #[test]
+  fn test_load_toml_rebases_relative_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write(
+      dir.path(),
+      "doc-docusaurus.toml",
+      r#"
+      inputs = ["target/doc/foo.json"]
+      output = "docs/api"
+      sidebar-output = "/abs/sidebars.ts"
+      "#,
+    );
+
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.inputs, vec![dir.path().join("target/doc/foo.json")]);
+    assert_eq!(config.options.output, Some(dir.path().join("docs/api")));
+    assert_eq!(
+      config.options.sidebar_output,
+      Some(PathBuf::from("/abs/sidebars.ts"))
+    );
+  }
+
+  #[test]
+  fn test_member_crate_names() {
+    let metadata = serde_json::json!({"packages": [
+      {"name": "my-crate", "targets": [
+        {"name": "my_crate", "kind": ["lib"]},
+        {"name": "my-tool", "kind": ["bin"]},
+        {"name": "build-script-build", "kind": ["custom-build"]},
+        {"name": "integration", "kind": ["test"]},
+      ]},
+      {"name": "my-macros", "targets": [{"name": "my_macros", "kind": ["proc-macro"]}]},
+    ]});
+
+    assert_eq!(
+      member_crate_names(&metadata),
+      BTreeSet::from([
+        "my_crate".to_string(),
+        "my_macros".to_string(),
+        "my_tool".to_string()
+      ])
+    );
+  }
+
+  #[test]
+  fn test_rustdoc_json_files() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "utils.json", "{}");
+    write(dir.path(), "core.json", "{}");
+    write(dir.path(), "core.js", "");
+    fs::create_dir(dir.path().join("src")).unwrap();
+
+    assert_eq!(
+      rustdoc_json_files(dir.path()).unwrap(),
+      vec![dir.path().join("core.json"), dir.path().join("utils.json")]
+    );
+    assert!(rustdoc_json_files(&dir.path().join("src")).is_err());
+  }
+
+  #[test]
+  fn test_load_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write(
+      dir.path(),
+      "doc-docusaurus.json",
+      r#"{ "base-path": "/docs", "workspace-crates": ["a", "b"] }"#,
+    );
+
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.options.base_path.as_deref(), Some("/docs"));
+    assert_eq!(
+      config.options.workspace_crates,
+      Some(vec!["a".to_string(), "b".to_string()])
+    );
+  }
+
+  #[test]
+  fn test_load_cargo_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let package = write(
+      dir.path(),
+      "Cargo.toml",
+      r#"
+      [package]
+      name = "foo"
+
+      [package.metadata.doc-docusaurus]
+      base-path = "/docs/package"
+      "#,
+    );
+    let config = Config::load(&package).unwrap();
+    assert_eq!(config.options.base_path.as_deref(), Some("/docs/package"));
+
+    let workspace_dir = dir.path().join("ws");
+    fs::create_dir(&workspace_dir).unwrap();
+    let workspace = write(
+      &workspace_dir,
+      "Cargo.toml",
+      r#"
+      [workspace]
+      members = []
+
+      [workspace.metadata.doc-docusaurus]
+      base-path = "/docs/workspace"
+      "#,
+    );
+    let config = Config::load(&workspace).unwrap();
+    assert_eq!(config.options.base_path.as_deref(), Some("/docs/workspace"));
+  }
+
+  #[test]
+  fn test_load_cargo_without_metadata_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write(dir.path(), "Cargo.toml", "[package]\nname = \"foo\"\n");
+
+    let err = Config::load(&path).unwrap_err().to_string();
+    assert!(err.contains("metadata.doc-docusaurus"));
+  }
+
+  #[test]
+  fn test_discover_prefers_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+      dir.path(),
+      "Cargo.toml",
+      "[package]\nname = \"foo\"\n\n[package.metadata.doc-docusaurus]\nbase-path = \"/from-cargo\"\n",
+    );
+    write(dir.path(), CONFIG_FILE_NAME, "base-path = \"/from-file\"\n");
+
+    let config = Config::discover(dir.path()).unwrap().unwrap();
+    assert_eq!(config.options.base_path.as_deref(), Some("/from-file"));
+
+    fs::remove_file(dir.path().join(CONFIG_FILE_NAME)).unwrap();
+    let config = Config::discover(dir.path()).unwrap().unwrap();
+    assert_eq!(config.options.base_path.as_deref(), Some("/from-cargo"));
+  }
+
+  #[test]
+  fn test_discover_without_config() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(Config::discover(dir.path()).unwrap().is_none());
+
+    write(dir.path(), "Cargo.toml", "[package]\nname = \"foo\"\n");
+    assert!(Config::discover(dir.path()).unwrap().is_none());
+  }
+}
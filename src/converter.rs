@@ -1,54 +1,895 @@
 //! Markdown converter for rustdoc JSON data.
 
-use anyhow::Result;
+use crate::error::{Error, Result};
+use crate::links::{LinkResolver, ModulePaths};
+use crate::sidebar::{
+  CategoryMetadata, SidebarCollapse, SidebarIcons, SidebarItem, SidebarKind, SidebarMap,
+};
+use crate::templates::{PageTemplate, PageTemplates};
+use rayon::prelude::*;
 use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
-use std::cell::RefCell;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Valid values for the sitemap `changefreq` field
+pub const SITEMAP_CHANGEFREQS: &[&str] = &[
+  "always", "hourly", "daily", "weekly", "monthly", "yearly", "never",
+];
+
+/// Settings shared by all rendering functions during a single conversion
+#[derive(Debug, Clone, Default)]
+pub struct RenderContext {
+  /// Base path to use in generated links
+  pub base_path: String,
+  /// Workspace crate names (linked internally instead of docs.rs)
+  pub workspace_crates: Vec<String>,
+  /// Sidebar root link URL
+  pub sidebar_root_link: Option<String>,
+  /// Maximum module depth that gets its own pages
+  pub max_depth: Option<usize>,
+  /// Sitemap front matter to emit on generated pages
+  pub sitemap: Option<SitemapOptions>,
+  /// Namespace nested below `base_path` (e.g. "v2"), so several versions of a crate can coexist
+  pub namespace: Option<String>,
+  /// Route of the docs plugin instance (e.g. "/rust"), below which versions are inserted
+  /// and doc IDs and sidebar keys start (the first segment of `base_path` if unset)
+  pub route_base_path: Option<String>,
+  /// File extension of generated pages
+  pub extension: OutputExtension,
+  /// Generate an index page of the crate's error types
+  pub error_index: bool,
+  /// Generate an index page of the crate's async functions, futures and streams
+  pub async_index: bool,
+  /// Generate a page showing which items each Cargo feature enables
+  pub feature_matrix: bool,
+  /// Generate a glossary page of terms defined in doc comments
+  pub glossary: bool,
+  /// Generate an `all` page listing every item of the crate by kind
+  pub all_items: bool,
+  /// Generate a page per item kind (e.g. `all-structs`) listing every item of that kind
+  pub kind_indexes: bool,
+  /// Write the JSON schemas of item docs to `.schema.json` files linked from their pages
+  pub json_schema_assets: bool,
+  /// Render `math`/`latex` fenced code blocks of doc comments as `$$` blocks for remark-math
+  pub math_blocks: bool,
+  /// Show `PhantomData` marker fields of structs (hidden by default)
+  pub show_phantom_data: bool,
+  /// Render a placeholder page for items that fail to render instead of aborting
+  pub keep_going: bool,
+  /// Write `_category_.json` files to module directories instead of a sidebar file
+  pub category_files: bool,
+  /// Generate no sidebars, for sites with hand-written navigation
+  pub no_sidebar: bool,
+  /// Markup flavor of generated pages
+  pub flavor: OutputFlavor,
+  /// Docusaurus major version the pages are generated for
+  pub docusaurus_major: DocusaurusMajor,
+  /// Order of the methods listed on type and trait pages
+  pub method_order: MethodOrder,
+  /// Line wrapping of function signatures
+  pub signature_style: SignatureStyle,
+  /// Width above which signature parameters go on their own lines (default depends on the style)
+  pub signature_width: Option<usize>,
+  /// Parameter count above which signature parameters go on their own lines (3 if unset)
+  pub signature_max_params: Option<usize>,
+  /// Layout of the generated pages
+  pub layout: OutputLayout,
+  /// Docusaurus docs version (e.g. "1.2"), inserted after the docs route in links and sidebar keys
+  pub docs_version: Option<String>,
+  /// Module path prefix (e.g. "my_crate::api::v1") stripped from displayed module paths
+  pub strip_prefix: Option<String>,
+  /// Separator between item names and their doc summaries in listings (`—` if unset)
+  pub summary_separator: Option<String>,
+  /// Marker after the names of restricted items in listings (`🔒` if unset)
+  pub restricted_marker: Option<String>,
+  /// Image of social cards, set as `image` in the front matter of pages
+  pub social_card: Option<String>,
+  /// Show only the first line of the docs of struct fields
+  pub field_summaries: bool,
+  /// Collapse the docs of methods below their first paragraph
+  pub collapse_method_docs: bool,
+  /// Add Parameters and Returns tables to functions and methods
+  pub parameter_tables: bool,
+  /// i18n locale (e.g. "fr") prefixed to sidebar keys
+  pub locale: Option<String>,
+  /// Icons of sidebar entries, keyed by item kind
+  pub sidebar_icons: Option<SidebarIcons>,
+  /// Sidebar levels to collapse, overriding `sidebarconfig_collapsed` (which collapses all levels)
+  pub sidebar_collapse: Option<SidebarCollapse>,
+  /// Admonition kinds of doc comment sections, overriding [`DEFAULT_DOC_ADMONITIONS`]
+  pub doc_admonitions: Option<DocAdmonitions>,
+  /// Changed source files of a preview; only items defined in them get pages
+  pub changed_files: Option<BTreeSet<PathBuf>>,
+  /// Production site URL that links to pages outside a preview point to
+  pub production_url: Option<String>,
+  /// Version that introduced each item, keyed by item path (e.g. `my_crate::Foo`)
+  pub since_versions: Option<BTreeMap<String, String>>,
+  /// New paths of renamed or moved items, keyed by their old path, written as client redirects
+  pub redirects: Option<BTreeMap<String, String>>,
+  /// docs.rs versions linked for dependencies, keyed by crate name (`latest` for others)
+  pub extern_versions: Option<ExternVersions>,
+  /// Documentation URL prefixes replacing docs.rs for some dependencies, keyed by crate name
+  pub extern_urls: Option<ExternUrls>,
+  /// Cargo features of the crate, listed on a `features` page
+  pub crate_features: Option<CrateFeatures>,
+  /// README content added to the crate index page after the crate docs
+  pub readme: Option<String>,
+  /// Custom rendering of item pages
+  pub item_renderer: Option<crate::renderer::CustomRenderer>,
+  /// Templates laying out item, module and crate pages
+  pub templates: PageTemplates,
+  /// Heading IDs of local items on a single-file page, used as their link targets
+  pub heading_ids: HashMap<Id, String>,
+  /// State of the conversion of a crate, set up afresh by every conversion
+  pub conversion: ConversionState,
+}
+
+/// Part of a URL path below a route (both with a leading and no trailing
+/// slash, the site root being empty): `/rust/api` is `api` below `/rust`.
+pub(crate) fn path_below_route<'a>(path: &'a str, route: &str) -> Option<&'a str> {
+  match path.strip_prefix(route)? {
+    "" => Some(""),
+    rest => rest.strip_prefix('/'),
+  }
+}
+
+/// State of the conversion of one crate, shared by its rendering threads: the
+/// cached module tree paths of linked items and the link targets that could not
+/// be resolved, with the number of links to each.
+///
+/// Every conversion starts with its own, so concurrent conversions with the same
+/// [`RenderContext`] do not mix their unresolved links.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionState {
+  module_paths: ModulePaths,
+  unresolved_links: Arc<Mutex<BTreeMap<String, usize>>>,
+}
+
+impl ConversionState {
+  fn record_unresolved(&self, path: &str) {
+    if let Ok(mut unresolved) = self.unresolved_links.lock() {
+      *unresolved.entry(path.to_string()).or_default() += 1;
+    }
+  }
+
+  /// Link targets that could not be resolved so far, with the number of links to each.
+  pub fn unresolved_links(&self) -> BTreeMap<String, usize> {
+    self
+      .unresolved_links
+      .lock()
+      .map(|unresolved| unresolved.clone())
+      .unwrap_or_default()
+  }
+}
+
+/// Valid values for the output flavor
+pub const OUTPUT_FLAVORS: &[&str] = &["docusaurus", "plain-markdown"];
+
+/// Markup flavor of generated pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFlavor {
+  /// MDX with `RustCode`/`Link` components and a Docusaurus sidebar
+  #[default]
+  Docusaurus,
+  /// Plain Markdown with fenced code blocks and file links (for mdBook, Obsidian, ...)
+  PlainMarkdown,
+}
+
+impl std::str::FromStr for OutputFlavor {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s {
+      "docusaurus" => Ok(OutputFlavor::Docusaurus),
+      "plain-markdown" => Ok(OutputFlavor::PlainMarkdown),
+      _ => Err(Error::InvalidOptions(format!(
+        "Invalid flavor '{}', expected one of: {}",
+        s,
+        OUTPUT_FLAVORS.join(", ")
+      ))),
+    }
+  }
+}
+
+/// Valid values for the page layout
+pub const LAYOUTS: &[&str] = &["per-item", "per-module", "single-file"];
+
+/// Layout of the generated pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputLayout {
+  /// A page per item, listed on the page of its module
+  #[default]
+  PerItem,
+  /// A page per module, with its items as anchored sections
+  PerModule,
+  /// The whole crate on a single page (the legacy single-file output)
+  SingleFile,
+}
+
+impl std::str::FromStr for OutputLayout {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s {
+      "per-item" => Ok(OutputLayout::PerItem),
+      "per-module" => Ok(OutputLayout::PerModule),
+      "single-file" => Ok(OutputLayout::SingleFile),
+      _ => Err(Error::InvalidOptions(format!(
+        "Invalid layout '{}', expected one of: {}",
+        s,
+        LAYOUTS.join(", ")
+      ))),
+    }
+  }
+}
+
+/// Valid values for the method order
+pub const METHOD_ORDERS: &[&str] = &["source", "alphabetical"];
+
+/// Order of the methods listed on type and trait pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MethodOrder {
+  /// Impl blocks and their methods in source order
+  #[default]
+  Source,
+  /// Methods sorted by name within each impl block
+  Alphabetical,
+}
+
+impl std::str::FromStr for MethodOrder {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s {
+      "source" => Ok(MethodOrder::Source),
+      "alphabetical" => Ok(MethodOrder::Alphabetical),
+      _ => Err(Error::InvalidOptions(format!(
+        "Invalid method order '{}', expected one of: {}",
+        s,
+        METHOD_ORDERS.join(", ")
+      ))),
+    }
+  }
+}
+
+/// Valid values for the signature style
+pub const SIGNATURE_STYLES: &[&str] = &["threshold", "rustfmt"];
+
+/// Line wrapping of function signatures, putting their parameters one per line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureStyle {
+  /// Wrap signatures with many parameters, or wider than the width without the
+  /// return type
+  #[default]
+  Threshold,
+  /// Wrap signatures wider than the width, return type included, like rustfmt
+  Rustfmt,
+}
+
+impl std::str::FromStr for SignatureStyle {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s {
+      "threshold" => Ok(SignatureStyle::Threshold),
+      "rustfmt" => Ok(SignatureStyle::Rustfmt),
+      _ => Err(Error::InvalidOptions(format!(
+        "Invalid signature style '{}', expected one of: {}",
+        s,
+        SIGNATURE_STYLES.join(", ")
+      ))),
+    }
+  }
+}
+
+/// Valid values for the targeted Docusaurus major version
+pub const DOCUSAURUS_MAJORS: &[&str] = &["2", "3"];
+
+/// Major version of the Docusaurus site that pages and sidebars are generated for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum DocusaurusMajor {
+  /// Docusaurus 2 (MDX 1, `:::note Title` admonitions)
+  V2,
+  /// Docusaurus 3 (MDX 3, `:::note[Title]` admonitions)
+  #[default]
+  V3,
+}
+
+impl DocusaurusMajor {
+  /// Opening line of an admonition with a title, in the syntax of this version.
+  pub fn admonition(self, kind: &str, title: &str) -> String {
+    match self {
+      DocusaurusMajor::V2 => format!(":::{} {}", kind, title),
+      DocusaurusMajor::V3 => format!(":::{}[{}]", kind, title),
+    }
+  }
+}
+
+impl TryFrom<u8> for DocusaurusMajor {
+  type Error = Error;
+
+  fn try_from(major: u8) -> Result<Self> {
+    match major {
+      2 => Ok(DocusaurusMajor::V2),
+      3 => Ok(DocusaurusMajor::V3),
+      _ => Err(Error::InvalidOptions(format!(
+        "Unsupported Docusaurus version {}, expected one of: {}",
+        major,
+        DOCUSAURUS_MAJORS.join(", ")
+      ))),
+    }
+  }
+}
+
+impl From<DocusaurusMajor> for u8 {
+  fn from(major: DocusaurusMajor) -> u8 {
+    match major {
+      DocusaurusMajor::V2 => 2,
+      DocusaurusMajor::V3 => 3,
+    }
+  }
+}
+
+impl std::str::FromStr for DocusaurusMajor {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    s.parse::<u8>()
+      .map_err(|_| {
+        Error::InvalidOptions(format!(
+          "Invalid Docusaurus version '{}', expected one of: {}",
+          s,
+          DOCUSAURUS_MAJORS.join(", ")
+        ))
+      })
+      .and_then(DocusaurusMajor::try_from)
+  }
+}
+
+/// Valid values for the output file extension
+pub const OUTPUT_EXTENSIONS: &[&str] = &["md", "mdx"];
+
+/// File extension used for generated pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputExtension {
+  /// Plain `.md` files
+  #[default]
+  Md,
+  /// `.mdx` files, for sites that parse `.md` as CommonMark
+  Mdx,
+}
+
+impl OutputExtension {
+  /// The extension without the leading dot
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      OutputExtension::Md => "md",
+      OutputExtension::Mdx => "mdx",
+    }
+  }
+}
+
+impl std::str::FromStr for OutputExtension {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s {
+      "md" => Ok(OutputExtension::Md),
+      "mdx" => Ok(OutputExtension::Mdx),
+      _ => Err(Error::InvalidOptions(format!(
+        "Invalid extension '{}', expected one of: {}",
+        s,
+        OUTPUT_EXTENSIONS.join(", ")
+      ))),
+    }
+  }
+}
+
+/// docs.rs versions of dependencies, keyed by crate name (e.g. `serde` -> `1.0.215`).
+pub type ExternVersions = BTreeMap<String, String>;
+
+/// Documentation URL prefixes of dependencies, keyed by crate name
+/// (e.g. `serde` -> `https://docs.internal/serde/1.0`), like rustdoc's
+/// `--extern-html-root-url`: pages are found below `<prefix>/<crate>/`.
+pub type ExternUrls = BTreeMap<String, String>;
+
+/// Cargo features of a crate, keyed by name, with the features and dependencies
+/// each one enables (the `[features]` table of its manifest, e.g. `std` -> `["serde?/std"]`).
+pub type CrateFeatures = BTreeMap<String, Vec<String>>;
+
+/// Admonition kinds of doc comment sections, keyed by section heading (e.g. "Safety").
+///
+/// The kind `heading` keeps the section as a regular (demoted) heading.
+pub type DocAdmonitions = BTreeMap<String, String>;
+
+/// Valid values of a [`DocAdmonitions`] entry
+pub const DOC_ADMONITION_KINDS: &[&str] = &[
+  "note", "tip", "info", "warning", "danger", "caution", "heading",
+];
+
+/// Doc comment sections rendered as admonitions unless configured otherwise
+pub const DEFAULT_DOC_ADMONITIONS: &[(&str, &str)] = &[
+  ("Safety", "danger"),
+  ("Panics", "warning"),
+  ("Errors", "info"),
+];
+
+/// Check that all doc admonition kinds are valid.
+pub fn validate_doc_admonitions(admonitions: &DocAdmonitions) -> Result<()> {
+  for (heading, kind) in admonitions {
+    if !DOC_ADMONITION_KINDS.contains(&kind.as_str()) {
+      return Err(Error::InvalidOptions(format!(
+        "Invalid admonition kind '{}' for '{}' (expected one of: {})",
+        kind,
+        heading,
+        DOC_ADMONITION_KINDS.join(", ")
+      )));
+    }
+  }
+  Ok(())
+}
+
+/// Sitemap hints emitted as `sitemap` front matter on generated pages.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SitemapOptions {
+  /// Default change frequency (e.g. "weekly")
+  pub changefreq: Option<String>,
+  /// Default priority between 0.0 and 1.0
+  pub priority: Option<f64>,
+  /// Overrides keyed by page kind: "crate", "module", "struct", "enum", "trait", "fn", ...
+  pub kinds: BTreeMap<String, SitemapEntry>,
+}
+
+/// Sitemap hints for one page kind.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SitemapEntry {
+  /// Change frequency (e.g. "monthly")
+  pub changefreq: Option<String>,
+  /// Priority between 0.0 and 1.0
+  pub priority: Option<f64>,
+}
+
+impl SitemapOptions {
+  /// Fill every unset field of `self` with the value from `fallback`.
+  pub fn or(self, fallback: &SitemapOptions) -> SitemapOptions {
+    let mut kinds = fallback.kinds.clone();
+    kinds.extend(self.kinds);
+    SitemapOptions {
+      changefreq: self.changefreq.or_else(|| fallback.changefreq.clone()),
+      priority: self.priority.or(fallback.priority),
+      kinds,
+    }
+  }
+
+  /// Check that all change frequencies and priorities are valid sitemap values.
+  pub fn validate(&self) -> Result<()> {
+    let entries = std::iter::once(("default", self.changefreq.as_deref(), self.priority)).chain(
+      self
+        .kinds
+        .iter()
+        .map(|(kind, entry)| (kind.as_str(), entry.changefreq.as_deref(), entry.priority)),
+    );
+
+    for (kind, changefreq, priority) in entries {
+      if let Some(changefreq) = changefreq {
+        if !SITEMAP_CHANGEFREQS.contains(&changefreq) {
+          return Err(Error::InvalidOptions(format!(
+            "Invalid sitemap changefreq '{}' for {} (expected one of: {})",
+            changefreq,
+            kind,
+            SITEMAP_CHANGEFREQS.join(", ")
+          )));
+        }
+      }
+      if let Some(priority) = priority {
+        if !(0.0..=1.0).contains(&priority) {
+          return Err(Error::InvalidOptions(format!(
+            "Invalid sitemap priority {} for {} (expected a value between 0.0 and 1.0)",
+            priority, kind
+          )));
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Front matter lines for a page of the given kind (empty if nothing applies).
+  fn frontmatter(&self, kind: &str) -> String {
+    let entry = self.kinds.get(kind);
+    let changefreq = entry
+      .and_then(|e| e.changefreq.as_deref())
+      .or(self.changefreq.as_deref());
+    let priority = entry.and_then(|e| e.priority).or(self.priority);
+
+    if changefreq.is_none() && priority.is_none() {
+      return String::new();
+    }
+
+    let mut output = String::from("sitemap:\n");
+    if let Some(changefreq) = changefreq {
+      output.push_str(&format!("  changefreq: {}\n", changefreq));
+    }
+    if let Some(priority) = priority {
+      output.push_str(&format!("  priority: {}\n", priority));
+    }
+    output
+  }
+}
+
+impl RenderContext {
+  /// Base path of generated links, including the docs version and namespace if set.
+  pub fn link_base_path(&self) -> String {
+    let Some(version) = &self.docs_version else {
+      return self.doc_base_path();
+    };
+
+    // Versioned docs are served below the docs route: /docs/api -> /docs/1.2/api
+    if let Some(route) = &self.route_base_path
+      && let Some(rest) = path_below_route(&self.base_path, route)
+    {
+      let base_path = match rest {
+        "" => format!("{}/{}", route, version),
+        rest => format!("{}/{}/{}", route, version, rest),
+      };
+      return self.with_namespace(&base_path);
+    }
+    let (root, path) = match self.base_path.strip_prefix('/') {
+      Some(path) => ("/", path.trim_end_matches('/')),
+      None => ("", self.base_path.trim_end_matches('/')),
+    };
+    let base_path = match path.split_once('/') {
+      Some((route, rest)) => format!("{}{}/{}/{}", root, route, version, rest),
+      None if path.is_empty() => format!("{}{}", root, version),
+      None => format!("{}{}/{}", root, path, version),
+    };
+    self.with_namespace(&base_path)
+  }
+
+  /// Base path of doc IDs: the link base path without the docs version, since
+  /// versioned doc IDs are relative to their `versioned_docs/version-*` directory.
+  fn doc_base_path(&self) -> String {
+    self.with_namespace(&self.base_path)
+  }
+
+  /// Prefix of doc IDs in sidebars: the doc base path relative to the docs folder.
+  fn doc_id_base(&self) -> String {
+    let base_path = self.doc_base_path();
+    if let Some(rest) = self.below_route(&base_path) {
+      return rest.trim_end_matches('/').to_string();
+    }
+    let base_path = base_path
+      .strip_prefix('/')
+      .unwrap_or(&base_path)
+      .trim_end_matches('/');
+    match base_path.strip_prefix("docs") {
+      Some("") => String::new(),
+      Some(rest) if rest.starts_with('/') => rest[1..].to_string(),
+      _ => base_path.to_string(),
+    }
+  }
+
+  /// This context with a fresh [`ConversionState`], for converting a crate.
+  pub(crate) fn for_conversion(&self) -> RenderContext {
+    RenderContext {
+      conversion: ConversionState::default(),
+      ..self.clone()
+    }
+  }
+
+  /// Resolver of the link targets of items, configured like this conversion.
+  pub fn link_resolver(&self) -> LinkResolver<'_> {
+    LinkResolver::new(self.link_base_path())
+      .workspace_crates(&self.workspace_crates)
+      .max_depth(self.max_depth)
+      .extern_urls(self.extern_urls.as_ref())
+      .extern_versions(self.extern_versions.as_ref())
+      .module_paths(&self.conversion.module_paths)
+  }
+
+  /// Separator between an item name and its doc summary, with surrounding spaces
+  fn summary_separator(&self) -> String {
+    format!(" {} ", self.summary_separator.as_deref().unwrap_or("—"))
+  }
+
+  /// Visibility indicator of an item (e.g. " 🔒" for restricted visibility)
+  fn visibility_indicator(&self, item: &Item) -> String {
+    match (&item.visibility, self.restricted_marker.as_deref()) {
+      (Visibility::Public, _) | (_, Some("")) => String::new(),
+      (_, marker) => format!(" {}", marker.unwrap_or("🔒")),
+    }
+  }
+
+  /// "Since" badge of the item at `path`, if the since map has a version for it
+  fn since_badge(&self, path: &str) -> Option<String> {
+    let version = self.since_versions.as_ref()?.get(path)?;
+    let version = version.strip_prefix('v').unwrap_or(version);
+    Some(format!(
+      "<span className=\"rust-since\">Since v{}</span>",
+      version
+    ))
+  }
+
+  /// Sidebar doc ID of a page, given its path below the docs folder of the
+  /// converted crates (e.g. `my_crate/struct.Foo`)
+  fn doc_id(&self, path: &str) -> String {
+    let path = path.trim_matches('/');
+    match self.doc_id_base().as_str() {
+      "" => path.to_string(),
+      base => format!("{}/{}", base, path),
+    }
+  }
 
-thread_local! {
-    /// Thread-local storage for the base path to use in generated links
-    static BASE_PATH: RefCell<String> = const { RefCell::new(String::new()) };
-    /// Thread-local storage for workspace crate names
-    static WORKSPACE_CRATES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
-    /// Thread-local storage for the sidebar root link URL
-    static SIDEBAR_ROOT_LINK: RefCell<Option<String>> = const { RefCell::new(None) };
+  /// Sidebar doc ID of a module's index page (e.g. `my_crate/mod_a/index`)
+  fn module_doc_id(&self, module_path: &str) -> String {
+    self.doc_id(&format!("{}/index", module_path.trim_end_matches('/')))
+  }
+
+  /// Part of a path below the route of the docs plugin, if `route_base_path` is set.
+  fn below_route<'a>(&self, path: &'a str) -> Option<&'a str> {
+    path_below_route(path, self.route_base_path.as_deref()?)
+  }
+
+  fn with_namespace(&self, base_path: &str) -> String {
+    match &self.namespace {
+      Some(namespace) => format!("{}/{}", base_path.trim_end_matches('/'), namespace),
+      None => base_path.to_string(),
+    }
+  }
+
+  /// Key of a sidebar of a module (e.g. `my_crate::io`), as written to the
+  /// sidebars file and the `displayed_sidebar` front matter of pages.
+  pub fn sidebar_key(&self, module_path: &str, kind: SidebarKind) -> String {
+    crate::sidebar::sidebar_key(
+      &self.doc_id(&module_path.replace("::", "/")),
+      kind,
+      self.docs_version.as_deref(),
+      self.locale.as_deref(),
+    )
+  }
+
+  /// Crate label shown in sidebars ("name (namespace)" when namespaced).
+  fn crate_label(&self, crate_name: &str) -> String {
+    match &self.namespace {
+      Some(namespace) => format!("{} ({})", crate_name, namespace),
+      None => crate_name.to_string(),
+    }
+  }
+
+  /// Module path as shown in breadcrumbs and sidebar labels, without `strip_prefix`.
+  ///
+  /// Links and doc IDs keep using the canonical path.
+  fn display_path<'a>(&self, path: &'a str) -> &'a str {
+    let Some(prefix) = self.strip_prefix.as_deref() else {
+      return path;
+    };
+    if path == prefix {
+      // The prefix module itself is shown by its own name
+      return path.rsplit("::").next().unwrap_or(path);
+    }
+    path
+      .strip_prefix(prefix)
+      .and_then(|rest| rest.strip_prefix("::"))
+      .unwrap_or(path)
+  }
+
+  /// `customProps` carrying the sidebar icon of an item kind, if one is configured.
+  fn sidebar_icon_props(&self, kind: &str) -> Option<serde_json::Value> {
+    let icon = self.sidebar_icons.as_ref()?.get(kind)?;
+    Some(serde_json::json!({ "icon": icon }))
+  }
+
+  /// Admonition kind of a doc comment section, if it should be rendered as one.
+  fn doc_admonition(&self, heading: &str) -> Option<&str> {
+    let configured = self.doc_admonitions.iter().flatten();
+    let kind = configured
+      .map(|(heading, kind)| (heading.as_str(), kind.as_str()))
+      .chain(DEFAULT_DOC_ADMONITIONS.iter().copied())
+      .find(|(section, _)| section.eq_ignore_ascii_case(heading))
+      .map(|(_, kind)| kind)?;
+    (kind != "heading").then_some(kind)
+  }
+
+  /// Whether an item gets a page: always, unless only changed items are previewed.
+  fn is_previewed(&self, item: &Item, crate_data: &Crate) -> bool {
+    self
+      .changed_files
+      .as_ref()
+      .is_none_or(|changed| crate::preview::item_changed(item, crate_data, changed))
+  }
+
+  /// Sitemap front matter lines for a page of the given kind.
+  /// `displayed_sidebar` front matter line of a page shown with a sidebar of a
+  /// module (none with category files, whose pages use the autogenerated
+  /// sidebar, or without sidebars)
+  pub fn displayed_sidebar(&self, module_path: &str, kind: SidebarKind) -> String {
+    if self.category_files || self.no_sidebar {
+      String::new()
+    } else {
+      format!(
+        "displayed_sidebar: '{}'\n",
+        self.sidebar_key(module_path, kind)
+      )
+    }
+  }
+
+  /// SEO front matter of a page: the first paragraph of its docs as
+  /// `description`, `keywords` and the social card `image`.
+  fn seo_frontmatter(&self, docs: Option<&str>, keywords: &[&str]) -> String {
+    let mut output = String::new();
+    if let Some(description) = docs.and_then(doc_description) {
+      output.push_str(&format!("description: {}\n", yaml_string(&description)));
+    }
+    let mut unique: Vec<&str> = Vec::new();
+    for keyword in keywords {
+      if !keyword.is_empty() && !unique.contains(keyword) {
+        unique.push(keyword);
+      }
+    }
+    if !unique.is_empty() {
+      let keywords: Vec<String> = unique.iter().map(|keyword| yaml_string(keyword)).collect();
+      output.push_str(&format!("keywords: [{}]\n", keywords.join(", ")));
+    }
+    if let Some(image) = &self.social_card {
+      output.push_str(&format!("image: {}\n", yaml_string(image)));
+    }
+    output
+  }
+
+  /// Lay out a page with its template. The last line of the template ends with
+  /// the content, which has one trailing newline less for it.
+  fn render_page(
+    &self,
+    template: PageTemplate,
+    title: &str,
+    frontmatter: &str,
+    breadcrumb: &str,
+    content: &str,
+  ) -> String {
+    let content = content.strip_suffix('\n').unwrap_or(content);
+    self.templates.render(
+      template,
+      &[
+        ("title", title),
+        ("frontmatter", frontmatter),
+        ("breadcrumb", breadcrumb),
+        ("content", content),
+      ],
+    )
+  }
+
+  fn sitemap_frontmatter(&self, kind: &str) -> String {
+    self
+      .sitemap
+      .as_ref()
+      .map(|sitemap| sitemap.frontmatter(kind))
+      .unwrap_or_default()
+  }
 }
 
 /// Represents the multi-file markdown output
+#[derive(Debug, Clone, Serialize)]
 pub struct MarkdownOutput {
   /// Crate name
   pub crate_name: String,
   /// Map of relative file path -> content
-  pub files: HashMap<String, String>,
-  /// Sidebar configuration (optional, for Docusaurus)
-  pub sidebar: Option<String>,
-}
-
-/// Represents a sidebar item for Docusaurus
-#[derive(Debug, Clone)]
-enum SidebarItem {
-  /// A document reference with optional label
-  Doc {
-    id: String,
-    label: Option<String>,
-    custom_props: Option<String>, // Can be either className or customProps JSON
-  },
-  /// A link item (for dynamic sidebars)
-  Link {
-    href: String,
-    label: String,
-    custom_props: Option<String>,
-  },
-  /// A category with sub-items
-  Category {
-    label: String,
-    items: Vec<SidebarItem>,
-    collapsed: bool,
-    link: Option<String>, // Optional link to make category clickable
-  },
+  pub files: BTreeMap<String, String>,
+  /// Sidebar configuration (optional, for Docusaurus), rendered when written
+  pub sidebar: Option<SidebarMap>,
+  /// Docusaurus major version the sidebar is rendered for
+  pub docusaurus_major: DocusaurusMajor,
+  /// Items that failed to render and got a placeholder page (with `keep_going`),
+  /// and a summary of `unresolved_links`
+  pub warnings: Vec<String>,
+  /// Link targets that could not be resolved, with the number of links to each
+  pub unresolved_links: BTreeMap<String, usize>,
+}
+
+impl MarkdownOutput {
+  /// Number of warnings, counting every unresolved link target instead of their summary.
+  pub fn warning_count(&self) -> usize {
+    match self.unresolved_links.len() {
+      0 => self.warnings.len(),
+      targets => self.warnings.len().saturating_sub(1) + targets,
+    }
+  }
+}
+
+/// Converts rustdoc crates to multi-file markdown with a fixed set of settings.
+///
+/// All settings are owned by the converter, so independent conversions can run
+/// concurrently on different threads.
+///
+/// ```no_run
+/// use cargo_doc_docusaurus::converter::{Converter, RenderContext};
+///
+/// # fn run(crate_data: &rustdoc_types::Crate) -> cargo_doc_docusaurus::Result<()> {
+/// let converter = Converter::new(RenderContext {
+///   base_path: "/docs/api".to_string(),
+///   ..Default::default()
+/// })
+/// .include_private(true);
+/// let output = converter.convert(crate_data)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Converter {
+  context: RenderContext,
+  include_private: bool,
+  sidebarconfig_collapsed: bool,
+  inlined_crates: Vec<Crate>,
+}
+
+impl Converter {
+  /// Create a converter rendering with the given settings.
+  pub fn new(context: RenderContext) -> Self {
+    Self {
+      context,
+      ..Default::default()
+    }
+  }
+
+  /// Include private items in the output.
+  pub fn include_private(mut self, include_private: bool) -> Self {
+    self.include_private = include_private;
+    self
+  }
+
+  /// Collapse sidebar categories by default.
+  pub fn sidebarconfig_collapsed(mut self, collapsed: bool) -> Self {
+    self.sidebarconfig_collapsed = collapsed;
+    self
+  }
+
+  /// Document items re-exported from these crates on the re-exporting crate's
+  /// pages, instead of only listing the `pub use`.
+  pub fn inline_reexports_from(mut self, crates: Vec<Crate>) -> Self {
+    self.inlined_crates = crates;
+    self
+  }
+
+  /// Render settings used by this converter.
+  pub fn context(&self) -> &RenderContext {
+    &self.context
+  }
+
+  /// Normalized model of a crate, with the URLs of its pages in the converted site.
+  pub fn model(&self, crate_data: &Crate) -> crate::model::CrateModel {
+    crate::model::crate_model(&self.context, crate_data, self.include_private)
+  }
+
+  /// Convert a rustdoc Crate to multi-file markdown format.
+  pub fn convert(&self, crate_data: &Crate) -> Result<MarkdownOutput> {
+    convert_crate(
+      &self.context,
+      crate_data,
+      self.include_private,
+      self.sidebarconfig_collapsed,
+      &self.inlined_crates,
+    )
+  }
+
+  /// Convert a rustdoc Crate to markdown format (legacy single-file).
+  pub fn convert_to_markdown(&self, crate_data: &Crate) -> Result<String> {
+    single_file_markdown(&self.context, crate_data, self.include_private)
+  }
 }
 
 /// Convert a rustdoc Crate to multi-file markdown format.
+///
+/// Further settings (e.g. the module depth limit) are set on the
+/// [`RenderContext`] of a [`Converter`].
 pub fn convert_to_markdown_multifile(
   crate_data: &Crate,
   include_private: bool,
@@ -57,15 +898,86 @@ pub fn convert_to_markdown_multifile(
   sidebarconfig_collapsed: bool,
   sidebar_root_link: Option<&str>,
 ) -> Result<MarkdownOutput> {
-  // Set the base path, workspace crates, and sidebar root link for this conversion in thread-local storage
-  BASE_PATH.with(|bp| *bp.borrow_mut() = base_path.to_string());
-  WORKSPACE_CRATES.with(|wc| *wc.borrow_mut() = workspace_crates.to_vec());
-  SIDEBAR_ROOT_LINK.with(|srl| *srl.borrow_mut() = sidebar_root_link.map(|s| s.to_string()));
+  Converter::new(RenderContext {
+    base_path: base_path.to_string(),
+    workspace_crates: workspace_crates.to_vec(),
+    sidebar_root_link: sidebar_root_link.map(|s| s.to_string()),
+    ..Default::default()
+  })
+  .include_private(include_private)
+  .sidebarconfig_collapsed(sidebarconfig_collapsed)
+  .convert(crate_data)
+}
+
+/// Convert a rustdoc Crate to multi-file markdown format using the given render settings.
+pub fn convert_to_markdown_multifile_with_context(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  include_private: bool,
+  sidebarconfig_collapsed: bool,
+) -> Result<MarkdownOutput> {
+  convert_crate(
+    ctx,
+    crate_data,
+    include_private,
+    sidebarconfig_collapsed,
+    &[],
+  )
+}
+
+fn convert_crate(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  include_private: bool,
+  sidebarconfig_collapsed: bool,
+  inlined_crates: &[Crate],
+) -> Result<MarkdownOutput> {
+  let ctx = &ctx.for_conversion();
+  let mut output = convert_crate_files(
+    ctx,
+    crate_data,
+    include_private,
+    sidebarconfig_collapsed,
+    inlined_crates,
+  )?;
+  output.unresolved_links = ctx.conversion.unresolved_links();
+  output
+    .warnings
+    .extend(unresolved_links_warning(&output.unresolved_links));
+  Ok(output)
+}
+
+/// Summary of the link targets that could not be resolved, one line per path.
+fn unresolved_links_warning(unresolved: &BTreeMap<String, usize>) -> Option<String> {
+  if unresolved.is_empty() {
+    return None;
+  }
+  let mut warning = format!(
+    "{} link target{} could not be resolved and rendered without a link:",
+    unresolved.len(),
+    if unresolved.len() == 1 { "" } else { "s" }
+  );
+  for (path, count) in unresolved {
+    warning.push_str(&format!("\n  - `{}` ({}×)", path, count));
+  }
+  Some(warning)
+}
+
+fn convert_crate_files(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  include_private: bool,
+  sidebarconfig_collapsed: bool,
+  inlined_crates: &[Crate],
+) -> Result<MarkdownOutput> {
+  if ctx.layout == OutputLayout::SingleFile {
+    return convert_single_file(ctx, crate_data, include_private);
+  }
 
   let root_item = crate_data
     .index
     .get(&crate_data.root)
-    .ok_or_else(|| anyhow::anyhow!("Root item not found in index"))?;
+    .ok_or(Error::MissingRootItem)?;
 
   let crate_name = root_item.name.as_deref().unwrap_or("unknown");
 
@@ -78,7 +990,7 @@ pub fn convert_to_markdown_multifile(
   // Build a map of re-exported modules (module_path -> list of re-exported submodule paths)
   let reexported_modules = build_reexported_modules(crate_data, &item_paths, include_private);
 
-  let mut files = HashMap::new();
+  let mut files = BTreeMap::new();
 
   // Check if we have items in the root crate
   let root_module_key = crate_name.to_string();
@@ -110,11 +1022,18 @@ pub fn convert_to_markdown_multifile(
     modules.entry(parent.clone()).or_default();
   }
 
+  // Drop modules below the depth limit; they are summarized on their parent's page
+  let hidden_modules = match ctx.max_depth {
+    Some(max_depth) => collapse_deep_modules(&mut modules, max_depth),
+    None => BTreeMap::new(),
+  };
+
   // Generate index.md - either with crate overview or with root module content
-  if has_root_items {
+  let mut index_content = if has_root_items {
     // If there are items in the root module, combine crate overview with root content
     let root_items = &modules[&root_module_key];
-    let index_content = generate_combined_crate_and_root_content(
+    generate_combined_crate_and_root_content(
+      ctx,
       crate_name,
       root_item,
       crate_data,
@@ -122,29 +1041,114 @@ pub fn convert_to_markdown_multifile(
       root_items,
       &module_hierarchy,
       &reexported_modules,
-    );
-    files.insert("index.md".to_string(), index_content);
+      &hidden_modules,
+    )
   } else {
     // Just crate overview if no root items
-    let index_content = generate_crate_index(crate_name, root_item, &modules);
-    files.insert("index.md".to_string(), index_content);
+    generate_crate_index(ctx, crate_name, root_item, &modules)
+  };
+
+  // Optional cross-cutting index pages, linked from the crate index
+  let mut index_pages = Vec::new();
+  if ctx.error_index {
+    index_pages.push((
+      "error-index",
+      "Errors",
+      "Types implementing `std::error::Error`",
+      generate_error_index(ctx, crate_data, crate_name, include_private),
+    ));
+  }
+  if ctx.async_index {
+    index_pages.push((
+      "async-index",
+      "Async API",
+      "Async functions and types implementing `Future` or `Stream`",
+      generate_async_index(ctx, crate_data, crate_name, include_private),
+    ));
+  }
+  if let Some(features) = &ctx.crate_features {
+    index_pages.push((
+      "features",
+      "Feature Flags",
+      "Cargo features of the crate and what they enable",
+      generate_features_page(ctx, crate_name, features),
+    ));
   }
+  if ctx.feature_matrix {
+    index_pages.push((
+      "feature-matrix",
+      "Feature Matrix",
+      "Items enabled by each Cargo feature",
+      generate_feature_matrix(ctx, crate_data, crate_name, include_private),
+    ));
+  }
+  if ctx.glossary {
+    index_pages.push((
+      "glossary",
+      "Glossary",
+      "Terms defined in the documentation",
+      generate_glossary(ctx, crate_data, crate_name, include_private),
+    ));
+  }
+  if ctx.all_items {
+    index_pages.push((
+      "all",
+      "All Items",
+      "Every item of the crate, grouped by kind",
+      generate_all_items(ctx, crate_data, crate_name, &modules),
+    ));
+  }
+  if ctx.kind_indexes {
+    let mut items_by_kind = items_by_kind(ctx, crate_data, &modules);
+    for (kind, slug, title, description) in ITEM_KINDS {
+      let Some(entries) = items_by_kind.get_mut(kind) else {
+        continue;
+      };
+      let mut content = index_page_header(ctx, crate_name, title, slug);
+      entries.sort();
+      for (_, entry) in entries.iter() {
+        content.push_str(entry);
+      }
+      index_pages.push((slug, title, description, content));
+    }
+  }
+  if !index_pages.is_empty() {
+    index_content.push_str("## Indexes\n\n");
+    for (slug, title, description, content) in index_pages {
+      index_content.push_str(&format!(
+        "<div><Link to=\"{}/{}/{}\" className=\"rust-mod\">{}</Link>{}{}</div>\n\n",
+        ctx.link_base_path(),
+        crate_name,
+        slug,
+        title,
+        ctx.summary_separator(),
+        description
+      ));
+      // Previews link to the production index pages
+      if ctx.changed_files.is_none() {
+        files.insert(format!("{}.{}", slug, ctx.extension.as_str()), content);
+      }
+    }
+  }
+
+  files.insert(format!("index.{}", ctx.extension.as_str()), index_content);
+
+  // Process modules in a stable order so the output is deterministic
+  let mut module_names: Vec<&String> = modules.keys().collect();
+  module_names.sort();
 
-  // Generate overview files and individual pages for each module
-  for (module_name, items) in &modules {
+  // Generate overview files for each module
+  for module_name in &module_names {
     // Skip the root module as it's already handled in index.md
-    if module_name == &root_module_key {
-      // Generate individual pages for root-level items
-      generate_individual_pages(
-        items,
-        "",
-        &mut files,
-        crate_data,
-        &item_paths,
-        crate_name,
-        crate_name,
-        include_private,
-      );
+    if *module_name == &root_module_key {
+      continue;
+    }
+
+    // Previews only include modules with changed items
+    if !modules[*module_name]
+      .iter()
+      .any(|(_, item)| ctx.is_previewed(item, crate_data))
+    {
       continue;
     }
 
@@ -155,62 +1159,315 @@ pub fn convert_to_markdown_multifile(
 
     // Always generate module overview (even if items are re-exported)
     // This ensures all modules are navigable
-    let overview_path = format!("{}/index.md", module_filename);
+    let overview_path = format!("{}/index.{}", module_filename, ctx.extension.as_str());
 
     // Generate module overview page (index-style)
     let module_overview = generate_module_overview(
+      ctx,
       module_name,
-      items, // Use direct items only, not all recursive items
+      &modules[*module_name], // Use direct items only, not all recursive items
       crate_data,
       &item_paths,
       crate_name,
       &module_hierarchy,
+      &hidden_modules,
     );
-    files.insert(overview_path.clone(), module_overview);
+    files.insert(overview_path, module_overview);
+  }
 
-    // Always generate individual pages for items
-    // All modules use subdirectories, so items go in the module directory
-    let item_prefix = if module_filename.is_empty() {
-      String::new()
-    } else {
-      format!("{}/", module_filename)
-    };
-    generate_individual_pages(
-      items,
-      &item_prefix,
-      &mut files,
+  // Always generate individual pages for items, in parallel across modules and items
+  // All modules use subdirectories, so items go in the module directory
+  let progress = crate::progress::bar(
+    module_names.iter().map(|name| modules[*name].len()).sum(),
+    "Rendering items",
+  );
+  let rendered_pages: Vec<(String, String, Option<String>)> = module_names
+    .par_iter()
+    .flat_map(|module_name| {
+      let item_prefix = if *module_name == &root_module_key {
+        String::new()
+      } else {
+        let module_filename = module_name
+          .strip_prefix(&format!("{}::", crate_name))
+          .unwrap_or(module_name)
+          .replace("::", "/");
+        format!("{}/", module_filename)
+      };
+      generate_individual_pages(
+        ctx,
+        &modules[*module_name],
+        &modules,
+        &item_prefix,
+        crate_data,
+        &item_paths,
+        crate_name,
+        module_name,
+        include_private,
+        &progress,
+      )
+    })
+    .collect();
+  progress.finish_and_clear();
+  let mut warnings = Vec::new();
+  let mut item_pages = Vec::with_capacity(rendered_pages.len());
+  for (path, content, failure) in rendered_pages {
+    if let Some(message) = failure {
+      if !ctx.keep_going {
+        return Err(Error::Render { path, message });
+      }
+      warnings.push(format!("Failed to render {}: {}", path, message));
+    }
+    item_pages.push((path, content));
+  }
+  if !inlined_crates.is_empty() {
+    inline_external_reexports(
+      ctx,
       crate_data,
       &item_paths,
-      crate_name,
-      module_name,
+      inlined_crates,
       include_private,
+      &mut files,
+      &mut item_pages,
+    );
+  }
+  if ctx.json_schema_assets {
+    for (path, content) in item_pages {
+      files.extend(extract_json_schema_assets(&path, &content));
+    }
+  } else {
+    files.extend(item_pages);
+  }
+
+  // Item pages become sections of their module page
+  let merged_pages = if ctx.layout == OutputLayout::PerModule {
+    let extension = ctx.extension.as_str();
+    let merged_pages = crate::layout::merge_item_pages(&mut files, extension);
+    let page_extension = format!(".{}", extension);
+    for (path, content) in files.iter_mut() {
+      if path.ends_with(&page_extension) {
+        *content = crate::layout::link_to_sections(content);
+      }
+    }
+    merged_pages
+  } else {
+    BTreeSet::new()
+  };
+
+  // Links to pages left out of a preview point to the production site
+  let doc_id_prefix = format!("{}/", ctx.doc_id(crate_name));
+  let link_base_path = ctx.link_base_path();
+  let preview_ctx = ctx
+    .production_url
+    .as_deref()
+    .filter(|_| ctx.changed_files.is_some())
+    .map(|production_url| crate::preview::PreviewLinkContext {
+      production_url,
+      link_base_path: &link_base_path,
+      doc_id_prefix: &doc_id_prefix,
+      crate_name,
+      extension: ctx.extension.as_str(),
+    });
+  if let Some(preview_ctx) = &preview_ctx {
+    files = files
+      .iter()
+      .map(|(path, content)| {
+        let content = crate::preview::link_to_production(content, path, &files, preview_ctx);
+        (path.clone(), content)
+      })
+      .collect();
+  }
+
+  // Plain Markdown has no components and no Docusaurus sidebar
+  if ctx.flavor == OutputFlavor::PlainMarkdown {
+    let link_base_path = ctx.link_base_path();
+    let link_ctx = crate::plain::PlainLinkContext {
+      link_base_path: &link_base_path,
+      extension: ctx.extension.as_str(),
+    };
+    let page_extension = format!(".{}", ctx.extension.as_str());
+    let files = files
+      .into_iter()
+      .map(|(path, content)| {
+        // Assets like JSON schema files are kept as they are
+        if !path.ends_with(&page_extension) {
+          return (path, content);
+        }
+        let page_path = format!("{}/{}", crate_name, path);
+        let content = crate::plain::to_plain_markdown(&content, &page_path, &link_ctx);
+        (path, content)
+      })
+      .collect();
+    return Ok(MarkdownOutput {
+      crate_name: crate_name.to_string(),
+      files,
+      sidebar: None,
+      docusaurus_major: ctx.docusaurus_major,
+      warnings,
+      unresolved_links: BTreeMap::new(),
+    });
+  }
+
+  if let Some(redirects) = &ctx.redirects {
+    let (redirects, unresolved) = client_redirects(ctx, redirects, crate_data, &item_paths);
+    warnings.extend(
+      unresolved
+        .into_iter()
+        .map(|path| format!("No item at `{}` to redirect to", path)),
     );
+    files.insert("redirects.json".to_string(), redirects);
+  }
+
+  let collapse = ctx
+    .sidebar_collapse
+    .unwrap_or(SidebarCollapse::all(sidebarconfig_collapsed));
+  if ctx.category_files {
+    let category_files = generate_category_files(ctx, crate_name, &files, collapse)?;
+    files.extend(category_files);
+    return Ok(MarkdownOutput {
+      crate_name: crate_name.to_string(),
+      files,
+      sidebar: None,
+      docusaurus_major: ctx.docusaurus_major,
+      warnings,
+      unresolved_links: BTreeMap::new(),
+    });
+  }
+
+  if ctx.no_sidebar {
+    return Ok(MarkdownOutput {
+      crate_name: crate_name.to_string(),
+      files,
+      sidebar: None,
+      docusaurus_major: ctx.docusaurus_major,
+      warnings,
+      unresolved_links: BTreeMap::new(),
+    });
   }
 
   // Generate sidebar structure with sidebars for each module
-  let sidebar = generate_all_sidebars(
-    crate_name,
-    &modules,
-    &item_paths,
-    crate_data,
-    sidebarconfig_collapsed,
-  );
+  let mut sidebar =
+    generate_all_sidebars(ctx, crate_name, &modules, &item_paths, crate_data, collapse);
+
+  if let Some(preview_ctx) = &preview_ctx {
+    crate::preview::link_sidebar_to_production(&mut sidebar, &files, preview_ctx);
+  }
+  if !merged_pages.is_empty() {
+    let layout_ctx = crate::layout::ModuleLayoutContext {
+      link_base_path: &link_base_path,
+      doc_id_prefix: &doc_id_prefix,
+      crate_name,
+      extension: ctx.extension.as_str(),
+    };
+    crate::layout::link_sidebar_to_sections(&mut sidebar, &merged_pages, &layout_ctx);
+  }
 
   Ok(MarkdownOutput {
     crate_name: crate_name.to_string(),
     files,
     sidebar: Some(sidebar),
+    docusaurus_major: ctx.docusaurus_major,
+    warnings,
+    unresolved_links: BTreeMap::new(),
+  })
+}
+
+/// Redirects from the old to the new URLs of renamed or moved items, as the
+/// `redirects` of the Docusaurus client-redirects plugin, and the new paths
+/// without an item of the crate.
+///
+/// Old URLs are built from the old path with the kind of the item at the new path.
+fn client_redirects(
+  ctx: &RenderContext,
+  redirects: &BTreeMap<String, String>,
+  crate_data: &Crate,
+  item_paths: &HashMap<Id, Vec<String>>,
+) -> (String, Vec<String>) {
+  let base = ctx.link_base_path();
+  let page_url = |path: &[&str], prefix: &str| match path.split_last() {
+    Some((name, module)) if !prefix.is_empty() => {
+      format!("{}/{}/{}{}", base, module.join("/"), prefix, name)
+    }
+    _ => format!("{}/{}/", base, path.join("/")),
+  };
+
+  let mut entries = Vec::new();
+  let mut unresolved = Vec::new();
+  for (old_path, new_path) in redirects {
+    let target = item_paths
+      .iter()
+      .filter(|(_, path)| path.join("::") == *new_path)
+      .find_map(|(id, _)| crate_data.index.get(id));
+    let Some(item) = target else {
+      unresolved.push(new_path.clone());
+      continue;
+    };
+    let prefix = get_item_prefix(item);
+    let mut to = page_url(&new_path.split("::").collect::<Vec<_>>(), prefix);
+    // Merged item pages are sections of their module page
+    if ctx.layout == OutputLayout::PerModule && !prefix.is_empty() {
+      to.truncate(to.rfind('/').map_or(0, |i| i + 1));
+    }
+    let from = page_url(&old_path.split("::").collect::<Vec<_>>(), prefix);
+    entries.push(serde_json::json!({ "from": from, "to": to }));
+  }
+  let json = serde_json::to_string_pretty(&entries).unwrap_or_default();
+  (format!("{}\n", json), unresolved)
+}
+
+/// Convert a rustdoc Crate to a single `index` page with the legacy single-file converter.
+fn convert_single_file(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  include_private: bool,
+) -> Result<MarkdownOutput> {
+  let markdown = single_file_markdown(ctx, crate_data, include_private)?;
+  let crate_name = crate_data
+    .index
+    .get(&crate_data.root)
+    .and_then(|root| root.name.as_deref())
+    .unwrap_or("unknown");
+
+  let page_path = format!("index.{}", ctx.extension.as_str());
+  let mut content = format!(
+    "---\ntitle: {}\n---\n\nimport RustCode from '@site/src/components/RustCode';\nimport Link from '@docusaurus/Link';\n\n{}",
+    crate_name, markdown
+  );
+  if ctx.flavor == OutputFlavor::PlainMarkdown {
+    let link_base_path = ctx.link_base_path();
+    let link_ctx = crate::plain::PlainLinkContext {
+      link_base_path: &link_base_path,
+      extension: ctx.extension.as_str(),
+    };
+    let crate_page_path = format!("{}/{}", crate_name, page_path);
+    content = crate::plain::to_plain_markdown(&content, &crate_page_path, &link_ctx);
+  }
+
+  Ok(MarkdownOutput {
+    crate_name: crate_name.to_string(),
+    files: BTreeMap::from([(page_path, content)]),
+    sidebar: None,
+    docusaurus_major: ctx.docusaurus_major,
+    warnings: Vec::new(),
+    unresolved_links: BTreeMap::new(),
   })
 }
 
 /// Convert a rustdoc Crate to markdown format (legacy single-file).
 pub fn convert_to_markdown(crate_data: &Crate, include_private: bool) -> Result<String> {
+  single_file_markdown(&RenderContext::default(), crate_data, include_private)
+}
+
+fn single_file_markdown(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  include_private: bool,
+) -> Result<String> {
   let mut output = String::new();
 
   let root_item = crate_data
     .index
     .get(&crate_data.root)
-    .ok_or_else(|| anyhow::anyhow!("Root item not found in index"))?;
+    .ok_or(Error::MissingRootItem)?;
 
   let crate_name = root_item.name.as_deref().unwrap_or("unknown");
   output.push_str(&format!("# {}\n\n", crate_name));
@@ -225,35 +1482,98 @@ pub fn convert_to_markdown(crate_data: &Crate, include_private: bool) -> Result<
   // Group items by module
   let modules = group_by_module(crate_data, &item_paths, include_private);
 
+  // Links to the items of the crate point to their heading on the page
+  let ids = HeadingIds::new(&modules);
+  let ctx = RenderContext {
+    heading_ids: ids.link_targets(&modules),
+    ..ctx.for_conversion()
+  };
+
   // Generate hierarchical ToC
   output.push_str("## Table of Contents\n\n");
-  output.push_str(&generate_toc(&modules, crate_name));
+  output.push_str(&generate_toc(&modules, crate_name, &ids));
   output.push_str("\n\n---\n\n");
 
   // Generate content organized by module
   output.push_str(&generate_content(
+    &ctx,
     &modules,
     crate_data,
     &item_paths,
     include_private,
+    &ids,
   ));
 
   Ok(output)
 }
 
 fn build_path_map(crate_data: &Crate) -> HashMap<Id, Vec<String>> {
-  crate_data
+  let mut paths: HashMap<Id, Vec<String>> = crate_data
     .paths
     .iter()
     .map(|(id, summary)| (*id, summary.path.clone()))
-    .collect()
+    .collect();
+
+  // Items inlined from other crates are documented in the module inlining them
+  for (id, path) in module_tree_paths(crate_data) {
+    let is_local = crate_data
+      .paths
+      .get(&id)
+      .is_some_and(|summary| summary.crate_id == 0);
+    if !is_local {
+      paths.insert(id, path);
+    }
+  }
+  paths
+}
+
+/// Paths of the items listed in the crate's modules, found by walking the module tree.
+///
+/// Unlike `paths` (and spans), this gives items inlined from other crates the
+/// path of the module that lists them.
+pub(crate) fn module_tree_paths(crate_data: &Crate) -> HashMap<Id, Vec<String>> {
+  let mut paths = HashMap::new();
+  let Some(crate_name) = crate_data
+    .index
+    .get(&crate_data.root)
+    .and_then(|root| root.name.clone())
+  else {
+    return paths;
+  };
+
+  let mut modules = vec![(crate_data.root, vec![crate_name])];
+  while let Some((module_id, module_path)) = modules.pop() {
+    let Some(ItemEnum::Module(module)) = crate_data.index.get(&module_id).map(|item| &item.inner)
+    else {
+      continue;
+    };
+    for id in &module.items {
+      let Some(item) = crate_data.index.get(id) else {
+        continue;
+      };
+      let Some(name) = &item.name else {
+        continue;
+      };
+      if matches!(item.inner, ItemEnum::Use(_)) || paths.contains_key(id) {
+        continue;
+      }
+
+      let mut path = module_path.clone();
+      path.push(name.clone());
+      if matches!(item.inner, ItemEnum::Module(_)) {
+        modules.push((*id, path.clone()));
+      }
+      paths.insert(*id, path);
+    }
+  }
+  paths
 }
 
 fn build_module_hierarchy(
-  modules: &HashMap<String, Vec<(Id, Item)>>,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
   crate_name: &str,
-) -> HashMap<String, Vec<String>> {
-  let mut hierarchy: HashMap<String, Vec<String>> = HashMap::new();
+) -> BTreeMap<String, Vec<String>> {
+  let mut hierarchy: BTreeMap<String, Vec<String>> = BTreeMap::new();
 
   for module_name in modules.keys() {
     // Skip the root crate module itself
@@ -309,14 +1629,58 @@ fn build_module_hierarchy(
   hierarchy
 }
 
+/// Depth of a module below the crate root (`my_crate` is 0, `my_crate::a::b` is 2)
+fn module_depth(module_path: &str) -> usize {
+  module_path.matches("::").count()
+}
+
+/// Remove modules deeper than `max_depth` from `modules`.
+/// Returns: topmost hidden module path -> number of items hidden in it (recursively)
+fn collapse_deep_modules(
+  modules: &mut BTreeMap<String, Vec<(Id, &Item)>>,
+  max_depth: usize,
+) -> BTreeMap<String, usize> {
+  let mut hidden: BTreeMap<String, usize> = BTreeMap::new();
+
+  modules.retain(|module_path, items| {
+    if module_depth(module_path) <= max_depth {
+      return true;
+    }
+
+    // Attribute the items to the first hidden ancestor (a direct child of a visible module)
+    let hidden_root = module_path
+      .split("::")
+      .take(max_depth + 2)
+      .collect::<Vec<_>>()
+      .join("::");
+    let item_count = items
+      .iter()
+      .filter(|(_, item)| !matches!(&item.inner, ItemEnum::Module(_) | ItemEnum::Use(_)))
+      .count();
+    *hidden.entry(hidden_root).or_default() += item_count;
+    false
+  });
+
+  hidden
+}
+
+/// Note shown next to modules that are not rendered because of the depth limit
+fn format_hidden_items_note(count: usize) -> String {
+  if count == 1 {
+    "<em>(1 item not shown)</em>".to_string()
+  } else {
+    format!("<em>({} items not shown)</em>", count)
+  }
+}
+
 /// Build a map of re-exported modules
 /// Returns: parent_module_path -> list of (child_module_name, child_module_full_path)
 fn build_reexported_modules(
   crate_data: &Crate,
   item_paths: &HashMap<Id, Vec<String>>,
   include_private: bool,
-) -> HashMap<String, Vec<(String, String)>> {
-  let mut reexports: HashMap<String, Vec<(String, String)>> = HashMap::new();
+) -> BTreeMap<String, Vec<(String, String)>> {
+  let mut reexports: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
 
   // Iterate through all modules to find their Use items
   for (module_id, module_item) in &crate_data.index {
@@ -398,13 +1762,15 @@ fn build_reexported_modules(
   reexports
 }
 
-/// Check if all items in a module are re-exported in its parent module
-fn group_by_module(
-  crate_data: &Crate,
+/// Group the formattable items of a crate by the path of their module.
+///
+/// Items are borrowed from `crate_data`, so large crates are not copied.
+fn group_by_module<'a>(
+  crate_data: &'a Crate,
   item_paths: &HashMap<Id, Vec<String>>,
   include_private: bool,
-) -> HashMap<String, Vec<(Id, Item)>> {
-  let mut modules: HashMap<String, Vec<(Id, Item)>> = HashMap::new();
+) -> BTreeMap<String, Vec<(Id, &'a Item)>> {
+  let mut modules: BTreeMap<String, Vec<(Id, &'a Item)>> = BTreeMap::new();
 
   for (id, item) in &crate_data.index {
     if id == &crate_data.root {
@@ -435,10 +1801,7 @@ fn group_by_module(
       continue; // Skip items without path info
     };
 
-    modules
-      .entry(module_path)
-      .or_default()
-      .push((*id, item.clone()));
+    modules.entry(module_path).or_default().push((*id, item));
   }
 
   // Process re-exports (ItemEnum::Use)
@@ -467,7 +1830,7 @@ fn group_by_module(
             modules
               .entry(module_path.clone())
               .or_default()
-              .push((*item_id, item.clone()));
+              .push((*item_id, item));
 
             // For glob re-exports (pub use module::*), also add all re-exported items
             // This matches rustdoc's behavior of generating duplicate documentation
@@ -522,7 +1885,7 @@ fn group_by_module(
                         modules
                           .entry(module_path.clone())
                           .or_default()
-                          .push((*imported_item_id, imported_item.clone()));
+                          .push((*imported_item_id, imported_item));
                       }
                     }
                   }
@@ -535,12 +1898,16 @@ fn group_by_module(
     }
   }
 
-  // Sort items within each module by name and remove duplicates
+  // Sort items within each module by name (then kind and id, so items sharing a
+  // name are listed in the same order on every run) and remove duplicates
   for items in modules.values_mut() {
     items.sort_by(|a, b| {
       let name_a = a.1.name.as_deref().unwrap_or("");
       let name_b = b.1.name.as_deref().unwrap_or("");
-      name_a.cmp(name_b)
+      name_a
+        .cmp(name_b)
+        .then_with(|| get_item_prefix(a.1).cmp(get_item_prefix(b.1)))
+        .then_with(|| a.0.0.cmp(&b.0.0))
     });
     // Remove duplicates (same ID)
     items.dedup_by(|a, b| a.0 == b.0);
@@ -559,18 +1926,56 @@ fn can_format_item(item: &Item) -> bool {
       | ItemEnum::Module(_)
       | ItemEnum::Constant { .. }
       | ItemEnum::TypeAlias(_)
+      | ItemEnum::Static(_)
+      | ItemEnum::ExternType
+      | ItemEnum::TraitAlias(_)
+      | ItemEnum::Macro(_)
+      | ItemEnum::ProcMacro(_)
   )
 }
 
+/// Public `extern crate` declarations of a module, listed with its re-exports
+fn format_extern_crates(module: &Item, crate_data: &Crate) -> String {
+  let ItemEnum::Module(module_data) = &module.inner else {
+    return String::new();
+  };
+
+  let mut output = String::new();
+  for item in module_data
+    .items
+    .iter()
+    .filter_map(|id| crate_data.index.get(id))
+    .filter(|item| is_public(item))
+  {
+    if let ItemEnum::ExternCrate { name, rename } = &item.inner {
+      let code = match rename {
+        Some(rename) => format!("pub extern crate {} as {};", name, rename),
+        None => format!("pub extern crate {};", name),
+      };
+      output.push_str(&rust_code(&code, &[], true));
+    }
+  }
+  output
+}
+
 /// Get the rustdoc-style prefix for an item type (e.g., "fn.", "struct.", etc.)
-fn get_item_prefix(item: &Item) -> &'static str {
+pub(crate) fn get_item_prefix(item: &Item) -> &'static str {
   match &item.inner {
     ItemEnum::Function(_) => "fn.",
     ItemEnum::Struct(_) => "struct.",
     ItemEnum::Enum(_) => "enum.",
     ItemEnum::Trait(_) => "trait.",
+    ItemEnum::TraitAlias(_) => "traitalias.",
     ItemEnum::Constant { .. } => "constant.",
     ItemEnum::TypeAlias(_) => "type.",
+    ItemEnum::Static(_) => "static.",
+    ItemEnum::ExternType => "foreigntype.",
+    ItemEnum::Macro(_) => "macro.",
+    ItemEnum::ProcMacro(proc_macro) => match proc_macro.kind {
+      rustdoc_types::MacroKind::Bang => "macro.",
+      rustdoc_types::MacroKind::Attr => "attr.",
+      rustdoc_types::MacroKind::Derive => "derive.",
+    },
     ItemEnum::Module(_) => "", // Modules don't get a prefix
     _ => "",
   }
@@ -582,34 +1987,126 @@ fn get_item_type_label(item: &Item) -> &'static str {
     ItemEnum::Struct(_) => "Struct",
     ItemEnum::Enum(_) => "Enum",
     ItemEnum::Trait(_) => "Trait",
+    ItemEnum::TraitAlias(_) => "Trait Alias",
     ItemEnum::Constant { .. } => "Constant",
     ItemEnum::TypeAlias(_) => "Type",
+    ItemEnum::Static(_) => "Static",
+    ItemEnum::ExternType => "Foreign Type",
+    ItemEnum::Macro(_) => "Macro",
+    ItemEnum::ProcMacro(proc_macro) => match proc_macro.kind {
+      rustdoc_types::MacroKind::Bang => "Macro",
+      rustdoc_types::MacroKind::Attr => "Attribute Macro",
+      rustdoc_types::MacroKind::Derive => "Derive Macro",
+    },
     ItemEnum::Module(_) => "Module",
     _ => "",
   }
 }
 
-fn generate_toc(modules: &HashMap<String, Vec<(Id, Item)>>, crate_name: &str) -> String {
-  let mut toc = String::new();
+/// Deterministic slugs for heading IDs (`{#id}`), unique within a page: repeated
+/// slugs get a `-1`, `-2`, ... suffix in the order they are requested.
+#[derive(Debug, Default)]
+struct Slugger {
+  seen: HashMap<String, usize>,
+}
 
-  // Sort modules alphabetically
-  let mut module_names: Vec<_> = modules.keys().collect();
-  module_names.sort();
+impl Slugger {
+  fn slug(&mut self, text: &str) -> String {
+    let base = slugify(text);
+    let mut slug = base.clone();
+    let mut count = self.seen.get(&base).copied().unwrap_or(0);
+    while self.seen.contains_key(&slug) {
+      count += 1;
+      slug = format!("{}-{}", base, count);
+    }
+    self.seen.insert(base, count);
+    self.seen.entry(slug.clone()).or_insert(0);
+    slug
+  }
+}
 
-  for module_name in module_names {
-    let items = &modules[module_name];
+/// Lowercase ASCII alphanumerics and underscores, other runs of characters
+/// (`::`, `<`, spaces, ...) becoming a single dash.
+fn slugify(text: &str) -> String {
+  let mut slug = String::with_capacity(text.len());
+  for c in text.chars() {
+    if c.is_ascii_alphanumeric() || c == '_' {
+      slug.push(c.to_ascii_lowercase());
+    } else if !slug.is_empty() && !slug.ends_with('-') {
+      slug.push('-');
+    }
+  }
+  let slug = slug.trim_end_matches('-');
+  if slug.is_empty() {
+    "section".to_string()
+  } else {
+    slug.to_string()
+  }
+}
+
+/// Heading IDs of the single-file layout, shared by the headings, the ToC and links.
+#[derive(Debug, Default)]
+struct HeadingIds {
+  modules: HashMap<String, String>,
+  items: HashMap<(String, Id), String>,
+}
+
+impl HeadingIds {
+  fn new(modules: &BTreeMap<String, Vec<(Id, &Item)>>) -> Self {
+    let mut slugger = Slugger::default();
+    let mut ids = Self::default();
+    for (module_name, items) in modules {
+      let module_id = slugger.slug(&format!("module {}", module_name));
+      ids.modules.insert(module_name.clone(), module_id);
+      for (id, item) in items {
+        if let Some(name) = &item.name {
+          // The kind keeps e.g. `fn foo` and `macro foo` apart
+          let kind = match get_item_prefix(item) {
+            "" => "mod",
+            prefix => prefix.trim_end_matches('.'),
+          };
+          let slug = slugger.slug(&format!("{} {}::{}", kind, module_name, name));
+          ids.items.insert((module_name.clone(), *id), slug);
+        }
+      }
+    }
+    ids
+  }
+
+  /// ID of the first heading of every item, the target of links to it.
+  fn link_targets(&self, modules: &BTreeMap<String, Vec<(Id, &Item)>>) -> HashMap<Id, String> {
+    let mut targets = HashMap::new();
+    for (module_name, items) in modules {
+      for (id, _) in items {
+        if let Some(slug) = self.items.get(&(module_name.clone(), *id)) {
+          targets.entry(*id).or_insert_with(|| slug.clone());
+        }
+      }
+    }
+    targets
+  }
+}
+
+fn generate_toc(
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
+  crate_name: &str,
+  ids: &HeadingIds,
+) -> String {
+  let mut toc = String::new();
 
+  for (module_name, items) in modules {
     // Get the last component of the module path for display
     let display_name = module_name
       .strip_prefix(&format!("{}::", crate_name))
       .unwrap_or(module_name);
 
-    toc.push_str(&format!("- **{}**\n", display_name));
+    toc.push_str(&format!(
+      "- **[{}](#{})**\n",
+      display_name, ids.modules[module_name]
+    ));
 
-    for (_id, item) in items {
-      if let Some(name) = &item.name {
-        let full_path = format!("{}::{}", module_name, name);
-        let anchor = full_path.to_lowercase().replace("::", "-");
+    for (id, item) in items {
+      if let (Some(name), Some(anchor)) = (&item.name, ids.items.get(&(module_name.clone(), *id))) {
         toc.push_str(&format!("  - [{}](#{})\n", name, anchor));
       }
     }
@@ -619,29 +2116,37 @@ fn generate_toc(modules: &HashMap<String, Vec<(Id, Item)>>, crate_name: &str) ->
 }
 
 fn generate_content(
-  modules: &HashMap<String, Vec<(Id, Item)>>,
+  ctx: &RenderContext,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
   crate_data: &Crate,
   item_paths: &HashMap<Id, Vec<String>>,
   include_private: bool,
+  ids: &HeadingIds,
 ) -> String {
   let mut output = String::new();
 
-  // Sort modules alphabetically
-  let mut module_names: Vec<_> = modules.keys().collect();
-  module_names.sort();
-
-  for module_name in module_names {
-    let items = &modules[module_name];
-
+  for (module_name, items) in modules {
     // Module header
-    output.push_str(&format!("# Module: `{}`\n\n", module_name));
+    output.push_str(&format!(
+      "# Module: `{}` {{#{}}}\n\n",
+      module_name, ids.modules[module_name]
+    ));
 
     // Generate content for each item in the module
     for (id, item) in items {
       if let Some(section) =
-        format_item_with_path(id, item, crate_data, item_paths, include_private)
+        format_item_with_path(ctx, id, item, crate_data, item_paths, include_private)
       {
-        output.push_str(&section);
+        if let (Some(name), Some(anchor)) = (&item.name, ids.items.get(&(module_name.clone(), *id)))
+        {
+          output.push_str(&format!("## {}::{} {{#{}}}\n\n", module_name, name, anchor));
+        }
+        // Items rendering their own heading get the one above instead
+        let section = match section.strip_prefix("## ") {
+          Some(rest) => rest.split_once("\n\n").map_or("", |(_, body)| body),
+          None => section.as_str(),
+        };
+        output.push_str(section);
         output.push_str("\n\n");
       }
     }
@@ -653,6 +2158,7 @@ fn generate_content(
 }
 
 fn format_item_with_path(
+  ctx: &RenderContext,
   item_id: &Id,
   item: &Item,
   crate_data: &Crate,
@@ -662,16 +2168,28 @@ fn format_item_with_path(
   let full_path = item_paths.get(item_id)?;
   let full_name = full_path.join("::");
 
-  let mut output = format_item(item_id, item, crate_data, include_private)?;
+  let render_default = || {
+    let mut output = format_item(ctx, item_id, item, crate_data, include_private)?;
 
-  // Replace the simple name header with the full path
-  if let Some(name) = &item.name {
-    let old_header = format!("## {}\n\n", name);
-    let new_header = format!("## {}\n\n", full_name);
-    output = output.replace(&old_header, &new_header);
-  }
+    // Replace the simple name header with the full path
+    if let Some(name) = &item.name {
+      let old_header = format!("## {}\n\n", name);
+      let new_header = format!("## {}\n\n", full_name);
+      output = output.replace(&old_header, &new_header);
+    }
+    Some(output)
+  };
 
-  Some(output)
+  match &ctx.item_renderer {
+    Some(renderer) => renderer.render(&crate::renderer::RenderItem {
+      id: item_id,
+      item,
+      path: full_path,
+      crate_data,
+      default: &render_default,
+    }),
+    None => render_default(),
+  }
 }
 
 fn is_public(item: &Item) -> bool {
@@ -689,11 +2207,13 @@ fn resolve_reexport_chain<'a>(
   const MAX_DEPTH: usize = 10;
 
   if depth > MAX_DEPTH {
+    log::debug!(target: "reexports", "Re-export chain of {:?} is too deep", item_id);
     return None;
   }
 
   if !visited.insert(*item_id) {
     // Circular reference detected
+    log::debug!(target: "reexports", "Circular re-export through {:?}", item_id);
     return None;
   }
 
@@ -701,27 +2221,22 @@ fn resolve_reexport_chain<'a>(
     if let ItemEnum::Use(import) = &item.inner {
       // This is a re-export, follow the chain
       if let Some(imported_id) = &import.id {
+        log::trace!(target: "reexports", "Following `use {}` to {:?}", import.source, imported_id);
         return resolve_reexport_chain(imported_id, crate_data, depth + 1, visited);
       }
     }
     // Not a re-export, return the item
     Some((*item_id, item))
   } else {
+    log::debug!(target: "reexports", "Re-exported item {:?} is not in the crate index", item_id);
     None
   }
 }
 
-/// Get the visibility indicator for an item (e.g., "🔒" for restricted visibility)
-fn get_visibility_indicator(item: &Item) -> &'static str {
-  match &item.visibility {
-    Visibility::Public => "",
-    _ => " 🔒", // Lock emoji for crate/restricted visibility
-  }
-}
-
 /// Format a struct definition with links extracted
 #[allow(clippy::single_char_add_str, clippy::manual_flatten)]
 fn format_struct_definition_with_links(
+  ctx: &RenderContext,
   name: &str,
   s: &rustdoc_types::Struct,
   item: &Item,
@@ -771,6 +2286,9 @@ fn format_struct_definition_with_links(
         code.push_str(" {");
         for field_id in fields {
           if let Some(field) = crate_data.index.get(field_id) {
+            if is_hidden_phantom_field(ctx, field) {
+              continue;
+            }
             if let Some(field_name) = &field.name {
               if let ItemEnum::StructField(ty) = &field.inner {
                 // Show field visibility based on include_private flag
@@ -788,7 +2306,7 @@ fn format_struct_definition_with_links(
                   }
                 };
 
-                let (field_type, links) = format_type_with_links(ty, crate_data, Some(item));
+                let (field_type, links) = format_type_with_links(ctx, ty, crate_data, Some(item));
                 all_links.extend(links);
                 code.push_str(&format!(
                   "\n    {}{}: {},",
@@ -815,7 +2333,7 @@ fn format_struct_definition_with_links(
                   rustdoc_types::Visibility::Restricted { .. } => "",
                   rustdoc_types::Visibility::Default => "",
                 };
-                let (field_type, links) = format_type_with_links(ty, crate_data, Some(item));
+                let (field_type, links) = format_type_with_links(ctx, ty, crate_data, Some(item));
                 all_links.extend(links);
                 if field_visibility.is_empty() {
                   visible_fields.push(field_type);
@@ -825,7 +2343,8 @@ fn format_struct_definition_with_links(
               } else {
                 match &field.visibility {
                   rustdoc_types::Visibility::Public => {
-                    let (field_type, links) = format_type_with_links(ty, crate_data, Some(item));
+                    let (field_type, links) =
+                      format_type_with_links(ctx, ty, crate_data, Some(item));
                     all_links.extend(links);
                     visible_fields.push(format!("pub {}", field_type));
                   }
@@ -850,6 +2369,7 @@ fn format_struct_definition_with_links(
 /// Format an enum definition with links extracted
 #[allow(clippy::manual_flatten)]
 fn format_enum_definition_with_links(
+  ctx: &RenderContext,
   name: &str,
   e: &rustdoc_types::Enum,
   item: &Item,
@@ -909,7 +2429,8 @@ fn format_enum_definition_with_links(
                 if let Some(id) = field_id {
                   if let Some(field_item) = crate_data.index.get(id) {
                     if let ItemEnum::StructField(ty) = &field_item.inner {
-                      let (type_str, links) = format_type_with_links(ty, crate_data, Some(item));
+                      let (type_str, links) =
+                        format_type_with_links(ctx, ty, crate_data, Some(item));
                       field_types.push(type_str);
                       all_links.extend(links);
                     }
@@ -930,7 +2451,8 @@ fn format_enum_definition_with_links(
                 if let Some(field_item) = crate_data.index.get(field_id) {
                   if let Some(field_name) = &field_item.name {
                     if let ItemEnum::StructField(ty) = &field_item.inner {
-                      let (type_str, links) = format_type_with_links(ty, crate_data, Some(item));
+                      let (type_str, links) =
+                        format_type_with_links(ctx, ty, crate_data, Some(item));
                       field_strs.push(format!("{}: {}", field_name, type_str));
                       all_links.extend(links);
                     }
@@ -941,6 +2463,10 @@ fn format_enum_definition_with_links(
               code.push_str(" }");
             }
           }
+
+          if let Some(discriminant) = &variant_inner.discriminant {
+            code.push_str(&format!(" = {}", discriminant.expr));
+          }
         }
         code.push(',');
       }
@@ -952,15 +2478,38 @@ fn format_enum_definition_with_links(
   (code, all_links)
 }
 
+/// The `extern "ABI" ` qualifier of a function, empty for the Rust ABI
+fn format_abi(abi: &rustdoc_types::Abi) -> String {
+  use rustdoc_types::Abi;
+  let (name, unwind) = match abi {
+    Abi::Rust => return String::new(),
+    Abi::C { unwind } => ("C", *unwind),
+    Abi::Cdecl { unwind } => ("cdecl", *unwind),
+    Abi::Stdcall { unwind } => ("stdcall", *unwind),
+    Abi::Fastcall { unwind } => ("fastcall", *unwind),
+    Abi::Aapcs { unwind } => ("aapcs", *unwind),
+    Abi::Win64 { unwind } => ("win64", *unwind),
+    Abi::SysV64 { unwind } => ("sysv64", *unwind),
+    Abi::System { unwind } => ("system", *unwind),
+    // Rustdoc keeps the quotes of ABIs it has no variant for
+    Abi::Other(name) => (name.trim_matches('"'), false),
+  };
+  format!(
+    "extern \"{}{}\" ",
+    name,
+    if unwind { "-unwind" } else { "" }
+  )
+}
+
 /// Format a function definition with links extracted
 #[allow(clippy::format_in_format_args)]
 fn format_function_definition_with_links(
+  ctx: &RenderContext,
   name: &str,
   f: &rustdoc_types::Function,
   item: &Item,
   crate_data: &Crate,
 ) -> (String, Vec<(String, String)>) {
-  let mut code = String::new();
   let mut all_links = Vec::new();
 
   // Collect generic parameters
@@ -973,63 +2522,34 @@ fn format_function_definition_with_links(
   // Collect function inputs
   let mut inputs = Vec::new();
   for (param_name, ty) in &f.sig.inputs {
-    let (type_str, links) = format_type_with_links(ty, crate_data, Some(item));
+    let (type_str, links) = format_type_with_links(ctx, ty, crate_data, Some(item));
     all_links.extend(links);
     inputs.push(format!("{}: {}", param_name, type_str));
   }
-
-  // Format on multiple lines if signature is too long (> 80 chars) or has many parameters (> 3)
-  let single_line = format!(
-    "fn {}{}",
-    if !generic_params.is_empty() {
-      format!("{}<{}>", name, generic_params.join(", "))
-    } else {
-      name.to_string()
-    },
-    format!("({})", inputs.join(", "))
-  );
-
-  if inputs.len() > 3 || single_line.len() > 80 {
-    // Multi-line format
-    code.push_str(&format!("fn {}", name));
-    if !generic_params.is_empty() {
-      code.push('<');
-      code.push_str(&generic_params.join(", "));
-      code.push('>');
-    }
-    code.push_str("(\n");
-    for (i, input) in inputs.iter().enumerate() {
-      code.push_str(&format!("    {}", input));
-      if i < inputs.len() - 1 {
-        code.push(',');
-      }
-      code.push('\n');
-    }
-    code.push(')');
-  } else {
-    // Single line format
-    code.push_str(&format!("fn {}", name));
-    if !generic_params.is_empty() {
-      code.push('<');
-      code.push_str(&generic_params.join(", "));
-      code.push('>');
-    }
-    code.push('(');
-    code.push_str(&inputs.join(", "));
-    code.push(')');
+  if f.sig.is_c_variadic {
+    inputs.push("...".to_string());
   }
+  let fn_keyword = format!("{}fn", format_abi(&f.header.abi));
 
-  if let Some(output_type) = &f.sig.output {
-    let (type_str, links) = format_type_with_links(output_type, crate_data, Some(item));
+  let head = if generic_params.is_empty() {
+    format!("{} {}", fn_keyword, name)
+  } else {
+    format!("{} {}<{}>", fn_keyword, name, generic_params.join(", "))
+  };
+  let output = f.sig.output.as_ref().map(|output_type| {
+    let (type_str, links) = format_type_with_links(ctx, output_type, crate_data, Some(item));
     all_links.extend(links);
-    code.push_str(&format!(" -> {}", type_str));
-  }
+    type_str
+  });
+  let where_bounds = format_generic_bounds(&f.generics, crate_data);
+  let code = layout_fn_signature(ctx, &head, &inputs, output.as_deref(), &where_bounds);
 
   (code, all_links)
 }
 
 #[allow(clippy::single_char_add_str)]
 fn format_item(
+  ctx: &RenderContext,
   item_id: &rustdoc_types::Id,
   item: &Item,
   crate_data: &Crate,
@@ -1042,15 +2562,11 @@ fn format_item(
     ItemEnum::Struct(s) => {
       // Format struct definition with links
       let (code, links) =
-        format_struct_definition_with_links(name, s, item, crate_data, include_private);
-      let links_json = format_links_as_json(&links);
-      output.push_str(&format!(
-        "<RustCode code={{`{}`}} links={{{}}} />\n\n",
-        code, links_json
-      ));
+        format_struct_definition_with_links(ctx, name, s, item, crate_data, include_private);
+      output.push_str(&rust_code(&code, &links, false));
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
       }
 
       let non_synthetic_params: Vec<_> = s
@@ -1075,20 +2591,14 @@ fn format_item(
         rustdoc_types::StructKind::Plain { fields, .. } => {
           if !fields.is_empty() {
             // Filter fields based on include_private flag
-            let visible_fields: Vec<_> = if include_private {
-              fields.iter().collect()
-            } else {
-              fields
-                .iter()
-                .filter(|&field_id| {
-                  if let Some(field) = crate_data.index.get(field_id) {
-                    is_public(field)
-                  } else {
-                    false
-                  }
+            let visible_fields: Vec<_> = fields
+              .iter()
+              .filter(|&field_id| {
+                crate_data.index.get(field_id).is_some_and(|field| {
+                  (include_private || is_public(field)) && !is_hidden_phantom_field(ctx, field)
                 })
-                .collect()
-            };
+              })
+              .collect();
 
             if !visible_fields.is_empty() {
               output.push_str("### Fields\n\n");
@@ -1096,26 +2606,16 @@ fn format_item(
                 if let Some(field) = crate_data.index.get(field_id) {
                   if let Some(field_name) = &field.name {
                     let (type_str, type_links) = if let ItemEnum::StructField(ty) = &field.inner {
-                      format_type_with_links(ty, crate_data, Some(item))
+                      format_type_with_links(ctx, ty, crate_data, Some(item))
                     } else {
                       ("?".to_string(), Vec::new())
                     };
 
                     let field_sig = format!("{}: {}", field_name, type_str);
-                    let links_json = format_links_as_json(&type_links);
-                    output.push_str(&format!(
-                      "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-                      field_sig, links_json
-                    ));
+                    output.push_str(&rust_code(&field_sig, &type_links, true));
 
                     if let Some(docs) = &field.docs {
-                      let first_line = docs.lines().next().unwrap_or("").trim();
-                      if !first_line.is_empty() {
-                        output.push_str(&format!(
-                          "<div className=\"rust-field-doc\">{}</div>\n\n",
-                          first_line
-                        ));
-                      }
+                      output.push_str(&format_field_docs(ctx, docs));
                     }
                   }
                 }
@@ -1131,7 +2631,7 @@ fn format_item(
               field_id.and_then(|id| {
                 crate_data.index.get(&id).map(|field| {
                   if let ItemEnum::StructField(ty) = &field.inner {
-                    format_type(ty, crate_data)
+                    format_type(ctx, ty, crate_data)
                   } else {
                     "?".to_string()
                   }
@@ -1148,23 +2648,12 @@ fn format_item(
 
       let (inherent_impls, trait_impls) = collect_impls_for_type(item_id, crate_data);
 
-      if !inherent_impls.is_empty() {
-        output.push_str("### Methods\n\n");
-        for impl_block in inherent_impls {
-          let methods = format_impl_methods(impl_block, crate_data, Some(item));
-          for (sig, links, doc) in methods {
-            let links_json = format_links_as_json(&links);
-            output.push_str(&format!(
-              "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-              sig, links_json
-            ));
-            if let Some(doc) = doc {
-              output.push_str(&format!("{}\n\n", doc));
-            }
-            output.push_str("---\n\n");
-          }
-        }
-      }
+      output.push_str(&format_inherent_impls(
+        ctx,
+        &inherent_impls,
+        crate_data,
+        item,
+      ));
 
       if !trait_impls.is_empty() {
         let user_impls: Vec<_> = trait_impls
@@ -1178,7 +2667,7 @@ fn format_item(
 
           for impl_block in user_impls {
             if let Some(trait_ref) = &impl_block.trait_ {
-              let methods = format_impl_methods(impl_block, crate_data, Some(item));
+              let methods = format_impl_methods(ctx, impl_block, crate_data, Some(item));
               if methods.is_empty() {
                 derives.push(trait_ref.path.as_str());
               } else {
@@ -1208,11 +2697,7 @@ fn format_item(
             for (trait_ref, methods) in sorted_trait_with_methods {
               output.push_str(&format!("#### {}\n\n", trait_ref.path));
               for (sig, links, doc) in methods {
-                let links_json = format_links_as_json(&links);
-                output.push_str(&format!(
-                  "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-                  sig, links_json
-                ));
+                output.push_str(&rust_code(&sig, &links, true));
                 if let Some(doc) = doc {
                   output.push_str(&format!("{}\n\n", doc));
                 }
@@ -1225,15 +2710,11 @@ fn format_item(
     }
     ItemEnum::Enum(e) => {
       // Format enum definition with links
-      let (code, links) = format_enum_definition_with_links(name, e, item, crate_data);
-      let links_json = format_links_as_json(&links);
-      output.push_str(&format!(
-        "<RustCode code={{`{}`}} links={{{}}} />\n\n",
-        code, links_json
-      ));
+      let (code, links) = format_enum_definition_with_links(ctx, name, e, item, crate_data);
+      output.push_str(&rust_code(&code, &links, false));
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
       }
 
       let non_synthetic_params: Vec<_> = e
@@ -1301,11 +2782,20 @@ fn format_item(
                 None
               };
 
-              output.push_str("- `");
+              output.push_str(&format!(
+                "- <span id=\"{}\"></span>`",
+                variant_anchor(variant_name)
+              ));
               output.push_str(variant_name);
               if let Some(kind) = variant_kind {
                 output.push_str(&kind);
               }
+              // Show the evaluated value, which may differ from the expression (`1 << 2`)
+              if let ItemEnum::Variant(v) = &variant.inner
+                && let Some(discriminant) = &v.discriminant
+              {
+                output.push_str(&format!(" = {}", discriminant.value));
+              }
               output.push('`');
 
               if let Some(docs) = &variant.docs {
@@ -1315,6 +2805,11 @@ fn format_item(
                 }
               }
               output.push('\n');
+              output.push_str(&format_variant_field_docs(
+                variant,
+                variant_name,
+                crate_data,
+              ));
             }
           }
         }
@@ -1323,23 +2818,12 @@ fn format_item(
 
       let (inherent_impls, trait_impls) = collect_impls_for_type(item_id, crate_data);
 
-      if !inherent_impls.is_empty() {
-        output.push_str("### Methods\n\n");
-        for impl_block in inherent_impls {
-          let methods = format_impl_methods(impl_block, crate_data, Some(item));
-          for (sig, links, doc) in methods {
-            let links_json = format_links_as_json(&links);
-            output.push_str(&format!(
-              "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-              sig, links_json
-            ));
-            if let Some(doc) = doc {
-              output.push_str(&format!("{}\n\n", doc));
-            }
-            output.push_str("---\n\n");
-          }
-        }
-      }
+      output.push_str(&format_inherent_impls(
+        ctx,
+        &inherent_impls,
+        crate_data,
+        item,
+      ));
 
       if !trait_impls.is_empty() {
         let user_impls: Vec<_> = trait_impls
@@ -1353,7 +2837,7 @@ fn format_item(
 
           for impl_block in user_impls {
             if let Some(trait_ref) = &impl_block.trait_ {
-              let methods = format_impl_methods(impl_block, crate_data, Some(item));
+              let methods = format_impl_methods(ctx, impl_block, crate_data, Some(item));
               if methods.is_empty() {
                 derives.push(trait_ref.path.as_str());
               } else {
@@ -1383,11 +2867,7 @@ fn format_item(
             for (trait_ref, methods) in sorted_trait_with_methods {
               output.push_str(&format!("#### {}\n\n", trait_ref.path));
               for (sig, links, doc) in methods {
-                let links_json = format_links_as_json(&links);
-                output.push_str(&format!(
-                  "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-                  sig, links_json
-                ));
+                output.push_str(&rust_code(&sig, &links, true));
                 if let Some(doc) = doc {
                   output.push_str(&format!("{}\n\n", doc));
                 }
@@ -1402,16 +2882,23 @@ fn format_item(
       output.push_str("*Function*\n\n");
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
       }
 
       // Format function definition with links
-      let (code, links) = format_function_definition_with_links(name, f, item, crate_data);
-      let links_json = format_links_as_json(&links);
-      output.push_str(&format!(
-        "<RustCode code={{`{}`}} links={{{}}} />\n\n",
-        code, links_json
-      ));
+      let (code, links) = format_function_definition_with_links(ctx, name, f, item, crate_data);
+      output.push_str(&rust_code(&code, &links, false));
+
+      if ctx.parameter_tables {
+        output.push_str(&format_parameter_tables(
+          ctx,
+          f,
+          item.docs.as_deref(),
+          crate_data,
+          Some(item),
+          |title| format!("### {}\n\n", title),
+        ));
+      }
     }
     ItemEnum::Trait(t) => {
       // Add code signature like rustdoc
@@ -1430,30 +2917,48 @@ fn format_item(
       output.push_str("```\n\n");
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
       }
 
-      if !t.items.is_empty() {
-        output.push_str("### Methods\n\n");
-        for method_id in &t.items {
-          if let Some(method) = crate_data.index.get(method_id) {
-            if let Some(method_name) = &method.name {
-              output.push_str(&format!("- `{}`", method_name));
-              if let Some(method_docs) = &method.docs {
-                output.push_str(&format!(": {}", method_docs.lines().next().unwrap_or("")));
-              }
-              output.push('\n');
-            }
+      // Associated items, then required and provided methods, like rustdoc
+      let mut sections: [(&str, Vec<_>); 3] = [
+        ("Associated Items", Vec::new()),
+        ("Required Methods", Vec::new()),
+        ("Provided Methods", Vec::new()),
+      ];
+      for member in format_assoc_items(ctx, &t.items, crate_data, Some(item)) {
+        let section = match &member.0.inner {
+          ItemEnum::Function(f) if f.has_body => 2,
+          ItemEnum::Function(_) => 1,
+          _ => 0,
+        };
+        sections[section].1.push(member);
+      }
+      for (title, members) in sections {
+        if members.is_empty() {
+          continue;
+        }
+        output.push_str(&format!("### {}\n\n", title));
+        for (member, sig, links, doc) in members {
+          if let Some(anchor) = trait_member_anchor(member) {
+            output.push_str(&format!("<span id=\"{}\"></span>\n\n", anchor));
+          }
+          output.push_str(&rust_code(&sig, &links, true));
+          if let Some(doc) = doc {
+            output.push_str(&format!("{}\n\n", doc));
           }
+          output.push_str("---\n\n");
         }
-        output.push('\n');
       }
+
+      output.push_str(&format_forwarding_impls(item_id, crate_data));
+      output.push_str(&format_foreign_type_impls(ctx, item_id, crate_data));
     }
     ItemEnum::Module(_) => {
       output.push_str(&format!("## Module: {}\n\n", name));
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 3)));
       }
     }
     ItemEnum::Constant { .. } => {
@@ -1461,18 +2966,103 @@ fn format_item(
       output.push_str("*Constant*\n\n");
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 3)));
       }
     }
     ItemEnum::TypeAlias(ta) => {
       output.push_str(&format!("## {}\n\n", name));
-      output.push_str(&format!(
-        "*Type Alias*: `{}`\n\n",
-        format_type(&ta.type_, crate_data)
-      ));
+      if let rustdoc_types::Type::ImplTrait(bounds) = &ta.type_ {
+        // `impl Trait` aliases are only known by their bounds
+        let (bounds_str, links) = format_bounds_with_links(ctx, bounds, crate_data);
+        output.push_str("*Type Alias*\n\n");
+        let code = format!(
+          "pub type {}{} = impl {};",
+          name,
+          format_generic_params(&ta.generics),
+          bounds_str
+        );
+        output.push_str(&rust_code(&code, &links, false));
+      } else {
+        output.push_str(&format!(
+          "*Type Alias*: `{}`\n\n",
+          format_type(ctx, &ta.type_, crate_data)
+        ));
+      }
+
+      if let Some(docs) = &item.docs {
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 3)));
+      }
+    }
+    ItemEnum::TraitAlias(alias) => {
+      output.push_str(&format!("## {}\n\n", name));
+      output.push_str("*Trait Alias*\n\n");
+
+      let (bounds_str, links) = format_bounds_with_links(ctx, &alias.params, crate_data);
+      let code = format!(
+        "pub trait {}{} = {};",
+        name,
+        format_generic_params(&alias.generics),
+        bounds_str
+      );
+      output.push_str(&rust_code(&code, &links, false));
+
+      if let Some(docs) = &item.docs {
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 3)));
+      }
+    }
+    ItemEnum::Static(st) => {
+      output.push_str(&format!("## {}\n\n", name));
+      output.push_str("*Static*\n\n");
+
+      let (type_str, links) = format_type_with_links(ctx, &st.type_, crate_data, Some(item));
+      let code = format!(
+        "pub {}static {}{}: {}",
+        if st.is_unsafe { "unsafe " } else { "" },
+        if st.is_mutable { "mut " } else { "" },
+        name,
+        type_str
+      );
+      output.push_str(&rust_code(&code, &links, false));
+
+      if let Some(docs) = &item.docs {
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 3)));
+      }
+    }
+    ItemEnum::Macro(definition) => {
+      output.push_str(&rust_code(definition, &[], false));
+
+      if let Some(docs) = &item.docs {
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
+      }
+    }
+    ItemEnum::ProcMacro(proc_macro) => {
+      let code = match proc_macro.kind {
+        rustdoc_types::MacroKind::Bang => format!("{}!() {{ /* proc-macro */ }}", name),
+        rustdoc_types::MacroKind::Attr => format!("#[{}]", name),
+        rustdoc_types::MacroKind::Derive => format!("#[derive({})]", name),
+      };
+      output.push_str(&rust_code(&code, &[], false));
+
+      if !proc_macro.helpers.is_empty() {
+        output.push_str("### Helper Attributes\n\n");
+        for helper in &proc_macro.helpers {
+          output.push_str(&format!("- `#[{}]`\n", helper));
+        }
+        output.push('\n');
+      }
 
       if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
+      }
+    }
+    ItemEnum::ExternType => {
+      output.push_str(&format!("## {}\n\n", name));
+      output.push_str("*Foreign Type*\n\n");
+      let code = format!("extern {{\n    pub type {};\n}}", name);
+      output.push_str(&rust_code(&code, &[], false));
+
+      if let Some(docs) = &item.docs {
+        output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 3)));
       }
     }
     _ => {
@@ -1483,6 +3073,231 @@ fn format_item(
   Some(output)
 }
 
+/// Smart pointers whose trait impls usually just forward to the pointee
+const FORWARDING_POINTERS: &[&str] = &["Box", "Rc", "Arc", "Cow", "Pin"];
+
+/// Whether an impl forwards a trait to a generic type behind a reference or smart
+/// pointer, like `impl<T: MyTrait> MyTrait for &T`
+fn is_forwarding_impl(impl_block: &rustdoc_types::Impl) -> bool {
+  use rustdoc_types::{GenericArg, GenericArgs, Type};
+  match &impl_block.for_ {
+    Type::BorrowedRef { type_, .. } => matches!(**type_, Type::Generic(_)),
+    Type::ResolvedPath(path) => {
+      FORWARDING_POINTERS.contains(&get_short_type_name(&path.path).as_str())
+        && matches!(
+          path.args.as_deref(),
+          Some(GenericArgs::AngleBracketed { args, .. })
+            if args.iter().any(|arg| matches!(arg, GenericArg::Type(Type::Generic(_))))
+        )
+    }
+    _ => false,
+  }
+}
+
+/// Format trait bounds without links (e.g. `MyTrait + ?Sized`)
+fn format_bounds_plain(bounds: &[rustdoc_types::GenericBound], crate_data: &Crate) -> String {
+  bounds
+    .iter()
+    .map(|bound| match bound {
+      rustdoc_types::GenericBound::TraitBound {
+        trait_, modifier, ..
+      } => {
+        let maybe = match modifier {
+          rustdoc_types::TraitBoundModifier::Maybe => "?",
+          _ => "",
+        };
+        let args = trait_
+          .args
+          .as_ref()
+          .map(|args| format_generic_args_plain(args, crate_data))
+          .unwrap_or_default();
+        format!("{}{}{}", maybe, get_short_type_name(&trait_.path), args)
+      }
+      rustdoc_types::GenericBound::Outlives(lifetime) => lifetime.clone(),
+      rustdoc_types::GenericBound::Use(_) => "use<..>".to_string(),
+    })
+    .collect::<Vec<_>>()
+    .join(" + ")
+}
+
+/// Format trait bounds (e.g. `MyTrait + Send`) along with links to their traits
+fn format_bounds_with_links(
+  ctx: &RenderContext,
+  bounds: &[rustdoc_types::GenericBound],
+  crate_data: &Crate,
+) -> (String, Vec<(String, String)>) {
+  let mut links = Vec::new();
+  for bound in bounds {
+    if let rustdoc_types::GenericBound::TraitBound { trait_, .. } = bound {
+      let short_name = get_short_type_name(&trait_.path);
+      if let Some(link) = generate_type_link(ctx, &trait_.path, &trait_.id, crate_data) {
+        links.push((short_name, link));
+      }
+      // Also extract links from generic arguments (e.g., Into<T>)
+      if let Some(args) = &trait_.args {
+        let (_, args_links) = format_generic_args_with_links(ctx, args, crate_data, None);
+        links.extend(args_links);
+      }
+    }
+  }
+  (format_bounds_plain(bounds, crate_data), links)
+}
+
+/// Format the generic parameters of an item (e.g. `<'a, T>`), empty if it has none
+fn format_generic_params(generics: &rustdoc_types::Generics) -> String {
+  let params: Vec<String> = generics
+    .params
+    .iter()
+    .filter(|p| {
+      !matches!(&p.kind, rustdoc_types::GenericParamDefKind::Lifetime { .. })
+        || !is_synthetic_lifetime(&p.name)
+    })
+    .map(format_generic_param)
+    .collect();
+  if params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", params.join(", "))
+  }
+}
+
+/// Bounds of generic parameters and where predicates (e.g. `T: Clone`)
+fn format_generic_bounds(generics: &rustdoc_types::Generics, crate_data: &Crate) -> Vec<String> {
+  let mut bounds: Vec<String> = generics
+    .params
+    .iter()
+    .filter_map(|param| match &param.kind {
+      rustdoc_types::GenericParamDefKind::Type {
+        bounds,
+        is_synthetic: false,
+        ..
+      } if !bounds.is_empty() => Some(format!(
+        "{}: {}",
+        param.name,
+        format_bounds_plain(bounds, crate_data)
+      )),
+      _ => None,
+    })
+    .collect();
+  bounds.extend(
+    generics
+      .where_predicates
+      .iter()
+      .filter_map(|predicate| match predicate {
+        rustdoc_types::WherePredicate::BoundPredicate { type_, bounds, .. } => Some(format!(
+          "{}: {}",
+          format_type_plain(type_, crate_data),
+          format_bounds_plain(bounds, crate_data)
+        )),
+        _ => None,
+      }),
+  );
+  bounds
+}
+
+/// Compact table of the forwarding impls of a trait (`&T`, `Box<T>`, ...), which
+/// would otherwise repeat the trait's methods once per pointer type
+fn format_forwarding_impls(trait_id: &Id, crate_data: &Crate) -> String {
+  let mut rows: Vec<(String, String)> = crate_data
+    .index
+    .values()
+    .filter_map(|item| match &item.inner {
+      ItemEnum::Impl(impl_block)
+        if impl_block
+          .trait_
+          .as_ref()
+          .is_some_and(|trait_ref| trait_ref.id == *trait_id)
+          && is_forwarding_impl(impl_block) =>
+      {
+        Some(impl_block)
+      }
+      _ => None,
+    })
+    .map(|impl_block| {
+      let bounds = format_generic_bounds(&impl_block.generics, crate_data)
+        .iter()
+        .map(|bound| format!("`{}`", bound))
+        .collect::<Vec<_>>()
+        .join(", ");
+      (format_type_plain(&impl_block.for_, crate_data), bounds)
+    })
+    .collect();
+  if rows.is_empty() {
+    return String::new();
+  }
+
+  rows.sort();
+  let mut output = String::from("### Forwarding Implementations\n\n");
+  output.push_str("| Implementor | Bounds |\n| --- | --- |\n");
+  for (implementor, bounds) in rows {
+    output.push_str(&format!("| `{}` | {} |\n", implementor, bounds));
+  }
+  output.push('\n');
+  output
+}
+
+/// Whether an impl is for a type without a page of its own: a primitive, reference,
+/// tuple, slice or a type from another crate
+fn is_foreign_type_impl(impl_block: &rustdoc_types::Impl, crate_data: &Crate) -> bool {
+  use rustdoc_types::Type;
+  match &impl_block.for_ {
+    Type::ResolvedPath(path) => !crate_data.index.contains_key(&path.id),
+    // Blanket impls are listed by the implementing types
+    Type::Generic(_) | Type::ImplTrait(_) | Type::Infer => false,
+    _ => !is_forwarding_impl(impl_block),
+  }
+}
+
+/// Impls of a trait for types without a page of their own (e.g. `impl MyTrait for String`),
+/// which would otherwise not be listed anywhere
+fn format_foreign_type_impls(ctx: &RenderContext, trait_id: &Id, crate_data: &Crate) -> String {
+  let mut impls: Vec<(String, Vec<(String, String)>)> = crate_data
+    .index
+    .values()
+    .filter_map(|item| match &item.inner {
+      ItemEnum::Impl(impl_block)
+        if impl_block
+          .trait_
+          .as_ref()
+          .is_some_and(|trait_ref| trait_ref.id == *trait_id)
+          && !impl_block.is_synthetic
+          && impl_block.blanket_impl.is_none()
+          && is_foreign_type_impl(impl_block, crate_data) =>
+      {
+        Some(impl_block)
+      }
+      _ => None,
+    })
+    .filter_map(|impl_block| {
+      let trait_ref = impl_block.trait_.as_ref()?;
+      let (for_str, links) = format_type_with_links(ctx, &impl_block.for_, crate_data, None);
+      let trait_args = trait_ref
+        .args
+        .as_ref()
+        .map(|args| format_generic_args_plain(args, crate_data))
+        .unwrap_or_default();
+      let code = format!(
+        "impl{} {}{} for {}",
+        format_generic_params(&impl_block.generics),
+        get_short_type_name(&trait_ref.path),
+        trait_args,
+        for_str
+      );
+      Some((code, links))
+    })
+    .collect();
+  if impls.is_empty() {
+    return String::new();
+  }
+
+  impls.sort();
+  let mut output = String::from("### Implementations on Foreign Types\n\n");
+  for (code, links) in impls {
+    output.push_str(&rust_code(&code, &links, true));
+  }
+  output
+}
+
 fn format_generic_param(param: &rustdoc_types::GenericParamDef) -> String {
   match &param.kind {
     rustdoc_types::GenericParamDefKind::Lifetime { .. } => {
@@ -1504,17 +3319,32 @@ fn is_synthetic_lifetime(name: &str) -> bool {
     || name == "'async_trait"
 }
 
-fn is_compiler_internal_trait(trait_name: &str) -> bool {
+/// Whether a struct field is a `PhantomData` marker left out of the docs
+fn is_hidden_phantom_field(ctx: &RenderContext, field: &Item) -> bool {
+  !ctx.show_phantom_data
+    && matches!(
+      &field.inner,
+      ItemEnum::StructField(rustdoc_types::Type::ResolvedPath(path))
+        if get_short_type_name(&path.path) == "PhantomData"
+    )
+}
+
+pub(crate) fn is_compiler_internal_trait(trait_name: &str) -> bool {
   matches!(
     trait_name,
     "StructuralPartialEq" | "StructuralEq" | "Freeze" | "Unpin" | "RefUnwindSafe" | "UnwindSafe"
   )
 }
 
+/// Inherent impl blocks (with their items, which hold their docs) and trait impls of a type
+#[allow(clippy::type_complexity)]
 fn collect_impls_for_type<'a>(
   type_id: &rustdoc_types::Id,
   crate_data: &'a Crate,
-) -> (Vec<&'a rustdoc_types::Impl>, Vec<&'a rustdoc_types::Impl>) {
+) -> (
+  Vec<(&'a Item, &'a rustdoc_types::Impl)>,
+  Vec<&'a rustdoc_types::Impl>,
+) {
   use rustdoc_types::Type;
 
   let mut inherent_impls = Vec::new();
@@ -1531,130 +3361,479 @@ fn collect_impls_for_type<'a>(
         if impl_block.trait_.is_some() {
           trait_impls.push(impl_block);
         } else {
-          inherent_impls.push(impl_block);
+          inherent_impls.push((item, impl_block));
         }
       }
     }
   }
 
+  // The index is a hash map, so inherent impl blocks are put back in source order
+  inherent_impls.sort_by_key(|(item, _)| {
+    item
+      .span
+      .as_ref()
+      .map(|span| (span.filename.clone(), span.begin))
+  });
+
   (inherent_impls, trait_impls)
 }
 
+/// Anchor of a member of a trait, like rustdoc's (e.g. `tymethod.next` for a
+/// required method, `method.map` for a provided one)
+fn trait_member_anchor(member: &Item) -> Option<String> {
+  let kind = match &member.inner {
+    ItemEnum::Function(f) if f.has_body => "method",
+    ItemEnum::Function(_) => "tymethod",
+    ItemEnum::AssocConst { .. } => "associatedconstant",
+    ItemEnum::AssocType { .. } => "associatedtype",
+    _ => return None,
+  };
+  Some(format!("{}.{}", kind, member.name.as_deref()?))
+}
+
+/// Signatures, links and docs of the methods, associated constants and
+/// associated types of an impl block
+///
+/// Methods of trait implementations link to the method of the trait they implement.
 #[allow(clippy::type_complexity)]
 fn format_impl_methods(
+  ctx: &RenderContext,
   impl_block: &rustdoc_types::Impl,
   crate_data: &Crate,
   parent_item: Option<&Item>,
 ) -> Vec<(String, Vec<(String, String)>, Option<String>)> {
+  let trait_url = impl_block.trait_.as_ref().and_then(|trait_ref| {
+    let url = generate_type_link(ctx, &trait_ref.path, &trait_ref.id, crate_data)?;
+    let trait_name = trait_ref
+      .path
+      .rsplit("::")
+      .next()
+      .unwrap_or(&trait_ref.path);
+    Some((url, trait_name))
+  });
+  format_assoc_items(ctx, &impl_block.items, crate_data, parent_item)
+    .into_iter()
+    .map(|(method, sig, links, doc)| {
+      let trait_link = trait_url
+        .as_ref()
+        .filter(|_| matches!(method.inner, ItemEnum::Function(_)));
+      let (Some((url, trait_name)), Some(name)) = (trait_link, &method.name) else {
+        return (sig, links, doc);
+      };
+      let (verb, anchor) = if impl_block.provided_trait_methods.contains(name) {
+        ("Overrides", "method")
+      } else {
+        ("Implements", "tymethod")
+      };
+      // Anchors of the single-file layout already point into the page
+      let target = match url.starts_with('#') {
+        true => url.clone(),
+        false => format!("{}#{}.{}", url, anchor, name),
+      };
+      let link = format!(
+        "<div className=\"rust-trait-method\">{} <Link to=\"{}\">{}::{}</Link></div>",
+        verb, target, trait_name, name
+      );
+      let doc = match doc {
+        Some(doc) => format!("{}\n\n{}", link, doc),
+        None => link,
+      };
+      (sig, links, Some(doc))
+    })
+    .collect()
+}
+
+/// Signatures, links and docs of associated items (methods, constants and types),
+/// in the configured method order
+#[allow(clippy::type_complexity)]
+fn format_assoc_items<'a>(
+  ctx: &RenderContext,
+  item_ids: &[Id],
+  crate_data: &'a Crate,
+  parent_item: Option<&Item>,
+) -> Vec<(&'a Item, String, Vec<(String, String)>, Option<String>)> {
   let mut methods = Vec::new();
 
-  for method_id in &impl_block.items {
-    if let Some(method) = crate_data.index.get(method_id) {
-      if let ItemEnum::Function(f) = &method.inner {
-        if let Some(method_name) = &method.name {
-          let (sig, links) =
-            format_function_signature_with_links(method_name, f, crate_data, parent_item);
-          let doc = method.docs.as_ref().and_then(|d| {
-            let first_line = d.lines().next().unwrap_or("").trim();
-            if !first_line.is_empty() {
-              Some(first_line.to_string())
-            } else {
-              None
-            }
-          });
-          methods.push((sig, links, doc));
+  for method_id in item_ids {
+    let Some(method) = crate_data.index.get(method_id) else {
+      continue;
+    };
+    let Some(method_name) = &method.name else {
+      continue;
+    };
+    let (sig, links) = match &method.inner {
+      ItemEnum::Function(f) => {
+        format_function_signature_with_links(ctx, method_name, f, crate_data, parent_item)
+      }
+      ItemEnum::AssocConst { type_, value } => {
+        let (type_str, links) = format_type_with_links(ctx, type_, crate_data, parent_item);
+        let value = value
+          .as_deref()
+          .map(|value| format!(" = {}", value))
+          .unwrap_or_default();
+        (
+          format!("const {}: {}{}", method_name, type_str, value),
+          links,
+        )
+      }
+      ItemEnum::AssocType {
+        generics,
+        bounds,
+        type_,
+      } => {
+        let mut sig = format!("type {}{}", method_name, format_generic_params(generics));
+        let mut links = Vec::new();
+        if !bounds.is_empty() {
+          let (bounds_str, bound_links) = format_bounds_with_links(ctx, bounds, crate_data);
+          sig.push_str(&format!(": {}", bounds_str));
+          links.extend(bound_links);
         }
+        if let Some(type_) = type_ {
+          let (type_str, type_links) = format_type_with_links(ctx, type_, crate_data, parent_item);
+          sig.push_str(&format!(" = {}", type_str));
+          links.extend(type_links);
+        }
+        (sig, links)
+      }
+      _ => continue,
+    };
+    let mut doc = method
+      .docs
+      .as_deref()
+      .and_then(|docs| format_method_docs(ctx, docs));
+    if ctx.parameter_tables
+      && let ItemEnum::Function(f) = &method.inner
+    {
+      let tables = format_parameter_tables(
+        ctx,
+        f,
+        method.docs.as_deref(),
+        crate_data,
+        parent_item,
+        |title| format!("**{}**\n\n", title),
+      );
+      if !tables.is_empty() {
+        let tables = tables.trim_end();
+        doc = Some(match doc {
+          Some(doc) => format!("{}\n\n{}", doc, tables),
+          None => tables.to_string(),
+        });
       }
     }
+    methods.push((method, sig, links, doc));
   }
 
+  if ctx.method_order == MethodOrder::Alphabetical {
+    methods.sort_by_key(|(method, ..)| method.name.as_deref());
+  }
   methods
 }
 
-#[allow(clippy::format_in_format_args)]
-fn format_function_signature_with_links(
-  name: &str,
-  f: &rustdoc_types::Function,
-  crate_data: &Crate,
-  current_item: Option<&Item>,
-) -> (String, Vec<(String, String)>) {
-  let mut sig = format!("fn {}", name);
-  let mut links = Vec::new();
-
-  let non_synthetic_params: Vec<String> = f
-    .generics
-    .params
-    .iter()
-    .filter(|p| {
-      !matches!(&p.kind, rustdoc_types::GenericParamDefKind::Lifetime { .. })
-        || !is_synthetic_lifetime(&p.name)
-    })
-    .map(format_generic_param)
-    .collect();
-
-  if !non_synthetic_params.is_empty() {
-    sig.push('<');
-    sig.push_str(&non_synthetic_params.join(", "));
-    sig.push('>');
-  }
-
-  sig.push('(');
-  let mut inputs = Vec::new();
-  for (param_name, ty) in &f.sig.inputs {
-    let (type_str, type_links) = format_type_with_links(ty, crate_data, current_item);
-    links.extend(type_links);
-    inputs.push(format!("{}: {}", param_name, type_str));
-  }
+/// Signature of an item on one line, as compared by the `diff` subcommand
+/// (e.g. `async fn fetch(url: &str) -> Result<String>`).
+///
+/// Signatures of structs, enums and traits leave out their fields, variants and
+/// methods, which have signatures of their own.
+pub(crate) fn item_signature(item: &Item, crate_data: &Crate) -> Option<String> {
+  let ctx = RenderContext::default();
+  let name = item.name.as_deref()?;
+  let header = |keyword: &str, generics: &rustdoc_types::Generics| {
+    let mut sig = format!("{} {}{}", keyword, name, format_generic_params(generics));
+    let bounds = format_generic_bounds(generics, crate_data);
+    if !bounds.is_empty() {
+      sig.push_str(&format!(" where {}", bounds.join(", ")));
+    }
+    sig
+  };
 
-  // Format on multiple lines if signature is too long (> 80 chars) or has many parameters (> 3)
-  let single_line = format!(
-    "fn {}{}",
-    if !non_synthetic_params.is_empty() {
-      format!("{}<{}>", name, non_synthetic_params.join(", "))
-    } else {
-      name.to_string()
+  let sig = match &item.inner {
+    ItemEnum::Function(f) => {
+      let (sig, _) = format_function_definition_with_links(&ctx, name, f, item, crate_data);
+      let qualifiers: String = [
+        (f.header.is_const, "const "),
+        (f.header.is_async, "async "),
+        (f.header.is_unsafe, "unsafe "),
+      ]
+      .iter()
+      .filter(|(is_set, _)| *is_set)
+      .map(|(_, qualifier)| *qualifier)
+      .collect();
+      format!("{}{}", qualifiers, sig)
+    }
+    ItemEnum::Struct(s) => header("struct", &s.generics),
+    ItemEnum::Enum(e) => header("enum", &e.generics),
+    ItemEnum::Union(u) => header("union", &u.generics),
+    ItemEnum::Trait(t) => {
+      let keyword = match t.is_unsafe {
+        true => "unsafe trait",
+        false => "trait",
+      };
+      let mut sig = header(keyword, &t.generics);
+      if !t.bounds.is_empty() {
+        sig.push_str(&format!(": {}", format_bounds_plain(&t.bounds, crate_data)));
+      }
+      sig
+    }
+    ItemEnum::TraitAlias(alias) => format!(
+      "trait {}{} = {}",
+      name,
+      format_generic_params(&alias.generics),
+      format_bounds_plain(&alias.params, crate_data)
+    ),
+    ItemEnum::TypeAlias(ta) => format!(
+      "type {}{} = {}",
+      name,
+      format_generic_params(&ta.generics),
+      format_type_plain(&ta.type_, crate_data)
+    ),
+    ItemEnum::Constant { type_, .. } => {
+      format!("const {}: {}", name, format_type_plain(type_, crate_data))
+    }
+    ItemEnum::Static(st) => format!(
+      "static {}{}: {}",
+      if st.is_mutable { "mut " } else { "" },
+      name,
+      format_type_plain(&st.type_, crate_data)
+    ),
+    ItemEnum::ExternType => format!("type {}", name),
+    ItemEnum::Macro(_) => format!("macro_rules! {}", name),
+    ItemEnum::ProcMacro(proc_macro) => match proc_macro.kind {
+      rustdoc_types::MacroKind::Bang => format!("{}!()", name),
+      rustdoc_types::MacroKind::Attr => format!("#[{}]", name),
+      rustdoc_types::MacroKind::Derive => format!("#[derive({})]", name),
     },
-    format!("({})", inputs.join(", "))
-  );
-
-  if inputs.len() > 3 || single_line.len() > 80 {
-    // Multi-line format
-    sig = format!("fn {}", name);
-    if !non_synthetic_params.is_empty() {
-      sig.push('<');
-      sig.push_str(&non_synthetic_params.join(", "));
-      sig.push('>');
+    ItemEnum::StructField(ty) => format!("{}: {}", name, format_type_plain(ty, crate_data)),
+    ItemEnum::Variant(variant) => {
+      let field_type = |id: &Id| match crate_data.index.get(id).map(|field| &field.inner) {
+        Some(ItemEnum::StructField(ty)) => format_type_plain(ty, crate_data),
+        _ => "_".to_string(),
+      };
+      match &variant.kind {
+        rustdoc_types::VariantKind::Plain => name.to_string(),
+        rustdoc_types::VariantKind::Tuple(fields) => format!(
+          "{}({})",
+          name,
+          fields
+            .iter()
+            .map(|id| id.as_ref().map_or("_".to_string(), field_type))
+            .collect::<Vec<_>>()
+            .join(", ")
+        ),
+        rustdoc_types::VariantKind::Struct { fields, .. } => format!(
+          "{} {{ {} }}",
+          name,
+          fields
+            .iter()
+            .filter_map(|id| {
+              let field_name = crate_data.index.get(id)?.name.as_deref()?;
+              Some(format!("{}: {}", field_name, field_type(id)))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+        ),
+      }
+    }
+    ItemEnum::AssocConst { type_, .. } => {
+      format!("const {}: {}", name, format_type_plain(type_, crate_data))
     }
-    sig.push_str("(\n");
-    for (i, input) in inputs.iter().enumerate() {
-      sig.push_str(&format!("    {}", input));
-      if i < inputs.len() - 1 {
-        sig.push(',');
+    ItemEnum::AssocType {
+      generics,
+      bounds,
+      type_,
+    } => {
+      let mut sig = format!("type {}{}", name, format_generic_params(generics));
+      if !bounds.is_empty() {
+        sig.push_str(&format!(": {}", format_bounds_plain(bounds, crate_data)));
       }
-      sig.push('\n');
+      if let Some(type_) = type_ {
+        sig.push_str(&format!(" = {}", format_type_plain(type_, crate_data)));
+      }
+      sig
     }
-    sig.push(')');
-  } else {
-    // Single line format
-    sig.push_str(&inputs.join(", "));
-    sig.push(')');
-  }
-
-  if let Some(output_type) = &f.sig.output {
-    let (type_str, type_links) = format_type_with_links(output_type, crate_data, current_item);
-    links.extend(type_links);
-    sig.push_str(&format!(" -> {}", type_str));
-  }
-
-  (sig, links)
-}
+    _ => return None,
+  };
 
-fn format_type(ty: &rustdoc_types::Type, crate_data: &Crate) -> String {
-  format_type_depth(ty, crate_data, 0)
+  // Multi-line layouts of long signatures
+  let sig = sig.split_whitespace().collect::<Vec<_>>().join(" ");
+  Some(sig.replace("( ", "(").replace(", )", ")"))
 }
 
-fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize) -> String {
-  const MAX_DEPTH: usize = 50;
+/// Signature of a trait implementation on one line (e.g. `impl<T> From<T> for Wrapper<T>`).
+pub(crate) fn impl_signature(impl_block: &rustdoc_types::Impl, crate_data: &Crate) -> String {
+  let trait_ = impl_block
+    .trait_
+    .as_ref()
+    .map(|trait_| {
+      format!(
+        "{}{} for ",
+        if impl_block.is_negative { "!" } else { "" },
+        format_type_plain(
+          &rustdoc_types::Type::ResolvedPath(trait_.clone()),
+          crate_data
+        )
+      )
+    })
+    .unwrap_or_default();
+  let mut sig = format!(
+    "impl{} {}{}",
+    format_generic_params(&impl_block.generics),
+    trait_,
+    format_type_plain(&impl_block.for_, crate_data)
+  );
+  let bounds = format_generic_bounds(&impl_block.generics, crate_data);
+  if !bounds.is_empty() {
+    sig.push_str(&format!(" where {}", bounds.join(", ")));
+  }
+  sig
+}
+
+/// "Methods" section of a type page, with a subheading per impl block when the
+/// type has several of them or the block has generics (like rustdoc), followed
+/// by the docs of the impl block
+fn format_inherent_impls(
+  ctx: &RenderContext,
+  inherent_impls: &[(&Item, &rustdoc_types::Impl)],
+  crate_data: &Crate,
+  item: &Item,
+) -> String {
+  if inherent_impls.is_empty() {
+    return String::new();
+  }
+
+  let mut output = String::from("### Methods\n\n");
+  for (impl_item, impl_block) in inherent_impls {
+    let generic_params = format_generic_params(&impl_block.generics);
+    let bounds = format_generic_bounds(&impl_block.generics, crate_data);
+    if inherent_impls.len() > 1 || !generic_params.is_empty() || !bounds.is_empty() {
+      let where_clause = if bounds.is_empty() {
+        String::new()
+      } else {
+        format!(" where {}", bounds.join(", "))
+      };
+      output.push_str(&format!(
+        "#### `impl{} {}{}`\n\n",
+        generic_params,
+        format_type_plain(&impl_block.for_, crate_data),
+        where_clause
+      ));
+    }
+    if let Some(docs) = impl_item
+      .docs
+      .as_deref()
+      .filter(|docs| !docs.trim().is_empty())
+    {
+      output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 5)));
+    }
+
+    for (sig, links, doc) in format_impl_methods(ctx, impl_block, crate_data, Some(item)) {
+      output.push_str(&rust_code(&sig, &links, true));
+      if let Some(doc) = doc {
+        output.push_str(&format!("{}\n\n", doc));
+      }
+      output.push_str("---\n\n");
+    }
+  }
+  output
+}
+
+/// Lay out a function signature like rustfmt: parameters one per line when the
+/// signature is too wide or has too many parameters (see [`SignatureStyle`]),
+/// followed by `) -> Output`, and `where` bounds one per line.
+///
+/// `head` is everything before the parameter list (e.g. `fn name<T>`).
+fn layout_fn_signature(
+  ctx: &RenderContext,
+  head: &str,
+  inputs: &[String],
+  output: Option<&str>,
+  where_bounds: &[String],
+) -> String {
+  let single_line = format!("{}({})", head, inputs.join(", "));
+  let wrap = match ctx.signature_style {
+    SignatureStyle::Threshold => {
+      inputs.len() > ctx.signature_max_params.unwrap_or(3)
+        || single_line.len() > ctx.signature_width.unwrap_or(80)
+    }
+    SignatureStyle::Rustfmt => {
+      let output_len = output.map_or(0, |output| " -> ".len() + output.len());
+      single_line.len() + output_len > ctx.signature_width.unwrap_or(100)
+    }
+  };
+  let mut sig = if wrap && !inputs.is_empty() {
+    let params: String = inputs
+      .iter()
+      .map(|input| format!("    {},\n", input))
+      .collect();
+    format!("{}(\n{})", head, params)
+  } else {
+    single_line
+  };
+
+  if let Some(output) = output {
+    sig.push_str(&format!(" -> {}", output));
+  }
+  if !where_bounds.is_empty() {
+    sig.push_str("\nwhere");
+    for bound in where_bounds {
+      sig.push_str(&format!("\n    {},", bound));
+    }
+  }
+  sig
+}
+
+fn format_function_signature_with_links(
+  ctx: &RenderContext,
+  name: &str,
+  f: &rustdoc_types::Function,
+  crate_data: &Crate,
+  current_item: Option<&Item>,
+) -> (String, Vec<(String, String)>) {
+  let mut sig = format!("fn {}", name);
+  let mut links = Vec::new();
+
+  let non_synthetic_params: Vec<String> = f
+    .generics
+    .params
+    .iter()
+    .filter(|p| {
+      !matches!(&p.kind, rustdoc_types::GenericParamDefKind::Lifetime { .. })
+        || !is_synthetic_lifetime(&p.name)
+    })
+    .map(format_generic_param)
+    .collect();
+
+  let mut inputs = Vec::new();
+  for (param_name, ty) in &f.sig.inputs {
+    let (type_str, type_links) = format_type_with_links(ctx, ty, crate_data, current_item);
+    links.extend(type_links);
+    inputs.push(format!("{}: {}", param_name, type_str));
+  }
+
+  if !non_synthetic_params.is_empty() {
+    sig = format!("fn {}<{}>", name, non_synthetic_params.join(", "));
+  }
+  let output = f.sig.output.as_ref().map(|output_type| {
+    let (type_str, type_links) = format_type_with_links(ctx, output_type, crate_data, current_item);
+    links.extend(type_links);
+    type_str
+  });
+  let where_bounds = format_generic_bounds(&f.generics, crate_data);
+  let sig = layout_fn_signature(ctx, &sig, &inputs, output.as_deref(), &where_bounds);
+
+  (sig, links)
+}
+
+fn format_type(ctx: &RenderContext, ty: &rustdoc_types::Type, crate_data: &Crate) -> String {
+  format_type_depth(ctx, ty, crate_data, 0)
+}
+
+fn format_type_depth(
+  ctx: &RenderContext,
+  ty: &rustdoc_types::Type,
+  crate_data: &Crate,
+  depth: usize,
+) -> String {
+  const MAX_DEPTH: usize = 50;
 
   if depth > MAX_DEPTH {
     return "...".to_string();
@@ -1666,21 +3845,21 @@ fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize)
       let short_name = get_short_type_name(&path.path);
       let link = Some(path.id)
         .as_ref()
-        .and_then(|id| generate_type_link(&path.path, id, crate_data, None));
+        .and_then(|id| generate_type_link(ctx, &path.path, id, crate_data));
       let mut result = if let Some(link) = link {
         format!("[{}]({})", short_name, link)
       } else {
         short_name
       };
       if let Some(args) = &path.args {
-        result.push_str(&format_generic_args(args, crate_data));
+        result.push_str(&format_generic_args(ctx, args, crate_data));
       }
       result
     }
     Type::DynTrait(dt) => {
       if let Some(first) = dt.traits.first() {
         let short_name = get_short_type_name(&first.trait_.path);
-        let link = generate_type_link(&first.trait_.path, &first.trait_.id, crate_data, None);
+        let link = generate_type_link(ctx, &first.trait_.path, &first.trait_.id, crate_data);
         if let Some(link) = link {
           format!("dyn [{}]({})", short_name, link)
         } else {
@@ -1696,24 +3875,30 @@ fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize)
     Type::Tuple(types) => {
       let formatted: Vec<_> = types
         .iter()
-        .map(|t| format_type_depth(t, crate_data, depth + 1))
+        .map(|t| format_type_depth(ctx, t, crate_data, depth + 1))
         .collect();
       format!("({})", formatted.join(", "))
     }
-    Type::Slice(inner) => format!("[{}]", format_type_depth(inner, crate_data, depth + 1)),
+    Type::Slice(inner) => format!("[{}]", format_type_depth(ctx, inner, crate_data, depth + 1)),
     Type::Array { type_, len } => format!(
       "[{}; {}]",
-      format_type_depth(type_, crate_data, depth + 1),
+      format_type_depth(ctx, type_, crate_data, depth + 1),
       len
     ),
-    Type::Pat { type_, .. } => format_type_depth(type_, crate_data, depth + 1),
+    Type::Pat { type_, .. } => format_type_depth(ctx, type_, crate_data, depth + 1),
     Type::ImplTrait(_bounds) => "impl Trait".to_string(),
     Type::Infer => "_".to_string(),
     Type::RawPointer { is_mutable, type_ } => {
       if *is_mutable {
-        format!("*mut {}", format_type_depth(type_, crate_data, depth + 1))
+        format!(
+          "*mut {}",
+          format_type_depth(ctx, type_, crate_data, depth + 1)
+        )
       } else {
-        format!("*const {}", format_type_depth(type_, crate_data, depth + 1))
+        format!(
+          "*const {}",
+          format_type_depth(ctx, type_, crate_data, depth + 1)
+        )
       }
     }
     Type::BorrowedRef {
@@ -1721,21 +3906,24 @@ fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize)
       is_mutable,
       type_,
     } => {
-      let lifetime_str = lifetime.as_deref().unwrap_or("");
+      let lifetime_str = lifetime
+        .as_deref()
+        .filter(|lifetime| !is_synthetic_lifetime(lifetime))
+        .unwrap_or("");
       let space = if lifetime_str.is_empty() { "" } else { " " };
       if *is_mutable {
         format!(
           "&{}{} mut {}",
           lifetime_str,
           space,
-          format_type_depth(type_, crate_data, depth + 1)
+          format_type_depth(ctx, type_, crate_data, depth + 1)
         )
       } else {
         format!(
           "&{}{}{}",
           lifetime_str,
           space,
-          format_type_depth(type_, crate_data, depth + 1)
+          format_type_depth(ctx, type_, crate_data, depth + 1)
         )
       }
     }
@@ -1747,7 +3935,7 @@ fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize)
     } => {
       if let Some(trait_) = trait_ {
         let trait_short = get_short_type_name(&trait_.path);
-        let trait_link = generate_type_link(&trait_.path, &trait_.id, crate_data, None);
+        let trait_link = generate_type_link(ctx, &trait_.path, &trait_.id, crate_data);
         let trait_part = if let Some(link) = trait_link {
           format!("[{}]({})", trait_short, link)
         } else {
@@ -1755,14 +3943,14 @@ fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize)
         };
         format!(
           "<{} as {}>::{}",
-          format_type_depth(self_type, crate_data, depth + 1),
+          format_type_depth(ctx, self_type, crate_data, depth + 1),
           trait_part,
           name
         )
       } else {
         format!(
           "{}::{}",
-          format_type_depth(self_type, crate_data, depth + 1),
+          format_type_depth(ctx, self_type, crate_data, depth + 1),
           name
         )
       }
@@ -1771,7 +3959,7 @@ fn format_type_depth(ty: &rustdoc_types::Type, crate_data: &Crate, depth: usize)
 }
 
 /// Format a type without links (for use in code blocks)
-fn format_type_plain(ty: &rustdoc_types::Type, crate_data: &Crate) -> String {
+pub(crate) fn format_type_plain(ty: &rustdoc_types::Type, crate_data: &Crate) -> String {
   use rustdoc_types::Type;
   match ty {
     Type::ResolvedPath(path) => {
@@ -1817,7 +4005,10 @@ fn format_type_plain(ty: &rustdoc_types::Type, crate_data: &Crate) -> String {
       is_mutable,
       type_,
     } => {
-      let lifetime_str = lifetime.as_deref().unwrap_or("");
+      let lifetime_str = lifetime
+        .as_deref()
+        .filter(|lifetime| !is_synthetic_lifetime(lifetime))
+        .unwrap_or("");
       let space = if lifetime_str.is_empty() { "" } else { " " };
       if *is_mutable {
         format!(
@@ -1866,7 +4057,7 @@ fn format_generic_args_plain(args: &rustdoc_types::GenericArgs, crate_data: &Cra
         let formatted: Vec<String> = args
           .iter()
           .filter_map(|arg| match arg {
-            GenericArg::Lifetime(lt) if lt != "'_" => Some(lt.clone()),
+            GenericArg::Lifetime(lt) if !is_synthetic_lifetime(lt) => Some(lt.clone()),
             GenericArg::Lifetime(_) => None,
             GenericArg::Type(ty) => Some(format_type_plain(ty, crate_data)),
             GenericArg::Const(c) => Some(c.expr.clone()),
@@ -1903,6 +4094,27 @@ fn get_short_type_name(full_path: &str) -> String {
     .to_string()
 }
 
+/// `RustCode` component showing `code` with the given links.
+///
+/// The code is escaped for the template literal of the `code` prop, as
+/// signatures can hold backticks and `${` (e.g. in constant values).
+fn rust_code(code: &str, links: &[(String, String)], inline: bool) -> String {
+  format!(
+    "<RustCode{} code={{`{}`}} links={{{}}} />\n\n",
+    if inline { " inline" } else { "" },
+    escape_template_literal(code),
+    format_links_as_json(links)
+  )
+}
+
+/// Escape source text for the `code` template literal of `RustCode`
+fn escape_template_literal(code: &str) -> String {
+  code
+    .replace('\\', "\\\\")
+    .replace('`', "\\`")
+    .replace("${", "\\${")
+}
+
 fn format_links_as_json(links: &[(String, String)]) -> String {
   if links.is_empty() {
     return "[]".to_string();
@@ -1925,20 +4137,424 @@ fn format_links_as_json(links: &[(String, String)]) -> String {
   format!("[{}]", items.join(", "))
 }
 
+/// "Parameters" and "Returns" tables of a function (`parameter_tables`), with
+/// linked types and the descriptions of the `# Arguments` and `# Returns`
+/// sections of its docs. Each table is introduced by `heading`.
+fn format_parameter_tables(
+  ctx: &RenderContext,
+  f: &rustdoc_types::Function,
+  docs: Option<&str>,
+  crate_data: &Crate,
+  current_item: Option<&Item>,
+  heading: impl Fn(&str) -> String,
+) -> String {
+  let arguments = docs.map(argument_docs).unwrap_or_default();
+  let type_cell = |ty: &rustdoc_types::Type| {
+    let (type_str, links) = format_type_with_links(ctx, ty, crate_data, current_item);
+    rust_code(&type_str, &links, true).trim_end().to_string()
+  };
+  let mut output = String::new();
+
+  // The receiver of methods needs no row
+  let inputs: Vec<_> = f
+    .sig
+    .inputs
+    .iter()
+    .filter(|(name, _)| name != "self")
+    .collect();
+  if !inputs.is_empty() {
+    output.push_str(&heading("Parameters"));
+    output.push_str("| Name | Type | Description |\n| --- | --- | --- |\n");
+    for (name, ty) in inputs {
+      let description = arguments
+        .iter()
+        .find(|(argument, _)| argument == name)
+        .map(|(_, description)| table_cell(description))
+        .unwrap_or_default();
+      output.push_str(&format!(
+        "| `{}` | {} | {} |\n",
+        name,
+        type_cell(ty),
+        description
+      ));
+    }
+    output.push('\n');
+  }
+
+  if let Some(output_type) = &f.sig.output {
+    let description = docs
+      .and_then(|docs| doc_section(docs, "Returns"))
+      .map(|returns| table_cell(&returns))
+      .unwrap_or_default();
+    output.push_str(&heading("Returns"));
+    output.push_str("| Type | Description |\n| --- | --- |\n");
+    output.push_str(&format!(
+      "| {} | {} |\n\n",
+      type_cell(output_type),
+      description
+    ));
+  }
+  output
+}
+
+/// Text of the section under the heading `name` (any level) of docs, on one line.
+fn doc_section(docs: &str, name: &str) -> Option<String> {
+  let mut lines = docs.lines().skip_while(|line| {
+    let line = line.trim();
+    !(line.starts_with('#') && line.trim_start_matches('#').trim() == name)
+  });
+  lines.next()?;
+  let text: Vec<&str> = lines
+    .take_while(|line| !line.trim_start().starts_with('#'))
+    .flat_map(str::split_whitespace)
+    .collect();
+  (!text.is_empty()).then(|| text.join(" "))
+}
+
+/// Descriptions of the parameters listed in the `# Arguments` section of docs,
+/// as `* `name` - description` (or `:`) list items.
+fn argument_docs(docs: &str) -> Vec<(String, String)> {
+  let mut arguments: Vec<(String, String)> = Vec::new();
+  let mut lines = docs.lines().skip_while(|line| {
+    let line = line.trim();
+    !(line.starts_with('#')
+      && matches!(
+        line.trim_start_matches('#').trim(),
+        "Arguments" | "Parameters"
+      ))
+  });
+  lines.next();
+  for line in lines {
+    let trimmed = line.trim();
+    if trimmed.starts_with('#') {
+      break;
+    }
+    let item = trimmed
+      .strip_prefix("* ")
+      .or_else(|| trimmed.strip_prefix("- "));
+    let argument = item.and_then(|item| {
+      let item = item.strip_prefix('`')?;
+      let (name, rest) = item.split_once('`')?;
+      let description = rest
+        .trim_start()
+        .trim_start_matches(['-', ':', '—', '–'])
+        .trim();
+      Some((name.to_string(), description.to_string()))
+    });
+    match (argument, arguments.last_mut()) {
+      (Some(argument), _) => arguments.push(argument),
+      // Continuation lines of the previous item
+      (None, Some((_, description))) if item.is_none() && !trimmed.is_empty() => {
+        description.push(' ');
+        description.push_str(trimmed);
+      }
+      _ => {}
+    }
+  }
+  arguments
+}
+
+/// Markdown text as the content of a table cell.
+fn table_cell(text: &str) -> String {
+  summary_to_html(text).replace('|', "&#124;")
+}
+
+/// Lines of field docs above which they are collapsed below their first paragraph
+const COLLAPSED_FIELD_DOCS_LINES: usize = 8;
+
+/// Format the docs of a struct field: the complete docs, collapsed in a
+/// `<details>` block when long, or only the first line with `field_summaries`.
+fn format_field_docs(ctx: &RenderContext, docs: &str) -> String {
+  let docs = docs.trim();
+  let first_line = docs.lines().next().unwrap_or("").trim();
+  if first_line.is_empty() {
+    return String::new();
+  }
+  if ctx.field_summaries || !docs.contains('\n') {
+    return format!(
+      "<div className=\"rust-field-doc\">{}</div>\n\n",
+      summary_to_html(first_line)
+    );
+  }
+
+  let collapsed = (docs.lines().count() > COLLAPSED_FIELD_DOCS_LINES)
+    .then(|| collapsed_docs(ctx, docs, "rust-field-doc", 4))
+    .flatten();
+  let docs = collapsed.unwrap_or_else(|| {
+    format!(
+      "<div className=\"rust-field-doc\">\n\n{}\n\n</div>",
+      format_docs(ctx, docs, 4)
+    )
+  });
+  format!("{}\n\n", docs)
+}
+
+/// Docs in a `<details>` block with `class`, summarized by their first
+/// paragraph (`None` for docs of a single paragraph).
+fn collapsed_docs(ctx: &RenderContext, docs: &str, class: &str, level: usize) -> Option<String> {
+  let (summary, details) = docs.trim().split_once("\n\n")?;
+  if details.trim().is_empty() {
+    return None;
+  }
+  let summary: Vec<&str> = summary.split_whitespace().collect();
+  Some(format!(
+    "<details className=\"{}\">\n<summary>{}</summary>\n\n{}\n\n</details>",
+    class,
+    summary_to_html(&summary.join(" ")),
+    format_docs(ctx, details, level)
+  ))
+}
+
+/// Format the docs of a method or associated item, in a `<details>` block
+/// with `collapse_method_docs`.
+fn format_method_docs(ctx: &RenderContext, docs: &str) -> Option<String> {
+  if docs.trim().is_empty() {
+    return None;
+  }
+  let collapsed = ctx
+    .collapse_method_docs
+    .then(|| collapsed_docs(ctx, docs, "rust-method-doc", 5))
+    .flatten();
+  Some(collapsed.unwrap_or_else(|| format_docs(ctx, docs, 5)))
+}
+
+/// Format item documentation for an item page.
+///
+/// Headings are demoted so that a top-level `#` heading ends up at `level`, and
+/// sections like `# Safety` become admonitions (see [`RenderContext::doc_admonition`]).
+fn format_docs(ctx: &RenderContext, docs: &str, level: usize) -> String {
+  let sanitized = sanitize_docs_for_mdx(docs);
+  let mut result: Vec<String> = Vec::new();
+  // Heading level of the open admonition section
+  let mut admonition: Option<usize> = None;
+  let mut fence: Option<&str> = None;
+  let mut math_fence = false;
+
+  for line in sanitized.lines() {
+    let trimmed = line.trim_start();
+    if let Some(marker) = fence {
+      if trimmed.starts_with(marker) {
+        fence = None;
+        if math_fence {
+          math_fence = false;
+          result.push("$$".to_string());
+          continue;
+        }
+      }
+      result.push(line.to_string());
+      continue;
+    }
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      fence = Some(&trimmed[..3]);
+      // KaTeX renders math blocks (`$$`) on sites using remark-math
+      math_fence = ctx.math_blocks && is_math_fence(trimmed);
+      result.push(if math_fence { "$$" } else { line }.to_string());
+      continue;
+    }
+
+    // Glossary markers are only read by the glossary page (and are invalid MDX)
+    if glossary_marker(line).is_some() {
+      continue;
+    }
+
+    let Some((heading_level, heading)) = parse_atx_heading(line) else {
+      // Closing an admonition already leaves a blank line behind
+      if !(line.trim().is_empty() && result.last().is_some_and(|last| last.is_empty())) {
+        result.push(line.to_string());
+      }
+      continue;
+    };
+
+    if admonition.is_some_and(|open| heading_level <= open) {
+      close_admonition(&mut result);
+      admonition = None;
+    }
+    match ctx.doc_admonition(heading).filter(|_| admonition.is_none()) {
+      Some(kind) => {
+        if result.last().is_some_and(|last| !last.is_empty()) {
+          result.push(String::new());
+        }
+        result.push(ctx.docusaurus_major.admonition(kind, heading));
+        admonition = Some(heading_level);
+      }
+      None => {
+        let demoted = (heading_level + level - 1).min(6);
+        result.push(format!("{} {}", "#".repeat(demoted), heading));
+      }
+    }
+  }
+
+  if admonition.is_some() {
+    close_admonition(&mut result);
+  }
+  result.join("\n").trim_end().to_string()
+}
+
+/// Whether a code fence opens a block of TeX math (e.g. ```` ```math ````).
+fn is_math_fence(fence: &str) -> bool {
+  let language = fence
+    .trim_start_matches(['`', '~'])
+    .split([',', ' '])
+    .next()
+    .unwrap_or_default();
+  matches!(language, "math" | "latex" | "katex" | "tex")
+}
+
+/// Parse an ATX heading (`## Title`) into its level and title.
+fn parse_atx_heading(line: &str) -> Option<(usize, &str)> {
+  let level = line.chars().take_while(|&c| c == '#').count();
+  if level == 0 || level > 6 {
+    return None;
+  }
+  let title = line[level..].strip_prefix(' ')?.trim();
+  (!title.is_empty()).then(|| (level, title.trim_end_matches('#').trim_end()))
+}
+
+/// Close an admonition opened by [`format_docs`].
+fn close_admonition(result: &mut Vec<String>) {
+  while result.last().is_some_and(|last| last.is_empty()) {
+    result.pop();
+  }
+  result.push(":::".to_string());
+  result.push(String::new());
+}
+
+/// Summary of the `<details>` blocks holding the JSON schema of a type
+const JSON_SCHEMA_SUMMARY: &str = "JSON schema";
+
+/// Parse a `<details><summary>JSON schema</summary>` block starting at line `start`,
+/// returning the schema lines and the index of the line after the block.
+fn parse_json_schema_block<'a>(lines: &[&'a str], start: usize) -> Option<(Vec<&'a str>, usize)> {
+  let summary = format!("<summary>{}</summary>", JSON_SCHEMA_SUMMARY);
+  let mut i = start;
+  let opening = lines[i].trim().strip_prefix("<details>")?.trim();
+  if opening.is_empty() {
+    i += 1;
+    if lines.get(i)?.trim() != summary {
+      return None;
+    }
+  } else if opening != summary {
+    return None;
+  }
+  i += 1;
+
+  let skip_blank = |mut i: usize| {
+    while lines.get(i).is_some_and(|line| line.trim().is_empty()) {
+      i += 1;
+    }
+    i
+  };
+  i = skip_blank(i);
+  if lines.get(i)?.trim() != "```json" {
+    return None;
+  }
+  let schema_start = i + 1;
+  let schema_end = schema_start
+    + lines[schema_start..]
+      .iter()
+      .position(|line| line.trim() == "```")?;
+  i = skip_blank(schema_end + 1);
+  if lines.get(i)?.trim() != "</details>" {
+    return None;
+  }
+  Some((lines[schema_start..schema_end].to_vec(), i + 1))
+}
+
+/// Move the JSON schemas of a page to `.schema.json` files next to it, linked
+/// from the page, returning the page and its schema files.
+fn extract_json_schema_assets(path: &str, content: &str) -> Vec<(String, String)> {
+  let opening = format!("<summary>{}</summary>\n\n```json\n", JSON_SCHEMA_SUMMARY);
+  let (dir, page_file) = match path.rsplit_once('/') {
+    Some((dir, page_file)) => (format!("{}/", dir), page_file),
+    None => (String::new(), path),
+  };
+  let stem = page_file
+    .rsplit_once('.')
+    .map_or(page_file, |(stem, _)| stem);
+  let file_name = |n: usize| match n {
+    1 => format!("{}.schema.json", stem),
+    n => format!("{}.schema-{}.json", stem, n),
+  };
+
+  let mut page = String::new();
+  let mut schemas = Vec::new();
+  let mut rest = content;
+  while let Some(start) = rest.find(&opening).map(|i| i + opening.len()) {
+    let Some(end) = rest[start..].find("```").map(|i| start + i) else {
+      break;
+    };
+    let name = file_name(schemas.len() + 1);
+    // Keep the code block, and link the file right after it
+    let block_end = end + "```".len();
+    page.push_str(&rest[..block_end]);
+    page.push_str(&format!(
+      "\n\n[Download {}](./{})",
+      JSON_SCHEMA_SUMMARY, name
+    ));
+    schemas.push((format!("{}{}", dir, name), rest[start..end].to_string()));
+    rest = &rest[block_end..];
+  }
+  page.push_str(rest);
+
+  let mut files = vec![(path.to_string(), page)];
+  files.extend(schemas);
+  files
+}
+
 /// Sanitize documentation comments for MDX compatibility
 ///
 /// MDX is stricter than regular markdown about HTML tags. This function ensures
 /// that HTML blocks (like <details>) are properly separated from text paragraphs
-/// with blank lines.
+/// with blank lines, and escapes the `<`, `>`, `{` and `}` of prose (see
+/// [`escape_mdx_line`]). Fenced code blocks (e.g. `mermaid` diagrams) are left untouched.
 fn sanitize_docs_for_mdx(docs: &str) -> String {
   let lines: Vec<&str> = docs.lines().collect();
   let mut result: Vec<String> = Vec::new();
+  let mut fence: Option<&str> = None;
+  let mut in_comment = false;
   let mut i = 0;
 
   while i < lines.len() {
     let current_line = lines[i];
     let trimmed = current_line.trim();
 
+    if let Some(marker) = fence {
+      if trimmed.starts_with(marker) {
+        fence = None;
+      }
+      result.push(current_line.to_string());
+      i += 1;
+      continue;
+    }
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      fence = Some(&trimmed[..3]);
+      result.push(current_line.to_string());
+      i += 1;
+      continue;
+    }
+
+    // JSON schema blocks (as generated for schemars types) become a collapsible
+    // Docusaurus `<details>` with a highlighted code block
+    if let Some((schema, next)) = parse_json_schema_block(&lines, i) {
+      log::trace!(target: "mdx", "Rendering JSON schema block at line {} as <details>", i + 1);
+      if result.last().is_some_and(|last| !last.is_empty()) {
+        result.push(String::new());
+      }
+      result.push("<details>".to_string());
+      result.push(format!("<summary>{}</summary>", JSON_SCHEMA_SUMMARY));
+      result.push(String::new());
+      result.push("```json".to_string());
+      result.extend(schema.iter().map(|line| line.to_string()));
+      result.push("```".to_string());
+      result.push(String::new());
+      result.push("</details>".to_string());
+      if next < lines.len() && !lines[next].trim().is_empty() {
+        result.push(String::new());
+      }
+      i = next;
+      continue;
+    }
+
     // Check if this line starts with an HTML opening tag
     if trimmed.starts_with('<') && !trimmed.starts_with("</") {
       // Extract tag name (e.g., "details" from "<details>")
@@ -1952,6 +4568,12 @@ fn sanitize_docs_for_mdx(docs: &str) -> String {
         ) {
           // Ensure blank line before the HTML block
           if !result.is_empty() && !result.last().unwrap().is_empty() {
+            log::debug!(
+              target: "mdx",
+              "Separating <{}> block at line {} from the preceding paragraph",
+              tag_name,
+              i + 1
+            );
             result.push(String::new());
           }
 
@@ -2032,433 +4654,531 @@ fn sanitize_docs_for_mdx(docs: &str) -> String {
       }
     }
 
-    // Preserve original line (don't trim it)
-    result.push(current_line.to_string());
+    // Preserve original line (don't trim it), escaping its prose
+    result.push(escape_mdx_line(current_line, &mut in_comment));
     i += 1;
   }
 
   result.join("\n")
 }
 
-fn generate_type_link(
-  full_path: &str,
-  item_id: &Id,
-  crate_data: &Crate,
-  current_item: Option<&Item>,
-) -> Option<String> {
-  generate_type_link_depth(full_path, item_id, crate_data, current_item, 0)
-}
-
-#[allow(clippy::bind_instead_of_map)]
-fn generate_type_link_depth(
-  full_path: &str,
-  item_id: &Id,
-  crate_data: &Crate,
-  current_item: Option<&Item>,
-  depth: usize,
-) -> Option<String> {
-  const MAX_DEPTH: usize = 10;
-
-  if depth >= MAX_DEPTH {
-    return None;
-  }
-
-  // Always prefer the path from crate_data.paths when available,
-  // as it contains the most accurate full path information
-  // BUT: if depth > 0, we're in a recursive call and should trust the provided full_path
-  let full_path = if depth == 0 {
-    if let Some(path_info) = crate_data.paths.get(item_id) {
-      path_info.path.join("::")
-    } else if full_path.starts_with("$crate") {
-      // Fallback for $crate placeholder
-      full_path.replace("$crate", "unknown")
-    } else {
-      full_path.to_string()
+/// HTML tags kept as they are in doc prose, as MDX renders them as elements.
+const MDX_HTML_TAGS: &[&str] = &[
+  "a",
+  "abbr",
+  "b",
+  "blockquote",
+  "br",
+  "code",
+  "dd",
+  "del",
+  "details",
+  "div",
+  "dl",
+  "dt",
+  "em",
+  "figcaption",
+  "figure",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "hr",
+  "i",
+  "img",
+  "ins",
+  "kbd",
+  "li",
+  "mark",
+  "ol",
+  "p",
+  "picture",
+  "pre",
+  "s",
+  "small",
+  "source",
+  "span",
+  "strong",
+  "sub",
+  "summary",
+  "sup",
+  "table",
+  "tbody",
+  "td",
+  "tfoot",
+  "th",
+  "thead",
+  "tr",
+  "u",
+  "ul",
+  "var",
+];
+
+/// Escape the characters of a doc line that MDX would parse as JSX or expressions.
+///
+/// `<`, `>`, `{` and `}` become HTML entities, except in code spans, backslash
+/// escapes, blockquote markers, HTML comments and tags of [`MDX_HTML_TAGS`].
+/// Autolinks (`<https://...>`), which MDX does not support, become links.
+/// `in_comment` tracks HTML comments spanning several lines.
+fn escape_mdx_line(line: &str, in_comment: &mut bool) -> String {
+  let mut output = String::new();
+  let mut rest = line;
+
+  if *in_comment {
+    match rest.find("-->") {
+      Some(end) => {
+        *in_comment = false;
+        output.push_str(&rest[..end + 3]);
+        rest = &rest[end + 3..];
+      }
+      None => return line.to_string(),
     }
   } else {
-    full_path.to_string()
-  };
-  let full_path = full_path.as_str();
-
-  // Check if this is a local or external type by looking at crate_id in paths
-  let is_local = if let Some(path_info) = crate_data.paths.get(item_id) {
-    path_info.crate_id == 0
-  } else {
-    // If not in paths, check if it's in the current crate's index
-    crate_data.index.contains_key(item_id)
-  };
+    // Blockquote markers
+    let quote = rest.len() - rest.trim_start_matches([' ', '\t', '>']).len();
+    output.push_str(&rest[..quote]);
+    rest = &rest[quote..];
+  }
 
-  // Handle local types (from the current crate)
-  if is_local {
-    if let Some(item) = crate_data.index.get(item_id) {
-      // Local type - we need to build the full path from the item's location
-      // Get the crate name
-      let _crate_name = crate_data.index.get(&crate_data.root)?.name.as_ref()?;
-
-      // Get the item prefix (struct., enum., trait., etc.)
-      let prefix = get_item_prefix(item);
-
-      // Extract module path for the target item
-      // First try from crate_data.paths as it's more reliable
-      let target_module_path = if let Some(path_info) = crate_data.paths.get(item_id) {
-        // Get the full path from paths (e.g., ["test_crate", "patterns", "Builder"])
-        let path_components: Vec<&str> = path_info.path.iter().map(|s| s.as_str()).collect();
-        if path_components.len() > 2 {
-          // Skip crate name and item name, join the middle parts
-          // e.g., ["test_crate", "patterns", "Builder"] -> "patterns"
-          Some(path_components[1..path_components.len() - 1].join("/"))
-        } else {
-          // Root module (only crate name and item name)
-          Some("".to_string())
+  while let Some(c) = rest.chars().next() {
+    match c {
+      '`' => {
+        let ticks = rest.len() - rest.trim_start_matches('`').len();
+        let end = rest[ticks..]
+          .find(&rest[..ticks])
+          .map_or(ticks, |end| ticks + end + ticks);
+        output.push_str(&rest[..end]);
+        rest = &rest[end..];
+      }
+      '\\' if rest[1..].starts_with(|c: char| c.is_ascii_punctuation()) => {
+        output.push_str(&rest[..2]);
+        rest = &rest[2..];
+      }
+      '<' if rest.starts_with("<!--") => match rest.find("-->") {
+        Some(end) => {
+          output.push_str(&rest[..end + 3]);
+          rest = &rest[end + 3..];
         }
-      } else if let Some(span) = &item.span {
-        // Fallback to span if paths is not available
-        let span_filename = &span.filename;
-        if let Some(filename_str) = span_filename.to_str() {
-          if let Some(src_idx) = filename_str.rfind("/src/") {
-            let after_src = &filename_str[src_idx + 5..];
-            if let Some(rs_idx) = after_src.rfind(".rs") {
-              let module_path = &after_src[..rs_idx];
-              if module_path == "lib" || module_path == "main" {
-                Some("".to_string())
-              } else {
-                Some(module_path.to_string())
-              }
-            } else {
-              None
-            }
-          } else {
-            None
-          }
+        None => {
+          *in_comment = true;
+          output.push_str(rest);
+          rest = "";
+        }
+      },
+      '<' => {
+        let tag_end = rest.find('>');
+        let inner = tag_end.map_or("", |end| &rest[1..end]);
+        let name = inner.strip_prefix('/').unwrap_or(inner);
+        let name_end = name
+          .find(|c: char| c.is_whitespace() || c == '/')
+          .unwrap_or(name.len());
+        if let Some(end) = tag_end
+          && MDX_HTML_TAGS.contains(&name[..name_end].to_ascii_lowercase().as_str())
+        {
+          output.push_str(&rest[..=end]);
+          rest = &rest[end + 1..];
+        } else if let Some(end) = tag_end
+          && (inner.contains("://") || inner.starts_with("mailto:"))
+          && !inner.contains(char::is_whitespace)
+        {
+          output.push_str(&format!("[{}]({})", inner, inner));
+          rest = &rest[end + 1..];
         } else {
-          None
+          output.push_str("&lt;");
+          rest = &rest[1..];
         }
-      } else {
-        None
-      };
-
-      // Extract module path for the current item (if provided)
-      let _current_module_path = if let Some(current) = current_item {
-        // First try to get it from span
-        if let Some(span) = &current.span {
-          let span_filename = &span.filename;
-          if let Some(filename_str) = span_filename.to_str() {
-            if let Some(src_idx) = filename_str.rfind("/src/") {
-              let after_src = &filename_str[src_idx + 5..];
-              if let Some(rs_idx) = after_src.rfind(".rs") {
-                let module_path = &after_src[..rs_idx];
-                if module_path == "lib" || module_path == "main" {
-                  Some("".to_string())
-                } else {
-                  Some(module_path.to_string())
-                }
-              } else {
-                None
-              }
-            } else {
-              None
-            }
-          } else {
-            None
-          }
-        } else {
-          // If no span (e.g., re-export), try to infer from the item's path in paths
-          // Get the item's id and look it up in paths
-          crate_data.paths.get(&current.id).map(|path_info| {
-            let full_path: Vec<&str> = path_info.path.iter().map(|s| s.as_str()).collect();
-            if full_path.len() > 2 {
-              // Skip crate name and item name, join the middle parts
-              full_path[1..full_path.len() - 1].join("/")
-            } else {
-              // Root module
-              String::new()
-            }
-          })
-        }
-      } else {
-        None
-      };
-
-      // The full_path might be just the type name or include module path
-      let path_segments: Vec<&str> = full_path.split("::").collect();
-      let type_name = path_segments.last().unwrap_or(&full_path);
-
-      // Generate absolute link from crate root
-      // This works for both original files and re-exports without any path calculations
-      if let Some(target_path) = target_module_path {
-        let crate_name = path_segments.first().unwrap_or(&"");
-
-        let base = BASE_PATH.with(|bp| bp.borrow().clone());
-        let base_prefix = if base.is_empty() { String::new() } else { base };
-
-        if target_path.is_empty() {
-          // Target is in root module: /base_path/crate_name/struct.TypeName
-          return Some(format!(
-            "{}/{}/{}{}",
-            base_prefix, crate_name, prefix, type_name
-          ));
-        } else {
-          // Target is in a nested module: /base_path/crate_name/module/path/struct.TypeName
-          return Some(format!(
-            "{}/{}/{}/{}{}",
-            base_prefix, crate_name, target_path, prefix, type_name
-          ));
-        }
-      } else {
-        // Fallback: use crate root path
-        let crate_name = path_segments.first().unwrap_or(&"");
-        let base = BASE_PATH.with(|bp| bp.borrow().clone());
-        let base_prefix = if base.is_empty() { String::new() } else { base };
-        return Some(format!(
-          "{}/{}/{}{}",
-          base_prefix, crate_name, prefix, type_name
-        ));
       }
-    } // end if let Some(item)
-  } // end if is_local
-
-  // External type - check if it's std/core/alloc first, then try docs.rs
-  let path_parts: Vec<&str> = full_path.split("::").collect();
-
-  if path_parts.len() >= 2 {
-    let crate_name = path_parts[0];
-
-    // Check if it's a standard library crate (std, core, alloc)
-    if crate_name == "std" || crate_name == "core" || crate_name == "alloc" {
-      // Build doc.rust-lang.org URL
-      // e.g., std::sync::Arc -> https://doc.rust-lang.org/std/sync/struct.Arc.html
-      // e.g., core::fmt::Formatter -> https://doc.rust-lang.org/core/fmt/struct.Formatter.html
-
-      let type_name = path_parts.last()?;
-
-      // Special handling for type aliases that should redirect to their canonical type
-      // Only redirect specific known aliases, not all instances of these types
-      if full_path == "core::fmt::Result" || full_path == "std::fmt::Result" {
-        // core::fmt::Result is an alias for Result<(), fmt::Error>
-        // Better to link to the generic Result documentation
-        return Some("https://doc.rust-lang.org/std/result/enum.Result.html".to_string());
+      '>' | '{' | '}' => {
+        output.push_str(match c {
+          '>' => "&gt;",
+          '{' => "&#123;",
+          _ => "&#125;",
+        });
+        rest = &rest[1..];
+      }
+      _ => {
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
       }
+    }
+  }
+  output
+}
 
-      // For core types, prefer linking to std documentation when available
-      // (std is re-exported and more familiar to users)
-      if crate_name == "core" {
-        match full_path {
-          "core::result::Result" => {
-            return Some("https://doc.rust-lang.org/std/result/enum.Result.html".to_string());
+/// Render the inline Markdown of a one-line doc summary as HTML, for summaries
+/// embedded in JSX rows (`<div>`), where MDX does not reliably parse Markdown.
+///
+/// Code spans become `<code>`, `**strong**` and `*emphasis*` become tags, links
+/// to URLs become `<a>` and intra-doc links keep only their label.
+fn summary_to_html(line: &str) -> String {
+  let mut output = String::new();
+  let mut rest = line.trim();
+
+  while let Some(c) = rest.chars().next() {
+    match c {
+      '`' => {
+        let ticks = rest.len() - rest.trim_start_matches('`').len();
+        let fence = &rest[..ticks];
+        match rest[ticks..].find(fence) {
+          Some(end) => {
+            let code = rest[ticks..ticks + end].trim();
+            output.push_str(&format!("<code>{}</code>", escape_html(code)));
+            rest = &rest[ticks + end + ticks..];
           }
-          "core::option::Option" => {
-            return Some("https://doc.rust-lang.org/std/option/enum.Option.html".to_string());
+          None => {
+            output.push_str(fence);
+            rest = &rest[ticks..];
           }
-          _ => {}
         }
       }
-
-      // Filter out internal implementation modules
-      let mut module_parts: Vec<&str> = path_parts[1..path_parts.len() - 1].to_vec();
-      let internal_modules = ["bounded", "unbounded", "inner", "private", "imp"];
-      module_parts.retain(|part| !internal_modules.contains(part));
-      let module_path = module_parts.join("/");
-
-      // Try to guess the item type from common patterns
-      let item_type =
-        if type_name.ends_with("Error") || *type_name == "Option" || *type_name == "Result" {
-          "enum"
+      '*' => {
+        let (marker, tag) = if rest.starts_with("**") {
+          ("**", "strong")
         } else {
-          "struct" // Default to struct for most std types
+          ("*", "em")
         };
-
-      return Some(format!(
-        "https://doc.rust-lang.org/{}/{}/{}.{}.html",
-        crate_name, module_path, item_type, type_name
-      ));
+        let inner = &rest[marker.len()..];
+        match inner
+          .find(marker)
+          .filter(|&end| end > 0 && !inner.starts_with(' '))
+        {
+          Some(end) => {
+            output.push_str(&format!(
+              "<{}>{}</{}>",
+              tag,
+              summary_to_html(&inner[..end]),
+              tag
+            ));
+            rest = &inner[end + marker.len()..];
+          }
+          None => {
+            output.push_str(marker);
+            rest = inner;
+          }
+        }
+      }
+      '[' => {
+        let Some(label_end) = rest.find(']') else {
+          output.push('[');
+          rest = &rest[1..];
+          continue;
+        };
+        let label = summary_to_html(&rest[1..label_end]);
+        let after = &rest[label_end + 1..];
+        if let Some(target) = after.strip_prefix('(')
+          && let Some(target_end) = target.find(')')
+        {
+          let url = &target[..target_end];
+          if url.contains("://") || url.starts_with('#') {
+            output.push_str(&format!("<a href=\"{}\">{}</a>", url, label));
+          } else {
+            output.push_str(&label);
+          }
+          rest = &target[target_end + 1..];
+        } else if let Some(reference) = after.strip_prefix('[')
+          && let Some(reference_end) = reference.find(']')
+        {
+          output.push_str(&label);
+          rest = &reference[reference_end + 1..];
+        } else {
+          output.push_str(&label);
+          rest = after;
+        }
+      }
+      '{' | '}' => {
+        output.push_str(if c == '{' { "&#123;" } else { "&#125;" });
+        rest = &rest[1..];
+      }
+      _ => {
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
+      }
     }
+  }
+  output
+}
 
-    // External crate - use crate_id to get the REAL crate name
-    // (not the first path segment which might be a module name)
-    let real_crate_name = if let Some(path_info) = crate_data.paths.get(item_id) {
-      if path_info.crate_id != 0 {
-        // It's from an external crate - look up the real name
-        crate_data
-          .external_crates
-          .get(&path_info.crate_id)
-          .map(|c| c.name.as_str())
-          .unwrap_or(crate_name)
-      } else {
-        // It's from the current crate
-        crate_name
+/// Plain text of the first paragraph of docs, for the `description` of a page,
+/// shortened to about 160 characters (the length shown in search results).
+fn doc_description(docs: &str) -> Option<String> {
+  let paragraph: Vec<&str> = docs
+    .lines()
+    .map(str::trim)
+    .skip_while(|line| line.is_empty())
+    .take_while(|line| !line.is_empty())
+    .collect();
+  if paragraph
+    .first()
+    .is_none_or(|line| line.starts_with('#') || line.starts_with("```") || line.starts_with('<'))
+  {
+    return None;
+  }
+
+  // Markdown of the paragraph: code spans, emphasis and links keep their text
+  let mut text = String::new();
+  let mut rest = paragraph.join(" ");
+  while let Some(start) = rest.find('[') {
+    text.push_str(&rest[..start]);
+    let Some(label_end) = rest[start..].find(']').map(|i| start + i) else {
+      text.push_str(&rest[start..]);
+      rest.clear();
+      break;
+    };
+    text.push_str(&rest[start + 1..label_end]);
+    let after = &rest[label_end + 1..];
+    let skip = match after.chars().next() {
+      Some(open @ ('(' | '[')) => {
+        let close = if open == '(' { ')' } else { ']' };
+        after.find(close).map_or(0, |i| i + 1)
       }
-    } else {
-      crate_name
+      _ => 0,
     };
+    rest = after[skip..].to_string();
+  }
+  text.push_str(&rest);
+  let text = text.replace(['`', '*'], "");
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if text.is_empty() {
+    return None;
+  }
 
-    // Check if this external crate is part of the workspace
-    // If so, generate an internal link instead of docs.rs
-    // Note: Normalize both names by replacing hyphens with underscores
-    // because crate names in Cargo.toml use hyphens but rustdoc uses underscores
-    let normalized_crate_name = real_crate_name.replace('-', "_");
-    let is_workspace_crate = WORKSPACE_CRATES.with(|wc| {
-      wc.borrow().iter().any(|c| {
-        let normalized_c = c.replace('-', "_");
-        normalized_c == normalized_crate_name
-      })
-    });
+  const MAX_LENGTH: usize = 160;
+  if text.chars().count() <= MAX_LENGTH {
+    return Some(text);
+  }
+  let mut shortened: String = text.chars().take(MAX_LENGTH).collect();
+  if let Some(space) = shortened.rfind(' ') {
+    shortened.truncate(space);
+  }
+  Some(format!(
+    "{}…",
+    shortened.trim_end_matches([',', ';', ':', '.'])
+  ))
+}
 
-    if is_workspace_crate {
-      // Generate internal link for workspace crate
-      // Get the item prefix
-      let prefix = crate_data
-        .paths
-        .get(item_id)
-        .map(|p| match p.kind {
-          rustdoc_types::ItemKind::Struct => "struct.",
-          rustdoc_types::ItemKind::Enum => "enum.",
-          rustdoc_types::ItemKind::Trait => "trait.",
-          rustdoc_types::ItemKind::Function => "fn.",
-          rustdoc_types::ItemKind::TypeAlias => "type.",
-          rustdoc_types::ItemKind::Constant => "constant.",
-          _ => "struct.",
-        })
-        .unwrap_or("struct.");
+/// Double-quoted YAML string of `text`.
+fn yaml_string(text: &str) -> String {
+  format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
 
-      let type_name = path_parts.last()?;
+/// Escape text for use inside an HTML element in MDX.
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('{', "&#123;")
+    .replace('}', "&#125;")
+}
 
-      // Get module path
-      let mut module_parts: Vec<&str> = path_parts[1..path_parts.len() - 1].to_vec();
-      let internal_modules = ["bounded", "unbounded", "inner", "private", "imp"];
-      module_parts.retain(|part| !internal_modules.contains(part));
-      let module_path = module_parts.join("/");
+/// Link of a glob re-export of a module of another crate (`pub use serde::*`)
+/// to the module's page on docs.rs (or its `extern_urls` mirror), or on
+/// doc.rust-lang.org for the standard library.
+///
+/// Re-exports without a target in the rustdoc JSON are resolved from their source path.
+fn external_glob_link(
+  ctx: &RenderContext,
+  import: &rustdoc_types::Use,
+  crate_data: &Crate,
+) -> Option<String> {
+  if !import.is_glob {
+    return None;
+  }
+  let summary = match &import.id {
+    Some(id) if crate_data.index.contains_key(id) => return None,
+    Some(id) => crate_data.paths.get(id),
+    None => None,
+  };
+  let segments: Vec<&str> = match summary {
+    Some(summary) if summary.kind != rustdoc_types::ItemKind::Module => return None,
+    Some(summary) => summary.path.iter().map(String::as_str).collect(),
+    None => import.source.trim_start_matches("::").split("::").collect(),
+  };
+  let crate_name = *segments.first()?;
 
-      let base = BASE_PATH.with(|bp| bp.borrow().clone());
-      let base_prefix = if base.is_empty() { String::new() } else { base };
+  if matches!(crate_name, "std" | "core" | "alloc") {
+    return Some(format!(
+      "https://doc.rust-lang.org/{}/index.html",
+      segments.join("/")
+    ));
+  }
+  if !crate_data
+    .external_crates
+    .values()
+    .any(|external| external.name == crate_name)
+  {
+    return None;
+  }
+  Some(format!(
+    "{}/{}/index.html",
+    ctx.link_resolver().extern_docs_root(crate_name),
+    segments.join("/")
+  ))
+}
 
-      if module_path.is_empty() {
-        // Top-level type: /base_path/crate_name/struct.TypeName
-        return Some(format!(
-          "{}/{}/{}{}",
-          base_prefix, real_crate_name, prefix, type_name
-        ));
-      } else {
-        // Nested module: /base_path/crate_name/module/path/struct.TypeName
-        return Some(format!(
-          "{}/{}/{}/{}{}",
-          base_prefix, real_crate_name, module_path, prefix, type_name
-        ));
-      }
-    }
+/// The enum declaring the variant `variant_id`, `None` for other items.
+fn variant_enum<'a>(variant_id: &Id, crate_data: &'a Crate) -> Option<(&'a Id, &'a Item)> {
+  if !matches!(
+    crate_data.index.get(variant_id)?.inner,
+    ItemEnum::Variant(_)
+  ) {
+    return None;
+  }
+  crate_data
+    .index
+    .iter()
+    .find(|(_, item)| matches!(&item.inner, ItemEnum::Enum(e) if e.variants.contains(variant_id)))
+}
 
-    // Not a workspace crate - generate docs.rs link
-    // Try to get the item kind from paths to generate correct URL
-    let item_kind = crate_data
-      .paths
-      .get(item_id)
-      .map(|p| &p.kind)
-      .and_then(|k| match k {
-        rustdoc_types::ItemKind::Struct => Some("struct"),
-        rustdoc_types::ItemKind::Enum => Some("enum"),
-        rustdoc_types::ItemKind::Trait => Some("trait"),
-        rustdoc_types::ItemKind::Function => Some("fn"),
-        rustdoc_types::ItemKind::TypeAlias => Some("type"),
-        rustdoc_types::ItemKind::Constant => Some("constant"),
-        _ => Some("struct"), // Default
-      })
-      .unwrap_or("struct");
+/// Whether `id` is a variant of a public enum (variants have no visibility of their own).
+fn is_public_variant(id: &Id, crate_data: &Crate) -> bool {
+  variant_enum(id, crate_data).is_some_and(|(_, enum_item)| is_public(enum_item))
+}
 
-    let type_name = path_parts.last()?;
+/// Anchor of a variant on the page of its enum.
+fn variant_anchor(name: &str) -> String {
+  format!("variant.{}", name)
+}
 
-    // Get module path, filtering out common internal implementation modules
-    let mut module_parts: Vec<&str> = path_parts[1..path_parts.len() - 1].to_vec();
+/// Nested list of the fields of a variant with their docs, like rustdoc's
+/// `variant.Name.field.name` entries. Empty unless a field is documented, as
+/// the variant line already shows the fields.
+fn format_variant_field_docs(variant: &Item, variant_name: &str, crate_data: &Crate) -> String {
+  let field_ids: Vec<&Id> = match &variant.inner {
+    ItemEnum::Variant(v) => match &v.kind {
+      rustdoc_types::VariantKind::Plain => Vec::new(),
+      rustdoc_types::VariantKind::Tuple(fields) => fields.iter().flatten().collect(),
+      rustdoc_types::VariantKind::Struct { fields, .. } => fields.iter().collect(),
+    },
+    _ => Vec::new(),
+  };
+  let fields: Vec<&Item> = field_ids
+    .into_iter()
+    .filter_map(|id| crate_data.index.get(id))
+    .collect();
+  if !fields.iter().any(|field| {
+    field
+      .docs
+      .as_deref()
+      .is_some_and(|docs| !docs.trim().is_empty())
+  }) {
+    return String::new();
+  }
 
-    // Remove common internal module names that are typically not in public re-exports
-    // These are often implementation details that docs.rs doesn't expose in URLs
-    let internal_modules = ["bounded", "unbounded", "inner", "private", "imp"];
-    module_parts.retain(|part| !internal_modules.contains(part));
+  let mut output = String::new();
+  for field in fields {
+    let (Some(field_name), ItemEnum::StructField(ty)) = (&field.name, &field.inner) else {
+      continue;
+    };
+    output.push_str(&format!(
+      "  - <span id=\"{}.field.{}\"></span>`{}: {}`",
+      variant_anchor(variant_name),
+      field_name,
+      field_name,
+      format_type_plain(ty, crate_data)
+    ));
+    if let Some(docs) = &field.docs {
+      let first_line = docs.lines().next().unwrap_or("").trim();
+      if !first_line.is_empty() {
+        output.push_str(&format!(" - {}", first_line));
+      }
+    }
+    output.push('\n');
+  }
+  output
+}
 
-    let module_path = module_parts.join("/");
+/// Links of a re-export of enum variants (`pub use Status::Active` or
+/// `pub use Status::*`), pointing to the variant anchors of the enum page
+/// instead of standalone pages, along with the list of glob-imported variants.
+///
+/// Returns `None` for re-exports of other items.
+fn variant_reexport_links(
+  ctx: &RenderContext,
+  import: &rustdoc_types::Use,
+  crate_data: &Crate,
+) -> Option<(Vec<(String, String)>, String)> {
+  let import_id = import.id.as_ref()?;
+  let imported_item = crate_data.index.get(import_id)?;
+  let source_segments: Vec<&str> = import.source.split("::").collect();
 
-    // Format: https://docs.rs/crate_name/latest/crate_name/module/path/struct.TypeName.html
-    if module_path.is_empty() {
-      // Top-level type in crate
-      return Some(format!(
-        "https://docs.rs/{}/latest/{}/{}.{}.html",
-        real_crate_name, real_crate_name, item_kind, type_name
-      ));
-    } else {
-      return Some(format!(
-        "https://docs.rs/{}/latest/{}/{}/{}.{}.html",
-        real_crate_name, real_crate_name, module_path, item_kind, type_name
-      ));
+  if let ItemEnum::Enum(e) = &imported_item.inner {
+    if !import.is_glob {
+      return None;
     }
+    let enum_name = imported_item.name.as_deref()?;
+    let enum_link = generate_type_link(ctx, &import.source, import_id, crate_data)?;
+    let variants: Vec<String> = e
+      .variants
+      .iter()
+      .filter_map(|id| crate_data.index.get(id)?.name.as_deref())
+      .map(|name| {
+        format!(
+          "<Link to=\"{}#{}\">{}</Link>",
+          enum_link,
+          variant_anchor(name),
+          name
+        )
+      })
+      .collect();
+    let variants = match variants.is_empty() {
+      true => String::new(),
+      false => format!("<div>Variants: {}</div>\n\n", variants.join(", ")),
+    };
+    return Some((vec![(enum_name.to_string(), enum_link)], variants));
   }
 
-  // Single-segment path - try to find in paths first (could be from any crate)
-  if path_parts.len() == 1 {
-    let type_name = path_parts[0];
+  let (enum_id, enum_item) = variant_enum(import_id, crate_data)?;
+  let enum_name = enum_item.name.as_deref()?;
+  let variant_name = imported_item.name.as_deref()?;
+  let enum_path = source_segments[..source_segments.len().saturating_sub(1)].join("::");
+  let enum_link = generate_type_link(ctx, &enum_path, enum_id, crate_data)?;
+  let variant_link = format!("{}#{}", enum_link, variant_anchor(variant_name));
+  Some((
+    vec![
+      (enum_name.to_string(), enum_link),
+      (variant_name.to_string(), variant_link),
+    ],
+    String::new(),
+  ))
+}
 
-    // Check if we have this type in paths (could be external or std)
-    if let Some(path_info) = crate_data.paths.get(item_id) {
-      let full_path_from_paths = path_info.path.join("::");
-      // Only recurse if the resolved path is different from the input path
-      if full_path_from_paths != full_path {
-        // Recursively call with the full path
-        return generate_type_link_depth(
-          &full_path_from_paths,
-          item_id,
-          crate_data,
-          current_item,
-          depth + 1,
-        );
-      }
-    }
+/// URL of the page of a local item, as linked by the converter.
+pub(crate) fn item_url(ctx: &RenderContext, crate_data: &Crate, id: &Id) -> Option<String> {
+  let path = crate_data.paths.get(id)?.path.join("::");
+  ctx.link_resolver().resolve(&path, id, crate_data)
+}
 
-    // Fallback: common std library types (for backward compatibility)
-    match type_name {
-      "String" => {
-        return Some("https://doc.rust-lang.org/std/string/struct.String.html".to_string());
-      }
-      "Vec" => return Some("https://doc.rust-lang.org/std/vec/struct.Vec.html".to_string()),
-      "Option" => return Some("https://doc.rust-lang.org/std/option/enum.Option.html".to_string()),
-      "Result" => return Some("https://doc.rust-lang.org/std/result/enum.Result.html".to_string()),
-      "Box" => return Some("https://doc.rust-lang.org/std/boxed/struct.Box.html".to_string()),
-      "Rc" => return Some("https://doc.rust-lang.org/std/rc/struct.Rc.html".to_string()),
-      "Arc" => return Some("https://doc.rust-lang.org/std/sync/struct.Arc.html".to_string()),
-      "HashMap" => {
-        return Some("https://doc.rust-lang.org/std/collections/struct.HashMap.html".to_string());
-      }
-      "HashSet" => {
-        return Some("https://doc.rust-lang.org/std/collections/struct.HashSet.html".to_string());
-      }
-      "BTreeMap" => {
-        return Some("https://doc.rust-lang.org/std/collections/struct.BTreeMap.html".to_string());
-      }
-      "BTreeSet" => {
-        return Some("https://doc.rust-lang.org/std/collections/struct.BTreeSet.html".to_string());
-      }
-      "Mutex" => return Some("https://doc.rust-lang.org/std/sync/struct.Mutex.html".to_string()),
-      "RwLock" => return Some("https://doc.rust-lang.org/std/sync/struct.RwLock.html".to_string()),
-      "Cell" => return Some("https://doc.rust-lang.org/std/cell/struct.Cell.html".to_string()),
-      "RefCell" => {
-        return Some("https://doc.rust-lang.org/std/cell/struct.RefCell.html".to_string());
-      }
-      "Path" => return Some("https://doc.rust-lang.org/std/path/struct.Path.html".to_string()),
-      "PathBuf" => {
-        return Some("https://doc.rust-lang.org/std/path/struct.PathBuf.html".to_string());
-      }
-      _ => {}
-    }
+fn generate_type_link(
+  ctx: &RenderContext,
+  full_path: &str,
+  item_id: &Id,
+  crate_data: &Crate,
+) -> Option<String> {
+  if let Some(heading_id) = ctx.heading_ids.get(item_id) {
+    return Some(format!("#{}", heading_id));
   }
-
-  None
+  let link = ctx.link_resolver().resolve(full_path, item_id, crate_data);
+  if link.is_none() {
+    log::debug!(target: "links", "No link target for `{}` ({:?})", full_path, item_id);
+    ctx.conversion.record_unresolved(full_path);
+  }
+  link
 }
 
 fn format_type_with_links(
+  ctx: &RenderContext,
   ty: &rustdoc_types::Type,
   crate_data: &Crate,
   current_item: Option<&Item>,
 ) -> (String, Vec<(String, String)>) {
-  format_type_with_links_depth(ty, crate_data, current_item, 0)
+  format_type_with_links_depth(ctx, ty, crate_data, current_item, 0)
 }
 
 fn format_type_with_links_depth(
+  ctx: &RenderContext,
   ty: &rustdoc_types::Type,
   crate_data: &Crate,
   current_item: Option<&Item>,
@@ -2478,13 +5198,14 @@ fn format_type_with_links_depth(
       let short_name = get_short_type_name(&path.path);
       if let Some(link) = Some(path.id)
         .as_ref()
-        .and_then(|id| generate_type_link(&path.path, id, crate_data, current_item))
+        .and_then(|id| generate_type_link(ctx, &path.path, id, crate_data))
       {
         links.push((short_name.clone(), link));
       }
       let mut result = short_name;
       if let Some(args) = &path.args {
-        let (args_str, args_links) = format_generic_args_with_links(args, crate_data, current_item);
+        let (args_str, args_links) =
+          format_generic_args_with_links(ctx, args, crate_data, current_item);
         links.extend(args_links);
         result.push_str(&args_str);
       }
@@ -2493,12 +5214,9 @@ fn format_type_with_links_depth(
     Type::DynTrait(dt) => {
       if let Some(first) = dt.traits.first() {
         let short_name = get_short_type_name(&first.trait_.path);
-        if let Some(link) = generate_type_link(
-          &first.trait_.path,
-          &first.trait_.id,
-          crate_data,
-          current_item,
-        ) {
+        if let Some(link) =
+          generate_type_link(ctx, &first.trait_.path, &first.trait_.id, crate_data)
+        {
           links.push((short_name.clone(), link));
         }
         format!("dyn {}", short_name)
@@ -2513,7 +5231,7 @@ fn format_type_with_links_depth(
       let mut parts = Vec::new();
       for t in types {
         let (type_str, type_links) =
-          format_type_with_links_depth(t, crate_data, current_item, depth + 1);
+          format_type_with_links_depth(ctx, t, crate_data, current_item, depth + 1);
         links.extend(type_links);
         parts.push(type_str);
       }
@@ -2521,44 +5239,32 @@ fn format_type_with_links_depth(
     }
     Type::Slice(inner) => {
       let (inner_str, inner_links) =
-        format_type_with_links_depth(inner, crate_data, current_item, depth + 1);
+        format_type_with_links_depth(ctx, inner, crate_data, current_item, depth + 1);
       links.extend(inner_links);
       format!("[{}]", inner_str)
     }
     Type::Array { type_, len } => {
       let (type_str, type_links) =
-        format_type_with_links_depth(type_, crate_data, current_item, depth + 1);
+        format_type_with_links_depth(ctx, type_, crate_data, current_item, depth + 1);
       links.extend(type_links);
       format!("[{}; {}]", type_str, len)
     }
     Type::Pat { type_, .. } => {
       let (type_str, type_links) =
-        format_type_with_links_depth(type_, crate_data, current_item, depth + 1);
+        format_type_with_links_depth(ctx, type_, crate_data, current_item, depth + 1);
       links.extend(type_links);
       type_str
     }
     Type::ImplTrait(bounds) => {
       // Extract links from trait bounds in impl Trait
-      for bound in bounds {
-        if let rustdoc_types::GenericBound::TraitBound { trait_, .. } = bound {
-          let short_name = get_short_type_name(&trait_.path);
-          if let Some(link) = generate_type_link(&trait_.path, &trait_.id, crate_data, current_item)
-          {
-            links.push((short_name, link));
-          }
-          // Also extract links from generic arguments (e.g., Into<T>)
-          if let Some(args) = &trait_.args {
-            let (_, args_links) = format_generic_args_with_links(args, crate_data, current_item);
-            links.extend(args_links);
-          }
-        }
-      }
+      let (_, bound_links) = format_bounds_with_links(ctx, bounds, crate_data);
+      links.extend(bound_links);
       "impl Trait".to_string()
     }
     Type::Infer => "_".to_string(),
     Type::RawPointer { is_mutable, type_ } => {
       let (type_str, type_links) =
-        format_type_with_links_depth(type_, crate_data, current_item, depth + 1);
+        format_type_with_links_depth(ctx, type_, crate_data, current_item, depth + 1);
       links.extend(type_links);
       if *is_mutable {
         format!("*mut {}", type_str)
@@ -2572,9 +5278,12 @@ fn format_type_with_links_depth(
       type_,
     } => {
       let (type_str, type_links) =
-        format_type_with_links_depth(type_, crate_data, current_item, depth + 1);
+        format_type_with_links_depth(ctx, type_, crate_data, current_item, depth + 1);
       links.extend(type_links);
-      let lifetime_str = lifetime.as_deref().unwrap_or("");
+      let lifetime_str = lifetime
+        .as_deref()
+        .filter(|lifetime| !is_synthetic_lifetime(lifetime))
+        .unwrap_or("");
       let space = if lifetime_str.is_empty() { "" } else { " " };
       if *is_mutable {
         format!("&{}{} mut {}", lifetime_str, space, type_str)
@@ -2589,11 +5298,11 @@ fn format_type_with_links_depth(
       ..
     } => {
       let (self_str, self_links) =
-        format_type_with_links_depth(self_type, crate_data, current_item, depth + 1);
+        format_type_with_links_depth(ctx, self_type, crate_data, current_item, depth + 1);
       links.extend(self_links);
       if let Some(trait_) = trait_ {
         let trait_short = get_short_type_name(&trait_.path);
-        if let Some(link) = generate_type_link(&trait_.path, &trait_.id, crate_data, current_item) {
+        if let Some(link) = generate_type_link(ctx, &trait_.path, &trait_.id, crate_data) {
           links.push((trait_short.clone(), link));
         }
         format!("<{} as {}>::{}", self_str, trait_short, name)
@@ -2607,6 +5316,7 @@ fn format_type_with_links_depth(
 }
 
 fn format_generic_args_with_links(
+  ctx: &RenderContext,
   args: &rustdoc_types::GenericArgs,
   crate_data: &Crate,
   current_item: Option<&Item>,
@@ -2623,14 +5333,13 @@ fn format_generic_args_with_links(
         for arg in args {
           match arg {
             GenericArg::Type(ty) => {
-              let (type_str, type_links) = format_type_with_links(ty, crate_data, current_item);
+              let (type_str, type_links) =
+                format_type_with_links(ctx, ty, crate_data, current_item);
               links.extend(type_links);
               formatted.push(type_str);
             }
-            GenericArg::Lifetime(lt) => {
-              if !is_synthetic_lifetime(lt) {
-                formatted.push(lt.clone());
-              }
+            GenericArg::Lifetime(lt) if !is_synthetic_lifetime(lt) => {
+              formatted.push(lt.clone());
             }
             _ => {}
           }
@@ -2645,12 +5354,12 @@ fn format_generic_args_with_links(
     GenericArgs::Parenthesized { inputs, output } => {
       let mut inputs_parts = Vec::new();
       for input in inputs {
-        let (type_str, type_links) = format_type_with_links(input, crate_data, current_item);
+        let (type_str, type_links) = format_type_with_links(ctx, input, crate_data, current_item);
         links.extend(type_links);
         inputs_parts.push(type_str);
       }
       if let Some(out) = output {
-        let (out_str, out_links) = format_type_with_links(out, crate_data, current_item);
+        let (out_str, out_links) = format_type_with_links(ctx, out, crate_data, current_item);
         links.extend(out_links);
         format!("({}) -> {}", inputs_parts.join(", "), out_str)
       } else {
@@ -2663,7 +5372,11 @@ fn format_generic_args_with_links(
   (args_str, links)
 }
 
-fn format_generic_args(args: &rustdoc_types::GenericArgs, crate_data: &Crate) -> String {
+fn format_generic_args(
+  ctx: &RenderContext,
+  args: &rustdoc_types::GenericArgs,
+  crate_data: &Crate,
+) -> String {
   use rustdoc_types::{GenericArg, GenericArgs};
   match args {
     GenericArgs::AngleBracketed { args, .. } => {
@@ -2673,9 +5386,9 @@ fn format_generic_args(args: &rustdoc_types::GenericArgs, crate_data: &Crate) ->
         let formatted: Vec<String> = args
           .iter()
           .filter_map(|arg| match arg {
-            GenericArg::Lifetime(lt) if lt != "'_" => Some(lt.clone()),
+            GenericArg::Lifetime(lt) if !is_synthetic_lifetime(lt) => Some(lt.clone()),
             GenericArg::Lifetime(_) => None,
-            GenericArg::Type(ty) => Some(format_type(ty, crate_data)),
+            GenericArg::Type(ty) => Some(format_type(ctx, ty, crate_data)),
             GenericArg::Const(c) => Some(c.expr.clone()),
             GenericArg::Infer => Some("_".to_string()),
           })
@@ -2688,10 +5401,13 @@ fn format_generic_args(args: &rustdoc_types::GenericArgs, crate_data: &Crate) ->
       }
     }
     GenericArgs::Parenthesized { inputs, output } => {
-      let inputs_str: Vec<_> = inputs.iter().map(|t| format_type(t, crate_data)).collect();
+      let inputs_str: Vec<_> = inputs
+        .iter()
+        .map(|t| format_type(ctx, t, crate_data))
+        .collect();
       let mut result = format!("({})", inputs_str.join(", "));
       if let Some(output) = output {
-        result.push_str(&format!(" -> {}", format_type(output, crate_data)));
+        result.push_str(&format!(" -> {}", format_type(ctx, output, crate_data)));
       }
       result
     }
@@ -2700,27 +5416,34 @@ fn format_generic_args(args: &rustdoc_types::GenericArgs, crate_data: &Crate) ->
 }
 
 fn generate_crate_index(
+  ctx: &RenderContext,
   crate_name: &str,
   root_item: &Item,
-  modules: &HashMap<String, Vec<(Id, Item)>>,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
 ) -> String {
   let mut output = String::new();
 
-  // Import RustCode component for inline code rendering
-  output.push_str("import RustCode from '@site/src/components/RustCode';\n");
-  output.push_str("import Link from '@docusaurus/Link';\n\n");
+  let frontmatter = format!(
+    "{}{}",
+    ctx.seo_frontmatter(root_item.docs.as_deref(), &["crate", crate_name]),
+    ctx.sitemap_frontmatter("crate")
+  );
 
   output.push_str(&format!("# {}\n\n", crate_name));
 
   if let Some(docs) = &root_item.docs {
-    output.push_str(&format!("{}\n\n", docs));
+    output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
+  }
+  if let Some(readme) = &ctx.readme {
+    output.push_str(&format!("{}\n\n", format_docs(ctx, readme, 2)));
   }
 
-  // Module listing with summary
-  output.push_str("## Modules\n\n");
-
-  let mut module_names: Vec<_> = modules.keys().collect();
+  // Module listing with summary; the crate root itself has no module page
+  let mut module_names: Vec<_> = modules.keys().filter(|name| *name != crate_name).collect();
   module_names.sort();
+  if !module_names.is_empty() {
+    output.push_str("## Modules\n\n");
+  }
 
   for module_name in module_names {
     let items = &modules[module_name];
@@ -2733,7 +5456,7 @@ fn generate_crate_index(
     let module_file = if display_name.contains("::") {
       format!("{}/", display_name.replace("::", "/"))
     } else {
-      format!("{}.md", display_name)
+      format!("{}.{}", display_name, ctx.extension.as_str())
     };
 
     // Count item types
@@ -2744,8 +5467,12 @@ fn generate_crate_index(
         ItemEnum::Enum(_) => "enums",
         ItemEnum::Function(_) => "functions",
         ItemEnum::Trait(_) => "traits",
+        ItemEnum::TraitAlias(_) => "trait aliases",
         ItemEnum::Constant { .. } => "constants",
         ItemEnum::TypeAlias(_) => "type aliases",
+        ItemEnum::Static(_) => "statics",
+        ItemEnum::ExternType => "foreign types",
+        ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => "macros",
         ItemEnum::Module(_) => "modules",
         _ => continue,
       };
@@ -2764,43 +5491,38 @@ fn generate_crate_index(
     }
   }
 
-  output
+  ctx.render_page(PageTemplate::Crate, crate_name, &frontmatter, "", &output)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_combined_crate_and_root_content(
+  ctx: &RenderContext,
   crate_name: &str,
   root_item: &Item,
   _crate_data: &Crate,
-  _modules: &HashMap<String, Vec<(Id, Item)>>,
-  root_items: &[(Id, Item)],
-  module_hierarchy: &HashMap<String, Vec<String>>,
-  reexported_modules: &HashMap<String, Vec<(String, String)>>,
+  _modules: &BTreeMap<String, Vec<(Id, &Item)>>,
+  root_items: &[(Id, &Item)],
+  module_hierarchy: &BTreeMap<String, Vec<String>>,
+  reexported_modules: &BTreeMap<String, Vec<(String, String)>>,
+  hidden_modules: &BTreeMap<String, usize>,
 ) -> String {
   let mut output = String::new();
 
-  // Calculate sidebar key for the crate
-  let base_path = BASE_PATH.with(|bp| bp.borrow().clone());
-  let base_path_for_sidebar = base_path
-    .strip_prefix("/docs/")
-    .or_else(|| base_path.strip_prefix("/docs"))
-    .or_else(|| base_path.strip_prefix("/"))
-    .unwrap_or(&base_path);
-  let sidebar_key = format!("{}/{}", base_path_for_sidebar, crate_name).replace("/", "_");
-
-  // Add frontmatter with displayed_sidebar
-  output.push_str("---\n");
-  output.push_str(&format!("title: {}\n", crate_name));
-  output.push_str(&format!("displayed_sidebar: '{}'\n", sidebar_key));
-  output.push_str("---\n\n");
-
-  // Import RustCode component for inline code rendering
-  output.push_str("import RustCode from '@site/src/components/RustCode';\n");
-  output.push_str("import Link from '@docusaurus/Link';\n\n");
+  // Frontmatter with displayed_sidebar
+  let frontmatter = format!(
+    "{}{}{}",
+    ctx.seo_frontmatter(root_item.docs.as_deref(), &["crate", crate_name]),
+    ctx.displayed_sidebar(crate_name, SidebarKind::Module),
+    ctx.sitemap_frontmatter("crate")
+  );
 
   output.push_str(&format!("# Crate {}\n\n", crate_name));
 
   if let Some(docs) = &root_item.docs {
-    output.push_str(&format!("{}\n\n", docs));
+    output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
+  }
+  if let Some(readme) = &ctx.readme {
+    output.push_str(&format!("{}\n\n", format_docs(ctx, readme, 2)));
   }
 
   // If we have root-level items, show them first
@@ -2819,7 +5541,8 @@ fn generate_combined_crate_and_root_content(
 
     // Show Re-exports section first (if any)
     // Only show re-exports where the source module/item is public (rustdoc behavior)
-    if !re_exports.is_empty() {
+    let extern_crates = format_extern_crates(root_item, _crate_data);
+    if !re_exports.is_empty() || !extern_crates.is_empty() {
       let mut public_re_exports = Vec::new();
 
       for (id, item) in &re_exports {
@@ -2827,7 +5550,7 @@ fn generate_combined_crate_and_root_content(
           // Check if the source is public
           let is_source_public = if let Some(import_id) = &use_item.id {
             if let Some(imported_item) = _crate_data.index.get(import_id) {
-              is_public(imported_item)
+              is_public(imported_item) || is_public_variant(import_id, _crate_data)
             } else {
               // Not found in crate index - external dependency, assume public
               true
@@ -2843,8 +5566,9 @@ fn generate_combined_crate_and_root_content(
         }
       }
 
-      if !public_re_exports.is_empty() {
+      if !public_re_exports.is_empty() || !extern_crates.is_empty() {
         output.push_str("## Re-exports\n\n");
+        output.push_str(&extern_crates);
 
         for (_id, _item, use_item) in &public_re_exports {
           // Use the full source path (e.g., "patterns::Builder")
@@ -2861,9 +5585,19 @@ fn generate_combined_crate_and_root_content(
           // e.g., "generated::MessageRole" -> "MessageRole"
           let type_name = source_path.split("::").last().unwrap_or(source_path);
 
+          // Enum variants link to their anchor on the enum page
+          let variant_reexport = variant_reexport_links(ctx, use_item, _crate_data);
+          if use_item.id.is_none() {
+            log::warn!(target: "reexports", "`{}` has no target in the rustdoc JSON", code_str);
+          }
+
           // Try to find link to the re-exported item using absolute links
-          let links: Vec<(String, String)> = if let Some(import_id) = &use_item.id {
-            if let Some(link) = generate_type_link(source_path, import_id, _crate_data, None) {
+          let links: Vec<(String, String)> = if let Some((links, _)) = &variant_reexport {
+            links.clone()
+          } else if let Some(link) = external_glob_link(ctx, use_item, _crate_data) {
+            vec![(type_name.to_string(), link)]
+          } else if let Some(import_id) = &use_item.id {
+            if let Some(link) = generate_type_link(ctx, source_path, import_id, _crate_data) {
               vec![(type_name.to_string(), link)]
             } else {
               // External dependency - no link
@@ -2873,13 +5607,11 @@ fn generate_combined_crate_and_root_content(
             vec![]
           };
 
-          let links_json = format_links_as_json(&links);
-
           // Use RustCode inline component for consistent formatting
-          output.push_str(&format!(
-            "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-            code_str, links_json
-          ));
+          output.push_str(&rust_code(&code_str, &links, true));
+          if let Some((_, variants)) = variant_reexport {
+            output.push_str(&variants);
+          }
         }
       }
     }
@@ -2891,8 +5623,12 @@ fn generate_combined_crate_and_root_content(
         ItemEnum::Enum(_) => "Enums",
         ItemEnum::Function(_) => "Functions",
         ItemEnum::Trait(_) => "Traits",
+        ItemEnum::TraitAlias(_) => "Trait Aliases",
         ItemEnum::Constant { .. } => "Constants",
         ItemEnum::TypeAlias(_) => "Type Aliases",
+        ItemEnum::Static(_) => "Statics",
+        ItemEnum::ExternType => "Foreign Types",
+        ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => "Macros",
         ItemEnum::Module(_) => continue, // Skip module items, use hierarchy instead
         ItemEnum::Use(_) => continue,    // Use items are handled separately in Re-exports section
         _ => continue,
@@ -2902,12 +5638,16 @@ fn generate_combined_crate_and_root_content(
 
     let type_order = [
       "Modules",
+      "Macros",
       "Structs",
       "Enums",
       "Functions",
       "Traits",
+      "Trait Aliases",
       "Constants",
       "Type Aliases",
+      "Statics",
+      "Foreign Types",
     ];
     for type_name in &type_order {
       // Special handling for Modules - use hierarchy to show top-level modules
@@ -2947,24 +5687,45 @@ fn generate_combined_crate_and_root_content(
               .replace("::", "/");
 
             // Try to get documentation from root_items
-            let doc_line = root_items
-              .iter()
-              .find(|(_, item)| {
-                if let Some(item_name) = &item.name {
-                  item_name == &module_name && matches!(&item.inner, ItemEnum::Module(_))
-                } else {
-                  false
-                }
-              })
+            let module_item = root_items.iter().find(|(_, item)| {
+              if let Some(item_name) = &item.name {
+                item_name == &module_name && matches!(&item.inner, ItemEnum::Module(_))
+              } else {
+                false
+              }
+            });
+            let doc_line = module_item
               .and_then(|(_, item)| item.docs.as_ref())
               .and_then(|docs| docs.lines().next())
-              .filter(|line| !line.is_empty());
+              .filter(|line| !line.is_empty())
+              .map(summary_to_html);
 
-            // Only add " — " if there's documentation
+            // Modules below the depth limit have no page - show a summary instead
+            if let Some(hidden_count) = hidden_modules.get(&module_path) {
+              let visibility_indicator = module_item
+                .map(|(_, item)| ctx.visibility_indicator(item))
+                .unwrap_or_default();
+              let doc_suffix = doc_line
+                .map(|doc_text| format!("{}{}", ctx.summary_separator(), doc_text))
+                .unwrap_or_default();
+              output.push_str(&format!(
+                "<div><span className=\"rust-mod\">{}</span>{}{} {}</div>\n\n",
+                module_name,
+                visibility_indicator,
+                doc_suffix,
+                format_hidden_items_note(*hidden_count)
+              ));
+              continue;
+            }
+
+            // Only add the separator if there's documentation
             if let Some(doc_text) = doc_line {
               output.push_str(&format!(
-                "<div><Link to=\"{}/\" className=\"rust-mod\">{}</Link> — {}</div>\n\n",
-                link_path, module_name, doc_text
+                "<div><Link to=\"{}/\" className=\"rust-mod\">{}</Link>{}{}</div>\n\n",
+                link_path,
+                module_name,
+                ctx.summary_separator(),
+                doc_text
               ));
             } else {
               output.push_str(&format!(
@@ -2983,10 +5744,12 @@ fn generate_combined_crate_and_root_content(
         // Determine CSS class based on type
         let css_class = match *type_name {
           "Structs" | "Enums" => "rust-struct",
-          "Traits" => "rust-trait",
+          "Traits" | "Trait Aliases" => "rust-trait",
           "Functions" => "rust-fn",
           "Constants" => "rust-constant",
-          "Type Aliases" => "rust-type",
+          "Type Aliases" | "Foreign Types" => "rust-type",
+          "Statics" => "rust-static",
+          "Macros" => "rust-macro",
           _ => "rust-item",
         };
 
@@ -2995,18 +5758,25 @@ fn generate_combined_crate_and_root_content(
             // Other items link to their individual pages with rustdoc-style prefix
             let prefix = get_item_prefix(item);
             let link = format!("{}{}", prefix, name);
-            let visibility_indicator = get_visibility_indicator(item);
+            let visibility_indicator = ctx.visibility_indicator(item);
 
             output.push_str("<div>");
             output.push_str(&format!(
               "<Link to=\"{}\" className=\"{}\">{}</Link> {}",
               link, css_class, name, visibility_indicator
             ));
+            if let Some(badge) = ctx.since_badge(&format!("{}::{}", crate_name, name)) {
+              output.push_str(&format!(" {}", badge));
+            }
             if let Some(docs) = &item.docs {
               let sanitized = sanitize_docs_for_mdx(docs);
               if let Some(first_line) = sanitized.lines().next() {
                 if !first_line.is_empty() {
-                  output.push_str(&format!(" — {}", first_line));
+                  output.push_str(&format!(
+                    "{}{}",
+                    ctx.summary_separator(),
+                    summary_to_html(first_line)
+                  ));
                 }
               }
             }
@@ -3017,105 +5787,1399 @@ fn generate_combined_crate_and_root_content(
     }
   }
 
-  output
+  ctx.render_page(PageTemplate::Crate, crate_name, &frontmatter, "", &output)
 }
 
-#[allow(clippy::too_many_arguments)]
-fn generate_individual_pages(
-  items: &[(Id, Item)],
-  path_prefix: &str,
-  files: &mut HashMap<String, String>,
-  _crate_data: &Crate,
-  item_paths: &HashMap<Id, Vec<String>>,
-  _crate_name: &str,
-  _module_name: &str,
+/// Whether the trait referenced by `trait_id` is `std::error::Error` (or its `core` original)
+fn is_std_error_trait(crate_data: &Crate, trait_id: &Id) -> bool {
+  crate_data.paths.get(trait_id).is_some_and(|summary| {
+    matches!(
+      summary.path.iter().map(String::as_str).collect::<Vec<_>>()[..],
+      ["core" | "std", "error", "Error"]
+    )
+  })
+}
+
+/// Local types implementing a trait matching `is_trait`, sorted by name
+fn collect_trait_implementors(
+  crate_data: &Crate,
   include_private: bool,
-) {
-  for (id, item) in items {
-    // Skip Use items (re-exports) - they're only shown in the module overview
-    // The actual items are documented in their original modules
-    if matches!(&item.inner, ItemEnum::Use(_)) {
-      continue;
+  is_trait: impl Fn(&Id) -> bool,
+) -> Vec<(&Id, &Item)> {
+  let mut implementors: Vec<(&Id, &Item)> = crate_data
+    .index
+    .values()
+    .filter_map(|item| match &item.inner {
+      ItemEnum::Impl(impl_block) => Some(impl_block),
+      _ => None,
+    })
+    .filter(|impl_block| {
+      impl_block
+        .trait_
+        .as_ref()
+        .is_some_and(|trait_ref| is_trait(&trait_ref.id))
+    })
+    .filter_map(|impl_block| match &impl_block.for_ {
+      rustdoc_types::Type::ResolvedPath(path) => crate_data.index.get_key_value(&path.id),
+      _ => None,
+    })
+    .filter(|(_, item)| include_private || matches!(item.visibility, Visibility::Public))
+    .collect();
+
+  implementors.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+  implementors.dedup_by_key(|(id, _)| *id);
+  implementors
+}
+
+/// Front matter and imports of a crate-level index page
+fn index_page_header(ctx: &RenderContext, crate_name: &str, title: &str, kind: &str) -> String {
+  format!(
+    "---\ntitle: {}\n{}{}---\n\nimport Link from '@docusaurus/Link';\n\n# {}\n\n",
+    title,
+    ctx.displayed_sidebar(crate_name, SidebarKind::Module),
+    ctx.sitemap_frontmatter(kind),
+    title
+  )
+}
+
+/// Format one linked entry of an index page, followed by the first line of `docs`
+fn format_index_entry(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  id: &Id,
+  item: &Item,
+  css_class: &str,
+  docs: Option<&str>,
+) -> String {
+  let name = item.name.as_deref().unwrap_or("");
+  let full_path = crate_data
+    .paths
+    .get(id)
+    .map(|summary| summary.path.join("::"))
+    .unwrap_or_else(|| name.to_string());
+  let link = generate_type_link(ctx, &full_path, id, crate_data);
+  format_linked_entry(ctx, link.as_deref(), &full_path, css_class, docs)
+}
+
+/// Format a `<div>` entry linking `label` to `link`, followed by the first line of `docs`
+fn format_linked_entry(
+  ctx: &RenderContext,
+  link: Option<&str>,
+  label: &str,
+  css_class: &str,
+  docs: Option<&str>,
+) -> String {
+  let mut output = String::from("<div>");
+  match link {
+    Some(link) => output.push_str(&format!(
+      "<Link to=\"{}\" className=\"{}\">{}</Link>",
+      link, css_class, label
+    )),
+    None => output.push_str(&format!(
+      "<span className=\"{}\">{}</span>",
+      css_class, label
+    )),
+  }
+  if let Some(docs) = docs {
+    let sanitized = sanitize_docs_for_mdx(docs);
+    if let Some(first_line) = sanitized.lines().next().filter(|line| !line.is_empty()) {
+      output.push_str(&format!(
+        "{}{}",
+        ctx.summary_separator(),
+        summary_to_html(first_line)
+      ));
     }
+  }
+  output.push_str("</div>\n\n");
+  output
+}
 
-    if let Some(name) = &item.name {
-      // Skip module items as they get their own overview pages
-      if matches!(&item.inner, ItemEnum::Module(_)) {
+/// Whether `type_id` is the self type of `impl_block`
+fn impl_is_for(impl_block: &rustdoc_types::Impl, type_id: &Id) -> bool {
+  matches!(&impl_block.for_, rustdoc_types::Type::ResolvedPath(path) if path.id == *type_id)
+}
+
+/// Docs of the `Display` impl of a type, which usually describe the error message
+fn display_impl_docs<'a>(crate_data: &'a Crate, type_id: &Id) -> Option<&'a str> {
+  crate_data
+    .index
+    .values()
+    .find_map(|item| match &item.inner {
+      ItemEnum::Impl(impl_block) if impl_is_for(impl_block, type_id) => {
+        let trait_ref = impl_block.trait_.as_ref()?;
+        let summary = crate_data.paths.get(&trait_ref.id)?;
+        if summary
+          .path
+          .iter()
+          .map(String::as_str)
+          .eq(["core", "fmt", "Display"])
+        {
+          item.docs.as_deref()
+        } else {
+          None
+        }
+      }
+      _ => None,
+    })
+}
+
+/// Generate the page listing all types implementing `std::error::Error`
+fn generate_error_index(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  crate_name: &str,
+  include_private: bool,
+) -> String {
+  let mut output = index_page_header(ctx, crate_name, "Errors", "errors");
+
+  let error_types = collect_trait_implementors(crate_data, include_private, |trait_id| {
+    is_std_error_trait(crate_data, trait_id)
+  });
+
+  if error_types.is_empty() {
+    output.push_str(&format!(
+      "`{}` has no types implementing `std::error::Error`.\n",
+      crate_name
+    ));
+    return output;
+  }
+
+  output.push_str(&format!(
+    "Types in `{}` implementing `std::error::Error`.\n\n",
+    crate_name
+  ));
+  for (id, item) in error_types {
+    // Prefer the type's own docs, falling back to the docs of its `Display` impl
+    let docs = item
+      .docs
+      .as_deref()
+      .or_else(|| display_impl_docs(crate_data, id));
+    output.push_str(&format_index_entry(
+      ctx,
+      crate_data,
+      id,
+      item,
+      "rust-struct",
+      docs,
+    ));
+  }
+
+  output
+}
+
+/// Generate the page listing every item that has a page, grouped by kind like
+/// rustdoc's `all.html`
+/// Item kinds of the `all` page in display order, with the slug, title and
+/// description of their `kind_indexes` page.
+const ITEM_KINDS: [(&str, &str, &str, &str); 11] = [
+  (
+    "Macros",
+    "all-macros",
+    "All Macros",
+    "Every macro of the crate",
+  ),
+  (
+    "Structs",
+    "all-structs",
+    "All Structs",
+    "Every struct of the crate",
+  ),
+  ("Enums", "all-enums", "All Enums", "Every enum of the crate"),
+  (
+    "Unions",
+    "all-unions",
+    "All Unions",
+    "Every union of the crate",
+  ),
+  (
+    "Functions",
+    "all-functions",
+    "All Functions",
+    "Every function of the crate",
+  ),
+  (
+    "Traits",
+    "all-traits",
+    "All Traits",
+    "Every trait of the crate",
+  ),
+  (
+    "Trait Aliases",
+    "all-trait-aliases",
+    "All Trait Aliases",
+    "Every trait alias of the crate",
+  ),
+  (
+    "Constants",
+    "all-constants",
+    "All Constants",
+    "Every constant of the crate",
+  ),
+  (
+    "Type Aliases",
+    "all-type-aliases",
+    "All Type Aliases",
+    "Every type alias of the crate",
+  ),
+  (
+    "Statics",
+    "all-statics",
+    "All Statics",
+    "Every static of the crate",
+  ),
+  (
+    "Foreign Types",
+    "all-foreign-types",
+    "All Foreign Types",
+    "Every foreign type of the crate",
+  ),
+];
+
+/// Index entries of the crate's items keyed by kind (see [`ITEM_KINDS`]),
+/// paired with the item's full path to sort them by.
+fn items_by_kind<'a>(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
+) -> HashMap<&'a str, Vec<(String, String)>> {
+  let mut by_type: HashMap<&str, Vec<(String, String)>> = HashMap::new();
+  let mut seen = std::collections::HashSet::new();
+  for (id, item) in modules.values().flatten() {
+    let type_name = match &item.inner {
+      ItemEnum::Struct(_) => "Structs",
+      ItemEnum::Enum(_) => "Enums",
+      ItemEnum::Union(_) => "Unions",
+      ItemEnum::Function(_) => "Functions",
+      ItemEnum::Trait(_) => "Traits",
+      ItemEnum::TraitAlias(_) => "Trait Aliases",
+      ItemEnum::Constant { .. } => "Constants",
+      ItemEnum::TypeAlias(_) => "Type Aliases",
+      ItemEnum::Static(_) => "Statics",
+      ItemEnum::ExternType => "Foreign Types",
+      ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => "Macros",
+      _ => continue,
+    };
+    // Items of glob re-exports are listed once, at their own path
+    if !seen.insert(id) {
+      continue;
+    }
+    let entry = format_index_entry(ctx, crate_data, id, item, index_item_class(item), None);
+    let full_path = crate_data
+      .paths
+      .get(id)
+      .map(|summary| summary.path.join("::"))
+      .unwrap_or_default();
+    by_type
+      .entry(type_name)
+      .or_default()
+      .push((full_path, entry));
+  }
+  by_type
+}
+
+fn generate_all_items(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  crate_name: &str,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
+) -> String {
+  let mut output = index_page_header(ctx, crate_name, "All Items", "all");
+
+  let mut by_type = items_by_kind(ctx, crate_data, modules);
+  if by_type.is_empty() {
+    output.push_str(&format!("`{}` has no documented items.\n", crate_name));
+    return output;
+  }
+
+  for (type_name, ..) in ITEM_KINDS {
+    let Some(entries) = by_type.get_mut(type_name) else {
+      continue;
+    };
+    entries.sort();
+    output.push_str(&format!("## {}\n\n", type_name));
+    for (_, entry) in entries.iter() {
+      output.push_str(entry);
+    }
+  }
+
+  output
+}
+
+/// Whether the trait referenced by `trait_id` is `Future` or a `Stream` trait
+fn is_future_or_stream_trait(crate_data: &Crate, trait_id: &Id) -> bool {
+  crate_data.paths.get(trait_id).is_some_and(|summary| {
+    let path: Vec<&str> = summary.path.iter().map(String::as_str).collect();
+    matches!(path[..], ["core" | "std", "future", .., "Future"]) || path.last() == Some(&"Stream")
+  })
+}
+
+/// Whether a function is `async` or returns `impl Future`
+fn is_async_function(crate_data: &Crate, function: &rustdoc_types::Function) -> bool {
+  function.header.is_async
+    || matches!(&function.sig.output, Some(rustdoc_types::Type::ImplTrait(bounds)) if bounds.iter().any(|bound| {
+      matches!(bound, rustdoc_types::GenericBound::TraitBound { trait_, .. } if is_future_or_stream_trait(crate_data, &trait_.id))
+    }))
+}
+
+/// Whether an item is shown given the `include_private` setting
+fn is_visible(item: &Item, include_private: bool) -> bool {
+  include_private || matches!(item.visibility, Visibility::Public | Visibility::Default)
+}
+
+/// Generate the page listing async functions, async methods and future/stream types
+fn generate_async_index(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  crate_name: &str,
+  include_private: bool,
+) -> String {
+  let mut output = index_page_header(ctx, crate_name, "Async API", "async");
+
+  // Methods are listed under their owning type or trait, so collect their ids first
+  let mut method_owners: HashMap<&Id, &Id> = HashMap::new();
+  for (owner_id, owner) in &crate_data.index {
+    match &owner.inner {
+      ItemEnum::Impl(impl_block) if impl_block.trait_.is_none() => {
+        if let rustdoc_types::Type::ResolvedPath(path) = &impl_block.for_ {
+          for method_id in &impl_block.items {
+            method_owners.insert(method_id, &path.id);
+          }
+        }
+      }
+      ItemEnum::Impl(impl_block) => {
+        // Trait impl methods are documented on the trait
+        for method_id in &impl_block.items {
+          method_owners.insert(method_id, owner_id);
+        }
+      }
+      ItemEnum::Trait(trait_) => {
+        for method_id in &trait_.items {
+          method_owners.insert(method_id, owner_id);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  let mut functions = Vec::new();
+  let mut methods = Vec::new();
+  for (id, item) in &crate_data.index {
+    let ItemEnum::Function(function) = &item.inner else {
+      continue;
+    };
+    if !is_async_function(crate_data, function) || !is_visible(item, include_private) {
+      continue;
+    }
+
+    match method_owners.get(id) {
+      None => functions.push((id, item)),
+      Some(owner_id) => {
+        // Skip methods of trait impls and of types that are not documented
+        let Some(owner) = crate_data.index.get(*owner_id) else {
+          continue;
+        };
+        if matches!(owner.inner, ItemEnum::Impl(_)) || !is_visible(owner, include_private) {
+          continue;
+        }
+        methods.push((*owner_id, owner, item));
+      }
+    }
+  }
+
+  let future_types = collect_trait_implementors(crate_data, include_private, |trait_id| {
+    is_future_or_stream_trait(crate_data, trait_id)
+  });
+
+  if functions.is_empty() && methods.is_empty() && future_types.is_empty() {
+    output.push_str(&format!("`{}` has no async API.\n", crate_name));
+    return output;
+  }
+
+  if !functions.is_empty() {
+    output.push_str("## Async Functions\n\n");
+    functions.sort_by_key(|(id, item)| {
+      crate_data
+        .paths
+        .get(*id)
+        .map(|summary| summary.path.join("::"))
+        .or_else(|| item.name.clone())
+    });
+    for (id, item) in functions {
+      output.push_str(&format_index_entry(
+        ctx,
+        crate_data,
+        id,
+        item,
+        "rust-fn",
+        item.docs.as_deref(),
+      ));
+    }
+  }
+
+  if !methods.is_empty() {
+    output.push_str("## Async Methods\n\n");
+    let mut entries: Vec<(String, String)> = methods
+      .into_iter()
+      .map(|(owner_id, owner, method)| {
+        let owner_path = crate_data
+          .paths
+          .get(owner_id)
+          .map(|summary| summary.path.join("::"))
+          .unwrap_or_else(|| owner.name.clone().unwrap_or_default());
+        let label = format!(
+          "{}::{}",
+          owner_path,
+          method.name.as_deref().unwrap_or_default()
+        );
+        let link = generate_type_link(ctx, &owner_path, owner_id, crate_data);
+        let entry = format_linked_entry(
+          ctx,
+          link.as_deref(),
+          &label,
+          "rust-fn",
+          method.docs.as_deref(),
+        );
+        (label, entry)
+      })
+      .collect();
+    entries.sort();
+    for (_, entry) in entries {
+      output.push_str(&entry);
+    }
+  }
+
+  if !future_types.is_empty() {
+    output.push_str("## Futures and Streams\n\n");
+    for (id, item) in future_types {
+      output.push_str(&format_index_entry(
+        ctx,
+        crate_data,
+        id,
+        item,
+        "rust-struct",
+        item.docs.as_deref(),
+      ));
+    }
+  }
+
+  output
+}
+
+/// Stability of an item, read from its attributes
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Stability {
+  /// `#[unstable(feature = "...")]`: an experimental API, with its feature
+  Experimental(Option<String>),
+  /// Gated on a nightly cfg or feature (e.g. `#[doc(cfg(feature = "nightly"))]`)
+  NightlyOnly,
+}
+
+impl Stability {
+  /// CSS class of the item in the sidebar
+  fn class_name(&self) -> &'static str {
+    match self {
+      Stability::Experimental(_) => "rust-experimental",
+      Stability::NightlyOnly => "rust-nightly",
+    }
+  }
+
+  /// Admonition shown at the top of the item page
+  fn admonition(&self, ctx: &RenderContext) -> String {
+    let (kind, title, text) = match self {
+      Stability::Experimental(Some(feature)) => (
+        "caution",
+        "Experimental",
+        format!(
+          "This is an experimental API behind the unstable `{}` feature.",
+          feature
+        ),
+      ),
+      Stability::Experimental(None) => (
+        "caution",
+        "Experimental",
+        "This is an experimental API.".to_string(),
+      ),
+      Stability::NightlyOnly => (
+        "info",
+        "Nightly-only",
+        "This item is only available on nightly Rust.".to_string(),
+      ),
+    };
+    format!(
+      "{}\n\n{}\n:::\n\n",
+      ctx.docusaurus_major.admonition(kind, title),
+      text
+    )
+  }
+}
+
+/// Stability of an item: experimental when `#[unstable]`, nightly-only when gated on
+/// a `nightly` cfg or a `nightly` / `unstable` Cargo feature
+fn item_stability(item: &Item) -> Option<Stability> {
+  let attrs = item.attrs.iter().filter_map(|attr| match attr {
+    rustdoc_types::Attribute::Other(attr) => Some(attr.as_str()),
+    _ => None,
+  });
+  let mut nightly = false;
+  for attr in attrs {
+    let attr = attr.trim_start_matches("#[").trim_end_matches(']');
+    if let Some(args) = attr.strip_prefix("unstable") {
+      let feature = args
+        .split_once("feature")
+        .and_then(|(_, rest)| rest.split('"').nth(1))
+        .map(str::to_string);
+      return Some(Stability::Experimental(feature));
+    }
+    nightly |= attr.contains("cfg(")
+      && attr
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|token| token == "nightly");
+  }
+  (nightly
+    || cfg_features(item)
+      .iter()
+      .any(|feature| feature == "unstable"))
+  .then_some(Stability::NightlyOnly)
+}
+
+/// Cargo features named in an item's `#[cfg(...)]` / `#[doc(cfg(...))]` attributes
+fn cfg_features(item: &Item) -> Vec<String> {
+  let mut features = Vec::new();
+  for attr in &item.attrs {
+    let rustdoc_types::Attribute::Other(attr) = attr else {
+      continue;
+    };
+    if !attr.contains("cfg(") {
+      continue;
+    }
+
+    let mut rest = attr.as_str();
+    while let Some(start) = rest.find("feature") {
+      // Skip other keys ending in "feature", like `target_feature`
+      let is_key = !rest[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_');
+      rest = &rest[start + "feature".len()..];
+      let Some(value) = rest.trim_start().strip_prefix('=') else {
+        continue;
+      };
+      if let Some(name) = value
+        .trim_start()
+        .strip_prefix('"')
+        .and_then(|v| v.split('"').next())
+        && is_key
+      {
+        features.push(name.to_string());
+      }
+    }
+  }
+
+  features.sort();
+  features.dedup();
+  features
+}
+
+/// Items gated on Cargo features (including features inherited from their modules), sorted by path
+fn collect_feature_gated_items(
+  crate_data: &Crate,
+  include_private: bool,
+) -> Vec<(String, &Id, &Item, Vec<String>)> {
+  let mut gated = Vec::new();
+  let mut modules = vec![(&crate_data.root, Vec::new())];
+
+  while let Some((module_id, inherited)) = modules.pop() {
+    let Some(ItemEnum::Module(module)) = crate_data.index.get(module_id).map(|item| &item.inner)
+    else {
+      continue;
+    };
+
+    for id in &module.items {
+      let Some(item) = crate_data.index.get(id) else {
+        continue;
+      };
+      if !is_visible(item, include_private) || matches!(item.inner, ItemEnum::Use(_)) {
+        continue;
+      }
+
+      let mut features = inherited.clone();
+      features.extend(cfg_features(item));
+      features.sort();
+      features.dedup();
+
+      if matches!(item.inner, ItemEnum::Module(_)) {
+        modules.push((id, features.clone()));
+      }
+      if !features.is_empty() {
+        let full_path = crate_data
+          .paths
+          .get(id)
+          .map(|summary| summary.path.join("::"))
+          .unwrap_or_else(|| item.name.clone().unwrap_or_default());
+        gated.push((full_path, id, item, features));
+      }
+    }
+  }
+
+  gated.sort_by(|a, b| a.0.cmp(&b.0));
+  gated
+}
+
+/// Link to the page of an item (including modules) listed on an index page
+fn index_item_link(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  crate_name: &str,
+  full_path: &str,
+  id: &Id,
+  item: &Item,
+) -> Option<String> {
+  match item.inner {
+    // Modules link to their index page
+    ItemEnum::Module(_) if full_path == crate_name => {
+      Some(format!("{}/{}/", ctx.link_base_path(), crate_name))
+    }
+    ItemEnum::Module(_) => {
+      full_path
+        .strip_prefix(&format!("{}::", crate_name))
+        .map(|module_path| {
+          format!(
+            "{}/{}/{}/",
+            ctx.link_base_path(),
+            crate_name,
+            module_path.replace("::", "/")
+          )
+        })
+    }
+    _ => generate_type_link(ctx, full_path, id, crate_data),
+  }
+}
+
+/// CSS class of an item listed on an index page
+fn index_item_class(item: &Item) -> &'static str {
+  match item.inner {
+    ItemEnum::Module(_) => "rust-mod",
+    ItemEnum::Struct(_) | ItemEnum::Enum(_) => "rust-struct",
+    ItemEnum::Trait(_) | ItemEnum::TraitAlias(_) => "rust-trait",
+    ItemEnum::Function(_) => "rust-fn",
+    ItemEnum::Constant { .. } => "rust-constant",
+    ItemEnum::TypeAlias(_) | ItemEnum::ExternType => "rust-type",
+    ItemEnum::Static(_) => "rust-static",
+    ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => "rust-macro",
+    _ => "rust-item",
+  }
+}
+
+/// Generate the page listing the crate's Cargo features, the features and
+/// dependencies each one enables, and which are enabled by default
+fn generate_features_page(
+  ctx: &RenderContext,
+  crate_name: &str,
+  features: &CrateFeatures,
+) -> String {
+  let mut output = index_page_header(ctx, crate_name, "Feature Flags", "features");
+  if features.is_empty() {
+    output.push_str(&format!("`{}` has no Cargo features.\n", crate_name));
+    return output;
+  }
+
+  let mut defaults = BTreeSet::new();
+  enabled_features(features, "default", &mut defaults);
+  defaults.remove("default");
+  output.push_str(&format!(
+    "Cargo features of `{}`. Features enabled by `default` are marked as such; \
+     each feature lists what it enables, with the features it enables expanded.\n\n",
+    crate_name
+  ));
+
+  // `default` comes first, like in the manifest
+  let names = features
+    .keys()
+    .filter(|name| *name == "default")
+    .chain(features.keys().filter(|name| *name != "default"));
+  for name in names {
+    output.push_str(&format!("## `{}`\n\n", name));
+    if defaults.contains(name.as_str()) {
+      output.push_str("*Enabled by default*\n\n");
+    }
+    if features[name].is_empty() {
+      output.push_str("Enables nothing else.\n\n");
+      continue;
+    }
+    format_feature_tree(features, name, 0, &mut vec![name.as_str()], &mut output);
+    output.push('\n');
+  }
+  output
+}
+
+/// Collect `feature` and the features it enables, transitively.
+fn enabled_features<'a>(
+  features: &'a CrateFeatures,
+  feature: &'a str,
+  enabled: &mut BTreeSet<&'a str>,
+) {
+  if !enabled.insert(feature) {
+    return;
+  }
+  for value in features.get(feature).into_iter().flatten() {
+    if features.contains_key(value.as_str()) {
+      enabled_features(features, value, enabled);
+    }
+  }
+}
+
+/// Append the values of `feature` as a nested list, expanding the features among
+/// them. `path` holds the features being expanded, so cycles stop.
+fn format_feature_tree<'a>(
+  features: &'a CrateFeatures,
+  feature: &str,
+  depth: usize,
+  path: &mut Vec<&'a str>,
+  output: &mut String,
+) {
+  for value in features.get(feature).into_iter().flatten() {
+    let description = match (value.strip_prefix("dep:"), value.split_once('/')) {
+      (Some(dependency), _) => format!("optional dependency `{}`", dependency),
+      (None, Some((dependency, dependency_feature))) => match dependency.strip_suffix('?') {
+        Some(dependency) => format!(
+          "feature `{}` of `{}`, if that dependency is enabled",
+          dependency_feature, dependency
+        ),
+        None => format!("feature `{}` of `{}`", dependency_feature, dependency),
+      },
+      (None, None) if features.contains_key(value.as_str()) => format!("feature `{}`", value),
+      (None, None) => format!("optional dependency `{}`", value),
+    };
+    output.push_str(&format!("{}- {}\n", "  ".repeat(depth), description));
+
+    if features.contains_key(value.as_str()) && !path.contains(&value.as_str()) {
+      path.push(value);
+      format_feature_tree(features, value, depth + 1, path, output);
+      path.pop();
+    }
+  }
+}
+
+/// Generate the page showing which items each Cargo feature enables
+fn generate_feature_matrix(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  crate_name: &str,
+  include_private: bool,
+) -> String {
+  let mut output = index_page_header(ctx, crate_name, "Feature Matrix", "features");
+
+  let gated = collect_feature_gated_items(crate_data, include_private);
+  if gated.is_empty() {
+    output.push_str(&format!("`{}` has no feature-gated items.\n", crate_name));
+    return output;
+  }
+
+  let mut features: Vec<&str> = gated
+    .iter()
+    .flat_map(|(_, _, _, features)| features.iter().map(String::as_str))
+    .collect();
+  features.sort();
+  features.dedup();
+
+  output.push_str(&format!(
+    "Items of `{}` that are only available with Cargo features enabled. \
+     A ✓ marks each feature named in the item's `cfg` (or its module's).\n\n",
+    crate_name
+  ));
+
+  output.push_str("| Item |");
+  for feature in &features {
+    output.push_str(&format!(" `{}` |", feature));
+  }
+  output.push_str("\n| --- |");
+  output.push_str(&" :---: |".repeat(features.len()));
+  output.push('\n');
+
+  for (full_path, id, item, item_features) in &gated {
+    let link = index_item_link(ctx, crate_data, crate_name, full_path, id, item);
+    let css_class = index_item_class(item);
+
+    match link {
+      Some(link) => output.push_str(&format!(
+        "| <Link to=\"{}\" className=\"{}\">{}</Link> |",
+        link, css_class, full_path
+      )),
+      None => output.push_str(&format!(
+        "| <span className=\"{}\">{}</span> |",
+        css_class, full_path
+      )),
+    }
+    for feature in &features {
+      if item_features.iter().any(|f| f == feature) {
+        output.push_str(" ✓ |");
+      } else {
+        output.push_str("  |");
+      }
+    }
+    output.push('\n');
+  }
+
+  output
+}
+
+/// Term named by a `<!-- glossary: term -->` marker line
+fn glossary_marker(line: &str) -> Option<&str> {
+  line
+    .trim()
+    .strip_prefix("<!--")?
+    .strip_suffix("-->")?
+    .trim()
+    .strip_prefix("glossary:")
+    .map(str::trim)
+    .filter(|term| !term.is_empty())
+}
+
+/// Glossary definitions of a doc comment, as (term, definition) pairs.
+///
+/// Terms are defined either by a paragraph following a `<!-- glossary: term -->`
+/// marker, or by list items (`- **term**: definition`) in a `# Terminology`
+/// or `# Glossary` section.
+fn glossary_terms(docs: &str) -> Vec<(String, String)> {
+  let mut terms: Vec<(String, String)> = Vec::new();
+  // Heading level of the open terminology section
+  let mut section: Option<usize> = None;
+  // Term of a marker whose definition paragraph has not started yet
+  let mut marker: Option<String> = None;
+  // Whether lines continue the last definition
+  let mut continues = false;
+  let mut in_fence = false;
+
+  for line in docs.lines() {
+    let trimmed = line.trim();
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      in_fence = !in_fence;
+      continues = false;
+      continue;
+    }
+    if in_fence {
+      continue;
+    }
+
+    if let Some((level, title)) = parse_atx_heading(line) {
+      if section.is_some_and(|open| level <= open) {
+        section = None;
+      }
+      if section.is_none()
+        && (title.eq_ignore_ascii_case("terminology") || title.eq_ignore_ascii_case("glossary"))
+      {
+        section = Some(level);
+      }
+      continues = false;
+      continue;
+    }
+
+    if let Some(term) = glossary_marker(line) {
+      marker = Some(term.to_string());
+      continues = false;
+      continue;
+    }
+
+    if trimmed.is_empty() {
+      continues = false;
+      continue;
+    }
+
+    if let Some(term) = marker.take() {
+      terms.push((term, trimmed.to_string()));
+      continues = true;
+      continue;
+    }
+
+    let list_item = trimmed
+      .strip_prefix("- ")
+      .or_else(|| trimmed.strip_prefix("* "));
+    if let Some(list_item) = list_item.filter(|_| section.is_some()) {
+      let (term, definition) = match list_item.strip_prefix("**") {
+        Some(rest) => match rest.split_once("**") {
+          Some((term, definition)) => (term, definition),
+          None => (list_item, ""),
+        },
+        None => list_item.split_once(':').unwrap_or((list_item, "")),
+      };
+      let definition = definition.trim_start_matches([':', '—', '-', ' ']);
+      continues = true;
+      terms.push((term.trim().to_string(), definition.to_string()));
+      continue;
+    }
+
+    if continues && let Some((_, definition)) = terms.last_mut() {
+      if !definition.is_empty() {
+        definition.push(' ');
+      }
+      definition.push_str(trimmed);
+    }
+  }
+
+  terms.retain(|(term, definition)| !term.is_empty() && !definition.is_empty());
+  terms
+}
+
+/// Glossary definitions of all documented items, keyed by lowercase term
+#[allow(clippy::type_complexity)]
+fn collect_glossary_terms(
+  crate_data: &Crate,
+  include_private: bool,
+) -> BTreeMap<String, Vec<(String, String, String, &Id, &Item)>> {
+  let mut glossary: BTreeMap<String, Vec<_>> = BTreeMap::new();
+  let mut items = vec![&crate_data.root];
+
+  while let Some(id) = items.pop() {
+    let Some(item) = crate_data.index.get(id) else {
+      continue;
+    };
+    if let ItemEnum::Module(module) = &item.inner {
+      items.extend(module.items.iter().filter(|id| {
+        crate_data.index.get(id).is_some_and(|item| {
+          is_visible(item, include_private) && !matches!(item.inner, ItemEnum::Use(_))
+        })
+      }));
+    }
+
+    let Some(docs) = &item.docs else {
+      continue;
+    };
+    let full_path = crate_data
+      .paths
+      .get(id)
+      .map(|summary| summary.path.join("::"))
+      .unwrap_or_else(|| item.name.clone().unwrap_or_default());
+    for (term, definition) in glossary_terms(docs) {
+      glossary.entry(term.to_lowercase()).or_default().push((
+        term,
+        definition,
+        full_path.clone(),
+        id,
+        item,
+      ));
+    }
+  }
+
+  for definitions in glossary.values_mut() {
+    definitions.sort_by(|a, b| a.2.cmp(&b.2));
+  }
+  glossary
+}
+
+/// Generate the glossary page of terms defined in doc comments
+fn generate_glossary(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  crate_name: &str,
+  include_private: bool,
+) -> String {
+  let mut output = index_page_header(ctx, crate_name, "Glossary", "glossary");
+
+  let glossary = collect_glossary_terms(crate_data, include_private);
+  if glossary.is_empty() {
+    output.push_str(&format!(
+      "`{}` defines no glossary terms. Add a `# Terminology` section with `- **term**: definition` items, \
+       or a `<!-- glossary: term -->` line before a paragraph, to a doc comment.\n",
+      crate_name
+    ));
+    return output;
+  }
+
+  output.push_str(&format!(
+    "Terms defined in the documentation of `{}`, each followed by the items defining it.\n\n",
+    crate_name
+  ));
+
+  for definitions in glossary.values() {
+    output.push_str(&format!("## {}\n\n", definitions[0].0));
+    for (_, definition, full_path, id, item) in definitions {
+      let link = index_item_link(ctx, crate_data, crate_name, full_path, id, item);
+      output.push_str(&format_linked_entry(
+        ctx,
+        link.as_deref(),
+        full_path,
+        index_item_class(item),
+        Some(definition),
+      ));
+    }
+  }
+
+  output
+}
+
+/// Generate the individual pages of a module's items, returning (file path, content,
+/// failure) triples.
+///
+/// Items whose rendering panics get a placeholder page, along with the panic message.
+#[allow(clippy::too_many_arguments)]
+fn generate_individual_pages(
+  ctx: &RenderContext,
+  items: &[(Id, &Item)],
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
+  path_prefix: &str,
+  _crate_data: &Crate,
+  item_paths: &HashMap<Id, Vec<String>>,
+  _crate_name: &str,
+  _module_name: &str,
+  include_private: bool,
+  progress: &indicatif::ProgressBar,
+) -> Vec<(String, String, Option<String>)> {
+  items
+    .par_iter()
+    .filter_map(|(id, item)| {
+      progress.inc(1);
+      // Skip Use items (re-exports) - they're only shown in the module overview
+      // The actual items are documented in their original modules
+      // Skip module items as they get their own overview pages
+      if matches!(&item.inner, ItemEnum::Use(_) | ItemEnum::Module(_)) {
+        return None;
+      }
+
+      if !ctx.is_previewed(item, _crate_data) {
+        return None;
+      }
+
+      let name = item.name.as_ref()?;
+
+      // Use rustdoc-style prefix for item filename (e.g., "fn.send_message.md")
+      let item_prefix = get_item_prefix(item);
+      let file_path = format!(
+        "{}{}{}.{}",
+        path_prefix,
+        item_prefix,
+        name,
+        ctx.extension.as_str()
+      );
+
+      let (content, failure) = catch_render_failure(ctx, || {
+        format_item_with_path(ctx, id, item, _crate_data, item_paths, include_private)
+      })?;
+
+      // Items duplicated by glob re-exports point to the page of their definition
+      let definition_path = item_paths.get(id).filter(|path| {
+        let definition_module = match path.len() {
+          1 => path[0].clone(),
+          len => path[..len - 1].join("::"),
+        };
+        definition_module != _module_name
+          && modules
+            .get(&definition_module)
+            .is_some_and(|items| items.iter().any(|(item_id, _)| item_id == id))
+      });
+
+      let page = item_page(
+        ctx,
+        item,
+        name,
+        _crate_name,
+        _module_name,
+        definition_path.map(Vec::as_slice),
+        &content,
+      );
+      Some((file_path, page, failure))
+    })
+    .collect()
+}
+
+/// Run `render`, turning a panic into a placeholder page body and the panic message.
+fn catch_render_failure(
+  ctx: &RenderContext,
+  render: impl FnOnce() -> Option<String>,
+) -> Option<(String, Option<String>)> {
+  match std::panic::catch_unwind(std::panic::AssertUnwindSafe(render)) {
+    Ok(content) => Some((content?, None)),
+    Err(panic) => {
+      let message = panic_message(panic.as_ref());
+      Some((render_failure_placeholder(ctx, &message), Some(message)))
+    }
+  }
+}
+
+/// Message of a caught panic.
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+  match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+    (Some(message), _) => message.to_string(),
+    (_, Some(message)) => message.clone(),
+    _ => "unknown error".to_string(),
+  }
+}
+
+/// Body of the page of an item that failed to render.
+fn render_failure_placeholder(ctx: &RenderContext, message: &str) -> String {
+  format!(
+    "{}\n\nThe documentation of this item could not be generated: `{}`\n:::\n",
+    ctx
+      .docusaurus_major
+      .admonition("danger", "Rendering failed"),
+    // A code span keeps `<` and `{` from being parsed as MDX
+    message.replace('`', "'").replace('\n', " ")
+  )
+}
+
+/// Bold `::`-separated breadcrumb of `path`, linking each ancestor module to its page.
+///
+/// Segments removed by `strip_prefix` are not shown, and modules below the
+/// depth limit (which have no page) are not linked.
+fn breadcrumb(ctx: &RenderContext, path: &str) -> String {
+  let segments: Vec<&str> = path.split("::").collect();
+  let shown = ctx.display_path(path).split("::").count();
+  let base = ctx.link_base_path();
+  let links: Vec<String> = segments
+    .iter()
+    .enumerate()
+    .skip(segments.len().saturating_sub(shown))
+    .map(|(i, segment)| {
+      let is_ancestor = i + 1 < segments.len();
+      if is_ancestor && ctx.max_depth.is_none_or(|max_depth| i <= max_depth) {
+        format!(
+          "<Link to=\"{}/{}/\">{}</Link>",
+          base,
+          segments[..=i].join("/"),
+          segment
+        )
+      } else {
+        segment.to_string()
+      }
+    })
+    .collect();
+  format!("**{}**", links.join("::"))
+}
+
+/// Page of an item named `name` in `module_name`, laid out by the item template
+/// around its rendered `content`
+///
+/// Pages of items defined elsewhere (`definition_path`, duplicated by a glob
+/// re-export) link to the page of the definition and declare it canonical.
+#[allow(clippy::too_many_arguments)]
+fn item_page(
+  ctx: &RenderContext,
+  item: &Item,
+  name: &str,
+  crate_name: &str,
+  module_name: &str,
+  definition_path: Option<&[String]>,
+  content: &str,
+) -> String {
+  // Add frontmatter for Docusaurus navigation with type label and sidebar
+  let type_label = get_item_type_label(item);
+  let title = if type_label.is_empty() {
+    name.to_string()
+  } else {
+    format!("{} {}", type_label, name)
+  };
+
+  // Items share the sidebar of their module's contents; the crate root has
+  // its own variant, since the crate's sidebar lists the crates
+  let sidebar_kind = if module_name == crate_name {
+    SidebarKind::CrateItems
+  } else {
+    SidebarKind::Module
+  };
+
+  let item_prefix = get_item_prefix(item);
+  let sitemap = ctx.sitemap_frontmatter(item_prefix.trim_end_matches('.'));
+  // Autogenerated sidebars label pages by title, so item pages get their plain name
+  let sidebar = if ctx.category_files {
+    format!("sidebar_label: \"{}\"\n", name)
+  } else {
+    ctx.displayed_sidebar(module_name, sidebar_kind)
+  };
+  let definition_url = definition_path.map(|path| {
+    let (module_path, _) = path.split_at(path.len().saturating_sub(1).max(1));
+    format!(
+      "{}/{}/{}{}",
+      ctx.link_base_path(),
+      module_path.join("/"),
+      item_prefix,
+      name
+    )
+  });
+  let canonical = definition_url
+    .as_ref()
+    .map(|url| format!("canonical_url: \"{}\"\n", url))
+    .unwrap_or_default();
+  let seo = ctx.seo_frontmatter(
+    item.docs.as_deref(),
+    &[
+      &type_label.to_lowercase(),
+      crate_name,
+      ctx.display_path(module_name),
+    ],
+  );
+  let stability = item_stability(item);
+  let sidebar_class = stability
+    .as_ref()
+    .map(|stability| format!("sidebar_class_name: \"{}\"\n", stability.class_name()))
+    .unwrap_or_default();
+  let frontmatter = format!(
+    "{}{}{}{}{}",
+    seo, sidebar, sidebar_class, canonical, sitemap
+  );
+
+  // Add breadcrumb path (like rustdoc does for all items)
+  // Re-exported items (duplicates) use the current module path, like original items
+  let path = format!("{}::{}", module_name, name);
+  let mut breadcrumb = format!("{}\n\n", breadcrumb(ctx, &path));
+  if let Some(badge) = ctx.since_badge(&path) {
+    breadcrumb.push_str(&format!("{}\n\n", badge));
+  }
+  if let Some(stability) = &stability {
+    breadcrumb.push_str(&stability.admonition(ctx));
+  }
+  if let (Some(path), Some(url)) = (definition_path, definition_url) {
+    breadcrumb.push_str(&format!(
+      "{}\n\nDefined in <Link to=\"{}\">{}</Link> and re-exported here.\n:::\n\n",
+      ctx.docusaurus_major.admonition("note", "Re-export"),
+      url,
+      ctx.display_path(&path.join("::"))
+    ));
+  }
+  ctx.render_page(
+    PageTemplate::Item,
+    &title,
+    &frontmatter,
+    &breadcrumb,
+    content,
+  )
+}
+
+/// Pages for items that modules re-export from other crates (`pub use other::Type`),
+/// rendered from the other crate's rustdoc JSON, so the re-exporting crate's docs
+/// are self-contained. The `pub use` lines of the module pages link to them.
+#[allow(clippy::too_many_arguments)]
+fn inline_external_reexports(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  item_paths: &HashMap<Id, Vec<String>>,
+  inlined_crates: &[Crate],
+  include_private: bool,
+  files: &mut BTreeMap<String, String>,
+  item_pages: &mut Vec<(String, String)>,
+) {
+  let sources: HashMap<&str, (&Crate, HashMap<Id, Vec<String>>)> = inlined_crates
+    .iter()
+    .filter_map(|source| {
+      let name = source.index.get(&source.root)?.name.as_deref()?;
+      Some((name, (source, build_path_map(source))))
+    })
+    .collect();
+  let extension = ctx.extension.as_str();
+
+  for (module_id, module_item) in &crate_data.index {
+    let (ItemEnum::Module(module), Some(module_path)) =
+      (&module_item.inner, item_paths.get(module_id))
+    else {
+      continue;
+    };
+    let crate_name = &module_path[0];
+    let module_dir: String = module_path[1..]
+      .iter()
+      .map(|segment| format!("{}/", segment))
+      .collect();
+    let overview_path = format!("{}index.{}", module_dir, extension);
+    // Modules without a page (beyond the depth limit, or left out of a preview)
+    if !files.contains_key(&overview_path) {
+      continue;
+    }
+
+    for use_item in module
+      .items
+      .iter()
+      .filter_map(|id| crate_data.index.get(id))
+    {
+      let ItemEnum::Use(import) = &use_item.inner else {
+        continue;
+      };
+      if import.is_glob || !is_visible(use_item, include_private) {
+        continue;
+      }
+      let Some(summary) = import
+        .id
+        .as_ref()
+        .filter(|id| !crate_data.index.contains_key(id))
+        .and_then(|id| crate_data.paths.get(id))
+      else {
+        continue;
+      };
+      let Some((source, source_paths)) = crate_data
+        .external_crates
+        .get(&summary.crate_id)
+        .and_then(|external| sources.get(external.name.as_str()))
+      else {
+        continue;
+      };
+      let Some((id, item)) = source
+        .paths
+        .iter()
+        .find(|(_, source_summary)| {
+          source_summary.crate_id == 0 && source_summary.path == summary.path
+        })
+        .and_then(|(id, _)| source.index.get_key_value(id))
+      else {
+        log::debug!(target: "reexports", "`{}` is not in the crate's rustdoc JSON", import.source);
+        continue;
+      };
+      if !can_format_item(item) || matches!(item.inner, ItemEnum::Module(_)) {
         continue;
       }
 
-      // Use rustdoc-style prefix for item filename (e.g., "fn.send_message.md")
-      let item_prefix = get_item_prefix(item);
-      let file_path = format!("{}{}{}.md", path_prefix, item_prefix, name);
-
-      if let Some(mut content) =
-        format_item_with_path(id, item, _crate_data, item_paths, include_private)
-      {
-        // Add frontmatter for Docusaurus navigation with type label and sidebar
-        let type_label = get_item_type_label(item);
-        let title = if type_label.is_empty() {
-          name.to_string()
-        } else {
-          format!("{} {}", type_label, name)
-        };
-
-        // Calculate sidebar key from module path (same as module overview)
-        let base_path = BASE_PATH.with(|bp| bp.borrow().clone());
-        let base_path_for_sidebar = base_path
-          .strip_prefix("/docs/")
-          .or_else(|| base_path.strip_prefix("/docs"))
-          .or_else(|| base_path.strip_prefix("/"))
-          .unwrap_or(&base_path);
-        let sidebar_key = if _module_name == _crate_name {
-          // For items in the crate root, use "_items" suffix
-          // to match the sidebar generated for leaf items of the crate
-          format!("{}/{}_items", base_path_for_sidebar, _crate_name).replace("/", "_")
-        } else {
-          let module_path = _module_name.replace("::", "/");
-          format!("{}/{}", base_path_for_sidebar, module_path).replace("/", "_")
-        };
-
-        let frontmatter = format!(
-          "---\ntitle: \"{}\"\ndisplayed_sidebar: '{}'\n---\n\nimport RustCode from '@site/src/components/RustCode';\nimport Link from '@docusaurus/Link';\n\n",
-          title, sidebar_key
-        );
-
-        // Add breadcrumb path (like rustdoc does for all items)
-        // For re-exported items (duplicates), use the current module path + item name
-        // For original items, use their full path from item_paths
-        let breadcrumb = if _module_name == _crate_name {
-          // Root module - just crate::ItemName
-          format!("**{}::{}**\n\n", _module_name, name)
-        } else {
-          // Check if this is the original location or a re-export
-          let original_path = item_paths.get(id).map(|p| p.join("::"));
-          let expected_path = format!("{}::{}", _module_name, name);
-
-          // If the original path matches the expected path, it's the original item
-          // Otherwise, it's a re-exported duplicate - use the current module path
-          if original_path.as_deref() == Some(expected_path.as_str()) {
-            format!("**{}**\n\n", expected_path)
-          } else {
-            // Re-exported item - use current module path
-            format!("**{}**\n\n", expected_path)
-          }
-        };
+      let page = format!("{}{}{}", module_dir, get_item_prefix(item), import.name);
+      let file_path = format!("{}.{}", page, extension);
+      if item_pages.iter().any(|(path, _)| *path == file_path) {
+        continue;
+      }
+      let Some(content) =
+        format_item_with_path(ctx, id, item, source, source_paths, include_private)
+      else {
+        continue;
+      };
+      let markdown = item_page(
+        ctx,
+        item,
+        &import.name,
+        crate_name,
+        &module_path.join("::"),
+        None,
+        &content,
+      );
+      item_pages.push((file_path, markdown));
 
-        content = format!("{}{}{}", frontmatter, breadcrumb, content);
-        files.insert(file_path, content);
+      // Point the `pub use` line of the module page to the inlined page
+      let reexport_line = format!(
+        "<RustCode inline code={{`{}`}}",
+        escape_template_literal(&format!("pub use {};", import.source))
+      );
+      let type_name = import.source.rsplit("::").next().unwrap_or(&import.source);
+      let link = format!("{}/{}/{}", ctx.link_base_path(), crate_name, page);
+      if let Some(overview) = files.get_mut(&overview_path) {
+        *overview = overview
+          .lines()
+          .map(|line| match line.starts_with(&reexport_line) {
+            true => format!(
+              "{} links={{{}}} />",
+              reexport_line,
+              format_links_as_json(&[(type_name.to_string(), link.clone())])
+            ),
+            false => line.to_string(),
+          })
+          .collect::<Vec<_>>()
+          .join("\n")
+          + "\n";
       }
     }
   }
 }
 
-#[allow(clippy::same_item_push)]
+#[allow(clippy::same_item_push, clippy::too_many_arguments)]
 fn generate_module_overview(
+  ctx: &RenderContext,
   module_name: &str,
-  items: &[(Id, Item)],
+  items: &[(Id, &Item)],
   _crate_data: &Crate,
   _item_paths: &HashMap<Id, Vec<String>>,
   crate_name: &str,
-  module_hierarchy: &HashMap<String, Vec<String>>,
+  module_hierarchy: &BTreeMap<String, Vec<String>>,
+  hidden_modules: &BTreeMap<String, usize>,
 ) -> String {
   let mut output = String::new();
 
@@ -3126,13 +7190,11 @@ fn generate_module_overview(
   // Get just the last component of the module name (rustdoc style)
   let short_name = display_name.split("::").last().unwrap_or(display_name);
 
-  // Calculate sidebar key from module path
-  let base_path = BASE_PATH.with(|bp| bp.borrow().clone());
-  let base_path_for_sidebar = base_path
-    .strip_prefix("/docs/")
-    .or_else(|| base_path.strip_prefix("/docs"))
-    .or_else(|| base_path.strip_prefix("/"))
-    .unwrap_or(&base_path);
+  // Module documentation (if any module item exists)
+  let module_docs = items
+    .iter()
+    .find(|(_, item)| matches!(&item.inner, ItemEnum::Module(_)))
+    .and_then(|(_, item)| item.docs.as_deref());
 
   // For module overview pages, use the PARENT module's sidebar
   // This way the module page shows "In <parent>" with siblings
@@ -3147,48 +7209,40 @@ fn generate_module_overview(
     crate_name.to_string()
   };
 
-  let sidebar_key = if sidebar_module == crate_name {
+  let sidebar_kind = if sidebar_module == crate_name {
     // If this module's parent is the crate root, use the "_modules" variant
     // which shows "In <crate>" with crate's modules, not "Crates"
     if module_name == crate_name {
       // This IS the crate root page itself - use the regular sidebar
-      format!("{}/{}", base_path_for_sidebar, crate_name).replace("/", "_")
+      SidebarKind::Module
     } else {
       // This is a child of the crate root - use the "_modules" variant
-      format!("{}/{}_modules", base_path_for_sidebar, crate_name).replace("/", "_")
+      SidebarKind::CrateModules
     }
   } else {
     // This module's parent is another module (not the crate)
     // Use the parent's "_children" sidebar which shows the parent's contents
-    let module_path = sidebar_module.replace("::", "/");
-    format!("{}/{}_children", base_path_for_sidebar, module_path).replace("/", "_")
+    SidebarKind::Children
   };
 
-  // Add FrontMatter for Docusaurus with the module name as title and sidebar
-  output.push_str("---\n");
-  output.push_str(&format!("title: {}\n", short_name));
-  output.push_str(&format!("sidebar_label: {}\n", short_name));
-  output.push_str(&format!("displayed_sidebar: '{}'\n", sidebar_key));
-  output.push_str("---\n\n");
-
-  // Import RustCode component
-  output.push_str("import RustCode from '@site/src/components/RustCode';\n");
-  output.push_str("import Link from '@docusaurus/Link';\n\n");
+  // FrontMatter for Docusaurus with the module name as title and sidebar
+  let frontmatter = format!(
+    "{}{}{}",
+    ctx.seo_frontmatter(
+      module_docs,
+      &["module", crate_name, ctx.display_path(module_name)],
+    ),
+    ctx.displayed_sidebar(&sidebar_module, sidebar_kind),
+    ctx.sitemap_frontmatter("module")
+  );
 
   // Breadcrumb with :: separator (rustdoc style)
-  let breadcrumb = module_name;
-  output.push_str(&format!("**{}**\n\n", breadcrumb));
+  let breadcrumb = format!("{}\n\n", breadcrumb(ctx, module_name));
 
   output.push_str(&format!("# Module {}\n\n", short_name));
 
-  // Module documentation (if any module item exists)
-  for (_id, item) in items {
-    if matches!(&item.inner, ItemEnum::Module(_)) {
-      if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", sanitize_docs_for_mdx(docs)));
-      }
-      break;
-    }
+  if let Some(docs) = module_docs {
+    output.push_str(&format!("{}\n\n", format_docs(ctx, docs, 2)));
   }
 
   // Separate re-exports (Use items) from regular items
@@ -3205,7 +7259,13 @@ fn generate_module_overview(
 
   // Show Re-exports section first (if any)
   // Only show re-exports where the source module/item is public (rustdoc behavior)
-  if !re_exports.is_empty() {
+  let extern_crates = _item_paths
+    .iter()
+    .find(|(_, path)| path.join("::") == module_name)
+    .and_then(|(id, _)| _crate_data.index.get(id))
+    .map(|module| format_extern_crates(module, _crate_data))
+    .unwrap_or_default();
+  if !re_exports.is_empty() || !extern_crates.is_empty() {
     let mut public_re_exports = Vec::new();
 
     for (id, item) in &re_exports {
@@ -3213,7 +7273,7 @@ fn generate_module_overview(
         // Check if the source is public
         let is_source_public = if let Some(import_id) = &use_item.id {
           if let Some(imported_item) = _crate_data.index.get(import_id) {
-            is_public(imported_item)
+            is_public(imported_item) || is_public_variant(import_id, _crate_data)
           } else {
             // External dependency - always show
             true
@@ -3229,8 +7289,9 @@ fn generate_module_overview(
       }
     }
 
-    if !public_re_exports.is_empty() {
+    if !public_re_exports.is_empty() || !extern_crates.is_empty() {
       output.push_str("## Re-exports\n\n");
+      output.push_str(&extern_crates);
 
       for (_id, _item, use_item) in &public_re_exports {
         // Use the full source path for proper linking
@@ -3247,9 +7308,19 @@ fn generate_module_overview(
         // e.g., "patterns::Builder" -> "Builder"
         let type_name = source_path.split("::").last().unwrap_or(source_path);
 
+        // Enum variants link to their anchor on the enum page
+        let variant_reexport = variant_reexport_links(ctx, use_item, _crate_data);
+        if use_item.id.is_none() {
+          log::warn!(target: "reexports", "`{}` has no target in the rustdoc JSON", code_str);
+        }
+
         // Try to find link to the re-exported item using absolute links
-        let links: Vec<(String, String)> = if let Some(import_id) = &use_item.id {
-          if let Some(link) = generate_type_link(source_path, import_id, _crate_data, None) {
+        let links: Vec<(String, String)> = if let Some((links, _)) = &variant_reexport {
+          links.clone()
+        } else if let Some(link) = external_glob_link(ctx, use_item, _crate_data) {
+          vec![(type_name.to_string(), link)]
+        } else if let Some(import_id) = &use_item.id {
+          if let Some(link) = generate_type_link(ctx, source_path, import_id, _crate_data) {
             vec![(type_name.to_string(), link)]
           } else {
             // External dependency - no link
@@ -3259,13 +7330,11 @@ fn generate_module_overview(
           vec![]
         };
 
-        let links_json = format_links_as_json(&links);
-
         // Use RustCode inline component for consistent formatting
-        output.push_str(&format!(
-          "<RustCode inline code={{`{}`}} links={{{}}} />\n\n",
-          code_str, links_json
-        ));
+        output.push_str(&rust_code(&code_str, &links, true));
+        if let Some((_, variants)) = variant_reexport {
+          output.push_str(&variants);
+        }
       }
     }
   }
@@ -3278,8 +7347,12 @@ fn generate_module_overview(
       ItemEnum::Enum(_) => "Enums",
       ItemEnum::Function(_) => "Functions",
       ItemEnum::Trait(_) => "Traits",
+      ItemEnum::TraitAlias(_) => "Trait Aliases",
       ItemEnum::Constant { .. } => "Constants",
       ItemEnum::TypeAlias(_) => "Type Aliases",
+      ItemEnum::Static(_) => "Statics",
+      ItemEnum::ExternType => "Foreign Types",
+      ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => "Macros",
       ItemEnum::Module(_) => continue, // Skip modules from items, we'll use hierarchy instead
       ItemEnum::Use(_) => continue,    // Use items are handled separately in Re-exports section
       _ => continue,
@@ -3289,12 +7362,16 @@ fn generate_module_overview(
 
   let type_order = [
     "Modules",
+    "Macros",
     "Structs",
     "Enums",
     "Functions",
     "Traits",
+    "Trait Aliases",
     "Constants",
     "Type Aliases",
+    "Statics",
+    "Foreign Types",
   ];
   for type_name in &type_order {
     // Special handling for Modules - use hierarchy instead of items
@@ -3325,19 +7402,39 @@ fn generate_module_overview(
               });
 
               let visibility_indicator = module_item
-                .map(|(_, item)| get_visibility_indicator(item))
-                .unwrap_or("");
+                .map(|(_, item)| ctx.visibility_indicator(item))
+                .unwrap_or_default();
 
               let doc_line = module_item
                 .and_then(|(_, item)| item.docs.as_ref())
                 .and_then(|docs| docs.lines().next())
-                .filter(|line| !line.is_empty());
+                .filter(|line| !line.is_empty())
+                .map(summary_to_html);
+
+              // Modules below the depth limit have no page - show a summary instead
+              if let Some(hidden_count) = hidden_modules.get(submodule_path) {
+                let doc_suffix = doc_line
+                  .map(|doc_text| format!("{}{}", ctx.summary_separator(), doc_text))
+                  .unwrap_or_default();
+                output.push_str(&format!(
+                  "<div><span className=\"rust-mod\">{}</span> {}{} {}</div>\n\n",
+                  submodule_name,
+                  visibility_indicator,
+                  doc_suffix,
+                  format_hidden_items_note(*hidden_count)
+                ));
+                continue;
+              }
 
-              // Only add " — " if there's documentation
+              // Only add the separator if there's documentation
               if let Some(doc_text) = doc_line {
                 output.push_str(&format!(
-                  "<div><Link to=\"{}/\" className=\"rust-mod\">{}</Link> {} — {}</div>\n\n",
-                  submodule_name, submodule_name, visibility_indicator, doc_text
+                  "<div><Link to=\"{}/\" className=\"rust-mod\">{}</Link> {}{}{}</div>\n\n",
+                  submodule_name,
+                  submodule_name,
+                  visibility_indicator,
+                  ctx.summary_separator(),
+                  doc_text
                 ));
               } else {
                 output.push_str(&format!(
@@ -3359,10 +7456,12 @@ fn generate_module_overview(
       let css_class = match *type_name {
         "Modules" => "rust-mod",
         "Structs" | "Enums" => "rust-struct",
-        "Traits" => "rust-trait",
+        "Traits" | "Trait Aliases" => "rust-trait",
         "Functions" => "rust-fn",
         "Constants" => "rust-constant",
-        "Type Aliases" => "rust-type",
+        "Type Aliases" | "Foreign Types" => "rust-type",
+        "Statics" => "rust-static",
+        "Macros" => "rust-macro",
         _ => "rust-item",
       };
 
@@ -3439,18 +7538,29 @@ fn generate_module_overview(
             format!("{}{}", prefix, name)
           };
 
-          let visibility_indicator = get_visibility_indicator(item);
+          let visibility_indicator = ctx.visibility_indicator(item);
 
           output.push_str("<div>");
           output.push_str(&format!(
             "<Link to=\"{}\" className=\"{}\">{}</Link> {}",
             link, css_class, name, visibility_indicator
           ));
+          let item_path = match _item_paths.get(id) {
+            Some(path) => path.join("::"),
+            None => format!("{}::{}", module_name, name),
+          };
+          if let Some(badge) = ctx.since_badge(&item_path) {
+            output.push_str(&format!(" {}", badge));
+          }
           if let Some(docs) = &item.docs {
             let sanitized = sanitize_docs_for_mdx(docs);
             if let Some(first_line) = sanitized.lines().next() {
               if !first_line.is_empty() {
-                output.push_str(&format!(" — {}", first_line));
+                output.push_str(&format!(
+                  "{}{}",
+                  ctx.summary_separator(),
+                  summary_to_html(first_line)
+                ));
               }
             }
           }
@@ -3460,101 +7570,86 @@ fn generate_module_overview(
     }
   }
 
-  output
+  ctx.render_page(
+    PageTemplate::Module,
+    short_name,
+    &frontmatter,
+    &breadcrumb,
+    &output,
+  )
 }
 
 /// Generate sidebar structure for Docusaurus
 /// This generates multiple sidebars - one for each module that has content
 fn generate_all_sidebars(
+  ctx: &RenderContext,
   crate_name: &str,
-  modules: &HashMap<String, Vec<(Id, Item)>>,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
   _item_paths: &HashMap<Id, Vec<String>>,
   crate_data: &Crate,
-  sidebarconfig_collapsed: bool,
-) -> String {
-  let mut all_sidebars = HashMap::new();
-
-  // Get the base_path from thread-local storage
-  let base_path = BASE_PATH.with(|bp| bp.borrow().clone());
-
-  // For Docusaurus sidebar, paths must be relative to the docs/ folder
-  let sidebar_prefix = if base_path == "/docs" || base_path == "docs" {
-    ""
-  } else if base_path.starts_with("/docs/") {
-    base_path.strip_prefix("/docs/").unwrap()
-  } else if base_path.starts_with("docs/") {
-    base_path.strip_prefix("docs/").unwrap()
-  } else {
-    &base_path
-  };
+  collapse: SidebarCollapse,
+) -> SidebarMap {
+  let mut all_sidebars = BTreeMap::new();
 
   // Generate TWO sidebars for the root crate:
   // 1. With is_root=true (shows "Crates" section) - used by the crate's own page
   let root_sidebar_for_crate = generate_sidebar_for_module(
+    ctx,
     crate_name,
     crate_name,
     modules,
     crate_data,
-    sidebar_prefix,
-    sidebarconfig_collapsed,
+    collapse,
     true, // is_root - shows "Crates" section
     &crate_data.crate_version,
     false, // show_all_parent_items - false for modules
   );
 
-  let root_path = if sidebar_prefix.is_empty() {
-    crate_name.to_string()
-  } else {
-    format!("{}/{}", sidebar_prefix, crate_name)
-  };
-  all_sidebars.insert(root_path.clone(), root_sidebar_for_crate);
+  all_sidebars.insert(
+    ctx.sidebar_key(crate_name, SidebarKind::Module),
+    root_sidebar_for_crate,
+  );
 
   // 2. With is_root=false (shows crate's modules) - used by the crate's child modules
   let root_sidebar_for_modules = generate_sidebar_for_module(
+    ctx,
     crate_name,
     crate_name,
     modules,
     crate_data,
-    sidebar_prefix,
-    sidebarconfig_collapsed,
+    collapse,
     false, // is_root=false - shows "In <parent>" with crate's modules
     &crate_data.crate_version,
     false, // show_all_parent_items - false for modules
   );
 
-  // Use a different key for this sidebar (add "_modules" suffix)
-  let root_path_for_modules = format!("{}_modules", root_path);
-  all_sidebars.insert(root_path_for_modules, root_sidebar_for_modules);
+  all_sidebars.insert(
+    ctx.sidebar_key(crate_name, SidebarKind::CrateModules),
+    root_sidebar_for_modules,
+  );
 
   // Generate sidebar for each submodule (for dynamic sidebar when entering modules)
-  eprintln!("[DEBUG] Total modules to process: {}", modules.keys().len());
+  log::debug!(target: "sidebar", "Total modules to process: {}", modules.keys().len());
   for module_key in modules.keys() {
-    eprintln!("[DEBUG] Processing module: {}", module_key);
+    log::debug!(target: "sidebar", "Processing module: {}", module_key);
     if module_key == crate_name {
-      eprintln!("[DEBUG] Skipping root crate: {}", crate_name);
+      log::debug!(target: "sidebar", "Skipping root crate: {}", crate_name);
       continue; // Skip root, already handled
     }
 
     let sidebar = generate_sidebar_for_module(
+      ctx,
       crate_name,
       module_key,
       modules,
       crate_data,
-      sidebar_prefix,
-      sidebarconfig_collapsed,
+      collapse,
       false, // not root
       &crate_data.crate_version,
       false, // show_all_parent_items - false for modules
     );
 
-    // Convert module_key from Rust path (::) to file path (/)
-    let module_path_normalized = module_key.replace("::", "/");
-    let module_path = if sidebar_prefix.is_empty() {
-      module_path_normalized.clone()
-    } else {
-      format!("{}/{}", sidebar_prefix, module_path_normalized)
-    };
-    all_sidebars.insert(module_path.clone(), sidebar);
+    all_sidebars.insert(ctx.sidebar_key(module_key, SidebarKind::Module), sidebar);
 
     // Check if this module has sub-modules (direct children) or items
     // Generate a _children sidebar if the module has submodules or items (structs, enums, etc.)
@@ -3573,28 +7668,28 @@ fn generate_all_sidebars(
       })
       .unwrap_or(false);
 
-    eprintln!(
-      "[DEBUG] Module '{}' has_submodules_or_items: {}",
+    log::debug!(target: "sidebar", "Module '{}' has_submodules_or_items: {}",
       module_key, has_submodules_or_items
     );
 
     // If this module has sub-modules or items, generate an additional sidebar for them
     if has_submodules_or_items {
       let submodule_sidebar = generate_sidebar_for_module(
+        ctx,
         crate_name,
         module_key, // Use this module as the "parent"
         modules,
         crate_data,
-        sidebar_prefix,
-        sidebarconfig_collapsed,
+        collapse,
         false,
         &crate_data.crate_version,
         true, // show_all_parent_items = true to show THIS module's contents
       );
 
-      // Use "_children" suffix to distinguish from the module's own sidebar
-      let submodule_sidebar_key = format!("{}_children", module_path.replace("/", "_"));
-      all_sidebars.insert(submodule_sidebar_key, submodule_sidebar);
+      all_sidebars.insert(
+        ctx.sidebar_key(module_key, SidebarKind::Children),
+        submodule_sidebar,
+      );
     }
   }
 
@@ -3624,62 +7719,108 @@ fn generate_all_sidebars(
     // Generate sidebar for this module (to be used by all leaf items in it)
     let parent_module = module_key;
 
-    eprintln!(
-      "[DEBUG] Generating leaf items sidebar for module_key: {}",
+    log::debug!(target: "sidebar", "Generating leaf items sidebar for module_key: {}",
       module_key
     );
 
     let item_sidebar = generate_sidebar_for_module(
+      ctx,
       crate_name,
       parent_module,
       modules,
       crate_data,
-      sidebar_prefix,
-      sidebarconfig_collapsed,
+      collapse,
       false, // is_root = false - leaf items always show "In <module>", never "Crates"
       &crate_data.crate_version,
       true, // show_all_parent_items - true for leaf items (struct, enum, etc.)
     );
 
-    // The sidebar key is the module path (not the item path!)
-    // This matches what's written in the frontmatter of item files
-    let parent_module_path = parent_module.replace("::", "/");
-
-    let sidebar_key = if sidebar_prefix.is_empty() {
-      parent_module_path.clone()
+    // The sidebar key is the module path (not the item path!), as in the
+    // front matter of item files; the leaf items of the crate root get their
+    // own variant to avoid collision with the crate's sidebar (which shows "Crates")
+    let sidebar_kind = if parent_module == crate_name {
+      SidebarKind::CrateItems
     } else {
-      format!("{}/{}", sidebar_prefix, parent_module_path)
+      SidebarKind::Module
     };
+    all_sidebars.insert(ctx.sidebar_key(parent_module, sidebar_kind), item_sidebar);
+  }
 
-    // If this is for leaf items of the crate root, add "_items" suffix
-    // to avoid collision with the crate's own sidebar (which shows "Crates")
-    let sidebar_key = if parent_module == crate_name {
-      format!("{}_items", sidebar_key.replace("/", "_"))
-    } else {
-      sidebar_key
-    };
+  all_sidebars
+}
 
-    all_sidebars.insert(sidebar_key, item_sidebar);
-  }
+/// `_category_.json` files of the crate directory and of each module directory
+/// with an index page, for Docusaurus autogenerated sidebars.
+///
+/// Module directories are positioned alphabetically, before the item pages.
+fn generate_category_files(
+  ctx: &RenderContext,
+  crate_name: &str,
+  files: &BTreeMap<String, String>,
+  collapse: SidebarCollapse,
+) -> Result<Vec<(String, String)>> {
+  let index_file = format!("index.{}", ctx.extension.as_str());
+  let mut dirs: Vec<&str> = files
+    .keys()
+    .filter_map(|path| path.strip_suffix(&index_file))
+    .filter_map(|dir| dir.strip_suffix('/'))
+    .collect();
+  dirs.sort();
+
+  let mut category_files = Vec::with_capacity(dirs.len() + 1);
+  let crate_category = CategoryMetadata {
+    label: crate_name.to_string(),
+    position: None,
+    collapsible: true,
+    collapsed: collapse.crates,
+    link: Some(ctx.doc_id(&format!("{}/index", crate_name))),
+    class_name: "rust-mod".to_string(),
+  };
+  category_files.push((
+    "_category_.json".to_string(),
+    serde_json::to_string_pretty(&crate_category)?,
+  ));
 
-  // Convert to TypeScript with multiple sidebars
-  sidebars_to_js(&all_sidebars, sidebarconfig_collapsed)
+  for dir in &dirs {
+    let (parent, label) = dir.rsplit_once('/').unwrap_or(("", dir));
+    let position = dirs
+      .iter()
+      .filter(|other| other.rsplit_once('/').map_or("", |(parent, _)| parent) == parent)
+      .position(|other| other == dir)
+      .map(|i| i + 1);
+    let category = CategoryMetadata {
+      label: label.to_string(),
+      position,
+      collapsible: true,
+      collapsed: collapse.modules,
+      link: Some(ctx.module_doc_id(&format!("{}/{}", crate_name, dir))),
+      class_name: "rust-mod".to_string(),
+    };
+    category_files.push((
+      format!("{}/_category_.json", dir),
+      serde_json::to_string_pretty(&category)?,
+    ));
+  }
+  Ok(category_files)
 }
 
 /// Generate sidebar for a specific module
 #[allow(clippy::too_many_arguments)]
 fn generate_sidebar_for_module(
+  ctx: &RenderContext,
   _crate_name: &str, // Prefixed with _ to avoid unused warning
   module_key: &str,
-  modules: &HashMap<String, Vec<(Id, Item)>>,
+  modules: &BTreeMap<String, Vec<(Id, &Item)>>,
   _crate_data: &Crate, // Prefixed with _ to avoid unused warning
-  sidebar_prefix: &str,
-  _sidebarconfig_collapsed: bool, // Prefixed with _ to avoid unused warning
+  collapse: SidebarCollapse,
   is_root: bool,
   crate_version: &Option<String>,
   show_all_parent_items: bool, // New parameter: if true, show all items in parent module (for leaf items)
 ) -> Vec<SidebarItem> {
-  let module_items = modules.get(module_key).cloned().unwrap_or_default();
+  let module_items = modules
+    .get(module_key)
+    .map(Vec::as_slice)
+    .unwrap_or_default();
 
   // Convert module_key from :: to / for doc IDs
   let _module_path = module_key.replace("::", "/"); // Prefixed with _ to avoid unused warning
@@ -3689,54 +7830,58 @@ fn generate_sidebar_for_module(
   // Add "Go back" link and crate title for root crates, or just crate title for modules
   if is_root {
     // For root crate: use the configured sidebar_root_link if available
-    let sidebar_root_link = SIDEBAR_ROOT_LINK.with(|srl| srl.borrow().clone());
+    let sidebar_root_link = ctx.sidebar_root_link.clone();
 
     if let Some(link) = sidebar_root_link {
       sidebar_items.push(SidebarItem::Link {
         href: link,
         label: "← Go back".to_string(),
-        custom_props: Some("rust-sidebar-back-link".to_string()),
+        class_name: Some("rust-sidebar-back-link".to_string()),
+        custom_props: None,
       });
     }
 
     // Add crate title with version for root crates
     // The title itself is clickable and links to the crate index
-    let crate_root_path = if sidebar_prefix.is_empty() {
-      format!("{}/index", _crate_name)
-    } else {
-      format!("{}/{}/index", sidebar_prefix, _crate_name)
-    };
+    let crate_root_path = ctx.module_doc_id(_crate_name);
 
     // Use customProps to pass crate name and version to a custom sidebar component
     sidebar_items.push(SidebarItem::Doc {
       id: crate_root_path,
-      label: Some(_crate_name.to_string()), // Fallback label
-      custom_props: Some(format!(
-        "{{ rustCrateTitle: true, crateName: '{}', version: '{}' }}",
-        _crate_name,
-        crate_version.as_deref().unwrap_or("")
-      )),
+      label: Some(ctx.crate_label(_crate_name)), // Fallback label
+      class_name: None,
+      custom_props: Some(serde_json::json!({
+        "rustCrateTitle": true,
+        "crateName": _crate_name,
+        "version": crate_version.as_deref().unwrap_or(""),
+      })),
     });
 
     // For root crate, the title is already clickable, so we don't add a separate Overview
+
+    if ctx.crate_features.is_some() {
+      sidebar_items.push(SidebarItem::Doc {
+        id: ctx.doc_id(&format!("{}/features", _crate_name)),
+        label: Some("Feature Flags".to_string()),
+        class_name: Some("rust-mod".to_string()),
+        custom_props: None,
+      });
+    }
   } else {
     // For submodules: show crate name with version (rustdoc style)
     // This links to the crate root
-    let crate_root_path = if sidebar_prefix.is_empty() {
-      format!("{}/index", _crate_name)
-    } else {
-      format!("{}/{}/index", sidebar_prefix, _crate_name)
-    };
+    let crate_root_path = ctx.module_doc_id(_crate_name);
 
     // Use customProps to pass crate name and version to a custom sidebar component
     sidebar_items.push(SidebarItem::Doc {
       id: crate_root_path,
-      label: Some(_crate_name.to_string()), // Fallback label
-      custom_props: Some(format!(
-        "{{ rustCrateTitle: true, crateName: '{}', version: '{}' }}",
-        _crate_name,
-        crate_version.as_deref().unwrap_or("")
-      )),
+      label: Some(ctx.crate_label(_crate_name)), // Fallback label
+      class_name: None,
+      custom_props: Some(serde_json::json!({
+        "rustCrateTitle": true,
+        "crateName": _crate_name,
+        "version": crate_version.as_deref().unwrap_or(""),
+      })),
     });
 
     // Module title commented out - the overview is already on the right side
@@ -3744,11 +7889,7 @@ fn generate_sidebar_for_module(
     /*
     // Add Overview link to the submodule's index
     // Use customProps to render it as a module title (similar to crate title but without version)
-    let module_index_path = if sidebar_prefix.is_empty() {
-        format!("{}/index", module_path)
-    } else {
-        format!("{}/{}/index", sidebar_prefix, module_path)
-    };
+    let module_index_path = ctx.module_doc_id(&module_path);
 
     let module_display_name = module_key.split("::").last().unwrap_or(module_key);
 
@@ -3766,7 +7907,7 @@ fn generate_sidebar_for_module(
   // Categorize items by type
   let mut by_type: HashMap<&str, Vec<&Item>> = HashMap::new();
 
-  for (_, item) in &module_items {
+  for (_, item) in module_items {
     if matches!(&item.inner, ItemEnum::Use(_)) {
       continue;
     }
@@ -3777,11 +7918,13 @@ fn generate_sidebar_for_module(
       ItemEnum::Enum(_) | ItemEnum::Variant(_) => "Enums",
       ItemEnum::Function(_) => "Functions",
       ItemEnum::Trait(_) => "Traits",
+      ItemEnum::TraitAlias(_) => "Trait Aliases",
       ItemEnum::Constant { .. } => "Constants",
       ItemEnum::TypeAlias(_) => "Type Aliases",
       ItemEnum::Macro(_) => "Macros",
       ItemEnum::ProcMacro(_) => "Proc Macros",
       ItemEnum::Static { .. } => "Statics",
+      ItemEnum::ExternType => "Foreign Types",
       _ => continue,
     };
 
@@ -3796,36 +7939,35 @@ fn generate_sidebar_for_module(
   // Determine which module's items to show based on show_all_parent_items and is_root:
   let (parent_module, siblings_label) = if show_all_parent_items {
     // For leaf items: show all items from the current module (not parent)
-    eprintln!("[DEBUG] Leaf item sidebar for module_key: {}", module_key);
-    (Some(module_key), format!("In {}", module_key))
+    log::debug!(target: "sidebar", "Leaf item sidebar for module_key: {}", module_key);
+    (
+      Some(module_key),
+      format!("In {}", ctx.display_path(module_key)),
+    )
   } else if is_root {
     // For root crate with is_root=true: show ONLY workspace crates, not the crate's modules
     // The workspace crates section is added separately below
-    eprintln!(
-      "[DEBUG] Root crate sidebar (is_root=true) for module_key: {}",
+    log::debug!(target: "sidebar", "Root crate sidebar (is_root=true) for module_key: {}",
       module_key
     );
     (None, String::new()) // Don't collect any modules, only show "Crates" section
   } else if module_key == _crate_name {
     // For root crate with is_root=false: show crate's own modules
     // This is used by the crate's child modules to navigate
-    eprintln!(
-      "[DEBUG] Root crate sidebar (is_root=false) for module_key: {}",
+    log::debug!(target: "sidebar", "Root crate sidebar (is_root=false) for module_key: {}",
       module_key
     );
     (Some(module_key), format!("In {}", _crate_name))
   } else if module_key.contains("::") {
     // For modules: has parent module - show siblings
     let parent = module_key.rsplit_once("::").unwrap().0;
-    eprintln!(
-      "[DEBUG] Module sidebar for module_key: {}, parent: {}",
+    log::debug!(target: "sidebar", "Module sidebar for module_key: {}, parent: {}",
       module_key, parent
     );
-    (Some(parent), format!("In {}", parent))
+    (Some(parent), format!("In {}", ctx.display_path(parent)))
   } else {
     // For top-level modules: show siblings in crate
-    eprintln!(
-      "[DEBUG] Top-level module sidebar for module_key: {}",
+    log::debug!(target: "sidebar", "Top-level module sidebar for module_key: {}",
       module_key
     );
     (None, format!("In crate {}", _crate_name))
@@ -3839,10 +7981,12 @@ fn generate_sidebar_for_module(
     "Structs",
     "Enums",
     "Traits",
+    "Trait Aliases",
     "Functions",
     "Type Aliases",
     "Constants",
     "Statics",
+    "Foreign Types",
     "Primitives",
   ];
 
@@ -3853,6 +7997,7 @@ fn generate_sidebar_for_module(
   // For both modules and leaf items, we need to add child modules
   // - For modules: children of the parent module (siblings of current module)
   // - For leaf items: children of the current module (submodules)
+  // Module keys are sorted, so the sidebar is the same on every run
   let child_modules: Vec<&String> = modules
     .keys()
     .filter(|key| {
@@ -3878,11 +8023,7 @@ fn generate_sidebar_for_module(
   for child_key in child_modules {
     let child_name = child_key.split("::").last().unwrap_or(child_key);
     let child_path = child_key.replace("::", "/");
-    let child_doc_id = if sidebar_prefix.is_empty() {
-      format!("{}/index", child_path)
-    } else {
-      format!("{}/{}/index", sidebar_prefix, child_path)
-    };
+    let child_doc_id = ctx.module_doc_id(&child_path);
 
     let label = child_name.to_string();
 
@@ -3892,7 +8033,8 @@ fn generate_sidebar_for_module(
       .push(SidebarItem::Doc {
         id: child_doc_id,
         label: Some(label),
-        custom_props: Some("rust-mod".to_string()),
+        class_name: Some("rust-mod".to_string()),
+        custom_props: ctx.sidebar_icon_props("module"),
       });
   }
 
@@ -3918,31 +8060,34 @@ fn generate_sidebar_for_module(
           _crate_name.to_string()
         };
 
-        let item_doc_id = if sidebar_prefix.is_empty() {
-          format!("{}/{}{}", parent_path, prefix, item_name)
-        } else {
-          format!("{}/{}/{}{}", sidebar_prefix, parent_path, prefix, item_name)
-        };
+        let item_doc_id = ctx.doc_id(&format!("{}/{}{}", parent_path, prefix, item_name));
 
         // Determine CSS class and type category based on item type
-        let (class_name, type_category) = if prefix.starts_with("struct.") {
-          ("rust-struct", "Structs")
+        let (class_name, type_category, kind) = if prefix.starts_with("struct.") {
+          ("rust-struct", "Structs", "struct")
         } else if prefix.starts_with("enum.") {
-          ("rust-struct", "Enums")
+          ("rust-struct", "Enums", "enum")
         } else if prefix.starts_with("trait.") {
-          ("rust-trait", "Traits")
+          ("rust-trait", "Traits", "trait")
+        } else if prefix.starts_with("traitalias.") {
+          ("rust-trait", "Trait Aliases", "traitalias")
         } else if prefix.starts_with("fn.") {
-          ("rust-fn", "Functions")
+          ("rust-fn", "Functions", "fn")
         } else if prefix.starts_with("constant.") {
-          ("rust-constant", "Constants")
+          ("rust-constant", "Constants", "constant")
         } else if prefix.starts_with("type.") {
-          ("rust-type", "Type Aliases")
-        } else if prefix.starts_with("macro.") {
-          ("rust-macro", "Macros")
+          ("rust-type", "Type Aliases", "type")
+        } else if ["macro.", "attr.", "derive."]
+          .iter()
+          .any(|macro_prefix| prefix.starts_with(macro_prefix))
+        {
+          ("rust-macro", "Macros", "macro")
         } else if prefix.starts_with("static.") {
-          ("rust-static", "Statics")
+          ("rust-static", "Statics", "static")
+        } else if prefix.starts_with("foreigntype.") {
+          ("rust-type", "Foreign Types", "foreigntype")
         } else {
-          ("rust-item", "Primitives")
+          ("rust-item", "Primitives", "primitive")
         };
 
         items_by_type
@@ -3951,7 +8096,11 @@ fn generate_sidebar_for_module(
           .push(SidebarItem::Doc {
             id: item_doc_id,
             label: Some(item_name.clone()),
-            custom_props: Some(class_name.to_string()),
+            class_name: Some(match item_stability(item) {
+              Some(stability) => format!("{} {}", class_name, stability.class_name()),
+              None => class_name.to_string(),
+            }),
+            custom_props: ctx.sidebar_icon_props(kind),
           });
       }
     }
@@ -3964,30 +8113,18 @@ fn generate_sidebar_for_module(
       if !items.is_empty() {
         parent_section_items.push(SidebarItem::Category {
           label: type_name.to_string(),
-          items: items.clone(),
-          collapsed: false, // Will be rendered as collapsible: false
           link: None,
+          collapsible: collapse.items,
+          collapsed: collapse.items,
+          items: items.clone(),
         });
       }
     }
   }
 
   // Generate link to parent module
-  let parent_link = if let Some(parent_key) = parent_module {
-    let parent_path = parent_key.replace("::", "/");
-    if sidebar_prefix.is_empty() {
-      Some(format!("{}/index", parent_path))
-    } else {
-      Some(format!("{}/{}/index", sidebar_prefix, parent_path))
-    }
-  } else {
-    // Parent is crate root
-    if sidebar_prefix.is_empty() {
-      Some(format!("{}/index", _crate_name))
-    } else {
-      Some(format!("{}/{}/index", sidebar_prefix, _crate_name))
-    }
-  };
+  let parent_link =
+    Some(ctx.module_doc_id(&parent_module.unwrap_or(_crate_name).replace("::", "/")));
 
   // Add "In <parent>" section in these cases:
   // - For leaf items (show_all_parent_items=true): always wrap in "In <module>"
@@ -4001,9 +8138,11 @@ fn generate_sidebar_for_module(
   if should_wrap_in_category {
     sidebar_items.push(SidebarItem::Category {
       label: siblings_label,
-      items: parent_section_items,
-      collapsed: false, // Keep open like rustdoc
       link: parent_link,
+      // Kept open like rustdoc unless collapsed
+      collapsible: collapse.modules,
+      collapsed: collapse.modules,
+      items: parent_section_items,
     });
   } else if !is_root && !parent_section_items.is_empty() {
     // For modules whose parent is the crate: add categories directly without wrapper
@@ -4012,7 +8151,7 @@ fn generate_sidebar_for_module(
 
   // For root crates: add "Crates" section with workspace sibling crates
   if is_root {
-    let workspace_crates = WORKSPACE_CRATES.with(|wc| wc.borrow().clone());
+    let workspace_crates = ctx.workspace_crates.clone();
 
     if workspace_crates.len() > 1 {
       let mut crate_items = Vec::new();
@@ -4021,41 +8160,27 @@ fn generate_sidebar_for_module(
         // Normalize crate name: replace hyphens with underscores for file paths
         let normalized_crate_name = crate_name.replace("-", "_");
 
-        let crate_doc_id = if sidebar_prefix.is_empty() {
-          format!("{}/index", normalized_crate_name)
-        } else {
-          format!("{}/{}/index", sidebar_prefix, normalized_crate_name)
-        };
+        let crate_doc_id = ctx.module_doc_id(&normalized_crate_name);
 
         let label = crate_name.to_string();
 
         crate_items.push(SidebarItem::Doc {
           id: crate_doc_id,
           label: Some(label),
-          custom_props: Some("rust-mod".to_string()),
+          class_name: Some("rust-mod".to_string()),
+          custom_props: ctx.sidebar_icon_props("crate"),
         });
       }
 
       // Sort crate items by label (alphabetically)
-      crate_items.sort_by(|a, b| {
-        let label_a = match a {
-          SidebarItem::Doc { label, .. } => label.as_deref().unwrap_or(""),
-          SidebarItem::Link { label, .. } => label.as_str(),
-          SidebarItem::Category { label, .. } => label.as_str(),
-        };
-        let label_b = match b {
-          SidebarItem::Doc { label, .. } => label.as_deref().unwrap_or(""),
-          SidebarItem::Link { label, .. } => label.as_str(),
-          SidebarItem::Category { label, .. } => label.as_str(),
-        };
-        label_a.cmp(label_b)
-      });
+      crate_items.sort_by(|a, b| a.label().cmp(b.label()));
 
       sidebar_items.push(SidebarItem::Category {
         label: "Crates".to_string(),
-        items: crate_items,
-        collapsed: false,
         link: None,
+        collapsible: collapse.crates,
+        collapsed: collapse.crates,
+        items: crate_items,
       });
     }
   }
@@ -4063,181 +8188,200 @@ fn generate_sidebar_for_module(
   sidebar_items
 }
 
-/// Convert multiple sidebars to TypeScript code
-fn sidebars_to_js(all_sidebars: &HashMap<String, Vec<SidebarItem>>, _collapsed: bool) -> String {
-  let mut output = String::new();
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-  output.push_str("// This file is auto-generated by cargo-doc-md\n");
-  output.push_str("// Do not edit manually - this file will be regenerated\n\n");
-  output.push_str("import type {SidebarsConfig} from '@docusaurus/plugin-content-docs';\n\n");
-  output.push_str("// Rust API documentation sidebars\n");
-  output.push_str("// Each module has its own sidebar for better navigation\n");
-  output.push_str("// Import this in your docusaurus.config.ts:\n");
-  output.push_str("// import { rustSidebars } from './sidebars-rust';\n");
-  output.push_str("//\n");
-  output.push_str("// Then configure in docs plugin:\n");
-  output.push_str("// docs: {\n");
-  output.push_str("//   sidebarPath: './sidebars.ts',\n");
-  output
-    .push_str("//   async sidebarItemsGenerator({ defaultSidebarItemsGenerator, ...args }) {\n");
-  output.push_str("//     const items = await defaultSidebarItemsGenerator(args);\n");
-  output.push_str("//     const docPath = args.item.id;\n");
-  output.push_str("//     // Use module-specific sidebar if available\n");
-  output.push_str("//     for (const [path, sidebar] of Object.entries(rustSidebars)) {\n");
-  output.push_str("//       if (docPath.startsWith(path + '/')) {\n");
-  output.push_str("//         return sidebar;\n");
-  output.push_str("//       }\n");
-  output.push_str("//     }\n");
-  output.push_str("//     return items;\n");
-  output.push_str("//   },\n");
-  output.push_str("// }\n\n");
-
-  output.push_str("export const rustSidebars: Record<string, any[]> = {\n");
-
-  // Sort by path for consistent output
-  let mut sorted_paths: Vec<_> = all_sidebars.keys().cloned().collect();
-  sorted_paths.sort();
-
-  let first_path = sorted_paths.first().cloned();
-
-  for path in &sorted_paths {
-    let items = &all_sidebars[path];
-    // Convert path with slashes and dots to valid sidebar key (replace / and . with _)
-    let sidebar_key = path.replace("/", "_").replace(".", "_");
-    output.push_str(&format!("  '{}': [\n", sidebar_key));
-    for item in items {
-      output.push_str(&format_sidebar_item(item, 2));
-    }
-    output.push_str("  ],\n");
-  }
-
-  output.push_str("};\n\n");
-
-  // NOTE: rootRustSidebar is generated during merge in writer.rs
-  // to include all crates from the workspace
-
-  // Also export the main sidebar for backward compatibility
-  if let Some(first_path) = first_path {
-    let first_sidebar_key = first_path.replace("/", "_").replace(".", "_");
-    output.push_str("// Main API documentation sidebar (for backward compatibility)\n");
-    output.push_str("export const rustApiDocumentation = rustSidebars['");
-    output.push_str(&first_sidebar_key);
-    output.push_str("'];\n\n");
-    output.push_str("// Or use as a single category:\n");
-    output.push_str("export const rustApiCategory = {\n");
-    output.push_str("  type: 'category' as const,\n");
-    output.push_str("  label: 'API Documentation',\n");
-    output.push_str("  collapsed: false,\n");
-    output.push_str("  items: rustApiDocumentation,\n");
-    output.push_str("};\n");
+  #[test]
+  fn test_slugger() {
+    let mut slugger = Slugger::default();
+    assert_eq!(
+      slugger.slug("struct my_crate::Foo<T>"),
+      "struct-my_crate-foo-t"
+    );
+    assert_eq!(slugger.slug("struct my_crate::foo"), "struct-my_crate-foo");
+    assert_eq!(
+      slugger.slug("struct my_crate::Foo"),
+      "struct-my_crate-foo-1"
+    );
+    assert_eq!(
+      slugger.slug("struct-my_crate-foo-1"),
+      "struct-my_crate-foo-1-1"
+    );
+    assert_eq!(slugger.slug("::"), "section");
   }
 
-  output
-}
+  #[test]
+  fn test_argument_docs() {
+    let docs =
+      "Intro.\n\n# Arguments\n\n* `a` - First\n  argument\n- `b`: Second\n\n# Returns\n\nThe sum";
+    assert_eq!(
+      argument_docs(docs),
+      vec![
+        ("a".to_string(), "First argument".to_string()),
+        ("b".to_string(), "Second".to_string())
+      ]
+    );
+    assert_eq!(doc_section(docs, "Returns").as_deref(), Some("The sum"));
+    assert_eq!(doc_section(docs, "Panics"), None);
+  }
 
-/// Format a single sidebar item with proper indentation
-fn format_sidebar_item(item: &SidebarItem, indent: usize) -> String {
-  let indent_str = "  ".repeat(indent);
+  #[test]
+  fn test_module_map_borrows_items() {
+    let crate_data =
+      crate::parser::load_rustdoc_json("tests/fixtures/test_crate.json".as_ref()).unwrap();
+    let item_paths = build_path_map(&crate_data);
+    let modules = group_by_module(&crate_data, &item_paths, true);
+
+    // Every grouped item is the item of the crate index, not a copy of it
+    let items: Vec<&(Id, &Item)> = modules.values().flatten().collect();
+    assert!(!items.is_empty());
+    for (id, item) in items {
+      assert!(std::ptr::eq(*item, &crate_data.index[id]));
+    }
+  }
 
-  match item {
-    SidebarItem::Doc {
-      id,
-      label,
-      custom_props,
-    } => {
-      // Remove .md extension if present and convert to doc ID
-      let doc_id = id.trim_end_matches(".md").replace(".md", "");
+  #[test]
+  fn test_impl_associated_items() {
+    let json = std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap();
+    let mut crate_data: Crate = serde_json::from_str(&json).unwrap();
+    let impl_id = *crate_data
+      .index
+      .iter()
+      .find(|(_, item)| matches!(&item.inner, ItemEnum::Impl(i) if i.trait_.is_none()))
+      .unwrap()
+      .0;
+    let const_id = Id(u32::MAX);
+    crate_data.index.insert(
+      const_id,
+      Item {
+        id: const_id,
+        crate_id: 0,
+        name: Some("LIMIT".to_string()),
+        span: None,
+        visibility: Visibility::Public,
+        docs: Some("Largest supported size.\n\nMore.".to_string()),
+        links: Default::default(),
+        attrs: Vec::new(),
+        deprecation: None,
+        inner: ItemEnum::AssocConst {
+          type_: rustdoc_types::Type::Primitive("usize".to_string()),
+          value: Some("`1 << 10`".to_string()),
+        },
+      },
+    );
+    let ItemEnum::Impl(impl_block) = &mut crate_data.index.get_mut(&impl_id).unwrap().inner else {
+      unreachable!()
+    };
+    impl_block.items.insert(0, const_id);
+    let impl_block = impl_block.clone();
+
+    let members = format_impl_methods(&RenderContext::default(), &impl_block, &crate_data, None);
+    assert_eq!(
+      members[0],
+      (
+        "const LIMIT: usize = `1 << 10`".to_string(),
+        Vec::new(),
+        Some("Largest supported size.\n\nMore.".to_string())
+      )
+    );
 
-      // If we have a label or customProps, create an object with type, id, label, and optional className/customProps
-      if label.is_some() || custom_props.is_some() {
-        let mut output = format!("{}{{ type: 'doc', id: '{}'", indent_str, doc_id);
+    let ctx = RenderContext {
+      collapse_method_docs: true,
+      ..Default::default()
+    };
+    let members = format_impl_methods(&ctx, &impl_block, &crate_data, None);
+    assert_eq!(
+      members[0].2.as_deref(),
+      Some(
+        "<details className=\"rust-method-doc\">\n<summary>Largest supported size.</summary>\n\nMore.\n\n</details>"
+      )
+    );
+  }
 
-        if let Some(label_text) = label {
-          output.push_str(&format!(", label: '{}'", label_text));
-        }
+  #[test]
+  fn test_format_field_docs() {
+    let ctx = RenderContext::default();
+    assert_eq!(
+      format_field_docs(&ctx, "The `id`."),
+      "<div className=\"rust-field-doc\">The <code>id</code>.</div>\n\n"
+    );
+    assert_eq!(
+      format_field_docs(&ctx, "Timeout in\nseconds.\n\nZero disables it."),
+      "<div className=\"rust-field-doc\">\n\nTimeout in\nseconds.\n\nZero disables it.\n\n</div>\n\n"
+    );
 
-        // Determine if custom_props is className or customProps based on format
-        if let Some(props) = custom_props {
-          if props.starts_with('{') {
-            // It's customProps JSON object
-            output.push_str(&format!(", customProps: {}", props));
-          } else {
-            // It's a className string
-            output.push_str(&format!(", className: '{}'", props));
-          }
-        }
+    let long = "Timeout in\nseconds.\n\n# Examples\n\n```\nlet t = 1;\n```\n\nZero disables it.";
+    assert_eq!(
+      format_field_docs(&ctx, long),
+      "<details className=\"rust-field-doc\">\n<summary>Timeout in seconds.</summary>\n\n#### Examples\n\n```\nlet t = 1;\n```\n\nZero disables it.\n\n</details>\n\n"
+    );
 
-        output.push_str(" },\n");
-        output
-      } else {
-        // Just a string reference (Docusaurus will infer the label)
-        format!("{}'{doc_id}',\n", indent_str)
-      }
-    }
-    SidebarItem::Link {
-      href,
-      label,
-      custom_props,
-    } => {
-      // Generate a link item with href
-      let mut output = format!(
-        "{}{{ type: 'link', href: '{}', label: '{}'",
-        indent_str, href, label
-      );
-      if let Some(props) = custom_props {
-        if props.starts_with('{') {
-          output.push_str(&format!(", customProps: {}", props));
-        } else {
-          output.push_str(&format!(", className: '{}'", props));
-        }
-      }
-      output.push_str(" },\n");
-      output
-    }
-    SidebarItem::Category {
-      label,
-      items,
-      collapsed,
-      link,
-    } => {
-      let mut output = String::new();
-      output.push_str(&format!("{}{{\n", indent_str));
-      output.push_str(&format!("{}  type: 'category',\n", indent_str));
-      output.push_str(&format!("{}  label: '{}',\n", indent_str, label));
-
-      // Add link if present (makes the category clickable)
-      if let Some(link_path) = link {
-        let doc_id = link_path.trim_end_matches(".md").replace(".md", "");
-        output.push_str(&format!("{}  link: {{\n", indent_str));
-        output.push_str(&format!("{}    type: 'doc',\n", indent_str));
-        output.push_str(&format!("{}    id: '{}',\n", indent_str, doc_id));
-        output.push_str(&format!("{}  }},\n", indent_str));
-      }
-
-      // Nested categories (indent > 0) are not collapsible (rustdoc style)
-      // Top-level categories use the collapsed parameter
-      if indent > 0 {
-        output.push_str(&format!("{}  collapsible: false,\n", indent_str));
-      } else {
-        output.push_str(&format!("{}  collapsed: {},\n", indent_str, collapsed));
-      }
+    let ctx = RenderContext {
+      field_summaries: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      format_field_docs(&ctx, long),
+      "<div className=\"rust-field-doc\">Timeout in</div>\n\n"
+    );
+  }
 
-      output.push_str(&format!("{}  items: [\n", indent_str));
+  #[test]
+  fn test_seo_frontmatter() {
+    let ctx = RenderContext {
+      social_card: Some("img/social-card.png".to_string()),
+      ..Default::default()
+    };
+    let docs = "\nParses a [`Config`](crate::Config) from **\"TOML\"** [files][1].\nSee `load`.\n\nMore details.";
+    assert_eq!(
+      ctx.seo_frontmatter(Some(docs), &["struct", "my_crate", "my_crate"]),
+      "description: \"Parses a Config from \\\"TOML\\\" files. See load.\"\nkeywords: [\"struct\", \"my_crate\"]\nimage: \"img/social-card.png\"\n"
+    );
 
-      for sub_item in items {
-        output.push_str(&format_sidebar_item(sub_item, indent + 2));
-      }
+    assert_eq!(doc_description("# Examples\n\nText"), None);
+    let long = "word ".repeat(40);
+    let description = doc_description(&long).unwrap();
+    assert!(description.ends_with("word…"));
+    assert!(description.chars().count() <= 161);
+  }
 
-      output.push_str(&format!("{}  ],\n", indent_str));
-      output.push_str(&format!("{}}},\n", indent_str));
-      output
-    }
+  #[test]
+  fn test_visibility_indicator() {
+    let json = std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap();
+    let crate_data: Crate = serde_json::from_str(&json).unwrap();
+    let mut item = crate_data.index[&crate_data.root].clone();
+    let ctx = RenderContext::default();
+    assert_eq!(ctx.visibility_indicator(&item), "");
+
+    item.visibility = Visibility::Crate;
+    assert_eq!(ctx.visibility_indicator(&item), " 🔒");
+    let ctx = RenderContext {
+      restricted_marker: Some("(crate)".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(ctx.visibility_indicator(&item), " (crate)");
+    let ctx = RenderContext {
+      restricted_marker: Some(String::new()),
+      ..Default::default()
+    };
+    assert_eq!(ctx.visibility_indicator(&item), "");
   }
-}
 
-#[cfg(test)]
-mod tests {
-  use super::*;
+  #[test]
+  fn test_rust_code_escapes_template_literal() {
+    assert_eq!(
+      rust_code(
+        "const GREETING: &str = \"`hi` ${name} \\n\";",
+        &[(
+          "str".to_string(),
+          "https://doc.rust-lang.org/std/primitive.str.html".to_string()
+        )],
+        true
+      ),
+      "<RustCode inline code={`const GREETING: &str = \"\\`hi\\` \\${name} \\\\n\";`} links={[{\"text\": \"str\", \"href\": \"https://doc.rust-lang.org/std/primitive.str.html\"}]} />\n\n"
+    );
+  }
 
   #[test]
   fn test_sanitize_docs_for_mdx_inline_html() {
@@ -4253,6 +8397,118 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_sanitize_docs_for_mdx_json_schema() {
+    let input = "Identifies the sender.\n<details><summary>JSON schema</summary>\n\n```json\n{\n  \"type\": \"string\"\n}\n```\n</details>\nMore text.";
+    let result = sanitize_docs_for_mdx(input);
+
+    assert_eq!(
+      result,
+      "Identifies the sender.\n\n<details>\n<summary>JSON schema</summary>\n\n```json\n{\n  \"type\": \"string\"\n}\n```\n\n</details>\n\nMore text."
+    );
+  }
+
+  #[test]
+  fn test_extract_json_schema_assets() {
+    let page =
+      "# Foo\n\n<details>\n<summary>JSON schema</summary>\n\n```json\n{}\n```\n\n</details>\n";
+    let files = extract_json_schema_assets("mod_a/struct.Foo.md", page);
+
+    assert_eq!(
+      files,
+      vec![
+        (
+          "mod_a/struct.Foo.md".to_string(),
+          "# Foo\n\n<details>\n<summary>JSON schema</summary>\n\n```json\n{}\n```\n\n[Download JSON schema](./struct.Foo.schema.json)\n\n</details>\n".to_string()
+        ),
+        ("mod_a/struct.Foo.schema.json".to_string(), "{}\n".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_doc_ids() {
+    let ctx = |base_path: &str| RenderContext {
+      base_path: base_path.to_string(),
+      ..Default::default()
+    };
+    assert_eq!(ctx("").module_doc_id("my_crate"), "my_crate/index");
+    assert_eq!(ctx("/docs").module_doc_id("my_crate"), "my_crate/index");
+    assert_eq!(
+      ctx("/docs/api/").module_doc_id("my_crate/mod_a/"),
+      "api/my_crate/mod_a/index"
+    );
+    assert_eq!(
+      ctx("/docs/api/v2").doc_id("/my_crate/struct.Foo"),
+      "api/v2/my_crate/struct.Foo"
+    );
+
+    // Doc IDs and sidebar keys start below the route of a docs plugin instance
+    let plugin = RenderContext {
+      base_path: "/reference/rust/api".to_string(),
+      route_base_path: Some("/reference/rust".to_string()),
+      docs_version: Some("1.2".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(plugin.module_doc_id("my_crate"), "api/my_crate/index");
+    assert_eq!(plugin.link_base_path(), "/reference/rust/1.2/api");
+    assert_eq!(
+      plugin.sidebar_key("my_crate", SidebarKind::Module),
+      "1_2_api_my_crate"
+    );
+  }
+
+  #[test]
+  fn test_layout_fn_signature() {
+    let ctx = RenderContext::default();
+    let inputs = |inputs: &[&str]| inputs.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+      layout_fn_signature(&ctx, "fn len", &inputs(&["&self"]), Some("usize"), &[]),
+      "fn len(&self) -> usize"
+    );
+    assert_eq!(
+      layout_fn_signature(
+        &ctx,
+        "fn connect<T>",
+        &inputs(&["host: &str", "port: u16", "timeout: u64", "handler: T"]),
+        Some("Result<Connection>"),
+        &[
+          "T: Handler + Send".to_string(),
+          "T::Error: Debug".to_string()
+        ],
+      ),
+      "fn connect<T>(\n    host: &str,\n    port: u16,\n    timeout: u64,\n    handler: T,\n) -> Result<Connection>\nwhere\n    T: Handler + Send,\n    T::Error: Debug,"
+    );
+
+    // rustfmt style: no parameter limit, the return type counts in the width
+    let params = inputs(&["a: u8", "b: u8", "c: u8", "d: u8"]);
+    let rustfmt = RenderContext {
+      signature_style: SignatureStyle::Rustfmt,
+      ..Default::default()
+    };
+    assert_eq!(
+      layout_fn_signature(&rustfmt, "fn sum", &params, Some("u32"), &[]),
+      "fn sum(a: u8, b: u8, c: u8, d: u8) -> u32"
+    );
+    let narrow = RenderContext {
+      signature_style: SignatureStyle::Rustfmt,
+      signature_width: Some(40),
+      ..Default::default()
+    };
+    assert_eq!(
+      layout_fn_signature(&narrow, "fn sum", &params, Some("u32"), &[]),
+      "fn sum(\n    a: u8,\n    b: u8,\n    c: u8,\n    d: u8,\n) -> u32"
+    );
+    let threshold = RenderContext {
+      signature_max_params: Some(4),
+      ..Default::default()
+    };
+    assert_eq!(
+      layout_fn_signature(&threshold, "fn sum", &params, Some("u32"), &[]),
+      "fn sum(a: u8, b: u8, c: u8, d: u8) -> u32"
+    );
+  }
+
   #[test]
   fn test_sanitize_docs_for_mdx_already_separated() {
     // Test case: HTML already properly separated
@@ -4285,4 +8541,104 @@ mod tests {
     // Should return unchanged (code is not a block-level tag)
     assert_eq!(result, input, "Inline HTML should be unchanged");
   }
+
+  #[test]
+  fn test_sanitize_docs_for_mdx_escapes_prose() {
+    let input = "Returns a Vec<T> of {threshold} items -> `Vec<T>` and ``{a}``.\n> Quoted <b>bold</b> <br/> <My-Tag>\nSee <https://docs.rs> and \\{raw\\}.\n<!-- glossary: Span -->\n<!-- a {\nb} -->\n```\nlet x = Vec::<u8>::new();\n```";
+    assert_eq!(
+      sanitize_docs_for_mdx(input),
+      "Returns a Vec&lt;T&gt; of &#123;threshold&#125; items -&gt; `Vec<T>` and ``{a}``.\n> Quoted <b>bold</b> <br/> &lt;My-Tag&gt;\nSee [https://docs.rs](https://docs.rs) and \\{raw\\}.\n<!-- glossary: Span -->\n<!-- a {\nb} -->\n```\nlet x = Vec::<u8>::new();\n```"
+    );
+  }
+
+  #[test]
+  fn test_glossary_terms() {
+    let docs = "Intro.\n\n<!-- glossary: Span -->\n\nA range of\nsource code.\n\nOther text.\n\n# Terminology\n\n- **Item**: Anything\n  with a path.\n- Crate: A compilation unit.\n\n```\n- **Code**: not a term\n```\n\n# Examples\n\n- **Example**: not a term";
+    assert_eq!(
+      glossary_terms(docs),
+      vec![
+        ("Span".to_string(), "A range of source code.".to_string()),
+        ("Item".to_string(), "Anything with a path.".to_string()),
+        ("Crate".to_string(), "A compilation unit.".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_format_docs_headings_and_admonitions() {
+    let input = "Does things.\n\n# Examples\n\n```\n# use foo;\n```\n\n# Panics\n\nIf empty.\n\n## Details\n\nMore.\n\n# Notes\n\nDone.";
+    let ctx = RenderContext::default();
+    assert_eq!(
+      format_docs(&ctx, input, 2),
+      "Does things.\n\n## Examples\n\n```\n# use foo;\n```\n\n:::warning[Panics]\n\nIf empty.\n\n### Details\n\nMore.\n:::\n\n## Notes\n\nDone."
+    );
+
+    let ctx = RenderContext {
+      doc_admonitions: Some(DocAdmonitions::from([
+        ("panics".to_string(), "heading".to_string()),
+        ("Notes".to_string(), "note".to_string()),
+      ])),
+      ..Default::default()
+    };
+    assert_eq!(
+      format_docs(&ctx, input, 3),
+      "Does things.\n\n### Examples\n\n```\n# use foo;\n```\n\n### Panics\n\nIf empty.\n\n#### Details\n\nMore.\n\n:::note[Notes]\n\nDone.\n:::"
+    );
+  }
+
+  #[test]
+  fn test_format_docs_diagram_and_math_blocks() {
+    let input =
+      "Flow:\n```mermaid\ngraph TD\n<details>\n# Not a heading\n```\n\n```math\n\\frac{a}{b}\n```";
+    let ctx = RenderContext::default();
+    assert_eq!(format_docs(&ctx, input, 2), input);
+
+    let ctx = RenderContext {
+      math_blocks: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      format_docs(&ctx, input, 2),
+      "Flow:\n```mermaid\ngraph TD\n<details>\n# Not a heading\n```\n\n$$\n\\frac{a}{b}\n$$"
+    );
+  }
+
+  #[test]
+  fn test_catch_render_failure() {
+    let ctx = RenderContext::default();
+    assert_eq!(
+      catch_render_failure(&ctx, || Some("content".to_string())),
+      Some(("content".to_string(), None))
+    );
+    assert_eq!(catch_render_failure(&ctx, || None), None);
+
+    let (content, failure) =
+      catch_render_failure(&ctx, || panic!("unsupported type <T>")).expect("placeholder page");
+    assert_eq!(failure.as_deref(), Some("unsupported type <T>"));
+    assert!(content.starts_with(":::danger[Rendering failed]"));
+    assert!(content.contains("`unsupported type <T>`"));
+
+    let ctx = RenderContext {
+      docusaurus_major: DocusaurusMajor::V2,
+      ..Default::default()
+    };
+    let (content, _) = catch_render_failure(&ctx, || panic!("oops")).expect("placeholder page");
+    assert!(content.starts_with(":::danger Rendering failed\n"));
+  }
+
+  #[test]
+  fn test_summary_to_html() {
+    assert_eq!(
+      summary_to_html("Returns a `Vec<T>` of **all** the *items*."),
+      "Returns a <code>Vec&lt;T&gt;</code> of <strong>all</strong> the <em>items</em>."
+    );
+    assert_eq!(
+      summary_to_html("Wraps [`Foo`] like [serde](https://serde.rs) and [Bar][crate::Bar]."),
+      "Wraps <code>Foo</code> like <a href=\"https://serde.rs\">serde</a> and Bar."
+    );
+    assert_eq!(
+      summary_to_html("Uses ``a`b`` for `{x}` and {y}, 2 * 3"),
+      "Uses <code>a`b</code> for <code>&#123;x&#125;</code> and &#123;y&#125;, 2 * 3"
+    );
+  }
 }
@@ -0,0 +1,193 @@
+//! Documentation coverage of a crate (`coverage` subcommand).
+//!
+//! Counts the public items that have doc comments, per module and per item
+//! kind, so CI can fail when the coverage drops below a threshold.
+
+use crate::tree::kind_label;
+use rustdoc_types::{Crate, Id, ItemEnum, Visibility};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Number of documented items out of a total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Coverage {
+  /// Items with a non-empty doc comment
+  pub documented: usize,
+  /// All counted items
+  pub total: usize,
+}
+
+impl Coverage {
+  /// Percentage of documented items (100 when there are no items).
+  pub fn percent(&self) -> f64 {
+    match self.total {
+      0 => 100.0,
+      total => self.documented as f64 * 100.0 / total as f64,
+    }
+  }
+
+  fn add(&mut self, documented: bool) {
+    self.total += 1;
+    self.documented += usize::from(documented);
+  }
+}
+
+/// Documentation coverage of the public items of a crate.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CoverageReport {
+  /// Coverage of all public items
+  pub total: Coverage,
+  /// Coverage per module path (e.g. `my_crate::nested`), counting the module itself
+  pub modules: BTreeMap<String, Coverage>,
+  /// Coverage per item kind (e.g. `Struct`)
+  pub kinds: BTreeMap<&'static str, Coverage>,
+}
+
+/// Compute the documentation coverage of the public modules and items of a crate.
+///
+/// Re-exports are not counted, as their docs come from the re-exported item.
+pub fn doc_coverage(crate_data: &Crate) -> CoverageReport {
+  let mut report = CoverageReport::default();
+  add_module(&mut report, crate_data, &crate_data.root, "");
+  report
+}
+
+fn add_module(report: &mut CoverageReport, crate_data: &Crate, id: &Id, parent_path: &str) {
+  let item = &crate_data.index[id];
+  let name = item.name.as_deref().unwrap_or_default();
+  let path = match parent_path {
+    "" => name.to_string(),
+    parent => format!("{}::{}", parent, name),
+  };
+  add_item(report, &path, "Module", is_documented(item.docs.as_deref()));
+
+  let ItemEnum::Module(module) = &item.inner else {
+    return;
+  };
+  for child_id in &module.items {
+    let Some(child) = crate_data.index.get(child_id) else {
+      continue;
+    };
+    if !matches!(child.visibility, Visibility::Public) {
+      continue;
+    }
+    if matches!(child.inner, ItemEnum::Module(_)) {
+      add_module(report, crate_data, child_id, &path);
+      continue;
+    }
+    let Some(kind) = kind_label(&child.inner).filter(|kind| *kind != "Re-export") else {
+      continue;
+    };
+    add_item(report, &path, kind, is_documented(child.docs.as_deref()));
+  }
+}
+
+fn add_item(report: &mut CoverageReport, module: &str, kind: &'static str, documented: bool) {
+  report.total.add(documented);
+  report
+    .modules
+    .entry(module.to_string())
+    .or_default()
+    .add(documented);
+  report.kinds.entry(kind).or_default().add(documented);
+}
+
+fn is_documented(docs: Option<&str>) -> bool {
+  docs.is_some_and(|docs| !docs.trim().is_empty())
+}
+
+/// Render the coverage report for the terminal as two tables, per module and per kind.
+pub fn render_coverage(report: &CoverageReport) -> String {
+  let width = report
+    .modules
+    .keys()
+    .map(String::len)
+    .chain(report.kinds.keys().map(|kind| kind.len()))
+    .chain(["Module".len()])
+    .max()
+    .unwrap_or_default();
+
+  let mut output = String::new();
+  let mut table = |heading: &str, rows: Vec<(&str, &Coverage)>| {
+    output.push_str(&format!(
+      "{:<width$}  {:>10}  {:>5}  {:>8}\n",
+      heading, "Documented", "Total", "Coverage"
+    ));
+    for (name, coverage) in rows {
+      output.push_str(&format!(
+        "{:<width$}  {:>10}  {:>5}  {:>7.1}%\n",
+        name,
+        coverage.documented,
+        coverage.total,
+        coverage.percent()
+      ));
+    }
+    output.push('\n');
+  };
+  table(
+    "Module",
+    report
+      .modules
+      .iter()
+      .map(|(module, coverage)| (module.as_str(), coverage))
+      .collect(),
+  );
+  table(
+    "Kind",
+    report
+      .kinds
+      .iter()
+      .map(|(kind, coverage)| (*kind, coverage))
+      .collect(),
+  );
+
+  output.push_str(&format!(
+    "{} of {} public items documented ({:.1}%)\n",
+    report.total.documented,
+    report.total.total,
+    report.total.percent()
+  ));
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_doc_coverage() {
+    let json = std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap();
+    let crate_data: Crate = serde_json::from_str(&json).unwrap();
+    let report = doc_coverage(&crate_data);
+
+    assert_eq!(
+      report.total.total,
+      report.kinds.values().map(|c| c.total).sum::<usize>()
+    );
+    assert_eq!(
+      report.total.documented,
+      report.modules.values().map(|c| c.documented).sum::<usize>()
+    );
+    assert!(report.modules["test_crate"].total > 0);
+    assert!(!report.kinds.contains_key("Re-export"));
+
+    let rendered = render_coverage(&report);
+    assert!(rendered.starts_with("Module "));
+    assert!(rendered.ends_with(&format!(
+      "{} of {} public items documented ({:.1}%)\n",
+      report.total.documented,
+      report.total.total,
+      report.total.percent()
+    )));
+  }
+
+  #[test]
+  fn test_coverage_percent() {
+    assert_eq!(Coverage::default().percent(), 100.0);
+    let coverage = Coverage {
+      documented: 1,
+      total: 4,
+    };
+    assert_eq!(coverage.percent(), 25.0);
+  }
+}
@@ -0,0 +1,343 @@
+//! Public API changes between two versions of a crate (`diff` subcommand).
+//!
+//! The public items of both rustdoc JSON files are keyed by path (members like
+//! `Foo::new` or `Color::Red` included) and their one-line signatures compared,
+//! which gives added, removed and changed items for changelogs and semver review.
+
+use crate::converter::{format_type_plain, impl_signature, item_signature};
+use crate::tree::kind_label;
+use rustdoc_types::Type::ResolvedPath;
+use rustdoc_types::{Crate, Id, ItemEnum, Visibility};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A public item of a crate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiItem {
+  /// Kind of the item (e.g. `Function` or `Variant`)
+  pub kind: &'static str,
+  /// Signature of the item on one line (e.g. `fn new(size: usize) -> Self`)
+  pub signature: String,
+}
+
+/// Public items of a crate, keyed by path (e.g. `my_crate::Foo::new`).
+pub type PublicApi = BTreeMap<String, ApiItem>;
+
+/// An added, removed or changed item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiChange {
+  /// Path of the item
+  pub path: String,
+  /// Kind of the item (in the new version, if it has one)
+  pub kind: &'static str,
+  /// Signature in the old version, `None` for added items
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub old: Option<String>,
+  /// Signature in the new version, `None` for removed items
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub new: Option<String>,
+}
+
+/// Public API changes between two versions of a crate, sorted by path.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApiDiff {
+  pub added: Vec<ApiChange>,
+  pub removed: Vec<ApiChange>,
+  pub changed: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+  /// Whether the public API is the same in both versions.
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+  }
+}
+
+/// Collect the public items of a crate, from its public modules down.
+///
+/// Re-exports are not followed. Trait implementations are keyed by the
+/// implementing type and trait (e.g. `my_crate::Foo: Clone`).
+pub fn public_api(crate_data: &Crate) -> PublicApi {
+  let mut api = PublicApi::new();
+  add_module(&mut api, crate_data, &crate_data.root, "");
+  api
+}
+
+fn add_module(api: &mut PublicApi, crate_data: &Crate, id: &Id, parent_path: &str) {
+  let Some(item) = crate_data.index.get(id) else {
+    return;
+  };
+  let name = item.name.as_deref().unwrap_or_default();
+  let path = match parent_path {
+    "" => name.to_string(),
+    parent => format!("{}::{}", parent, name),
+  };
+  let ItemEnum::Module(module) = &item.inner else {
+    return;
+  };
+
+  for child_id in &module.items {
+    let Some(child) = crate_data.index.get(child_id) else {
+      continue;
+    };
+    if !matches!(child.visibility, Visibility::Public) {
+      continue;
+    }
+    let Some(child_name) = &child.name else {
+      continue;
+    };
+    let child_path = format!("{}::{}", path, child_name);
+    match &child.inner {
+      ItemEnum::Module(_) => {
+        add_module(api, crate_data, child_id, &path);
+        continue;
+      }
+      ItemEnum::Use(_) => continue,
+      _ => {}
+    }
+    let Some(kind) = kind_label(&child.inner) else {
+      continue;
+    };
+    add_item(api, crate_data, child_id, &child_path, kind);
+
+    // Members of the item, with their kind (derived from the member if empty)
+    let (members, impls): (Vec<(&Id, &'static str)>, &[Id]) = match &child.inner {
+      ItemEnum::Struct(s) => {
+        let fields = match &s.kind {
+          rustdoc_types::StructKind::Plain { fields, .. } => fields.iter().collect(),
+          rustdoc_types::StructKind::Tuple(fields) => fields.iter().flatten().collect(),
+          rustdoc_types::StructKind::Unit => Vec::new(),
+        };
+        (
+          fields.into_iter().map(|id| (id, "Field")).collect(),
+          &s.impls,
+        )
+      }
+      ItemEnum::Union(u) => (u.fields.iter().map(|id| (id, "Field")).collect(), &u.impls),
+      ItemEnum::Enum(e) => (
+        e.variants.iter().map(|id| (id, "Variant")).collect(),
+        &e.impls,
+      ),
+      ItemEnum::Trait(t) => (t.items.iter().map(|id| (id, "")).collect(), &[]),
+      _ => (Vec::new(), &[]),
+    };
+    for (member_id, member_kind) in members {
+      // Trait items and enum variants have the visibility of their parent
+      let public_only = member_kind == "Field";
+      add_member(
+        api,
+        crate_data,
+        member_id,
+        &child_path,
+        member_kind,
+        public_only,
+      );
+    }
+    for impl_id in impls {
+      let Some(ItemEnum::Impl(impl_block)) = crate_data.index.get(impl_id).map(|item| &item.inner)
+      else {
+        continue;
+      };
+      if impl_block.is_synthetic || impl_block.blanket_impl.is_some() {
+        continue;
+      }
+      match &impl_block.trait_ {
+        Some(trait_) => {
+          let trait_ = ResolvedPath(trait_.clone());
+          api.insert(
+            format!("{}: {}", child_path, format_type_plain(&trait_, crate_data)),
+            ApiItem {
+              kind: "Trait Implementation",
+              signature: impl_signature(impl_block, crate_data),
+            },
+          );
+        }
+        None => {
+          for member_id in &impl_block.items {
+            add_member(api, crate_data, member_id, &child_path, "", true);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Add a member of the item at `parent_path`, with a kind derived from the
+/// member if `kind` is empty.
+fn add_member(
+  api: &mut PublicApi,
+  crate_data: &Crate,
+  id: &Id,
+  parent_path: &str,
+  kind: &'static str,
+  public_only: bool,
+) {
+  let Some(member) = crate_data.index.get(id) else {
+    return;
+  };
+  if public_only && !matches!(member.visibility, Visibility::Public) {
+    return;
+  }
+  let kind = match (&member.inner, kind) {
+    (_, "Field" | "Variant") => kind,
+    (ItemEnum::Function(_), _) => "Method",
+    (ItemEnum::AssocConst { .. }, _) => "Associated Constant",
+    (ItemEnum::AssocType { .. }, _) => "Associated Type",
+    _ => return,
+  };
+  let Some(name) = &member.name else {
+    return;
+  };
+  add_item(
+    api,
+    crate_data,
+    id,
+    &format!("{}::{}", parent_path, name),
+    kind,
+  );
+}
+
+fn add_item(api: &mut PublicApi, crate_data: &Crate, id: &Id, path: &str, kind: &'static str) {
+  let Some(signature) = item_signature(&crate_data.index[id], crate_data) else {
+    return;
+  };
+  api.insert(path.to_string(), ApiItem { kind, signature });
+}
+
+/// Compare the public APIs of two versions of a crate.
+pub fn diff_apis(old: &PublicApi, new: &PublicApi) -> ApiDiff {
+  let mut diff = ApiDiff::default();
+  for (path, old_item) in old {
+    match new.get(path) {
+      None => diff.removed.push(ApiChange {
+        path: path.clone(),
+        kind: old_item.kind,
+        old: Some(old_item.signature.clone()),
+        new: None,
+      }),
+      Some(new_item) if new_item != old_item => diff.changed.push(ApiChange {
+        path: path.clone(),
+        kind: new_item.kind,
+        old: Some(old_item.signature.clone()),
+        new: Some(new_item.signature.clone()),
+      }),
+      Some(_) => {}
+    }
+  }
+  for (path, new_item) in new {
+    if !old.contains_key(path) {
+      diff.added.push(ApiChange {
+        path: path.clone(),
+        kind: new_item.kind,
+        old: None,
+        new: Some(new_item.signature.clone()),
+      });
+    }
+  }
+  diff
+}
+
+/// Render API changes as Markdown, with a section per kind of change.
+pub fn render_diff_markdown(diff: &ApiDiff) -> String {
+  if diff.is_empty() {
+    return "No public API changes.\n".to_string();
+  }
+  let mut output = String::from("# Public API changes\n");
+  for (heading, changes) in [
+    ("Removed", &diff.removed),
+    ("Changed", &diff.changed),
+    ("Added", &diff.added),
+  ] {
+    if changes.is_empty() {
+      continue;
+    }
+    output.push_str(&format!("\n## {} ({})\n\n", heading, changes.len()));
+    for change in changes {
+      match (&change.old, &change.new) {
+        (Some(old), Some(new)) => output.push_str(&format!(
+          "- {} `{}`\n  - Old: `{}`\n  - New: `{}`\n",
+          change.kind, change.path, old, new
+        )),
+        (Some(signature), None) | (None, Some(signature)) => output.push_str(&format!(
+          "- {} `{}`: `{}`\n",
+          change.kind, change.path, signature
+        )),
+        (None, None) => {}
+      }
+    }
+  }
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(kind: &'static str, signature: &str) -> ApiItem {
+    ApiItem {
+      kind,
+      signature: signature.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_diff_apis() {
+    let old = PublicApi::from([
+      ("a::Color".to_string(), item("Enum", "enum Color")),
+      ("a::Color::Red".to_string(), item("Variant", "Red")),
+      (
+        "a::Foo::len".to_string(),
+        item("Method", "fn len(&self) -> u32"),
+      ),
+      ("a::old".to_string(), item("Function", "fn old()")),
+    ]);
+    let new = PublicApi::from([
+      ("a::Color".to_string(), item("Enum", "enum Color")),
+      ("a::Color::Red".to_string(), item("Variant", "Red")),
+      ("a::Color::Blue".to_string(), item("Variant", "Blue")),
+      (
+        "a::Foo::len".to_string(),
+        item("Method", "fn len(&self) -> usize"),
+      ),
+    ]);
+
+    let diff = diff_apis(&old, &new);
+    assert_eq!(
+      render_diff_markdown(&diff),
+      "# Public API changes\n\
+       \n\
+       ## Removed (1)\n\
+       \n\
+       - Function `a::old`: `fn old()`\n\
+       \n\
+       ## Changed (1)\n\
+       \n\
+       - Method `a::Foo::len`\n  - Old: `fn len(&self) -> u32`\n  - New: `fn len(&self) -> usize`\n\
+       \n\
+       ## Added (1)\n\
+       \n\
+       - Variant `a::Color::Blue`: `Blue`\n"
+    );
+    assert!(diff_apis(&new, &new).is_empty());
+  }
+
+  #[test]
+  fn test_public_api() {
+    let json = std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap();
+    let mut crate_data: Crate = serde_json::from_str(&json).unwrap();
+    let api = public_api(&crate_data);
+    assert!(api.values().any(|item| item.kind == "Variant"));
+    assert!(api.values().any(|item| item.kind == "Method"));
+    assert!(api.values().all(|item| !item.signature.contains('\n')));
+
+    // Removing a module's items removes them from the API
+    let root = crate_data.root;
+    let ItemEnum::Module(module) = &mut crate_data.index.get_mut(&root).unwrap().inner else {
+      unreachable!()
+    };
+    module.items.clear();
+    let diff = diff_apis(&api, &public_api(&crate_data));
+    assert_eq!(diff.removed.len(), api.len());
+    assert!(diff.added.is_empty() && diff.changed.is_empty());
+  }
+}
@@ -0,0 +1,126 @@
+//! Errors of the library API.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Result type of the library API.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by the parser, converter and writer.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+  /// A file or directory could not be read
+  #[error("Failed to read {}", path.display())]
+  Read {
+    path: PathBuf,
+    #[source]
+    source: io::Error,
+  },
+
+  /// A file or directory could not be created, written or removed
+  #[error("Failed to write {}", path.display())]
+  Write {
+    path: PathBuf,
+    #[source]
+    source: io::Error,
+  },
+
+  /// A JSON file (rustdoc output, since map, manifest, sidebar, ...) could not be parsed
+  #[error("Failed to parse {}", path.display())]
+  Json {
+    path: PathBuf,
+    #[source]
+    source: serde_json::Error,
+  },
+
+  /// A TOML file (config file or Cargo manifest) could not be parsed
+  #[error("Failed to parse {}", path.display())]
+  Toml {
+    path: PathBuf,
+    #[source]
+    source: toml::de::Error,
+  },
+
+  /// The rustdoc JSON was written by a rustdoc with an incompatible format version
+  #[error(
+    "Unsupported rustdoc JSON format version {found} (supported: {min} to {expected}), {}",
+    if found > expected {
+      "update cargo-doc-docusaurus or generate it with an older nightly toolchain"
+    } else {
+      "generate it with a newer nightly toolchain (`rustup update nightly`)"
+    }
+  )]
+  UnsupportedFormatVersion { found: u32, min: u32, expected: u32 },
+
+  /// The crate root module is missing from the rustdoc JSON index
+  #[error("Root item not found in index")]
+  MissingRootItem,
+
+  /// An item page failed to render (see `keep_going`)
+  #[error(
+    "Failed to render {path}: {message} (use --keep-going to write a placeholder page instead)"
+  )]
+  Render { path: String, message: String },
+
+  /// The conversion had warnings and `deny_warnings` is set
+  #[error("The conversion of {crate_name} had {count} warning(s) (denied by --deny-warnings)")]
+  DeniedWarnings { crate_name: String, count: usize },
+
+  /// An option has an invalid value or conflicts with another option
+  #[error("{0}")]
+  InvalidOptions(String),
+
+  /// A Cargo manifest has no `doc-docusaurus` metadata table
+  #[error(
+    "No [package.metadata.doc-docusaurus] or [workspace.metadata.doc-docusaurus] table in {}",
+    .0.display()
+  )]
+  MissingConfig(PathBuf),
+
+  /// An input directory contains no rustdoc JSON files
+  #[error("No rustdoc JSON files found in {}", .0.display())]
+  NoInputFiles(PathBuf),
+
+  /// An existing sidebar file could not be merged with the generated sidebars
+  #[error("{0}")]
+  Sidebar(String),
+
+  /// git could not be run to find changed files
+  #[error("Failed to run git")]
+  GitUnavailable(#[source] io::Error),
+
+  /// git failed to list the files changed in a range
+  #[error("git diff {range} failed: {stderr}")]
+  GitDiff { range: String, stderr: String },
+
+  /// In-memory data could not be converted from or to JSON
+  #[error(transparent)]
+  SerdeJson(#[from] serde_json::Error),
+}
+
+impl Error {
+  /// Adapter for `map_err` on failures to read `path`.
+  pub(crate) fn read(path: &Path) -> impl FnOnce(io::Error) -> Error {
+    let path = path.to_path_buf();
+    move |source| Error::Read { path, source }
+  }
+
+  /// Adapter for `map_err` on failures to write `path`.
+  pub(crate) fn write(path: &Path) -> impl FnOnce(io::Error) -> Error {
+    let path = path.to_path_buf();
+    move |source| Error::Write { path, source }
+  }
+
+  /// Adapter for `map_err` on failures to parse the JSON file `path`.
+  pub(crate) fn json(path: &Path) -> impl FnOnce(serde_json::Error) -> Error {
+    let path = path.to_path_buf();
+    move |source| Error::Json { path, source }
+  }
+
+  /// Adapter for `map_err` on failures to parse the TOML file `path`.
+  pub(crate) fn toml(path: &Path) -> impl FnOnce(toml::de::Error) -> Error {
+    let path = path.to_path_buf();
+    move |source| Error::Toml { path, source }
+  }
+}
@@ -0,0 +1,336 @@
+//! Per-module output layout.
+//!
+//! Instead of a page per item, the item pages of each module are appended to
+//! the module's page as anchored sections. Links to item pages, both in pages
+//! and in sidebars, point to these sections instead.
+
+use crate::sidebar::{SidebarItem, SidebarMap};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Prefixes of item pages, in the order their sections appear on module pages.
+const ITEM_PAGE_PREFIXES: &[&str] = &[
+  "macro",
+  "struct",
+  "enum",
+  "union",
+  "trait",
+  "traitalias",
+  "type",
+  "foreigntype",
+  "constant",
+  "static",
+  "fn",
+  "attr",
+  "derive",
+];
+
+/// Settings needed to point sidebar entries to item sections.
+pub(crate) struct ModuleLayoutContext<'a> {
+  /// Base path of absolute links
+  pub link_base_path: &'a str,
+  /// Doc ID prefix of the crate's pages in sidebars (e.g. `api/my_crate/`)
+  pub doc_id_prefix: &'a str,
+  /// Name of the converted crate
+  pub crate_name: &'a str,
+  /// Extension of the generated files (without the dot)
+  pub extension: &'a str,
+}
+
+/// Split a page path into its directory (with a trailing `/`, empty at the
+/// crate root) and item page stem (e.g. `struct.Foo`), if it is an item page.
+fn split_item_page<'a>(path: &'a str, extension: &str) -> Option<(&'a str, &'a str)> {
+  let stem = path.strip_suffix(extension)?.strip_suffix('.')?;
+  let (dir, stem) = match stem.rfind('/') {
+    Some(i) => (&stem[..=i], &stem[i + 1..]),
+    None => ("", stem),
+  };
+  is_item_page_stem(stem).then_some((dir, stem))
+}
+
+/// Whether a file stem (e.g. `struct.Foo`) is the stem of an item page.
+fn is_item_page_stem(stem: &str) -> bool {
+  stem
+    .split_once('.')
+    .is_some_and(|(prefix, name)| ITEM_PAGE_PREFIXES.contains(&prefix) && !name.contains('.'))
+}
+
+/// Merge item pages into the page of their module.
+///
+/// Item pages of modules without a page (e.g. in previews) are kept as they are.
+/// Returns the paths of the merged pages, relative to the crate directory.
+pub(crate) fn merge_item_pages(
+  files: &mut BTreeMap<String, String>,
+  extension: &str,
+) -> BTreeSet<String> {
+  let mut sections: BTreeMap<String, Vec<(usize, String)>> = BTreeMap::new();
+  for path in files.keys() {
+    let Some((dir, stem)) = split_item_page(path, extension) else {
+      continue;
+    };
+    let module_page = format!("{}index.{}", dir, extension);
+    if files.contains_key(&module_page) {
+      let prefix = stem.split('.').next().unwrap_or_default();
+      let order = ITEM_PAGE_PREFIXES
+        .iter()
+        .position(|p| *p == prefix)
+        .unwrap_or_default();
+      sections
+        .entry(module_page)
+        .or_default()
+        .push((order, path.clone()));
+    }
+  }
+
+  let mut merged = BTreeSet::new();
+  for (module_page, mut item_pages) in sections {
+    item_pages.sort();
+    let (frontmatter, mut imports, mut body) = split_page(&files[&module_page]);
+    body = body.trim_end().to_string();
+    body.push('\n');
+
+    for (_, path) in item_pages {
+      let Some(content) = files.remove(&path) else {
+        continue;
+      };
+      let stem = split_item_page(&path, extension).map_or("", |(_, stem)| stem);
+      let (item_frontmatter, item_imports, item_body) = split_page(&content);
+      for import in item_imports {
+        if !imports.contains(&import) {
+          imports.push(import);
+        }
+      }
+      body.push_str(&item_section(stem, &item_frontmatter, &item_body));
+      merged.insert(path);
+    }
+
+    let mut content = frontmatter;
+    if !imports.is_empty() {
+      content.push_str(&imports.join("\n"));
+      content.push_str("\n\n");
+    }
+    content.push_str(&body);
+    files.insert(module_page, content);
+  }
+  merged
+}
+
+/// Split a page into its front matter (with delimiters), import lines and body.
+fn split_page(content: &str) -> (String, Vec<String>, String) {
+  let (frontmatter, mut rest) = match content
+    .strip_prefix("---\n")
+    .and_then(|rest| rest.find("\n---\n"))
+  {
+    Some(end) => content.split_at(end + 4 + "\n---\n".len()),
+    None => ("", content),
+  };
+
+  let mut imports = Vec::new();
+  loop {
+    let trimmed = rest.trim_start_matches('\n');
+    let (line, remaining) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+    if !line.starts_with("import ") {
+      rest = trimmed;
+      break;
+    }
+    imports.push(line.to_string());
+    rest = remaining;
+  }
+
+  let frontmatter = match frontmatter {
+    "" => String::new(),
+    frontmatter => format!("{}\n", frontmatter),
+  };
+  (frontmatter, imports, rest.to_string())
+}
+
+/// Section of a module page holding an item page: an anchor, the page title as
+/// heading and the page body with its breadcrumb dropped and headings demoted.
+fn item_section(stem: &str, frontmatter: &str, body: &str) -> String {
+  let title = frontmatter
+    .lines()
+    .find_map(|line| line.strip_prefix("title:"))
+    .map(|title| title.trim().trim_matches('"').to_string())
+    .unwrap_or_else(|| stem.to_string());
+
+  // The breadcrumb duplicates the module page's own
+  let body = match body.split_once('\n') {
+    Some((first, rest)) if first.starts_with("**") && first.ends_with("**") => rest,
+    _ => body,
+  };
+
+  // Sections are separated by rules already
+  let body = body.trim();
+  let body = body.strip_suffix("---").unwrap_or(body);
+
+  let mut section = format!("\n---\n\n<a id=\"{}\"></a>\n\n## {}\n\n", stem, title);
+  let mut in_fence = false;
+  for line in body.trim_end().lines() {
+    if line.trim_start().starts_with("```") {
+      in_fence = !in_fence;
+    }
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    if !in_fence && (1..6).contains(&level) && line[level..].starts_with(' ') {
+      section.push('#');
+    }
+    section.push_str(line);
+    section.push('\n');
+  }
+  section
+}
+
+/// Point links to merged item pages to their section of the module page.
+pub(crate) fn link_to_sections(content: &str) -> String {
+  // `Link` components and the links of `RustCode` components
+  ["<Link to=\"", "\"href\": \""]
+    .iter()
+    .fold(content.to_string(), |content, marker| {
+      let mut output = String::new();
+      let mut rest = content.as_str();
+      while let Some(start) = rest.find(marker).map(|i| i + marker.len()) {
+        let Some(end) = rest[start..].find('"').map(|i| start + i) else {
+          break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&section_link_target(&rest[start..end]));
+        rest = &rest[end..];
+      }
+      output.push_str(rest);
+      output
+    })
+}
+
+/// Rewrite a link to an item page into a link to its module page section.
+///
+/// Anchors within item pages are dropped, as they are not unique on module pages.
+fn section_link_target(href: &str) -> String {
+  if href.contains("://") || href.starts_with('#') {
+    return href.to_string();
+  }
+  let route = href.split('#').next().unwrap_or(href);
+  let (dir, stem) = match route.rfind('/') {
+    Some(i) => (&route[..=i], &route[i + 1..]),
+    None => ("", route),
+  };
+  if !is_item_page_stem(stem) {
+    return href.to_string();
+  }
+  format!("{}#{}", dir, stem)
+}
+
+/// Point sidebar entries of merged item pages to their module page section.
+pub(crate) fn link_sidebar_to_sections(
+  sidebars: &mut SidebarMap,
+  merged: &BTreeSet<String>,
+  ctx: &ModuleLayoutContext,
+) {
+  for items in sidebars.values_mut() {
+    link_sidebar_items_to_sections(items, merged, ctx);
+  }
+}
+
+fn link_sidebar_items_to_sections(
+  items: &mut [SidebarItem],
+  merged: &BTreeSet<String>,
+  ctx: &ModuleLayoutContext,
+) {
+  // Section link of a doc ID of the crate, if its page was merged
+  let section_href = |id: &str| {
+    let page = id.strip_prefix(ctx.doc_id_prefix)?;
+    let path = format!("{}.{}", page, ctx.extension);
+    if !merged.contains(&path) {
+      return None;
+    }
+    let (dir, stem) = split_item_page(&path, ctx.extension)?;
+    Some(format!(
+      "{}/{}/{}#{}",
+      ctx.link_base_path.trim_end_matches('/'),
+      ctx.crate_name,
+      dir,
+      stem
+    ))
+  };
+
+  for item in items {
+    match item {
+      SidebarItem::Doc {
+        id,
+        label,
+        class_name,
+        custom_props,
+      } => {
+        if let Some(href) = section_href(id) {
+          let label = label
+            .clone()
+            .unwrap_or_else(|| id.rsplit('/').next().unwrap_or(id).to_string());
+          *item = SidebarItem::Link {
+            href,
+            label,
+            class_name: class_name.take(),
+            custom_props: custom_props.take(),
+          };
+        }
+      }
+      SidebarItem::Category { link, items, .. } => {
+        if link.as_deref().and_then(section_href).is_some() {
+          *link = None;
+        }
+        link_sidebar_items_to_sections(items, merged, ctx);
+      }
+      SidebarItem::Link { .. } => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_section_link_targets() {
+    assert_eq!(
+      section_link_target("/docs/api/my_crate/mod_a/struct.Foo#method.new"),
+      "/docs/api/my_crate/mod_a/#struct.Foo"
+    );
+    assert_eq!(section_link_target("fn.bar"), "#fn.bar");
+    assert_eq!(
+      section_link_target("../mod_b/enum.Bar"),
+      "../mod_b/#enum.Bar"
+    );
+    assert_eq!(section_link_target("inner/"), "inner/");
+    assert_eq!(
+      section_link_target("struct.Foo.schema.json"),
+      "struct.Foo.schema.json"
+    );
+    assert_eq!(
+      section_link_target("https://docs.rs/serde/latest/serde/trait.Serialize.html"),
+      "https://docs.rs/serde/latest/serde/trait.Serialize.html"
+    );
+  }
+
+  #[test]
+  fn test_merge_item_pages() {
+    let mut files: BTreeMap<String, String> = [
+      (
+        "mod_a/index.md",
+        "---\ntitle: mod_a\n---\n\nimport Link from '@docusaurus/Link';\n\n# Module mod_a\n\n<div><Link to=\"struct.Foo\">Foo</Link></div>\n",
+      ),
+      (
+        "mod_a/struct.Foo.md",
+        "---\ntitle: \"Struct Foo\"\n---\n\nimport RustCode from '@site/src/components/RustCode';\nimport Link from '@docusaurus/Link';\n\n**<Link to=\"/my_crate/\">my_crate</Link>::Foo**\n\n## Examples\n\n```rust\n# let foo = Foo;\n```\n",
+      ),
+      ("mod_b/fn.bar.md", "---\ntitle: \"Function bar\"\n---\n\nbar\n"),
+    ]
+    .into_iter()
+    .map(|(path, content)| (path.to_string(), content.to_string()))
+    .collect();
+
+    let merged = merge_item_pages(&mut files, "md");
+
+    assert_eq!(merged, BTreeSet::from(["mod_a/struct.Foo.md".to_string()]));
+    assert!(files.contains_key("mod_b/fn.bar.md"));
+    assert_eq!(
+      files["mod_a/index.md"],
+      "---\ntitle: mod_a\n---\n\nimport Link from '@docusaurus/Link';\nimport RustCode from '@site/src/components/RustCode';\n\n# Module mod_a\n\n<div><Link to=\"struct.Foo\">Foo</Link></div>\n\n---\n\n<a id=\"struct.Foo\"></a>\n\n## Struct Foo\n\n### Examples\n\n```rust\n# let foo = Foo;\n```\n"
+    );
+  }
+}
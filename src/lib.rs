@@ -9,38 +9,67 @@
 //! use cargo_doc_docusaurus::{convert_json_file, ConversionOptions};
 //! use std::path::Path;
 //!
-//! let options = ConversionOptions {
-//!     input_path: Path::new("target/doc/my_crate.json"),
-//!     output_dir: Path::new("docs"),
-//!     include_private: false,
-//!     base_path: "",
-//!     workspace_crates: &[],
-//!     sidebarconfig_collapsed: false,
-//!     sidebar_output: None,
-//!     sidebar_root_link: None,
-//! };
+//! let mut options = ConversionOptions::default();
+//! options.input_path = Path::new("target/doc/my_crate.json");
+//! options.output_dir = Path::new("docs");
 //!
 //! convert_json_file(&options).expect("Conversion failed");
 //! ```
+//!
+//! Or, with owned options:
+//!
+//! ```no_run
+//! use cargo_doc_docusaurus::ConversionOptionsBuilder;
+//!
+//! ConversionOptionsBuilder::new("target/doc/my_crate.json", "docs")
+//!     .base_path("/docs/api")
+//!     .convert()
+//!     .expect("Conversion failed");
+//! ```
 
+mod assets;
+pub mod config;
 pub mod converter;
+pub mod coverage;
+pub mod diff;
+pub mod error;
+pub mod layout;
+pub mod links;
+pub mod model;
 pub mod parser;
+pub mod plain;
+pub mod preview;
+pub mod progress;
+pub mod renderer;
+pub mod sidebar;
+pub mod site_config;
+pub mod stats;
+pub mod templates;
+pub mod tree;
 pub mod writer;
 
+pub use error::{Error, Result};
 pub use rustdoc_types;
 
-use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
 /// Options for converting rustdoc JSON to markdown.
+///
+/// See [`ConversionOptionsBuilder`] for an owned version that is easier to build
+/// programmatically. New options are only added as builder setters; this struct is
+/// `#[non_exhaustive]`, so set its fields on [`ConversionOptions::default`] instead
+/// of writing it as a struct literal.
+#[non_exhaustive]
 pub struct ConversionOptions<'a> {
-  /// Path to the input rustdoc JSON file
+  /// Path to the input rustdoc JSON file (optionally `.gz` or `.zst` compressed, `-` for stdin)
   pub input_path: &'a Path,
   /// Directory where markdown files will be written
   pub output_dir: &'a Path,
   /// Whether to include private items in the output
   pub include_private: bool,
-  /// Base path for links (e.g., "/docs/runtime/rust" for Docusaurus routing)
+  /// Base path for links (e.g., "/docs/runtime/rust" for Docusaurus routing),
+  /// normalized to a leading and no trailing slash
   pub base_path: &'a str,
   /// List of workspace crate names - external crates in this list will use internal links
   pub workspace_crates: &'a [String],
@@ -48,8 +77,802 @@ pub struct ConversionOptions<'a> {
   pub sidebarconfig_collapsed: bool,
   /// Custom path for the sidebar configuration file
   pub sidebar_output: Option<&'a Path>,
+  /// Map of item paths to the version that introduced them, shown as "Since" badges
+  pub since_map: Option<&'a Path>,
+  /// JSON file mapping old item paths to their new paths, written as client redirects
+  pub redirect_map: Option<&'a Path>,
+  /// JSON file to write the normalized model of the crate to (see [`crate::model`])
+  pub emit_model: Option<&'a Path>,
   /// URL for the 'Go back' link in root crate sidebars
   pub sidebar_root_link: Option<&'a str>,
+  /// Maximum module depth that gets its own pages (crate root is 0, `None` for no limit)
+  pub max_depth: Option<usize>,
+  /// Sidebar levels to collapse, overriding `sidebarconfig_collapsed` (which collapses all levels)
+  pub sidebar_collapse: Option<sidebar::SidebarCollapse>,
+  /// Only write files whose content changed and remove files of items that no longer exist
+  pub incremental: bool,
+  /// Sitemap `changefreq`/`priority` front matter for generated pages (per page kind)
+  pub sitemap: Option<&'a converter::SitemapOptions>,
+  /// Namespace (e.g. "v2") prefixed to output directories, doc IDs and sidebar keys
+  pub namespace: Option<&'a str>,
+  /// Route of the docs plugin instance serving the pages (e.g. "/rust"), the part of `base_path` that is not a docs folder
+  pub route_base_path: Option<&'a str>,
+  /// Id of the docs plugin instance, naming its versioned and translated docs directories
+  pub docs_plugin_id: Option<&'a str>,
+  /// File extension of generated pages (`.md` or `.mdx`)
+  pub extension: converter::OutputExtension,
+  /// Markup flavor of generated pages (Docusaurus MDX or plain Markdown)
+  pub flavor: converter::OutputFlavor,
+  /// Format of the sidebar file (TypeScript, JSON or CommonJS)
+  pub sidebar_format: sidebar::SidebarFormat,
+  /// Order of the methods listed on type and trait pages (source or alphabetical)
+  pub method_order: converter::MethodOrder,
+  /// Line wrapping of function signatures: `threshold` (parameter count and width limits) or `rustfmt`
+  pub signature_style: converter::SignatureStyle,
+  /// Width above which the parameters of a function signature go on their own lines (80, or 100 with the `rustfmt` style)
+  pub signature_width: Option<usize>,
+  /// Parameter count above which the parameters of a function signature go on their own lines (3, ignored by the `rustfmt` style)
+  pub signature_max_params: Option<usize>,
+  /// Layout of the generated pages (a page per item, per module or a single file)
+  pub layout: converter::OutputLayout,
+  /// Generate an `error-index` page listing all types implementing `std::error::Error`
+  pub error_index: bool,
+  /// Generate an `async-index` page listing async functions and `Future`/`Stream` types
+  pub async_index: bool,
+  /// Generate a `feature-matrix` page showing which items each Cargo feature enables
+  pub feature_matrix: bool,
+  /// Generate a `features` page listing the crate's Cargo features and what they enable
+  pub features_page: bool,
+  /// Generate a glossary page from `# Terminology` sections and `<!-- glossary: term -->` markers in doc comments
+  pub glossary: bool,
+  /// Generate an `all` page listing every item of the crate by kind
+  pub all_items: bool,
+  /// Generate a page per item kind (e.g. `all-structs`) listing every item of that kind
+  pub kind_indexes: bool,
+  /// Extract the `JSON schema` blocks of doc comments (as generated for schemars types) to downloadable `.schema.json` files
+  pub json_schema_assets: bool,
+  /// Render `math`/`latex` fenced code blocks of doc comments as `$$` blocks for remark-math and KaTeX
+  pub math_blocks: bool,
+  /// Copy local images referenced in doc comments to an `_assets` folder of the crate output
+  pub copy_assets: bool,
+  /// Show `PhantomData` marker fields of structs (hidden by default)
+  pub show_phantom_data: bool,
+  /// Render a placeholder page for items that fail to render instead of aborting
+  pub keep_going: bool,
+  /// Fail the conversion when it has warnings (unresolved links, placeholder pages), after writing the output
+  pub deny_warnings: bool,
+  /// Write `_category_.json` files to module directories instead of a sidebar file
+  pub category_files: bool,
+  /// Generate no sidebars: pages get no `displayed_sidebar` and no sidebar file is written or merged
+  pub no_sidebar: bool,
+  /// Record hashes of the rustdoc JSON input and of all generated files in the output manifest, for attesting reproducible builds
+  pub reproducible: bool,
+  /// Wire the generated sidebars into the site's sidebars file and Docusaurus config (keeping `.bak` backups)
+  pub patch_config: bool,
+  /// Document items re-exported from other crates on this crate's pages, when their rustdoc JSON is next to the input
+  pub inline_reexports: bool,
+  /// Docusaurus version label (e.g. "1.2") like `docs_version`, also registered in `versions.json` and `versioned_sidebars`
+  pub docusaurus_version: Option<&'a str>,
+  /// Docusaurus major version to generate pages and sidebars for, detected from the site's `package.json` when unset
+  pub docusaurus_major: Option<converter::DocusaurusMajor>,
+  /// Docusaurus docs version (e.g. "1.2") to write versioned docs for
+  pub docs_version: Option<&'a str>,
+  /// Module path prefix (e.g. "my_crate::api::v1") stripped from displayed module paths
+  pub strip_prefix: Option<&'a str>,
+  /// Separator between item names and their doc summaries in listings (default `—`)
+  pub summary_separator: Option<&'a str>,
+  /// Marker after the names of items with restricted visibility in listings (default `🔒`, empty to hide)
+  pub restricted_marker: Option<&'a str>,
+  /// Image (URL or path of the site's static files) set as `image` in the front matter of generated pages, for social cards
+  pub social_card: Option<&'a str>,
+  /// Show only the first line of the docs of struct fields instead of their complete docs
+  pub field_summaries: bool,
+  /// Collapse the docs of methods below their first paragraph in a `<details>` block
+  pub collapse_method_docs: bool,
+  /// Add Parameters and Returns tables to functions and methods, with parameter docs from their `# Arguments` section
+  pub parameter_tables: bool,
+  /// Docusaurus i18n locale (e.g. "fr") to write translation sources for
+  pub locale: Option<&'a str>,
+  /// Translated docs content directory replacing `i18n/<LOCALE>/docusaurus-plugin-content-docs` (e.g. for a docs plugin instance with an id); the locale defaults to the directory below `i18n`
+  pub locale_dir: Option<&'a Path>,
+  /// Git range (e.g. "origin/main...HEAD") whose changed source files are the only ones to get pages
+  pub changed_only: Option<&'a str>,
+  /// Production site URL (e.g. `https://docs.example.com`) linked to for pages outside a `changed_only` preview
+  pub production_url: Option<&'a str>,
+  /// Icons (`customProps.icon`) of sidebar entries, keyed by item kind
+  pub sidebar_icons: Option<&'a sidebar::SidebarIcons>,
+  /// Admonition kinds (or `heading`) of doc comment sections, keyed by heading (e.g. "Safety")
+  pub doc_admonitions: Option<&'a converter::DocAdmonitions>,
+  /// Cargo.lock whose dependency versions pin docs.rs links (instead of `latest`)
+  pub cargo_lock: Option<&'a Path>,
+  /// Cargo.toml of the crate, read by `features_page` and `copy_assets` (found from the input's location if unset)
+  pub cargo_manifest: Option<&'a Path>,
+  /// README whose content is added to the crate index page, with relative image paths rewritten
+  pub include_readme: Option<&'a Path>,
+  /// Directory of page templates (`item.md`, `module.md`, `crate.md`) overriding the built-in ones
+  pub template_dir: Option<&'a Path>,
+  /// docs.rs versions of dependencies, keyed by crate name, overriding `cargo_lock`
+  pub extern_versions: Option<&'a converter::ExternVersions>,
+  /// Documentation URL prefixes of dependencies (e.g. internal mirrors), keyed by crate name, replacing docs.rs
+  pub extern_urls: Option<&'a converter::ExternUrls>,
+  /// Transformation of the generated sidebars before they are written (not called with `category_files`)
+  pub sidebar_hook: Option<&'a sidebar::SidebarHook>,
+  /// Custom rendering of item pages (see [`renderer::ItemRenderer`])
+  pub item_renderer: Option<&'a renderer::CustomRenderer>,
+}
+
+impl Default for ConversionOptions<'_> {
+  /// The defaults of [`ConversionOptionsBuilder`], which are those of the CLI.
+  fn default() -> Self {
+    static DEFAULTS: LazyLock<ConversionOptionsBuilder> =
+      LazyLock::new(ConversionOptionsBuilder::default);
+    DEFAULTS.options()
+  }
+}
+
+/// Owned, reusable [`ConversionOptions`] with builder-style setters.
+///
+/// Unlike `ConversionOptions`, the builder owns all of its values, so it can be
+/// stored in other structs and passed between threads.
+///
+/// ```no_run
+/// use cargo_doc_docusaurus::ConversionOptionsBuilder;
+///
+/// let options = ConversionOptionsBuilder::new("target/doc/my_crate.json", "docs")
+///   .base_path("/docs/api")
+///   .include_private(true)
+///   .max_depth(Some(2));
+///
+/// options.convert().expect("Conversion failed");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConversionOptionsBuilder {
+  input_path: PathBuf,
+  output_dir: PathBuf,
+  include_private: bool,
+  base_path: String,
+  workspace_crates: Vec<String>,
+  sidebarconfig_collapsed: Option<bool>,
+  sidebar_output: Option<PathBuf>,
+  since_map: Option<PathBuf>,
+  redirect_map: Option<PathBuf>,
+  emit_model: Option<PathBuf>,
+  sidebar_root_link: Option<String>,
+  max_depth: Option<usize>,
+  sidebar_collapse: Option<sidebar::SidebarCollapse>,
+  incremental: bool,
+  sitemap: Option<converter::SitemapOptions>,
+  namespace: Option<String>,
+  route_base_path: Option<String>,
+  docs_plugin_id: Option<String>,
+  extension: converter::OutputExtension,
+  flavor: converter::OutputFlavor,
+  sidebar_format: sidebar::SidebarFormat,
+  method_order: converter::MethodOrder,
+  signature_style: converter::SignatureStyle,
+  signature_width: Option<usize>,
+  signature_max_params: Option<usize>,
+  layout: converter::OutputLayout,
+  error_index: bool,
+  async_index: bool,
+  feature_matrix: bool,
+  features_page: bool,
+  glossary: bool,
+  all_items: bool,
+  kind_indexes: bool,
+  json_schema_assets: bool,
+  math_blocks: bool,
+  copy_assets: bool,
+  show_phantom_data: bool,
+  keep_going: bool,
+  deny_warnings: bool,
+  category_files: bool,
+  no_sidebar: bool,
+  reproducible: bool,
+  patch_config: bool,
+  inline_reexports: bool,
+  docusaurus_version: Option<String>,
+  docusaurus_major: Option<converter::DocusaurusMajor>,
+  docs_version: Option<String>,
+  strip_prefix: Option<String>,
+  summary_separator: Option<String>,
+  restricted_marker: Option<String>,
+  social_card: Option<String>,
+  field_summaries: bool,
+  collapse_method_docs: bool,
+  parameter_tables: bool,
+  locale: Option<String>,
+  locale_dir: Option<PathBuf>,
+  changed_only: Option<String>,
+  production_url: Option<String>,
+  sidebar_icons: Option<sidebar::SidebarIcons>,
+  doc_admonitions: Option<converter::DocAdmonitions>,
+  cargo_lock: Option<PathBuf>,
+  cargo_manifest: Option<PathBuf>,
+  include_readme: Option<PathBuf>,
+  template_dir: Option<PathBuf>,
+  extern_versions: Option<converter::ExternVersions>,
+  extern_urls: Option<converter::ExternUrls>,
+  sidebar_hook: Option<sidebar::SidebarHook>,
+  item_renderer: Option<renderer::CustomRenderer>,
+}
+
+impl ConversionOptionsBuilder {
+  /// Create options converting `input_path` into `output_dir` with default settings.
+  pub fn new(input_path: impl Into<PathBuf>, output_dir: impl Into<PathBuf>) -> Self {
+    Self {
+      input_path: input_path.into(),
+      output_dir: output_dir.into(),
+      ..Default::default()
+    }
+  }
+
+  /// Path to the input rustdoc JSON file
+  pub fn input_path(mut self, input_path: impl Into<PathBuf>) -> Self {
+    self.input_path = input_path.into();
+    self
+  }
+
+  /// Directory where markdown files will be written
+  pub fn output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+    self.output_dir = output_dir.into();
+    self
+  }
+
+  /// Whether to include private items in the output
+  pub fn include_private(mut self, include_private: bool) -> Self {
+    self.include_private = include_private;
+    self
+  }
+
+  /// Base path for links (e.g., "/docs/runtime/rust" for Docusaurus routing)
+  pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+    self.base_path = base_path.into();
+    self
+  }
+
+  /// List of workspace crate names - external crates in this list will use internal links
+  pub fn workspace_crates<I, S>(mut self, workspace_crates: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.workspace_crates = workspace_crates.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Whether to generate sidebar categories as collapsed
+  pub fn sidebarconfig_collapsed(mut self, collapsed: bool) -> Self {
+    self.sidebarconfig_collapsed = Some(collapsed);
+    self
+  }
+
+  /// Custom path for the sidebar configuration file
+  pub fn sidebar_output(mut self, sidebar_output: Option<PathBuf>) -> Self {
+    self.sidebar_output = sidebar_output;
+    self
+  }
+
+  /// JSON file mapping item paths (e.g. `my_crate::Foo`) to the version that introduced them
+  pub fn since_map(mut self, since_map: Option<PathBuf>) -> Self {
+    self.since_map = since_map;
+    self
+  }
+
+  /// JSON file mapping old item paths to their new paths, written as client redirects
+  pub fn redirect_map(mut self, redirect_map: Option<PathBuf>) -> Self {
+    self.redirect_map = redirect_map;
+    self
+  }
+
+  /// JSON file to write the normalized model of the crate to (see [`crate::model`])
+  pub fn emit_model(mut self, emit_model: Option<PathBuf>) -> Self {
+    self.emit_model = emit_model;
+    self
+  }
+
+  /// URL for the 'Go back' link in root crate sidebars
+  pub fn sidebar_root_link(mut self, sidebar_root_link: Option<String>) -> Self {
+    self.sidebar_root_link = sidebar_root_link;
+    self
+  }
+
+  /// Maximum module depth that gets its own pages (crate root is 0, `None` for no limit)
+  pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
+
+  /// Sidebar levels to collapse, overriding `sidebarconfig_collapsed` (which collapses all levels)
+  pub fn sidebar_collapse(mut self, sidebar_collapse: Option<sidebar::SidebarCollapse>) -> Self {
+    self.sidebar_collapse = sidebar_collapse;
+    self
+  }
+
+  /// Only write files whose content changed and remove files of items that no longer exist
+  pub fn incremental(mut self, incremental: bool) -> Self {
+    self.incremental = incremental;
+    self
+  }
+
+  /// Sitemap `changefreq`/`priority` front matter for generated pages (per page kind)
+  pub fn sitemap(mut self, sitemap: Option<converter::SitemapOptions>) -> Self {
+    self.sitemap = sitemap;
+    self
+  }
+
+  /// Namespace (e.g. "v2") prefixed to output directories, doc IDs and sidebar keys
+  pub fn namespace(mut self, namespace: Option<String>) -> Self {
+    self.namespace = namespace;
+    self
+  }
+
+  /// File extension of generated pages (`.md` or `.mdx`)
+  pub fn extension(mut self, extension: converter::OutputExtension) -> Self {
+    self.extension = extension;
+    self
+  }
+
+  /// Markup flavor of generated pages (Docusaurus MDX or plain Markdown)
+  pub fn flavor(mut self, flavor: converter::OutputFlavor) -> Self {
+    self.flavor = flavor;
+    self
+  }
+
+  /// Format of the sidebar file (TypeScript, JSON or CommonJS)
+  pub fn sidebar_format(mut self, sidebar_format: sidebar::SidebarFormat) -> Self {
+    self.sidebar_format = sidebar_format;
+    self
+  }
+
+  /// Order of the methods listed on type and trait pages (source or alphabetical)
+  pub fn method_order(mut self, method_order: converter::MethodOrder) -> Self {
+    self.method_order = method_order;
+    self
+  }
+
+  /// Line wrapping of function signatures: `threshold` (parameter count and width limits) or `rustfmt`
+  pub fn signature_style(mut self, signature_style: converter::SignatureStyle) -> Self {
+    self.signature_style = signature_style;
+    self
+  }
+
+  /// Width above which the parameters of a function signature go on their own lines (80, or 100 with the `rustfmt` style)
+  pub fn signature_width(mut self, signature_width: Option<usize>) -> Self {
+    self.signature_width = signature_width;
+    self
+  }
+
+  /// Parameter count above which the parameters of a function signature go on their own lines (3, ignored by the `rustfmt` style)
+  pub fn signature_max_params(mut self, signature_max_params: Option<usize>) -> Self {
+    self.signature_max_params = signature_max_params;
+    self
+  }
+
+  /// Layout of the generated pages (a page per item, per module or a single file)
+  pub fn layout(mut self, layout: converter::OutputLayout) -> Self {
+    self.layout = layout;
+    self
+  }
+
+  /// Generate an `error-index` page listing all types implementing `std::error::Error`
+  pub fn error_index(mut self, error_index: bool) -> Self {
+    self.error_index = error_index;
+    self
+  }
+
+  /// Generate an `async-index` page listing async functions and `Future`/`Stream` types
+  pub fn async_index(mut self, async_index: bool) -> Self {
+    self.async_index = async_index;
+    self
+  }
+
+  /// Docusaurus docs version (e.g. "1.2") to write versioned docs for
+  pub fn docs_version(mut self, docs_version: Option<String>) -> Self {
+    self.docs_version = docs_version;
+    self
+  }
+
+  /// Generate a `feature-matrix` page showing which items each Cargo feature enables
+  pub fn feature_matrix(mut self, feature_matrix: bool) -> Self {
+    self.feature_matrix = feature_matrix;
+    self
+  }
+
+  /// Module path prefix (e.g. "my_crate::api::v1") stripped from displayed module paths
+  pub fn strip_prefix(mut self, strip_prefix: Option<String>) -> Self {
+    self.strip_prefix = strip_prefix;
+    self
+  }
+
+  /// Docusaurus i18n locale (e.g. "fr") to write translation sources for
+  pub fn locale(mut self, locale: Option<String>) -> Self {
+    self.locale = locale;
+    self
+  }
+
+  /// Translated docs content directory replacing `i18n/<LOCALE>/docusaurus-plugin-content-docs` (e.g. for a docs plugin instance with an id); the locale defaults to the directory below `i18n`
+  pub fn locale_dir(mut self, locale_dir: Option<PathBuf>) -> Self {
+    self.locale_dir = locale_dir;
+    self
+  }
+
+  /// Icons (`customProps.icon`) of sidebar entries, keyed by item kind
+  pub fn sidebar_icons(mut self, sidebar_icons: Option<sidebar::SidebarIcons>) -> Self {
+    self.sidebar_icons = sidebar_icons;
+    self
+  }
+
+  /// Admonition kinds (or `heading`) of doc comment sections, keyed by heading (e.g. "Safety")
+  pub fn doc_admonitions(mut self, doc_admonitions: Option<converter::DocAdmonitions>) -> Self {
+    self.doc_admonitions = doc_admonitions;
+    self
+  }
+
+  /// Git range (e.g. "origin/main...HEAD") whose changed source files are the only ones to get pages
+  pub fn changed_only(mut self, changed_only: Option<String>) -> Self {
+    self.changed_only = changed_only;
+    self
+  }
+
+  /// Production site URL (e.g. `https://docs.example.com`) linked to for pages outside a `changed_only` preview
+  pub fn production_url(mut self, production_url: Option<String>) -> Self {
+    self.production_url = production_url;
+    self
+  }
+
+  /// Generate a glossary page from `# Terminology` sections and `<!-- glossary: term -->` markers in doc comments
+  pub fn glossary(mut self, glossary: bool) -> Self {
+    self.glossary = glossary;
+    self
+  }
+
+  /// Extract the `JSON schema` blocks of doc comments (as generated for schemars types) to downloadable `.schema.json` files
+  pub fn json_schema_assets(mut self, json_schema_assets: bool) -> Self {
+    self.json_schema_assets = json_schema_assets;
+    self
+  }
+
+  /// Record hashes of the rustdoc JSON input and of all generated files in the output manifest, for attesting reproducible builds
+  pub fn reproducible(mut self, reproducible: bool) -> Self {
+    self.reproducible = reproducible;
+    self
+  }
+
+  /// Docusaurus version label (e.g. "1.2") like `docs_version`, also registered in `versions.json` and `versioned_sidebars`
+  pub fn docusaurus_version(mut self, docusaurus_version: Option<String>) -> Self {
+    self.docusaurus_version = docusaurus_version;
+    self
+  }
+
+  /// Document items re-exported from other crates on this crate's pages, when their rustdoc JSON is next to the input
+  pub fn inline_reexports(mut self, inline_reexports: bool) -> Self {
+    self.inline_reexports = inline_reexports;
+    self
+  }
+
+  /// Show `PhantomData` marker fields of structs (hidden by default)
+  pub fn show_phantom_data(mut self, show_phantom_data: bool) -> Self {
+    self.show_phantom_data = show_phantom_data;
+    self
+  }
+
+  /// Render a placeholder page for items that fail to render instead of aborting
+  pub fn keep_going(mut self, keep_going: bool) -> Self {
+    self.keep_going = keep_going;
+    self
+  }
+
+  /// Fail the conversion when it has warnings (unresolved links, placeholder pages), after writing the output
+  pub fn deny_warnings(mut self, deny_warnings: bool) -> Self {
+    self.deny_warnings = deny_warnings;
+    self
+  }
+
+  /// Write `_category_.json` files to module directories instead of a sidebar file
+  pub fn category_files(mut self, category_files: bool) -> Self {
+    self.category_files = category_files;
+    self
+  }
+
+  /// Generate no sidebars: pages get no `displayed_sidebar` and no sidebar file is written or merged
+  pub fn no_sidebar(mut self, no_sidebar: bool) -> Self {
+    self.no_sidebar = no_sidebar;
+    self
+  }
+
+  /// Wire the generated sidebars into the site's sidebars file and Docusaurus config (keeping `.bak` backups)
+  pub fn patch_config(mut self, patch_config: bool) -> Self {
+    self.patch_config = patch_config;
+    self
+  }
+
+  /// Generate an `all` page listing every item of the crate by kind
+  pub fn all_items(mut self, all_items: bool) -> Self {
+    self.all_items = all_items;
+    self
+  }
+
+  /// Cargo.lock whose dependency versions pin docs.rs links (instead of `latest`)
+  pub fn cargo_lock(mut self, cargo_lock: Option<PathBuf>) -> Self {
+    self.cargo_lock = cargo_lock;
+    self
+  }
+
+  /// Cargo.toml of the crate, read by `features_page` and `copy_assets` (found from the input's location if unset)
+  pub fn cargo_manifest(mut self, cargo_manifest: Option<PathBuf>) -> Self {
+    self.cargo_manifest = cargo_manifest;
+    self
+  }
+
+  /// README whose content is added to the crate index page, with relative image paths rewritten
+  pub fn include_readme(mut self, include_readme: Option<PathBuf>) -> Self {
+    self.include_readme = include_readme;
+    self
+  }
+
+  /// Directory of page templates (`item.md`, `module.md`, `crate.md`) overriding the built-in ones
+  pub fn template_dir(mut self, template_dir: Option<PathBuf>) -> Self {
+    self.template_dir = template_dir;
+    self
+  }
+
+  /// docs.rs versions of dependencies, keyed by crate name, overriding `cargo_lock`
+  pub fn extern_versions(mut self, extern_versions: Option<converter::ExternVersions>) -> Self {
+    self.extern_versions = extern_versions;
+    self
+  }
+
+  /// Documentation URL prefixes of dependencies (e.g. internal mirrors), keyed by crate name, replacing docs.rs
+  pub fn extern_urls(mut self, extern_urls: Option<converter::ExternUrls>) -> Self {
+    self.extern_urls = extern_urls;
+    self
+  }
+
+  /// Transformation of the generated sidebars before they are written (not called with `category_files`)
+  pub fn sidebar_hook(mut self, sidebar_hook: Option<sidebar::SidebarHook>) -> Self {
+    self.sidebar_hook = sidebar_hook;
+    self
+  }
+
+  /// Custom rendering of item pages (see [`renderer::ItemRenderer`])
+  pub fn item_renderer(mut self, item_renderer: Option<renderer::CustomRenderer>) -> Self {
+    self.item_renderer = item_renderer;
+    self
+  }
+
+  /// Generate a page per item kind (e.g. `all-structs`) listing every item of that kind
+  pub fn kind_indexes(mut self, kind_indexes: bool) -> Self {
+    self.kind_indexes = kind_indexes;
+    self
+  }
+
+  /// Docusaurus major version to generate pages and sidebars for, detected from the site's `package.json` when unset
+  pub fn docusaurus_major(mut self, docusaurus_major: Option<converter::DocusaurusMajor>) -> Self {
+    self.docusaurus_major = docusaurus_major;
+    self
+  }
+
+  /// Generate a `features` page listing the crate's Cargo features and what they enable
+  pub fn features_page(mut self, features_page: bool) -> Self {
+    self.features_page = features_page;
+    self
+  }
+
+  /// Copy local images referenced in doc comments to an `_assets` folder of the crate output
+  pub fn copy_assets(mut self, copy_assets: bool) -> Self {
+    self.copy_assets = copy_assets;
+    self
+  }
+
+  /// Render `math`/`latex` fenced code blocks of doc comments as `$$` blocks for remark-math and KaTeX
+  pub fn math_blocks(mut self, math_blocks: bool) -> Self {
+    self.math_blocks = math_blocks;
+    self
+  }
+
+  /// Separator between item names and their doc summaries in listings (default `—`)
+  pub fn summary_separator(mut self, summary_separator: Option<String>) -> Self {
+    self.summary_separator = summary_separator;
+    self
+  }
+
+  /// Marker after the names of items with restricted visibility in listings (default `🔒`, empty to hide)
+  pub fn restricted_marker(mut self, restricted_marker: Option<String>) -> Self {
+    self.restricted_marker = restricted_marker;
+    self
+  }
+
+  /// Image (URL or path of the site's static files) set as `image` in the front matter of generated pages, for social cards
+  pub fn social_card(mut self, social_card: Option<String>) -> Self {
+    self.social_card = social_card;
+    self
+  }
+
+  /// Show only the first line of the docs of struct fields instead of their complete docs
+  pub fn field_summaries(mut self, field_summaries: bool) -> Self {
+    self.field_summaries = field_summaries;
+    self
+  }
+
+  /// Collapse the docs of methods below their first paragraph in a `<details>` block
+  pub fn collapse_method_docs(mut self, collapse_method_docs: bool) -> Self {
+    self.collapse_method_docs = collapse_method_docs;
+    self
+  }
+
+  /// Add Parameters and Returns tables to functions and methods, with parameter docs from their `# Arguments` section
+  pub fn parameter_tables(mut self, parameter_tables: bool) -> Self {
+    self.parameter_tables = parameter_tables;
+    self
+  }
+
+  /// Route of the docs plugin instance serving the pages (e.g. "/rust"), the part of `base_path` that is not a docs folder
+  pub fn route_base_path(mut self, route_base_path: Option<String>) -> Self {
+    self.route_base_path = route_base_path;
+    self
+  }
+
+  /// Id of the docs plugin instance, naming its versioned and translated docs directories
+  pub fn docs_plugin_id(mut self, docs_plugin_id: Option<String>) -> Self {
+    self.docs_plugin_id = docs_plugin_id;
+    self
+  }
+
+  /// Borrow these settings as [`ConversionOptions`].
+  pub fn options(&self) -> ConversionOptions<'_> {
+    ConversionOptions {
+      input_path: &self.input_path,
+      output_dir: &self.output_dir,
+      include_private: self.include_private,
+      base_path: &self.base_path,
+      workspace_crates: &self.workspace_crates,
+      // Collapsed unless set otherwise, like on the command line
+      sidebarconfig_collapsed: self.sidebarconfig_collapsed.unwrap_or(true),
+      sidebar_output: self.sidebar_output.as_deref(),
+      since_map: self.since_map.as_deref(),
+      redirect_map: self.redirect_map.as_deref(),
+      emit_model: self.emit_model.as_deref(),
+      sidebar_root_link: self.sidebar_root_link.as_deref(),
+      max_depth: self.max_depth,
+      sidebar_collapse: self.sidebar_collapse,
+      incremental: self.incremental,
+      sitemap: self.sitemap.as_ref(),
+      namespace: self.namespace.as_deref(),
+      route_base_path: self.route_base_path.as_deref(),
+      docs_plugin_id: self.docs_plugin_id.as_deref(),
+      extension: self.extension,
+      flavor: self.flavor,
+      sidebar_format: self.sidebar_format,
+      method_order: self.method_order,
+      signature_style: self.signature_style,
+      signature_width: self.signature_width,
+      signature_max_params: self.signature_max_params,
+      layout: self.layout,
+      error_index: self.error_index,
+      async_index: self.async_index,
+      feature_matrix: self.feature_matrix,
+      features_page: self.features_page,
+      glossary: self.glossary,
+      all_items: self.all_items,
+      kind_indexes: self.kind_indexes,
+      json_schema_assets: self.json_schema_assets,
+      math_blocks: self.math_blocks,
+      copy_assets: self.copy_assets,
+      show_phantom_data: self.show_phantom_data,
+      keep_going: self.keep_going,
+      deny_warnings: self.deny_warnings,
+      category_files: self.category_files,
+      no_sidebar: self.no_sidebar,
+      reproducible: self.reproducible,
+      patch_config: self.patch_config,
+      inline_reexports: self.inline_reexports,
+      docusaurus_version: self.docusaurus_version.as_deref(),
+      docusaurus_major: self.docusaurus_major,
+      docs_version: self.docs_version.as_deref(),
+      strip_prefix: self.strip_prefix.as_deref(),
+      summary_separator: self.summary_separator.as_deref(),
+      restricted_marker: self.restricted_marker.as_deref(),
+      social_card: self.social_card.as_deref(),
+      field_summaries: self.field_summaries,
+      collapse_method_docs: self.collapse_method_docs,
+      parameter_tables: self.parameter_tables,
+      locale: self.locale.as_deref(),
+      locale_dir: self.locale_dir.as_deref(),
+      changed_only: self.changed_only.as_deref(),
+      production_url: self.production_url.as_deref(),
+      sidebar_icons: self.sidebar_icons.as_ref(),
+      doc_admonitions: self.doc_admonitions.as_ref(),
+      cargo_lock: self.cargo_lock.as_deref(),
+      cargo_manifest: self.cargo_manifest.as_deref(),
+      include_readme: self.include_readme.as_deref(),
+      template_dir: self.template_dir.as_deref(),
+      extern_versions: self.extern_versions.as_ref(),
+      extern_urls: self.extern_urls.as_ref(),
+      sidebar_hook: self.sidebar_hook.as_ref(),
+      item_renderer: self.item_renderer.as_ref(),
+    }
+  }
+
+  /// Run the conversion with these settings (see [`convert_json_file`]).
+  pub fn convert(&self) -> Result<stats::ConversionStats> {
+    convert_json_file(&self.options())
+  }
+}
+
+impl From<&ConversionOptions<'_>> for ConversionOptionsBuilder {
+  fn from(options: &ConversionOptions<'_>) -> Self {
+    Self {
+      input_path: options.input_path.to_path_buf(),
+      output_dir: options.output_dir.to_path_buf(),
+      include_private: options.include_private,
+      base_path: options.base_path.to_string(),
+      workspace_crates: options.workspace_crates.to_vec(),
+      sidebarconfig_collapsed: Some(options.sidebarconfig_collapsed),
+      sidebar_output: options.sidebar_output.map(Path::to_path_buf),
+      since_map: options.since_map.map(Path::to_path_buf),
+      redirect_map: options.redirect_map.map(Path::to_path_buf),
+      emit_model: options.emit_model.map(Path::to_path_buf),
+      sidebar_root_link: options.sidebar_root_link.map(str::to_string),
+      max_depth: options.max_depth,
+      sidebar_collapse: options.sidebar_collapse,
+      incremental: options.incremental,
+      sitemap: options.sitemap.cloned(),
+      namespace: options.namespace.map(str::to_string),
+      route_base_path: options.route_base_path.map(str::to_string),
+      docs_plugin_id: options.docs_plugin_id.map(str::to_string),
+      extension: options.extension,
+      flavor: options.flavor,
+      sidebar_format: options.sidebar_format,
+      method_order: options.method_order,
+      signature_style: options.signature_style,
+      signature_width: options.signature_width,
+      signature_max_params: options.signature_max_params,
+      layout: options.layout,
+      error_index: options.error_index,
+      async_index: options.async_index,
+      feature_matrix: options.feature_matrix,
+      features_page: options.features_page,
+      glossary: options.glossary,
+      all_items: options.all_items,
+      kind_indexes: options.kind_indexes,
+      json_schema_assets: options.json_schema_assets,
+      math_blocks: options.math_blocks,
+      copy_assets: options.copy_assets,
+      show_phantom_data: options.show_phantom_data,
+      keep_going: options.keep_going,
+      deny_warnings: options.deny_warnings,
+      category_files: options.category_files,
+      no_sidebar: options.no_sidebar,
+      reproducible: options.reproducible,
+      patch_config: options.patch_config,
+      inline_reexports: options.inline_reexports,
+      docusaurus_version: options.docusaurus_version.map(str::to_string),
+      docusaurus_major: options.docusaurus_major,
+      docs_version: options.docs_version.map(str::to_string),
+      strip_prefix: options.strip_prefix.map(str::to_string),
+      summary_separator: options.summary_separator.map(str::to_string),
+      restricted_marker: options.restricted_marker.map(str::to_string),
+      social_card: options.social_card.map(str::to_string),
+      field_summaries: options.field_summaries,
+      collapse_method_docs: options.collapse_method_docs,
+      parameter_tables: options.parameter_tables,
+      locale: options.locale.map(str::to_string),
+      locale_dir: options.locale_dir.map(Path::to_path_buf),
+      changed_only: options.changed_only.map(str::to_string),
+      production_url: options.production_url.map(str::to_string),
+      sidebar_icons: options.sidebar_icons.cloned(),
+      doc_admonitions: options.doc_admonitions.cloned(),
+      cargo_lock: options.cargo_lock.map(Path::to_path_buf),
+      cargo_manifest: options.cargo_manifest.map(Path::to_path_buf),
+      include_readme: options.include_readme.map(Path::to_path_buf),
+      template_dir: options.template_dir.map(Path::to_path_buf),
+      extern_versions: options.extern_versions.cloned(),
+      extern_urls: options.extern_urls.cloned(),
+      sidebar_hook: options.sidebar_hook.cloned(),
+      item_renderer: options.item_renderer.cloned(),
+    }
+  }
 }
 
 /// Convert a rustdoc JSON file to markdown (multi-file output).
@@ -62,7 +885,7 @@ pub struct ConversionOptions<'a> {
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success, or an error if the conversion fails.
+/// Returns the statistics of the conversion on success, or an error if the conversion fails.
 ///
 /// # Example
 ///
@@ -70,40 +893,827 @@ pub struct ConversionOptions<'a> {
 /// use cargo_doc_docusaurus::{convert_json_file, ConversionOptions};
 /// use std::path::Path;
 ///
-/// let options = ConversionOptions {
-///     input_path: Path::new("target/doc/my_crate.json"),
-///     output_dir: Path::new("docs"),
-///     include_private: false,
-///     base_path: "",  // Optional: use "/docs/runtime/rust" for Docusaurus routing
-///     workspace_crates: &[],
-///     sidebarconfig_collapsed: false,
-///     sidebar_output: None,
-///     sidebar_root_link: None,
-/// };
+/// let mut options = ConversionOptions::default();
+/// options.input_path = Path::new("target/doc/my_crate.json");
+/// options.output_dir = Path::new("docs");
+/// // Optional: use "/docs/runtime/rust" for Docusaurus routing
+/// options.base_path = "";
 ///
 /// convert_json_file(&options).expect("Conversion failed");
 /// ```
-pub fn convert_json_file(options: &ConversionOptions) -> Result<()> {
-  let crate_data = parser::load_rustdoc_json(options.input_path)?;
-  let output = converter::convert_to_markdown_multifile(
-    &crate_data,
-    options.include_private,
-    options.base_path,
-    options.workspace_crates,
-    options.sidebarconfig_collapsed,
-    options.sidebar_root_link,
-  )?;
-
-  // Write to crate-specific subdirectory
-  let crate_output_dir = options.output_dir.join(&output.crate_name);
-  writer::write_markdown_multifile_with_sidebar_path(
-    &crate_output_dir,
+pub fn convert_json_file(options: &ConversionOptions) -> Result<stats::ConversionStats> {
+  let start = std::time::Instant::now();
+  let PreparedConversion {
+    crate_data,
+    converter,
+    crate_dir,
+    site_paths,
+    crate_output_dir,
+  } = prepare_conversion(options)?;
+  if let Some(path) = options.emit_model {
+    let model = serde_json::to_string_pretty(&converter.model(&crate_data))?;
+    std::fs::write(path, model + "\n").map_err(Error::write(path))?;
+  }
+  let mut output = converter.convert(&crate_data)?;
+  for warning in &output.warnings {
+    log::warn!("{}", warning);
+  }
+  let doc_images = match &crate_dir {
+    Some(crate_dir) => assets::collect_doc_images(
+      &mut output.files,
+      crate_dir,
+      options.extension.as_str(),
+      options.flavor,
+    ),
+    None => Default::default(),
+  };
+  if let (Some(hook), Some(sidebars)) = (options.sidebar_hook, output.sidebar.as_mut()) {
+    hook.apply(sidebars);
+  }
+
+  // Namespaced crates still share the sidebar file of the un-namespaced layout,
+  // and other sidebar formats use the default file name with their extension
+  let sidebar_format = options.sidebar_format;
+  let default_sidebar_path = match site_paths {
+    Some((_, sidebar_path)) => Some(sidebar_path),
+    None if options.namespace.is_some() || sidebar_format != sidebar::SidebarFormat::Ts => Some(
+      writer::default_sidebar_path(&options.output_dir.join(&output.crate_name)),
+    ),
+    None => None,
+  }
+  .map(|path| match path.extension() {
+    Some(ext) if ext == "ts" => path.with_extension(sidebar_format.extension()),
+    _ => path,
+  });
+  let sidebar_output = options.sidebar_output.or(default_sidebar_path.as_deref());
+
+  if options.incremental {
+    writer::write_markdown_multifile_incremental(&crate_output_dir, &output, sidebar_output)?;
+  } else {
+    writer::write_markdown_multifile_with_sidebar_path(&crate_output_dir, &output, sidebar_output)?;
+  }
+
+  assets::copy_doc_images(&doc_images, &crate_output_dir)?;
+
+  if options.patch_config {
+    let sidebar_path = match sidebar_output {
+      Some(path) => path.to_path_buf(),
+      None => writer::default_sidebar_path(&crate_output_dir),
+    };
+    site_config::patch_site_config(&sidebar_path)?;
+  }
+
+  if options.reproducible {
+    writer::write_attested_manifest(&crate_output_dir, &output, options.input_path)?;
+  }
+
+  if let Some(label) = options.docusaurus_version {
+    let (site_dir, _) = split_docs_dir(options.output_dir, options.docs_plugin_id)?;
+    writer::register_docs_version(&site_dir, options.docs_plugin_id, label)?;
+  }
+  if options.deny_warnings && !output.warnings.is_empty() {
+    return Err(Error::DeniedWarnings {
+      count: output.warning_count(),
+      crate_name: output.crate_name,
+    });
+  }
+  Ok(stats::ConversionStats::new(
+    &coverage::doc_coverage(&crate_data),
     &output,
-    options.sidebar_output,
-  )?;
+    start.elapsed(),
+  ))
+}
+
+/// Convert a rustdoc JSON file to the legacy single-file markdown, without writing anything.
+///
+/// The markdown is rendered with the same options as [`convert_json_file`]
+/// (base path, prefix stripping, extern URLs, ...); options only affecting the
+/// written site are ignored.
+pub fn convert_json_to_markdown(options: &ConversionOptions) -> Result<String> {
+  let prepared = prepare_conversion(options)?;
+  prepared.converter.convert_to_markdown(&prepared.crate_data)
+}
+
+/// A loaded crate with the converter for its options.
+struct PreparedConversion {
+  crate_data: rustdoc_types::Crate,
+  converter: converter::Converter,
+  /// Crate directory doc images are resolved against (with `--copy-assets`)
+  crate_dir: Option<PathBuf>,
+  /// Content directory and sidebar path of versioned or translated docs
+  site_paths: Option<(PathBuf, PathBuf)>,
+  crate_output_dir: PathBuf,
+}
+
+/// Validate the options, load the crate and set up its converter.
+fn prepare_conversion(options: &ConversionOptions) -> Result<PreparedConversion> {
+  if let Some(sitemap) = options.sitemap {
+    sitemap.validate()?;
+  }
+
+  let base_path = normalize_url_path("--base-path", options.base_path)?;
+  let route_base_path = options
+    .route_base_path
+    .map(|route| normalize_url_path("--route-base-path", route))
+    .transpose()?;
+  if let Some(route) = &route_base_path
+    && converter::path_below_route(&base_path, route).is_none()
+  {
+    return Err(Error::InvalidOptions(format!(
+      "--base-path {} is not below --route-base-path {}",
+      display_url_path(&base_path),
+      display_url_path(route)
+    )));
+  }
+  if let Some(plugin_id) = options.docs_plugin_id {
+    validate_path_segment("docs plugin id", plugin_id, "rust")?;
+  }
+  check_base_path_layout(
+    &base_path,
+    route_base_path.as_deref(),
+    options.docs_plugin_id,
+    options.output_dir,
+  );
+
+  if let Some(namespace) = options.namespace {
+    validate_path_segment("namespace", namespace, "v2")?;
+  }
+
+  // `--docusaurus-version` is `--docs-version` plus registering the version with Docusaurus
+  let docs_version = match (options.docs_version, options.docusaurus_version) {
+    (Some(docs_version), Some(label)) if docs_version != label => {
+      return Err(Error::InvalidOptions(format!(
+        "--docs-version {} and --docusaurus-version {} name different versions",
+        docs_version, label
+      )));
+    }
+    (docs_version, label) => docs_version.or(label),
+  };
+  if let Some(docs_version) = docs_version {
+    validate_path_segment("docs version", docs_version, "1.2")?;
+  }
+
+  let locale = resolve_locale(options.locale, options.locale_dir)?;
+  if let Some(locale) = &locale {
+    validate_path_segment("locale", locale, "fr")?;
+  }
+
+  if let Some(sidebar_output) = options.sidebar_output
+    && options.sidebar_format != sidebar::SidebarFormat::Ts
+    && sidebar::SidebarFormat::from_path(sidebar_output) != options.sidebar_format
+  {
+    return Err(Error::InvalidOptions(format!(
+      "--sidebar-format {} does not match the extension of the sidebar output {}",
+      options.sidebar_format.extension(),
+      sidebar_output.display()
+    )));
+  }
+
+  if options.category_files
+    && (options.sidebar_output.is_some() || options.sidebar_format != sidebar::SidebarFormat::Ts)
+  {
+    return Err(Error::InvalidOptions(
+      "--category-files writes no sidebar file, so it cannot be combined with --sidebar-output or --sidebar-format"
+        .to_string(),
+    ));
+  }
+
+  if options.patch_config && options.category_files {
+    return Err(Error::InvalidOptions(
+      "--category-files writes no sidebar file, so it cannot be combined with --patch-config"
+        .to_string(),
+    ));
+  }
+
+  if options.no_sidebar {
+    let conflict = [
+      (options.category_files, "--category-files"),
+      (options.sidebar_output.is_some(), "--sidebar-output"),
+      (
+        options.sidebar_format != sidebar::SidebarFormat::Ts,
+        "--sidebar-format",
+      ),
+      (options.patch_config, "--patch-config"),
+    ]
+    .into_iter()
+    .find_map(|(set, option)| set.then_some(option));
+    if let Some(option) = conflict {
+      return Err(Error::InvalidOptions(format!(
+        "--no-sidebar writes no sidebar, so it cannot be combined with {}",
+        option
+      )));
+    }
+  }
+
+  if let Some(sidebar_icons) = options.sidebar_icons {
+    sidebar::validate_icons(sidebar_icons)?;
+  }
+
+  if let Some((crate_name, _)) = options
+    .extern_urls
+    .into_iter()
+    .flatten()
+    .find(|(_, url)| url.trim().is_empty())
+  {
+    return Err(Error::InvalidOptions(format!(
+      "--extern-url {} needs a URL prefix (e.g. {}=https://docs.internal/{})",
+      crate_name, crate_name, crate_name
+    )));
+  }
+
+  if let Some(doc_admonitions) = options.doc_admonitions {
+    converter::validate_doc_admonitions(doc_admonitions)?;
+  }
+
+  if options.reproducible && options.input_path == Path::new(parser::STDIN_PATH) {
+    return Err(Error::InvalidOptions(
+      "--reproducible reads the input twice, so it cannot read rustdoc JSON from stdin".to_string(),
+    ));
+  }
+
+  let changed_files = match options.changed_only {
+    Some(_) if options.reproducible => {
+      return Err(Error::InvalidOptions(
+        "--changed-only cannot be combined with --reproducible: its output depends on the git checkout"
+          .to_string(),
+      ));
+    }
+    Some(range) => {
+      if options.production_url.is_none() {
+        return Err(Error::InvalidOptions(
+          "--changed-only needs --production-url to link pages left out of the preview".to_string(),
+        ));
+      }
+      Some(preview::changed_files(range)?)
+    }
+    None => None,
+  };
+
+  let since_versions = options.since_map.map(load_path_map).transpose()?;
+  let redirects = options.redirect_map.map(load_path_map).transpose()?;
+  let extern_versions = extern_versions(options.cargo_lock, options.extern_versions)?;
+  let docusaurus_major = options
+    .docusaurus_major
+    .or_else(|| site_config::detect_docusaurus_major(options.output_dir))
+    .unwrap_or_default();
+
+  let crate_data = parser::load_rustdoc_json(options.input_path)?;
+  let crate_name = crate_data
+    .index
+    .get(&crate_data.root)
+    .and_then(|root| root.name.as_deref())
+    .unwrap_or("unknown");
+  let crate_manifest = |flag| {
+    crate_manifest(
+      options.cargo_manifest,
+      options.input_path,
+      &crate_data,
+      flag,
+    )
+  };
+  let crate_features = if options.features_page {
+    Some(load_crate_features(
+      &crate_manifest("--features-page")?,
+      &crate_data,
+    )?)
+  } else {
+    None
+  };
+  // Doc images are resolved against the crate directory
+  let crate_dir = if options.copy_assets {
+    let manifest = crate_manifest("--copy-assets")?;
+    Some(manifest.parent().unwrap_or(Path::new("")).to_path_buf())
+  } else {
+    None
+  };
+  // Versioned and translated docs live outside the docs folder
+  let site_paths = if docs_version.is_some() || locale.is_some() {
+    Some(site_content_paths(
+      options.output_dir,
+      docs_version,
+      locale.as_deref(),
+      options.locale_dir,
+      options.docs_plugin_id,
+      options.docusaurus_version.is_some(),
+    )?)
+  } else {
+    None
+  };
+  let output_dir = match &site_paths {
+    Some((content_dir, _)) => content_dir.clone(),
+    None => options.output_dir.to_path_buf(),
+  };
+
+  // Write to crate-specific subdirectory (below the namespace, if any)
+  let crate_output_dir = match options.namespace {
+    Some(namespace) => output_dir.join(namespace).join(crate_name),
+    None => output_dir.join(crate_name),
+  };
+
+  let readme = options
+    .include_readme
+    .map(|path| match crate_dir {
+      // Copied with the doc images instead
+      Some(_) => std::fs::read_to_string(path).map_err(Error::read(path)),
+      None => load_readme(path, &crate_output_dir, options.flavor),
+    })
+    .transpose()?;
+  let templates = options
+    .template_dir
+    .map(templates::PageTemplates::load)
+    .transpose()?
+    .unwrap_or_default();
+  let inlined_crates = if options.inline_reexports {
+    load_reexported_crates(&crate_data, options.input_path)?
+  } else {
+    Vec::new()
+  };
+  let converter = converter::Converter::new(converter::RenderContext {
+    base_path,
+    workspace_crates: options.workspace_crates.to_vec(),
+    sidebar_root_link: options.sidebar_root_link.map(|s| s.to_string()),
+    max_depth: options.max_depth,
+    sidebar_collapse: options.sidebar_collapse,
+    sitemap: options.sitemap.cloned(),
+    namespace: options.namespace.map(|s| s.to_string()),
+    route_base_path,
+    extension: options.extension,
+    flavor: options.flavor,
+    docusaurus_major,
+    method_order: options.method_order,
+    signature_style: options.signature_style,
+    signature_width: options.signature_width,
+    signature_max_params: options.signature_max_params,
+    layout: options.layout,
+    error_index: options.error_index,
+    async_index: options.async_index,
+    feature_matrix: options.feature_matrix,
+    glossary: options.glossary,
+    all_items: options.all_items,
+    kind_indexes: options.kind_indexes,
+    json_schema_assets: options.json_schema_assets,
+    math_blocks: options.math_blocks,
+    show_phantom_data: options.show_phantom_data,
+    keep_going: options.keep_going,
+    category_files: options.category_files,
+    no_sidebar: options.no_sidebar,
+    docs_version: docs_version.map(|s| s.to_string()),
+    strip_prefix: options.strip_prefix.map(|s| s.to_string()),
+    summary_separator: options.summary_separator.map(|s| s.to_string()),
+    restricted_marker: options.restricted_marker.map(|s| s.to_string()),
+    social_card: options.social_card.map(|s| s.to_string()),
+    field_summaries: options.field_summaries,
+    collapse_method_docs: options.collapse_method_docs,
+    parameter_tables: options.parameter_tables,
+    locale: locale.clone(),
+    sidebar_icons: options.sidebar_icons.cloned(),
+    doc_admonitions: options.doc_admonitions.cloned(),
+    changed_files,
+    production_url: options.production_url.map(|s| s.to_string()),
+    since_versions,
+    redirects,
+    extern_versions,
+    extern_urls: options.extern_urls.cloned(),
+    crate_features,
+    readme,
+    item_renderer: options.item_renderer.cloned(),
+    templates,
+    heading_ids: Default::default(),
+    conversion: Default::default(),
+  })
+  .include_private(options.include_private)
+  .sidebarconfig_collapsed(options.sidebarconfig_collapsed)
+  .inline_reexports_from(inlined_crates);
+  Ok(PreparedConversion {
+    crate_data,
+    converter,
+    crate_dir,
+    site_paths,
+    crate_output_dir,
+  })
+}
+
+/// Read a JSON object mapping item paths to strings (versions, new paths).
+fn load_path_map(path: &Path) -> Result<std::collections::BTreeMap<String, String>> {
+  let content = std::fs::read_to_string(path).map_err(Error::read(path))?;
+  serde_json::from_str(&content).map_err(Error::json(path))
+}
+
+/// Manifest of the converted crate: `cargo_manifest`, or the one found from the
+/// input's location. `flag` names the option needing it in the error.
+fn crate_manifest(
+  cargo_manifest: Option<&Path>,
+  input_path: &Path,
+  crate_data: &rustdoc_types::Crate,
+  flag: &str,
+) -> Result<PathBuf> {
+  if let Some(path) = cargo_manifest {
+    return Ok(path.to_path_buf());
+  }
+  crate_data
+    .index
+    .get(&crate_data.root)
+    .and_then(|root| root.span.as_ref())
+    .and_then(|span| find_crate_manifest(input_path, &span.filename))
+    .ok_or_else(|| {
+      Error::InvalidOptions(format!(
+        "{} found no Cargo.toml for {}; pass it with --cargo-manifest",
+        flag,
+        input_path.display()
+      ))
+    })
+}
+
+/// Cargo features of the converted crate, read from its manifest at `path`.
+/// Optional dependencies not named by a `dep:` value are implicit features
+/// enabling the dependency.
+fn load_crate_features(
+  path: &Path,
+  crate_data: &rustdoc_types::Crate,
+) -> Result<converter::CrateFeatures> {
+  #[derive(serde::Deserialize)]
+  struct Manifest {
+    package: Option<Package>,
+    #[serde(default)]
+    features: converter::CrateFeatures,
+    #[serde(default)]
+    dependencies: std::collections::BTreeMap<String, toml::Value>,
+  }
+  #[derive(serde::Deserialize)]
+  struct Package {
+    name: String,
+  }
+
+  let content = std::fs::read_to_string(path).map_err(Error::read(path))?;
+  let manifest: Manifest = toml::from_str(&content).map_err(Error::toml(path))?;
+
+  let root = crate_data.index.get(&crate_data.root);
+  let crate_name = root.and_then(|root| root.name.as_deref());
+  if let (Some(package), Some(crate_name)) = (&manifest.package, crate_name)
+    && package.name.replace('-', "_") != crate_name
+  {
+    log::warn!(
+      "{} is the manifest of `{}`, not `{}`",
+      path.display(),
+      package.name,
+      crate_name
+    );
+  }
+
+  let mut features = manifest.features;
+  for (name, dependency) in &manifest.dependencies {
+    let optional = dependency.get("optional").and_then(toml::Value::as_bool) == Some(true);
+    let dep_value = format!("dep:{}", name);
+    if optional && !features.values().flatten().any(|value| *value == dep_value) {
+      features
+        .entry(name.clone())
+        .or_insert_with(|| vec![dep_value]);
+    }
+  }
+  Ok(features)
+}
+
+/// Manifest of the crate whose root module is in `root_file`: the closest
+/// `Cargo.toml` above it. Relative spans are relative to the directory `cargo doc`
+/// ran in, taken to be the closest directory with a `Cargo.toml` above the input.
+fn find_crate_manifest(input_path: &Path, root_file: &Path) -> Option<PathBuf> {
+  let manifest_above = |dir: &Path| {
+    dir
+      .ancestors()
+      .map(|dir| dir.join("Cargo.toml"))
+      .find(|manifest| manifest.is_file())
+  };
+
+  let root_file = if root_file.is_absolute() {
+    root_file.to_path_buf()
+  } else {
+    let input_path = std::path::absolute(input_path).ok()?;
+    let workspace_manifest = manifest_above(input_path.parent()?)?;
+    workspace_manifest.parent()?.join(root_file)
+  };
+  manifest_above(root_file.parent()?)
+}
+
+/// Read a README to add to the crate index page written to `crate_output_dir`,
+/// pointing its relative image paths to the images next to the README.
+fn load_readme(
+  path: &Path,
+  crate_output_dir: &Path,
+  flavor: converter::OutputFlavor,
+) -> Result<String> {
+  let content = std::fs::read_to_string(path).map_err(Error::read(path))?;
+  let absolute = |path: &Path| std::path::absolute(path).map_err(Error::read(path));
+  let readme_dir = absolute(path)?.parent().map(Path::to_path_buf);
+  let readme_dir = readme_dir.as_deref().unwrap_or(Path::new(""));
+  let crate_output_dir = absolute(crate_output_dir)?;
+
+  Ok(assets::rewrite_image_paths(&content, flavor, |target| {
+    Some(assets::relative_link(
+      &crate_output_dir,
+      &readme_dir.join(target),
+    ))
+  }))
+}
+
+/// docs.rs versions of dependencies: those locked in `cargo_lock`, overridden by
+/// the explicit `versions`. Crates locked at several versions are left out.
+fn extern_versions(
+  cargo_lock: Option<&Path>,
+  versions: Option<&converter::ExternVersions>,
+) -> Result<Option<converter::ExternVersions>> {
+  #[derive(serde::Deserialize)]
+  struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+  }
+  #[derive(serde::Deserialize)]
+  struct LockedPackage {
+    name: String,
+    version: String,
+  }
+
+  if cargo_lock.is_none() && versions.is_none() {
+    return Ok(None);
+  }
+  let mut extern_versions = converter::ExternVersions::new();
+  if let Some(path) = cargo_lock {
+    let content = std::fs::read_to_string(path).map_err(Error::read(path))?;
+    let lockfile: Lockfile = toml::from_str(&content).map_err(Error::toml(path))?;
+    let mut duplicates = std::collections::BTreeSet::new();
+    for package in lockfile.package {
+      let name = package.name.replace('-', "_");
+      match extern_versions.get(&name) {
+        Some(version) if *version != package.version => {
+          duplicates.insert(name);
+        }
+        _ => {
+          extern_versions.insert(name, package.version);
+        }
+      }
+    }
+    for name in duplicates {
+      log::debug!(target: "links", "`{}` is locked at several versions, linking to latest", name);
+      extern_versions.remove(&name);
+    }
+  }
+  for (name, version) in versions.into_iter().flatten() {
+    validate_path_segment("--extern-version", version, "1.0.215")?;
+    extern_versions.insert(name.replace('-', "_"), version.clone());
+  }
+  Ok(Some(extern_versions))
+}
+
+/// Rustdoc JSON of the crates that `crate_data` re-exports items from, for those
+/// found next to the input (`cargo doc` writes them all to `target/doc`).
+fn load_reexported_crates(
+  crate_data: &rustdoc_types::Crate,
+  input_path: &Path,
+) -> Result<Vec<rustdoc_types::Crate>> {
+  let crate_names: std::collections::BTreeSet<&str> = crate_data
+    .index
+    .values()
+    .filter_map(|item| match &item.inner {
+      rustdoc_types::ItemEnum::Use(import) if !import.is_glob => import.id.as_ref(),
+      _ => None,
+    })
+    .filter(|id| !crate_data.index.contains_key(id))
+    .filter_map(|id| crate_data.paths.get(id))
+    .filter_map(|summary| crate_data.external_crates.get(&summary.crate_id))
+    .map(|external| external.name.as_str())
+    .collect();
+
+  if input_path == Path::new(parser::STDIN_PATH) {
+    return Ok(Vec::new());
+  }
+  let input_dir = input_path.parent().unwrap_or(Path::new(""));
+  crate_names
+    .into_iter()
+    .filter_map(|name| {
+      ["json", "json.gz", "json.zst"]
+        .iter()
+        .map(|extension| input_dir.join(format!("{}.{}", name, extension)))
+        .find(|path| path.exists())
+    })
+    .map(|path| parser::load_rustdoc_json(&path))
+    .collect()
+}
+
+/// Namespaces and docs versions must be a single path segment usable in URLs and doc IDs.
+fn validate_path_segment(kind: &str, value: &str, example: &str) -> Result<()> {
+  let valid = !value.is_empty()
+    && !value.starts_with('.')
+    && value
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+  if !valid {
+    return Err(Error::InvalidOptions(format!(
+      "Invalid {} '{}': use letters, digits, '-', '_' or '.' (e.g. \"{}\")",
+      kind, value, example
+    )));
+  }
   Ok(())
 }
 
+/// Normalize the URL path of `option` to a leading and no trailing slash
+/// (`docs/api/` -> `/docs/api`, `/` -> empty for the site root).
+fn normalize_url_path(option: &str, path: &str) -> Result<String> {
+  let path = path.trim();
+  if path.contains("://") {
+    return Err(Error::InvalidOptions(format!(
+      "{} '{}' must be a URL path like /docs/api, not a full URL (the site URL goes to --production-url)",
+      option, path
+    )));
+  }
+  if let Some(c) = path
+    .chars()
+    .find(|c| c.is_whitespace() || matches!(c, '\\' | '?' | '#'))
+  {
+    return Err(Error::InvalidOptions(format!(
+      "{} '{}' contains {:?}, use a URL path like /docs/api",
+      option, path, c
+    )));
+  }
+
+  let segments: Vec<&str> = path
+    .split('/')
+    .filter(|segment| !segment.is_empty() && *segment != ".")
+    .collect();
+  if segments.contains(&"..") {
+    return Err(Error::InvalidOptions(format!(
+      "{} '{}' cannot contain '..', use the absolute URL path (e.g. /docs/api)",
+      option, path
+    )));
+  }
+  Ok(
+    segments
+      .iter()
+      .map(|segment| format!("/{}", segment))
+      .collect(),
+  )
+}
+
+/// URL path as shown in messages (`/` for the site root).
+fn display_url_path(path: &str) -> &str {
+  if path.is_empty() { "/" } else { path }
+}
+
+/// Warn when a base path below the docs route (`route_base_path`, `/docs` by
+/// default) does not match the directory the pages are written to, since doc
+/// IDs and `displayed_sidebar` keys are derived from the base path.
+fn check_base_path_layout(
+  base_path: &str,
+  route_base_path: Option<&str>,
+  docs_plugin_id: Option<&str>,
+  output_dir: &Path,
+) {
+  let Ok((_, docs_subdir)) = split_docs_dir(output_dir, docs_plugin_id) else {
+    return;
+  };
+  let route = route_base_path.unwrap_or("/docs");
+  let Some(route_subdir) = converter::path_below_route(base_path, route) else {
+    return;
+  };
+
+  let docs_subdir: Vec<_> = docs_subdir
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+    .collect();
+  let docs_subdir = docs_subdir.join("/");
+  if route_subdir != docs_subdir {
+    let expected = if docs_subdir.is_empty() {
+      display_url_path(route).to_string()
+    } else {
+      format!("{}/{}", route, docs_subdir)
+    };
+    log::warn!(
+      "--base-path {} does not match the output directory {} (expected --base-path {}): links, doc IDs and displayed_sidebar keys will not match the generated pages",
+      display_url_path(base_path),
+      output_dir.display(),
+      expected
+    );
+  }
+}
+
+/// Split an output directory inside a Docusaurus site into the site directory
+/// and the path below its `docs` folder (e.g. `website/docs/api` -> `website`, `api`).
+///
+/// The content folder of a docs plugin instance may also be named after its id
+/// (e.g. `website/rust/api`).
+fn split_docs_dir(output_dir: &Path, docs_plugin_id: Option<&str>) -> Result<(PathBuf, PathBuf)> {
+  let components: Vec<_> = output_dir.components().collect();
+  let Some(docs_index) = components
+    .iter()
+    .rposition(|c| c.as_os_str() == "docs" || docs_plugin_id.is_some_and(|id| c.as_os_str() == id))
+  else {
+    return Err(Error::InvalidOptions(format!(
+      "--docs-version, --docusaurus-version and --locale need an output directory inside the Docusaurus docs folder (e.g. website/docs/api), got {}",
+      output_dir.display()
+    )));
+  };
+  Ok((
+    components[..docs_index].iter().collect(),
+    components[docs_index + 1..].iter().collect(),
+  ))
+}
+
+/// Locale of translated docs: `--locale`, or the directory below `i18n` in `--locale-dir`.
+fn resolve_locale(locale: Option<&str>, locale_dir: Option<&Path>) -> Result<Option<String>> {
+  let Some(locale_dir) = locale_dir else {
+    return Ok(locale.map(str::to_string));
+  };
+  let components: Vec<_> = locale_dir.components().collect();
+  let dir_locale = components
+    .iter()
+    .position(|c| c.as_os_str() == "i18n")
+    .and_then(|index| components.get(index + 1))
+    .map(|c| c.as_os_str().to_string_lossy().into_owned());
+
+  match (locale, dir_locale) {
+    (Some(locale), Some(dir_locale)) if locale != dir_locale => {
+      Err(Error::InvalidOptions(format!(
+        "--locale {} does not match the locale '{}' of --locale-dir {}",
+        locale,
+        dir_locale,
+        locale_dir.display()
+      )))
+    }
+    (Some(locale), _) => Ok(Some(locale.to_string())),
+    (None, Some(dir_locale)) => Ok(Some(dir_locale)),
+    (None, None) => Err(Error::InvalidOptions(format!(
+      "--locale-dir {} is not below an i18n directory, pass its locale with --locale",
+      locale_dir.display()
+    ))),
+  }
+}
+
+/// Content directory and default sidebar file for versioned and/or translated docs.
+///
+/// Versioned docs go to `versioned_docs/version-<VERSION>` with a sidebar in
+/// `versioned_sidebars`; translations go to
+/// `i18n/<LOCALE>/docusaurus-plugin-content-docs/<current|version-*>` (or
+/// `<LOCALE_DIR>/<current|version-*>`) with their sidebar file in the locale
+/// directory above. Registered versions use the JSON sidebar file Docusaurus
+/// loads for the version instead. A docs plugin instance with an id has its
+/// own directories: `<ID>_versioned_docs`, `<ID>_versioned_sidebars` and
+/// `docusaurus-plugin-content-docs-<ID>`.
+fn site_content_paths(
+  output_dir: &Path,
+  docs_version: Option<&str>,
+  locale: Option<&str>,
+  locale_dir: Option<&Path>,
+  docs_plugin_id: Option<&str>,
+  register_version: bool,
+) -> Result<(PathBuf, PathBuf)> {
+  let (site_dir, docs_subdir) = split_docs_dir(output_dir, docs_plugin_id)?;
+  let plugin_id = docs_plugin_id.filter(|id| *id != writer::DEFAULT_DOCS_PLUGIN_ID);
+  let versioned = |dir: &str| match plugin_id {
+    Some(id) => format!("{}_{}", id, dir),
+    None => dir.to_string(),
+  };
+  let version_dir = match docs_version {
+    Some(docs_version) => format!("version-{}", docs_version),
+    None => "current".to_string(),
+  };
+
+  let (content_dir, sidebar_dir) = match (locale, locale_dir) {
+    (Some(_), Some(locale_dir)) => (
+      locale_dir.join(&version_dir),
+      locale_dir
+        .parent()
+        .map_or_else(|| locale_dir.to_path_buf(), Path::to_path_buf),
+    ),
+    (Some(locale), None) => {
+      let locale_dir = site_dir.join("i18n").join(locale);
+      (
+        locale_dir
+          .join(match plugin_id {
+            Some(id) => format!("docusaurus-plugin-content-docs-{}", id),
+            None => "docusaurus-plugin-content-docs".to_string(),
+          })
+          .join(&version_dir),
+        locale_dir,
+      )
+    }
+    (None, _) => (
+      site_dir
+        .join(versioned("versioned_docs"))
+        .join(&version_dir),
+      site_dir,
+    ),
+  };
+
+  let sidebar_path = match docs_version {
+    Some(docs_version) if register_version && locale.is_none() => sidebar_dir
+      .join(versioned("versioned_sidebars"))
+      .join(format!("version-{}-sidebars.json", docs_version)),
+    Some(docs_version) => sidebar_dir
+      .join(versioned("versioned_sidebars"))
+      .join(format!("version-{}-sidebars-rust.ts", docs_version)),
+    None => sidebar_dir.join("sidebars-rust.ts"),
+  };
+  Ok((content_dir.join(docs_subdir), sidebar_path))
+}
+
 /// Convert rustdoc JSON data (already loaded) to markdown.
 ///
 /// Use this if you want more control over the loading and writing process.
@@ -117,6 +1727,6 @@ pub fn convert_json_file(options: &ConversionOptions) -> Result<()> {
 ///
 /// Returns the markdown as a String, or an error.
 pub fn convert_json_string(json_data: &str, include_private: bool) -> Result<String> {
-  let crate_data: rustdoc_types::Crate = serde_json::from_str(json_data)?;
+  let crate_data = parser::parse_rustdoc_json(json_data)?;
   converter::convert_to_markdown(&crate_data, include_private)
 }
@@ -0,0 +1,642 @@
+//! Link targets of the items referenced in generated pages.
+//!
+//! [`LinkResolver`] turns an item id and the path it is referenced by into a
+//! URL: the page of a local item in the generated site, the page of an item of
+//! a workspace crate in the same site, the standard library docs on
+//! doc.rust-lang.org, or the docs of a dependency on docs.rs (or below its
+//! `extern_urls` prefix).
+
+use crate::converter::{self, ExternUrls, ExternVersions};
+use rustdoc_types::{Crate, Id, ItemKind};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of path rewrites while resolving a link
+const MAX_DEPTH: usize = 10;
+
+/// Implementation modules left out of external URLs, since their items are
+/// documented where they are re-exported
+const INTERNAL_MODULES: [&str; 5] = ["bounded", "unbounded", "inner", "private", "imp"];
+
+const STD_RESULT_URL: &str = "https://doc.rust-lang.org/std/result/enum.Result.html";
+const STD_OPTION_URL: &str = "https://doc.rust-lang.org/std/option/enum.Option.html";
+
+/// Paths of the items listed in a crate's modules (see [`converter::module_tree_paths`])
+type CrateModulePaths = Arc<HashMap<Id, Vec<String>>>;
+
+/// Module tree paths of the items of crates, keyed by crate name, built once per
+/// crate and shared by the resolvers of a conversion (and its rendering threads).
+#[derive(Debug, Clone, Default)]
+pub struct ModulePaths(Arc<Mutex<HashMap<String, CrateModulePaths>>>);
+
+impl ModulePaths {
+  /// Path of the module listing `id` in `crate_data`, plus the item name.
+  fn get(&self, crate_data: &Crate, id: &Id) -> Option<Vec<String>> {
+    let crate_name = crate_data.index.get(&crate_data.root)?.name.as_deref()?;
+    let paths = {
+      let mut crates = self.0.lock().ok()?;
+      crates
+        .entry(crate_name.to_string())
+        .or_insert_with(|| Arc::new(converter::module_tree_paths(crate_data)))
+        .clone()
+    };
+    paths.get(id).cloned()
+  }
+}
+
+/// Resolves the URLs of the items linked from generated pages.
+#[derive(Debug, Clone, Default)]
+pub struct LinkResolver<'a> {
+  base_path: String,
+  workspace_crates: &'a [String],
+  max_depth: Option<usize>,
+  extern_urls: Option<&'a ExternUrls>,
+  extern_versions: Option<&'a ExternVersions>,
+  module_paths: Option<&'a ModulePaths>,
+}
+
+impl<'a> LinkResolver<'a> {
+  /// Resolver linking the pages of local items below `base_path` (e.g. `/docs/api`).
+  pub fn new(base_path: impl Into<String>) -> Self {
+    Self {
+      base_path: base_path.into(),
+      ..Default::default()
+    }
+  }
+
+  /// Link the items of these crates to their pages in the site instead of docs.rs.
+  pub fn workspace_crates(mut self, workspace_crates: &'a [String]) -> Self {
+    self.workspace_crates = workspace_crates;
+    self
+  }
+
+  /// Link local items of modules deeper than `max_depth` to their deepest rendered module.
+  pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
+
+  /// Documentation URL prefixes replacing docs.rs for some dependencies, keyed by crate name.
+  pub fn extern_urls(mut self, extern_urls: Option<&'a ExternUrls>) -> Self {
+    self.extern_urls = extern_urls;
+    self
+  }
+
+  /// docs.rs versions linked for dependencies, keyed by crate name (`latest` for others).
+  pub fn extern_versions(mut self, extern_versions: Option<&'a ExternVersions>) -> Self {
+    self.extern_versions = extern_versions;
+    self
+  }
+
+  /// Cache of the module tree paths of inlined items; without one, the module
+  /// tree is walked for every link to an inlined item.
+  pub fn module_paths(mut self, module_paths: &'a ModulePaths) -> Self {
+    self.module_paths = Some(module_paths);
+    self
+  }
+
+  /// URL of the item `id`, referenced by `path` (e.g. `Vec` or `std::vec::Vec`),
+  /// `None` when the target is unknown.
+  pub fn resolve(&self, path: &str, id: &Id, crate_data: &Crate) -> Option<String> {
+    self.resolve_depth(path, id, crate_data, 0)
+  }
+
+  fn resolve_depth(
+    &self,
+    full_path: &str,
+    id: &Id,
+    crate_data: &Crate,
+    depth: usize,
+  ) -> Option<String> {
+    if depth >= MAX_DEPTH {
+      return None;
+    }
+
+    // Items inlined from other crates are documented where they are inlined,
+    // not at their original path
+    let local_path = match crate_data.paths.get(id) {
+      Some(summary) if summary.crate_id == 0 => Some(summary.path.clone()),
+      _ if crate_data.index.contains_key(id) => match self.module_paths {
+        Some(module_paths) => module_paths.get(crate_data, id),
+        None => converter::module_tree_paths(crate_data).remove(id),
+      },
+      _ => None,
+    };
+
+    // The path known to rustdoc is more accurate than the referenced one, but
+    // recursive calls already resolved it
+    let full_path = if depth > 0 {
+      full_path.to_string()
+    } else if let Some(local_path) = &local_path {
+      local_path.join("::")
+    } else if let Some(summary) = crate_data.paths.get(id) {
+      summary.path.join("::")
+    } else if full_path.starts_with("$crate") {
+      full_path.replace("$crate", "unknown")
+    } else {
+      full_path.to_string()
+    };
+
+    let is_local = local_path.is_some()
+      || (!crate_data.paths.contains_key(id) && crate_data.index.contains_key(id));
+    if is_local && let Some(item) = crate_data.index.get(id) {
+      // Local links start with the crate name, which the root module must have
+      crate_data.index.get(&crate_data.root)?.name.as_ref()?;
+      return Some(self.local_link(
+        &full_path,
+        local_path.as_deref(),
+        converter::get_item_prefix(item),
+      ));
+    }
+
+    let path_parts: Vec<&str> = full_path.split("::").collect();
+    let kind = crate_data.paths.get(id).map(|summary| &summary.kind);
+    match path_parts.as_slice() {
+      [type_name] => {
+        // rustdoc may know the full path of the item
+        if let Some(summary) = crate_data.paths.get(id) {
+          let known_path = summary.path.join("::");
+          if known_path != full_path {
+            return self.resolve_depth(&known_path, id, crate_data, depth + 1);
+          }
+        }
+        std_fallback(type_name).map(str::to_string)
+      }
+      ["std" | "core" | "alloc", ..] => Some(std_link(&full_path, &path_parts, kind)),
+      _ => Some(self.extern_link(&path_parts, id, crate_data)),
+    }
+  }
+
+  /// Link to the page of a local item, at `local_path` in the module tree
+  /// (never from spans, which point into other crates for inlined items).
+  fn local_link(&self, full_path: &str, local_path: Option<&[String]>, prefix: &str) -> String {
+    let segments: Vec<&str> = full_path.split("::").collect();
+    let crate_name = segments[0];
+    let type_name = segments[segments.len() - 1];
+
+    // Skip the crate name and item name: ["my_crate", "a", "b", "Item"] -> ["a", "b"]
+    let modules = match local_path {
+      Some(path) if path.len() > 2 => &path[1..path.len() - 1],
+      _ => &[],
+    };
+
+    // Items below the depth limit have no page - link to the deepest rendered ancestor module
+    if let Some(max_depth) = self.max_depth
+      && modules.len() > max_depth
+    {
+      return match modules[..max_depth].join("/") {
+        visible if visible.is_empty() => format!("{}/{}/", self.base_path, crate_name),
+        visible => format!("{}/{}/{}/", self.base_path, crate_name, visible),
+      };
+    }
+
+    if modules.is_empty() {
+      format!("{}/{}/{}{}", self.base_path, crate_name, prefix, type_name)
+    } else {
+      format!(
+        "{}/{}/{}/{}{}",
+        self.base_path,
+        crate_name,
+        modules.join("/"),
+        prefix,
+        type_name
+      )
+    }
+  }
+
+  /// Link to an item of a dependency: its page in the site for workspace
+  /// crates, its docs.rs page otherwise.
+  fn extern_link(&self, path_parts: &[&str], id: &Id, crate_data: &Crate) -> String {
+    // The crate id gives the real crate name, the first segment of the path
+    // may be a module
+    let crate_name = crate_data
+      .paths
+      .get(id)
+      .filter(|summary| summary.crate_id != 0)
+      .and_then(|summary| crate_data.external_crates.get(&summary.crate_id))
+      .map_or(path_parts[0], |external| external.name.as_str());
+    let prefix = crate_data
+      .paths
+      .get(id)
+      .and_then(|summary| item_kind_page_prefix(&summary.kind))
+      .unwrap_or("struct");
+    let type_name = path_parts[path_parts.len() - 1];
+    let page = match module_path(path_parts) {
+      module_path if module_path.is_empty() => format!("{}.{}", prefix, type_name),
+      module_path => format!("{}/{}.{}", module_path, prefix, type_name),
+    };
+
+    if self.is_workspace_crate(crate_name) {
+      format!("{}/{}/{}", self.base_path, crate_name, page)
+    } else {
+      format!(
+        "{}/{}/{}.html",
+        self.extern_docs_root(crate_name),
+        crate_name,
+        page
+      )
+    }
+  }
+
+  /// Whether a crate is part of the workspace (crate names in Cargo.toml use
+  /// hyphens where rustdoc uses underscores).
+  fn is_workspace_crate(&self, crate_name: &str) -> bool {
+    let crate_name = crate_name.replace('-', "_");
+    self
+      .workspace_crates
+      .iter()
+      .any(|workspace_crate| workspace_crate.replace('-', "_") == crate_name)
+  }
+
+  /// Root URL of a dependency's docs, below which its crate directory is found:
+  /// its `extern_urls` prefix, or its docs.rs version.
+  pub(crate) fn extern_docs_root(&self, crate_name: &str) -> String {
+    let normalized = crate_name.replace('-', "_");
+    match self
+      .extern_urls
+      .iter()
+      .copied()
+      .flatten()
+      .find(|(name, _)| name.replace('-', "_") == normalized)
+    {
+      Some((_, prefix)) => prefix.trim_end_matches('/').to_string(),
+      None => format!(
+        "https://docs.rs/{}/{}",
+        crate_name,
+        self.docs_rs_version(crate_name)
+      ),
+    }
+  }
+
+  /// Version of a dependency's docs on docs.rs, `latest` when it is not pinned.
+  fn docs_rs_version(&self, crate_name: &str) -> &str {
+    let crate_name = crate_name.replace('-', "_");
+    self
+      .extern_versions
+      .iter()
+      .copied()
+      .flatten()
+      .find(|(name, _)| name.replace('-', "_") == crate_name)
+      .map_or("latest", |(_, version)| version.as_str())
+  }
+}
+
+/// Link to the standard library docs, e.g. `std::sync::Arc` ->
+/// `https://doc.rust-lang.org/std/sync/struct.Arc.html`.
+fn std_link(full_path: &str, path_parts: &[&str], kind: Option<&ItemKind>) -> String {
+  match full_path {
+    // `fmt::Result` is an alias of `Result<(), fmt::Error>`, better documented by `Result`
+    "core::fmt::Result" | "std::fmt::Result" => return STD_RESULT_URL.to_string(),
+    // std re-exports core and is more familiar to users
+    "core::result::Result" => return STD_RESULT_URL.to_string(),
+    "core::option::Option" => return STD_OPTION_URL.to_string(),
+    _ => {}
+  }
+
+  let crate_name = path_parts[0];
+  let type_name = path_parts[path_parts.len() - 1];
+  if kind == Some(&ItemKind::Module) {
+    return format!(
+      "https://doc.rust-lang.org/{}/{}/index.html",
+      crate_name,
+      path_parts[1..].join("/")
+    );
+  }
+  // The item kind (e.g. `Cow` is an enum) names the page; items without a
+  // known kind are found with rustdoc's search
+  let Some(item_type) = kind.and_then(item_kind_page_prefix) else {
+    return format!("https://doc.rust-lang.org/std/?search={}", type_name);
+  };
+  let page_dir = match module_path(path_parts) {
+    module_path if module_path.is_empty() => crate_name.to_string(),
+    module_path => format!("{}/{}", crate_name, module_path),
+  };
+  format!(
+    "https://doc.rust-lang.org/{}/{}.{}.html",
+    page_dir, item_type, type_name
+  )
+}
+
+/// Docs of common std types referenced by their name only.
+fn std_fallback(type_name: &str) -> Option<&'static str> {
+  Some(match type_name {
+    "String" => "https://doc.rust-lang.org/std/string/struct.String.html",
+    "Vec" => "https://doc.rust-lang.org/std/vec/struct.Vec.html",
+    "Option" => STD_OPTION_URL,
+    "Result" => STD_RESULT_URL,
+    "Box" => "https://doc.rust-lang.org/std/boxed/struct.Box.html",
+    "Rc" => "https://doc.rust-lang.org/std/rc/struct.Rc.html",
+    "Arc" => "https://doc.rust-lang.org/std/sync/struct.Arc.html",
+    "Cow" => "https://doc.rust-lang.org/std/borrow/enum.Cow.html",
+    "PhantomData" => "https://doc.rust-lang.org/std/marker/struct.PhantomData.html",
+    "HashMap" => "https://doc.rust-lang.org/std/collections/struct.HashMap.html",
+    "HashSet" => "https://doc.rust-lang.org/std/collections/struct.HashSet.html",
+    "BTreeMap" => "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html",
+    "BTreeSet" => "https://doc.rust-lang.org/std/collections/struct.BTreeSet.html",
+    "Mutex" => "https://doc.rust-lang.org/std/sync/struct.Mutex.html",
+    "RwLock" => "https://doc.rust-lang.org/std/sync/struct.RwLock.html",
+    "Cell" => "https://doc.rust-lang.org/std/cell/struct.Cell.html",
+    "RefCell" => "https://doc.rust-lang.org/std/cell/struct.RefCell.html",
+    "Path" => "https://doc.rust-lang.org/std/path/struct.Path.html",
+    "PathBuf" => "https://doc.rust-lang.org/std/path/struct.PathBuf.html",
+    _ => return None,
+  })
+}
+
+/// Module path of an external item in URLs, e.g. `a/b` for `my_crate::a::b::Item`.
+fn module_path(path_parts: &[&str]) -> String {
+  let modules: Vec<&str> = path_parts[1..path_parts.len() - 1]
+    .iter()
+    .copied()
+    .filter(|part| !INTERNAL_MODULES.contains(part))
+    .collect();
+  modules.join("/")
+}
+
+/// Prefix of the rustdoc page of an item of this kind (e.g. `trait` for
+/// `trait.Iterator.html`), `None` for kinds without a page of their own.
+fn item_kind_page_prefix(kind: &ItemKind) -> Option<&'static str> {
+  Some(match kind {
+    ItemKind::Struct => "struct",
+    ItemKind::Enum => "enum",
+    ItemKind::Union => "union",
+    ItemKind::Trait => "trait",
+    ItemKind::TraitAlias => "traitalias",
+    ItemKind::Function => "fn",
+    ItemKind::TypeAlias => "type",
+    ItemKind::Constant => "constant",
+    ItemKind::Static => "static",
+    ItemKind::ExternType => "foreigntype",
+    ItemKind::Macro => "macro",
+    ItemKind::ProcAttribute => "attr",
+    ItemKind::ProcDerive => "derive",
+    ItemKind::Primitive => "primitive",
+    ItemKind::Keyword => "keyword",
+    _ => return None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rustdoc_types::{ExternalCrate, ItemSummary};
+
+  fn test_crate() -> Crate {
+    crate::parser::load_rustdoc_json("tests/fixtures/test_crate.json".as_ref()).unwrap()
+  }
+
+  /// Add an item of another crate to `paths`, returning its id.
+  fn add_extern_item(
+    crate_data: &mut Crate,
+    crate_name: &str,
+    path: &[&str],
+    kind: ItemKind,
+  ) -> Id {
+    let crate_id = match crate_data
+      .external_crates
+      .iter()
+      .find(|(_, external)| external.name == crate_name)
+    {
+      Some((crate_id, _)) => *crate_id,
+      None => {
+        let crate_id = u32::MAX - crate_data.external_crates.len() as u32;
+        crate_data.external_crates.insert(
+          crate_id,
+          ExternalCrate {
+            name: crate_name.to_string(),
+            html_root_url: None,
+          },
+        );
+        crate_id
+      }
+    };
+    let id = Id(u32::MAX - crate_data.paths.len() as u32);
+    crate_data.paths.insert(
+      id,
+      ItemSummary {
+        crate_id,
+        path: path.iter().map(|segment| segment.to_string()).collect(),
+        kind,
+      },
+    );
+    id
+  }
+
+  fn local_id(crate_data: &Crate, path: &[&str]) -> Id {
+    *crate_data
+      .paths
+      .iter()
+      .find(|(_, summary)| summary.crate_id == 0 && summary.path == path)
+      .unwrap()
+      .0
+  }
+
+  #[test]
+  fn test_local_links() {
+    let crate_data = test_crate();
+    let resolver = LinkResolver::new("/docs/api");
+    let pair = local_id(&crate_data, &["test_crate", "types", "Pair"]);
+    let plain = local_id(&crate_data, &["test_crate", "PlainStruct"]);
+    let deeper = local_id(
+      &crate_data,
+      &[
+        "test_crate",
+        "nested",
+        "inner",
+        "deep",
+        "deeper",
+        "DeeperStruct",
+      ],
+    );
+
+    // The path known to rustdoc wins over the referenced one
+    assert_eq!(
+      resolver.resolve("Pair", &pair, &crate_data).unwrap(),
+      "/docs/api/test_crate/types/struct.Pair"
+    );
+    assert_eq!(
+      resolver
+        .resolve("PlainStruct", &plain, &crate_data)
+        .unwrap(),
+      "/docs/api/test_crate/struct.PlainStruct"
+    );
+    assert_eq!(
+      LinkResolver::default()
+        .resolve("PlainStruct", &plain, &crate_data)
+        .unwrap(),
+      "/test_crate/struct.PlainStruct"
+    );
+
+    // Items below the depth limit link to their deepest rendered module
+    let resolver = resolver.max_depth(Some(2));
+    assert_eq!(
+      resolver
+        .resolve("DeeperStruct", &deeper, &crate_data)
+        .unwrap(),
+      "/docs/api/test_crate/nested/inner/"
+    );
+    assert_eq!(
+      resolver.resolve("Pair", &pair, &crate_data).unwrap(),
+      "/docs/api/test_crate/types/struct.Pair"
+    );
+    assert_eq!(
+      LinkResolver::new("/docs/api")
+        .max_depth(Some(0))
+        .resolve("Pair", &pair, &crate_data)
+        .unwrap(),
+      "/docs/api/test_crate/"
+    );
+  }
+
+  #[test]
+  fn test_inlined_item_links() {
+    let mut crate_data = test_crate();
+    let pair = local_id(&crate_data, &["test_crate", "types", "Pair"]);
+    // Items inlined from other crates are only found in the module tree
+    crate_data.paths.remove(&pair);
+
+    let module_paths = ModulePaths::default();
+    let cached = LinkResolver::new("/docs").module_paths(&module_paths);
+    for resolver in [LinkResolver::new("/docs"), cached.clone(), cached] {
+      assert_eq!(
+        resolver.resolve("Pair", &pair, &crate_data).unwrap(),
+        "/docs/test_crate/types/struct.Pair"
+      );
+    }
+    assert_eq!(module_paths.0.lock().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_std_links() {
+    let mut crate_data = test_crate();
+    let resolver = LinkResolver::new("/docs");
+    let mut link = |path: &[&str], kind: Option<ItemKind>| {
+      let id = match kind {
+        Some(kind) => add_extern_item(&mut crate_data, path[0], path, kind),
+        None => Id(u32::MAX - 1_000_000),
+      };
+      resolver.resolve(&path.join("::"), &id, &crate_data)
+    };
+
+    assert_eq!(
+      link(
+        &["core", "iter", "traits", "iterator", "Iterator"],
+        Some(ItemKind::Trait)
+      )
+      .unwrap(),
+      "https://doc.rust-lang.org/core/iter/traits/iterator/trait.Iterator.html"
+    );
+    assert_eq!(
+      link(&["std", "io", "Error"], Some(ItemKind::Struct)).unwrap(),
+      "https://doc.rust-lang.org/std/io/struct.Error.html"
+    );
+    assert_eq!(
+      link(&["std", "vec"], Some(ItemKind::Macro)).unwrap(),
+      "https://doc.rust-lang.org/std/macro.vec.html"
+    );
+    assert_eq!(
+      link(&["std", "collections"], Some(ItemKind::Module)).unwrap(),
+      "https://doc.rust-lang.org/std/collections/index.html"
+    );
+    // Unknown kinds are searched for instead of guessed
+    assert_eq!(
+      link(&["std", "fmt", "Display"], None).unwrap(),
+      "https://doc.rust-lang.org/std/?search=Display"
+    );
+    // core types re-exported by std link to the std docs
+    assert_eq!(
+      link(&["core", "option", "Option"], Some(ItemKind::Enum)).unwrap(),
+      STD_OPTION_URL
+    );
+    assert_eq!(
+      link(&["core", "fmt", "Result"], Some(ItemKind::TypeAlias)).unwrap(),
+      STD_RESULT_URL
+    );
+  }
+
+  #[test]
+  fn test_single_segment_links() {
+    let mut crate_data = test_crate();
+    let resolver = LinkResolver::new("/docs");
+
+    // Single-segment paths are resolved with the full path known to rustdoc
+    let cow = add_extern_item(
+      &mut crate_data,
+      "alloc",
+      &["alloc", "borrow", "Cow"],
+      ItemKind::Enum,
+    );
+    assert_eq!(
+      resolver.resolve("Cow", &cow, &crate_data).unwrap(),
+      "https://doc.rust-lang.org/alloc/borrow/enum.Cow.html"
+    );
+
+    // Common std types are known by name, other unknown items are not linked
+    let unknown = Id(u32::MAX - 1_000_000);
+    assert_eq!(
+      resolver.resolve("PathBuf", &unknown, &crate_data).unwrap(),
+      "https://doc.rust-lang.org/std/path/struct.PathBuf.html"
+    );
+    assert_eq!(resolver.resolve("Missing", &unknown, &crate_data), None);
+  }
+
+  #[test]
+  fn test_workspace_links() {
+    let mut crate_data = test_crate();
+    let id = add_extern_item(
+      &mut crate_data,
+      "my_core",
+      &["my_core", "config", "inner", "Config"],
+      ItemKind::Struct,
+    );
+    let workspace_crates = ["my-core".to_string()];
+    let resolver = LinkResolver::new("/docs").workspace_crates(&workspace_crates);
+    assert_eq!(
+      resolver
+        .resolve("my_core::config::Config", &id, &crate_data)
+        .unwrap(),
+      "/docs/my_core/config/struct.Config"
+    );
+  }
+
+  #[test]
+  fn test_extern_links() {
+    let mut crate_data = test_crate();
+    let id = add_extern_item(
+      &mut crate_data,
+      "serde_json",
+      &["serde_json", "Value"],
+      ItemKind::Enum,
+    );
+
+    let resolver = LinkResolver::new("/docs");
+    assert_eq!(
+      resolver
+        .resolve("serde_json::Value", &id, &crate_data)
+        .unwrap(),
+      "https://docs.rs/serde_json/latest/serde_json/enum.Value.html"
+    );
+
+    let versions = ExternVersions::from([("serde-json".to_string(), "1.0.145".to_string())]);
+    let resolver = resolver.extern_versions(Some(&versions));
+    assert_eq!(
+      resolver
+        .resolve("serde_json::Value", &id, &crate_data)
+        .unwrap(),
+      "https://docs.rs/serde_json/1.0.145/serde_json/enum.Value.html"
+    );
+
+    // Mirrors replace docs.rs
+    let urls = ExternUrls::from([(
+      "serde_json".to_string(),
+      "https://docs.internal/serde_json/1.0/".to_string(),
+    )]);
+    let resolver = resolver.extern_urls(Some(&urls));
+    assert_eq!(
+      resolver
+        .resolve("serde_json::Value", &id, &crate_data)
+        .unwrap(),
+      "https://docs.internal/serde_json/1.0/serde_json/enum.Value.html"
+    );
+  }
+}
@@ -1,9 +1,22 @@
-use anyhow::Result;
-use cargo_doc_docusaurus::ConversionOptions;
+use anyhow::{Context, Result};
+use cargo_doc_docusaurus::config::{self, Config, OptionsLayer};
+use cargo_doc_docusaurus::converter::{
+  DOCUSAURUS_MAJORS, DocusaurusMajor, LAYOUTS, METHOD_ORDERS, MethodOrder, OUTPUT_EXTENSIONS,
+  OUTPUT_FLAVORS, OutputExtension, OutputFlavor, OutputLayout, SIGNATURE_STYLES,
+  SITEMAP_CHANGEFREQS, SignatureStyle, SitemapOptions,
+};
+use cargo_doc_docusaurus::sidebar::{
+  SIDEBAR_FORMATS, SIDEBAR_LEVELS, SidebarCollapse, SidebarFormat,
+};
+use cargo_doc_docusaurus::stats::render_stats;
+use clap::builder::TypedValueParser;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
 mod components;
+mod scaffold;
 
 #[derive(Parser)]
 #[command(name = "cargo-doc-docusaurus")]
@@ -12,29 +25,525 @@ struct Cli {
   #[command(subcommand)]
   command: Option<Commands>,
 
-  #[arg(help = "Path to rustdoc JSON file")]
+  #[arg(
+    help = "Path to a rustdoc JSON file (.json, .json.gz or .json.zst, - for stdin), or a directory of them (e.g. target/doc)"
+  )]
   input: Option<PathBuf>,
 
-  #[arg(short, long, default_value = "target/doc-md")]
-  output: PathBuf,
+  #[arg(
+    long,
+    conflicts_with = "input",
+    help = "Read the rustdoc JSON from stdin (same as the input -)"
+  )]
+  stdin: bool,
 
-  #[arg(long)]
-  include_private: bool,
+  #[arg(
+    long,
+    conflicts_with_all = ["stats", "stats_json"],
+    help = "Print the legacy single-file markdown of the crate to stdout instead of writing a site (same as --single-file -)"
+  )]
+  stdout: bool,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    conflicts_with_all = ["stdout", "stats", "stats_json"],
+    help = "Write the legacy single-file markdown of the crate to PATH (- for stdout) instead of writing a site"
+  )]
+  single_file: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help = "Path to a config file (defaults to doc-docusaurus.toml or Cargo.toml metadata)"
+  )]
+  config: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help = "With a directory input, convert every rustdoc JSON file in it, not only those of workspace members"
+  )]
+  all_crates: bool,
+
+  #[arg(
+    long,
+    help = "Print statistics of each conversion: modules, items by kind, undocumented items, files, bytes and elapsed time"
+  )]
+  stats: bool,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    help = "Write the statistics of the conversions to a JSON file, keyed by crate name"
+  )]
+  stats_json: Option<PathBuf>,
+
+  #[arg(
+    short,
+    long,
+    action = clap::ArgAction::Count,
+    global = true,
+    help = "Log diagnostics (-v for debug, -vv for trace)"
+  )]
+  verbose: u8,
 
-  #[arg(long, default_value = "")]
-  base_path: String,
+  #[arg(
+    short,
+    long,
+    global = true,
+    conflicts_with = "verbose",
+    help = "Only print errors"
+  )]
+  quiet: bool,
+
+  #[arg(short, long, help = "Output directory [default: target/doc-md]")]
+  output: Option<PathBuf>,
+
+  #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+  include_private: Option<bool>,
+
+  #[arg(long)]
+  base_path: Option<String>,
 
   #[arg(long, value_delimiter = ',')]
   workspace_crates: Vec<String>,
 
-  #[arg(long = "sidebarconfig-collapsed", num_args = 0..=1, default_missing_value = "true", default_value = "true")]
-  sidebarconfig_collapsed: bool,
+  #[arg(long = "sidebarconfig-collapsed", num_args = 0..=1, default_missing_value = "true")]
+  sidebarconfig_collapsed: Option<bool>,
 
   #[arg(long)]
   sidebar_output: Option<PathBuf>,
 
+  #[arg(
+    long,
+    help = "JSON file mapping item paths (e.g. my_crate::Foo) to the version that introduced them, shown as \"Since\" badges"
+  )]
+  since_map: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    help = "JSON file mapping old item paths (e.g. my_crate::old::Foo) to their new paths, written to redirects.json for the client-redirects plugin"
+  )]
+  redirect_map: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    help = "Write the normalized model of the crate (modules, items, signatures, links, docs) to a JSON file"
+  )]
+  emit_model: Option<PathBuf>,
+
   #[arg(long)]
   sidebar_root_link: Option<String>,
+
+  #[arg(
+    long,
+    help = "Only generate pages for modules up to this depth (crate root is 0)"
+  )]
+  max_depth: Option<usize>,
+
+  #[arg(
+    long,
+    value_delimiter = ',',
+    value_name = "LEVELS",
+    value_parser = clap::builder::PossibleValuesParser::new(SIDEBAR_LEVELS),
+    help = "Sidebar levels whose categories start collapsed (crates, modules, items), overriding --sidebarconfig-collapsed"
+  )]
+  sidebar_collapse: Vec<String>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Only write files whose content changed and remove stale files"
+  )]
+  incremental: Option<bool>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(SITEMAP_CHANGEFREQS),
+    help = "Sitemap changefreq front matter for generated pages"
+  )]
+  sitemap_changefreq: Option<String>,
+
+  #[arg(
+    long,
+    help = "Sitemap priority front matter for generated pages (0.0 - 1.0)"
+  )]
+  sitemap_priority: Option<f64>,
+
+  #[arg(
+    long,
+    help = "Namespace (e.g. v2) prefixed to output directories, doc IDs and sidebar keys"
+  )]
+  namespace: Option<String>,
+
+  #[arg(
+    long,
+    help = "Route base path of the docs plugin instance serving the pages (its routeBasePath, e.g. /rust); versioned links, doc IDs and sidebar keys are computed below it (default: the first segment of --base-path)"
+  )]
+  route_base_path: Option<String>,
+
+  #[arg(
+    long,
+    help = "Id of the docs plugin instance (e.g. rust): versioned docs, sidebars and versions.json get the <ID>_ prefix, translations go to i18n/<LOCALE>/docusaurus-plugin-content-docs-<ID>, and its content folder may be named <ID> instead of docs"
+  )]
+  docs_plugin_id: Option<String>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(OUTPUT_EXTENSIONS)
+      .map(|s| s.parse::<OutputExtension>().expect("validated by PossibleValuesParser")),
+    help = "File extension of generated pages (use mdx if .md is parsed as CommonMark)"
+  )]
+  extension: Option<OutputExtension>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(OUTPUT_FLAVORS)
+      .map(|s| s.parse::<OutputFlavor>().expect("validated by PossibleValuesParser")),
+    help = "Markup flavor: docusaurus (MDX components + sidebar) or plain-markdown (mdBook, Obsidian)"
+  )]
+  flavor: Option<OutputFlavor>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(SIDEBAR_FORMATS)
+      .map(|s| s.parse::<SidebarFormat>().expect("validated by PossibleValuesParser")),
+    help = "Sidebar file format: ts (TypeScript module), json (plain data) or js (CommonJS module)"
+  )]
+  sidebar_format: Option<SidebarFormat>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(METHOD_ORDERS)
+      .map(|s| s.parse::<MethodOrder>().expect("validated by PossibleValuesParser")),
+    help = "Order of methods on type and trait pages: source (impl blocks in source order) or alphabetical"
+  )]
+  method_order: Option<MethodOrder>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(SIGNATURE_STYLES)
+      .map(|s| s.parse::<SignatureStyle>().expect("validated by PossibleValuesParser")),
+    help = "Line wrapping of function signatures: threshold (--signature-width and --signature-max-params) or rustfmt (wrap when the whole signature is wider than --signature-width, 100 by default)"
+  )]
+  signature_style: Option<SignatureStyle>,
+
+  #[arg(
+    long,
+    help = "Width above which the parameters of a function signature go on their own lines (default: 80, 100 with --signature-style rustfmt)"
+  )]
+  signature_width: Option<usize>,
+
+  #[arg(
+    long,
+    help = "Parameter count above which the parameters of a function signature go on their own lines (default: 3)"
+  )]
+  signature_max_params: Option<usize>,
+
+  #[arg(
+    long,
+    value_parser = clap::builder::PossibleValuesParser::new(LAYOUTS)
+      .map(|s| s.parse::<OutputLayout>().expect("validated by PossibleValuesParser")),
+    help = "Page layout: per-item (a page per item), per-module (items as sections of their module page) or single-file (legacy single-file output)"
+  )]
+  layout: Option<OutputLayout>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate an index page of all types implementing std::error::Error"
+  )]
+  error_index: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate an index page of async functions and Future/Stream types"
+  )]
+  async_index: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate a feature matrix page showing which items each Cargo feature enables"
+  )]
+  feature_matrix: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate a features page listing the Cargo features of the crate, their dependency trees and defaults (read from --cargo-manifest or the Cargo.toml found from the input)"
+  )]
+  features_page: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate a glossary page aggregating '# Terminology' sections and '<!-- glossary: term -->' definitions from doc comments"
+  )]
+  glossary: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate an all-items page listing every item of the crate grouped by kind"
+  )]
+  all_items: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Generate a page per item kind (all structs, all traits, ...) listing every item of that kind"
+  )]
+  kind_indexes: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Extract the 'JSON schema' blocks of doc comments to downloadable .schema.json files next to their pages"
+  )]
+  json_schema_assets: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Render math/latex/katex/tex fenced code blocks of doc comments as $$ blocks, for sites using remark-math and rehype-katex"
+  )]
+  math_blocks: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Copy local images referenced in doc comments (resolved against the crate directory) to an _assets folder of the crate output and point their links there"
+  )]
+  copy_assets: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Show PhantomData marker fields of structs, which are hidden by default"
+  )]
+  show_phantom_data: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Render a placeholder page for items that fail to render, with a warning, instead of aborting the conversion"
+  )]
+  keep_going: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Fail when the conversion has warnings (unresolved links, placeholder pages), e.g. in CI"
+  )]
+  deny_warnings: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Write _category_.json files (label, position, collapsed state, index page link) to module directories for autogenerated sidebars, instead of a sidebar file"
+  )]
+  category_files: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Skip sidebar generation: no sidebar file is written or merged and pages get no displayed_sidebar, for sites with hand-written navigation"
+  )]
+  no_sidebar: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Record hashes of the rustdoc JSON input and of all generated files in the output manifest, to attest reproducible builds"
+  )]
+  reproducible: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Add the generated sidebars to sidebars.ts and docusaurus.config.ts next to the sidebar file, keeping .bak backups"
+  )]
+  patch_config: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Document items re-exported from other crates (pub use other_crate::Type) on this crate's pages, when the other crate's rustdoc JSON is next to the input"
+  )]
+  inline_reexports: Option<bool>,
+
+  #[arg(
+    long,
+    help = "Docusaurus version label (e.g. 1.2): like --docs-version, but also adds the version to versions.json and merges the sidebars into versioned_sidebars/version-<LABEL>-sidebars.json"
+  )]
+  docusaurus_version: Option<String>,
+
+  #[arg(
+    long,
+    value_name = "MAJOR",
+    value_parser = clap::builder::PossibleValuesParser::new(DOCUSAURUS_MAJORS)
+      .map(|s| s.parse::<DocusaurusMajor>().expect("validated by PossibleValuesParser")),
+    help = "Docusaurus major version (2 or 3) to generate for, instead of detecting it from the site's package.json"
+  )]
+  docusaurus_major: Option<DocusaurusMajor>,
+
+  #[arg(
+    long,
+    help = "Docusaurus docs version (e.g. 1.2): write into versioned_docs/version-<VERSION> and version links and sidebar keys"
+  )]
+  docs_version: Option<String>,
+
+  #[arg(
+    long,
+    help = "Module path prefix (e.g. my_crate::api::v1) stripped from breadcrumbs and sidebar labels; links keep canonical paths"
+  )]
+  strip_prefix: Option<String>,
+
+  #[arg(
+    long,
+    help = "Separator between item names and their doc summaries in listings (default: —)"
+  )]
+  summary_separator: Option<String>,
+
+  #[arg(
+    long,
+    help = "Marker after the names of items with restricted visibility in listings (default: 🔒, empty to hide)"
+  )]
+  restricted_marker: Option<String>,
+
+  #[arg(
+    long,
+    help = "Image URL or static path set as the image front matter of generated pages, for social cards"
+  )]
+  social_card: Option<String>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Show only the first line of the docs of struct fields instead of their complete docs"
+  )]
+  field_summaries: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Collapse the docs of methods below their first paragraph in a <details> block"
+  )]
+  collapse_method_docs: Option<bool>,
+
+  #[arg(
+    long,
+    num_args = 0..=1,
+    default_missing_value = "true",
+    help = "Add Parameters and Returns tables to functions and methods, with parameter docs from their # Arguments section"
+  )]
+  parameter_tables: Option<bool>,
+
+  #[arg(
+    long,
+    help = "i18n locale (e.g. fr): write into i18n/<LOCALE>/docusaurus-plugin-content-docs and prefix sidebar keys with the locale"
+  )]
+  locale: Option<String>,
+
+  #[arg(
+    long,
+    help = "Translated docs directory replacing i18n/<LOCALE>/docusaurus-plugin-content-docs (e.g. website/i18n/fr/docusaurus-plugin-content-docs-api); the locale defaults to the directory below i18n"
+  )]
+  locale_dir: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help = "Only generate pages of items whose source files changed in this git range (e.g. origin/main...HEAD), for PR previews; needs --production-url"
+  )]
+  changed_only: Option<String>,
+
+  #[arg(
+    long,
+    help = "Production site URL (e.g. https://docs.example.com) that links to pages not generated with --changed-only point to"
+  )]
+  production_url: Option<String>,
+
+  #[arg(
+    long,
+    value_name = "KIND=ICON",
+    value_parser = parse_key_value,
+    help = "Sidebar icon (customProps.icon) for an item kind, e.g. struct=🅢 (repeatable)"
+  )]
+  sidebar_icon: Vec<(String, String)>,
+
+  #[arg(
+    long,
+    value_name = "HEADING=KIND",
+    value_parser = parse_key_value,
+    help = "Render a doc comment section as an admonition (note, tip, info, warning, danger, caution) or keep it as a heading, e.g. Panics=danger (repeatable)"
+  )]
+  doc_admonition: Vec<(String, String)>,
+
+  #[arg(
+    long,
+    help = "Cargo.lock whose dependency versions pin docs.rs links (instead of linking to latest)"
+  )]
+  cargo_lock: Option<PathBuf>,
+
+  #[arg(
+    long,
+    help = "Cargo.toml of the crate, for --features-page and --copy-assets (defaults to the manifest found from the input's location)"
+  )]
+  cargo_manifest: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    help = "README added to the crate index page after the crate docs, with relative image paths rewritten"
+  )]
+  include_readme: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    help = "Directory of page templates (item.md, module.md, crate.md) overriding the built-in page layouts"
+  )]
+  template_dir: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "CRATE=VERSION",
+    value_parser = parse_key_value,
+    help = "docs.rs version to link for a dependency, e.g. serde=1.0.215, overriding --cargo-lock (repeatable)"
+  )]
+  extern_version: Vec<(String, String)>,
+
+  #[arg(
+    long,
+    value_name = "CRATE=URL_PREFIX",
+    value_parser = parse_key_value,
+    help = "Documentation URL prefix for a dependency replacing docs.rs, e.g. serde=https://docs.internal/serde/1.0 (repeatable)"
+  )]
+  extern_url: Vec<(String, String)>,
 }
 
 #[derive(Subcommand)]
@@ -44,6 +553,81 @@ enum Commands {
     #[command(subcommand)]
     command: ComponentsCommand,
   },
+
+  #[command(about = "Create a Docusaurus site set up for the generated documentation")]
+  #[command(
+    long_about = "Create a minimal Docusaurus project with the components and CSS installed,\n\
+                            the generated sidebars imported in sidebars.ts, and a doc-docusaurus.toml\n\
+                            that writes the documentation into the site's docs/api directory.\n\n\
+                            Example:\n  \
+                            cargo doc-docusaurus scaffold website"
+  )]
+  Scaffold {
+    #[arg(help = "Directory of the new site (created if missing)")]
+    dir: PathBuf,
+  },
+
+  #[command(about = "Print the module tree of a crate with item counts per kind and visibility")]
+  #[command(
+    long_about = "Print the module hierarchy of a rustdoc JSON file with the number of items\n\
+                            of each kind and visibility in every module, to help choose filters\n\
+                            like --include-private or --max-depth before generating a site.\n\n\
+                            Examples:\n  \
+                            cargo doc-docusaurus tree target/doc/my_crate.json\n  \
+                            cargo doc-docusaurus tree target/doc/my_crate.json --json"
+  )]
+  Tree {
+    #[arg(help = "Path to a rustdoc JSON file")]
+    input: PathBuf,
+
+    #[arg(long, help = "Print the tree as JSON")]
+    json: bool,
+  },
+
+  #[command(about = "Report the percentage of public items with documentation")]
+  #[command(
+    long_about = "Report how many public modules and items of a rustdoc JSON file have doc\n\
+                            comments, per module and per item kind. With --fail-under, exit with an\n\
+                            error when the total coverage is below the threshold (for CI).\n\n\
+                            Examples:\n  \
+                            cargo doc-docusaurus coverage target/doc/my_crate.json\n  \
+                            cargo doc-docusaurus coverage target/doc/my_crate.json --fail-under 80"
+  )]
+  Coverage {
+    #[arg(help = "Path to a rustdoc JSON file")]
+    input: PathBuf,
+
+    #[arg(long, help = "Print the report as JSON")]
+    json: bool,
+
+    #[arg(
+      long,
+      value_name = "PERCENT",
+      help = "Fail when less than PERCENT percent of the public items are documented"
+    )]
+    fail_under: Option<f64>,
+  },
+
+  #[command(about = "Report the public API changes between two versions of a crate")]
+  #[command(
+    long_about = "Compare the public items of two rustdoc JSON files and report the added,\n\
+                            removed and changed items (signature changes, new variants, removed\n\
+                            methods, trait implementations) as Markdown, e.g. for changelogs and\n\
+                            semver review.\n\n\
+                            Examples:\n  \
+                            cargo doc-docusaurus diff old/my_crate.json target/doc/my_crate.json\n  \
+                            cargo doc-docusaurus diff old/my_crate.json target/doc/my_crate.json --json"
+  )]
+  Diff {
+    #[arg(help = "Rustdoc JSON file of the old version")]
+    old: PathBuf,
+
+    #[arg(help = "Rustdoc JSON file of the new version")]
+    new: PathBuf,
+
+    #[arg(long, help = "Print the changes as JSON")]
+    json: bool,
+  },
 }
 
 #[derive(Subcommand)]
@@ -59,6 +643,15 @@ enum ComponentsCommand {
   Init {
     #[arg(help = "Path to Docusaurus project root directory")]
     docusaurus_path: PathBuf,
+
+    #[arg(
+      long,
+      value_name = "MAJOR",
+      value_parser = clap::builder::PossibleValuesParser::new(DOCUSAURUS_MAJORS)
+        .map(|s| s.parse::<DocusaurusMajor>().expect("validated by PossibleValuesParser")),
+      help = "Docusaurus major version (2 or 3) of the project, instead of detecting it from its package.json"
+    )]
+    docusaurus_major: Option<DocusaurusMajor>,
   },
 
   #[command(about = "Update existing components to latest version")]
@@ -72,6 +665,15 @@ enum ComponentsCommand {
   Sync {
     #[arg(help = "Path to Docusaurus project root directory")]
     docusaurus_path: PathBuf,
+
+    #[arg(
+      long,
+      value_name = "MAJOR",
+      value_parser = clap::builder::PossibleValuesParser::new(DOCUSAURUS_MAJORS)
+        .map(|s| s.parse::<DocusaurusMajor>().expect("validated by PossibleValuesParser")),
+      help = "Docusaurus major version (2 or 3) of the project, instead of detecting it from its package.json"
+    )]
+    docusaurus_major: Option<DocusaurusMajor>,
   },
 
   #[command(about = "List all available components and their installation status")]
@@ -115,15 +717,22 @@ fn main() -> Result<()> {
     .map(|(_, arg)| arg);
 
   let cli = Cli::parse_from(args);
+  init_logging(&cli);
 
   if let Some(command) = cli.command {
     match command {
       Commands::Components { command } => match command {
-        ComponentsCommand::Init { docusaurus_path } => {
-          components::init_components(&docusaurus_path)?;
+        ComponentsCommand::Init {
+          docusaurus_path,
+          docusaurus_major,
+        } => {
+          components::init_components(&docusaurus_path, docusaurus_major)?;
         }
-        ComponentsCommand::Sync { docusaurus_path } => {
-          components::sync_components(&docusaurus_path)?;
+        ComponentsCommand::Sync {
+          docusaurus_path,
+          docusaurus_major,
+        } => {
+          components::sync_components(&docusaurus_path, docusaurus_major)?;
         }
         ComponentsCommand::List { docusaurus_path } => {
           components::list_components(docusaurus_path.as_deref())?;
@@ -132,29 +741,310 @@ fn main() -> Result<()> {
           components::print_css_variables(css_only);
         }
       },
+      Commands::Scaffold { dir } => {
+        scaffold::scaffold_site(&dir)?;
+      }
+      Commands::Tree { input, json } => {
+        let crate_data = cargo_doc_docusaurus::parser::load_rustdoc_json(&input)?;
+        let tree = cargo_doc_docusaurus::tree::module_tree(&crate_data);
+        if json {
+          println!("{}", serde_json::to_string_pretty(&tree)?);
+        } else {
+          print!("{}", cargo_doc_docusaurus::tree::render_tree(&tree));
+        }
+      }
+      Commands::Diff { old, new, json } => {
+        let old = cargo_doc_docusaurus::parser::load_rustdoc_json(&old)?;
+        let new = cargo_doc_docusaurus::parser::load_rustdoc_json(&new)?;
+        let diff = cargo_doc_docusaurus::diff::diff_apis(
+          &cargo_doc_docusaurus::diff::public_api(&old),
+          &cargo_doc_docusaurus::diff::public_api(&new),
+        );
+        if json {
+          println!("{}", serde_json::to_string_pretty(&diff)?);
+        } else {
+          print!(
+            "{}",
+            cargo_doc_docusaurus::diff::render_diff_markdown(&diff)
+          );
+        }
+      }
+      Commands::Coverage {
+        input,
+        json,
+        fail_under,
+      } => {
+        let crate_data = cargo_doc_docusaurus::parser::load_rustdoc_json(&input)?;
+        let report = cargo_doc_docusaurus::coverage::doc_coverage(&crate_data);
+        if json {
+          println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+          print!(
+            "{}",
+            cargo_doc_docusaurus::coverage::render_coverage(&report)
+          );
+        }
+        if let Some(threshold) = fail_under
+          && report.total.percent() < threshold
+        {
+          anyhow::bail!(
+            "Documentation coverage {:.1}% is below --fail-under {}",
+            report.total.percent(),
+            threshold
+          );
+        }
+      }
     }
     return Ok(());
   }
 
-  if let Some(input) = cli.input.as_ref() {
-    let options = ConversionOptions {
-      input_path: input,
-      output_dir: &cli.output,
-      include_private: cli.include_private,
-      base_path: &cli.base_path,
-      workspace_crates: &cli.workspace_crates,
-      sidebarconfig_collapsed: cli.sidebarconfig_collapsed,
-      sidebar_output: cli.sidebar_output.as_deref(),
-      sidebar_root_link: cli.sidebar_root_link.as_deref(),
-    };
-
-    cargo_doc_docusaurus::convert_json_file(&options)?;
-    println!("✓ Conversion complete! Output: {}", cli.output.display());
-    return Ok(());
+  let config = match cli.config.as_deref() {
+    Some(path) => Config::load(path)?,
+    None => Config::discover(Path::new("."))?.unwrap_or_default(),
+  };
+
+  let inputs: Vec<PathBuf> = match cli.input.clone() {
+    Some(input) => vec![input],
+    None if cli.stdin => vec![PathBuf::from(cargo_doc_docusaurus::parser::STDIN_PATH)],
+    None => config.inputs.clone(),
+  };
+
+  if inputs.is_empty() {
+    eprintln!("Error: No input file or command specified");
+    eprintln!("  cargo doc-docusaurus <INPUT.json> -o <OUTPUT>");
+    eprintln!("  cargo doc-docusaurus components init <PATH>");
+    eprintln!("  cargo doc-docusaurus scaffold <DIR>");
+    eprintln!(
+      "  (or list `inputs` in {} or [package.metadata.doc-docusaurus] in Cargo.toml)",
+      config::CONFIG_FILE_NAME
+    );
+    std::process::exit(1);
+  }
+
+  // Directories (e.g. target/doc) stand for the rustdoc JSON files of the
+  // workspace members in them, whose crates then link to each other as
+  // workspace crates
+  let mut json_inputs = Vec::new();
+  let mut found_crates = Vec::new();
+  for input in inputs {
+    if input.is_dir() {
+      let mut files = config::rustdoc_json_files(&input)?;
+      if !cli.all_crates
+        && let Some(members) = config::workspace_member_crates(&input)
+      {
+        files.retain(|file| members.contains(&config::crate_name_from_input(file)));
+        if files.is_empty() {
+          anyhow::bail!(
+            "No rustdoc JSON of workspace members found in {} (use --all-crates to convert every file)",
+            input.display()
+          );
+        }
+      }
+      found_crates.extend(files.iter().map(|file| config::crate_name_from_input(file)));
+      json_inputs.extend(files);
+    } else {
+      json_inputs.push(input);
+    }
+  }
+
+  let single_file = cli
+    .single_file
+    .clone()
+    .or_else(|| cli.stdout.then(|| PathBuf::from("-")));
+  if let Some(path) = &single_file
+    && path != Path::new("-")
+    && json_inputs.len() > 1
+  {
+    anyhow::bail!(
+      "--single-file {} can only hold one crate, but {} were given (use --single-file - to print them all)",
+      path.display(),
+      json_inputs.len()
+    );
   }
 
-  eprintln!("Error: No input file or command specified");
-  eprintln!("  cargo doc-docusaurus <INPUT.json> -o <OUTPUT>");
-  eprintln!("  cargo doc-docusaurus components init <PATH>");
-  std::process::exit(1);
+  let cli_layer = cli_options_layer(&cli);
+
+  let mut stats = BTreeMap::new();
+  for input in &json_inputs {
+    let crate_name = config::crate_name_from_input(input);
+    let mut resolved = config.options_for_crate(&crate_name, &cli_layer);
+    for found_crate in &found_crates {
+      if !resolved.workspace_crates.contains(found_crate) {
+        resolved.workspace_crates.push(found_crate.clone());
+      }
+    }
+    // `--layout single-file -o -` prints the single-file markdown as well
+    let single_file = single_file.clone().or_else(|| {
+      (resolved.layout == OutputLayout::SingleFile && resolved.output == Path::new("-"))
+        .then(|| resolved.output.clone())
+    });
+    if let Some(path) = single_file {
+      let options = resolved.as_conversion_options(input);
+      let markdown = cargo_doc_docusaurus::convert_json_to_markdown(&options)
+        .with_context(|| format!("Failed to convert {}", input.display()))?;
+      if path != Path::new("-") {
+        std::fs::write(&path, markdown)
+          .with_context(|| format!("Failed to write {}", path.display()))?;
+        continue;
+      }
+      // A closed pipe (e.g. `| head`) only means the reader has seen enough
+      match std::io::stdout().lock().write_all(markdown.as_bytes()) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+        result => result.context("Failed to write to stdout")?,
+      }
+      continue;
+    }
+    if resolved.output == Path::new("-") {
+      anyhow::bail!("-o - prints the single-file markdown, so it needs --layout single-file");
+    }
+    let options = resolved.as_conversion_options(input);
+
+    let crate_stats = cargo_doc_docusaurus::convert_json_file(&options)
+      .with_context(|| format!("Failed to convert {}", input.display()))?;
+    log::info!(
+      "✓ Conversion complete! Output: {}",
+      resolved.output.display()
+    );
+    if cli.stats {
+      print!("{}", render_stats(&crate_stats));
+    }
+    stats.insert(crate_stats.crate_name.clone(), crate_stats);
+  }
+
+  if let Some(path) = &cli.stats_json {
+    std::fs::write(path, serde_json::to_string_pretty(&stats)? + "\n")
+      .with_context(|| format!("Failed to write {}", path.display()))?;
+  }
+
+  Ok(())
+}
+
+/// Log progress messages, or only errors with `--quiet` and diagnostics with `--verbose`.
+///
+/// `RUST_LOG` (e.g. `RUST_LOG=links=debug`) overrides the level, see the README.
+/// Progress bars are shown for large crates unless diagnostics are logged.
+fn init_logging(cli: &Cli) {
+  let level = match (cli.quiet, cli.verbose) {
+    (true, _) => log::LevelFilter::Error,
+    (false, 0) => log::LevelFilter::Info,
+    (false, 1) => log::LevelFilter::Debug,
+    (false, _) => log::LevelFilter::Trace,
+  };
+  env_logger::Builder::new()
+    .filter_level(level)
+    .parse_default_env()
+    .format(|buf, record| match record.level() {
+      log::Level::Info => writeln!(buf, "{}", record.args()),
+      log::Level::Warn | log::Level::Error => writeln!(
+        buf,
+        "{}: {}",
+        record.level().as_str().to_lowercase(),
+        record.args()
+      ),
+      _ => writeln!(
+        buf,
+        "[{} {}] {}",
+        record.level().as_str().to_lowercase(),
+        record.target(),
+        record.args()
+      ),
+    })
+    .init();
+
+  cargo_doc_docusaurus::progress::set_enabled(
+    level == log::LevelFilter::Info
+      && !log::log_enabled!(log::Level::Debug)
+      && std::io::stderr().is_terminal(),
+  );
+}
+
+/// Collect the options explicitly given on the command line.
+fn cli_options_layer(cli: &Cli) -> OptionsLayer {
+  OptionsLayer {
+    output: cli.output.clone(),
+    include_private: cli.include_private,
+    base_path: cli.base_path.clone(),
+    workspace_crates: (!cli.workspace_crates.is_empty()).then(|| cli.workspace_crates.clone()),
+    sidebarconfig_collapsed: cli.sidebarconfig_collapsed,
+    sidebar_output: cli.sidebar_output.clone(),
+    since_map: cli.since_map.clone(),
+    redirect_map: cli.redirect_map.clone(),
+    emit_model: cli.emit_model.clone(),
+    sidebar_root_link: cli.sidebar_root_link.clone(),
+    max_depth: cli.max_depth,
+    sidebar_collapse: (!cli.sidebar_collapse.is_empty()).then(|| {
+      SidebarCollapse::from_levels(&cli.sidebar_collapse)
+        .expect("validated by PossibleValuesParser")
+    }),
+    incremental: cli.incremental,
+    sitemap: (cli.sitemap_changefreq.is_some() || cli.sitemap_priority.is_some()).then(|| {
+      SitemapOptions {
+        changefreq: cli.sitemap_changefreq.clone(),
+        priority: cli.sitemap_priority,
+        ..Default::default()
+      }
+    }),
+    namespace: cli.namespace.clone(),
+    route_base_path: cli.route_base_path.clone(),
+    docs_plugin_id: cli.docs_plugin_id.clone(),
+    extension: cli.extension,
+    flavor: cli.flavor,
+    sidebar_format: cli.sidebar_format,
+    method_order: cli.method_order,
+    signature_style: cli.signature_style,
+    signature_width: cli.signature_width,
+    signature_max_params: cli.signature_max_params,
+    layout: cli.layout,
+    error_index: cli.error_index,
+    async_index: cli.async_index,
+    feature_matrix: cli.feature_matrix,
+    features_page: cli.features_page,
+    glossary: cli.glossary,
+    all_items: cli.all_items,
+    kind_indexes: cli.kind_indexes,
+    json_schema_assets: cli.json_schema_assets,
+    math_blocks: cli.math_blocks,
+    copy_assets: cli.copy_assets,
+    show_phantom_data: cli.show_phantom_data,
+    keep_going: cli.keep_going,
+    deny_warnings: cli.deny_warnings,
+    category_files: cli.category_files,
+    no_sidebar: cli.no_sidebar,
+    reproducible: cli.reproducible,
+    patch_config: cli.patch_config,
+    inline_reexports: cli.inline_reexports,
+    docusaurus_version: cli.docusaurus_version.clone(),
+    docusaurus_major: cli.docusaurus_major,
+    docs_version: cli.docs_version.clone(),
+    strip_prefix: cli.strip_prefix.clone(),
+    summary_separator: cli.summary_separator.clone(),
+    restricted_marker: cli.restricted_marker.clone(),
+    social_card: cli.social_card.clone(),
+    field_summaries: cli.field_summaries,
+    collapse_method_docs: cli.collapse_method_docs,
+    parameter_tables: cli.parameter_tables,
+    locale: cli.locale.clone(),
+    locale_dir: cli.locale_dir.clone(),
+    changed_only: cli.changed_only.clone(),
+    production_url: cli.production_url.clone(),
+    sidebar_icons: (!cli.sidebar_icon.is_empty())
+      .then(|| cli.sidebar_icon.iter().cloned().collect()),
+    doc_admonitions: (!cli.doc_admonition.is_empty())
+      .then(|| cli.doc_admonition.iter().cloned().collect()),
+    cargo_lock: cli.cargo_lock.clone(),
+    cargo_manifest: cli.cargo_manifest.clone(),
+    include_readme: cli.include_readme.clone(),
+    template_dir: cli.template_dir.clone(),
+    extern_versions: (!cli.extern_version.is_empty())
+      .then(|| cli.extern_version.iter().cloned().collect()),
+    extern_urls: (!cli.extern_url.is_empty()).then(|| cli.extern_url.iter().cloned().collect()),
+  }
+}
+
+/// Parse a `KEY=VALUE` mapping entry (e.g. `struct=🅢` for `--sidebar-icon`).
+fn parse_key_value(value: &str) -> Result<(String, String), String> {
+  let (key, value) = value
+    .split_once('=')
+    .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", value))?;
+  Ok((key.to_string(), value.to_string()))
 }
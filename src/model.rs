@@ -0,0 +1,289 @@
+//! Normalized model of a crate (`--emit-model`), for site generators built on
+//! the converter without knowledge of rustdoc-types.
+//!
+//! The model is the module tree of the crate: modules with their items, and
+//! items with their signature, docs, intra-doc link targets, page URL and
+//! members (fields, variants, methods and associated items). Its JSON schema
+//! is versioned by [`MODEL_VERSION`], bumped on incompatible changes.
+
+use crate::converter::{self, RenderContext};
+use rustdoc_types::{Crate, Id, Item, ItemEnum, StructKind, Visibility};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Version of the model schema.
+pub const MODEL_VERSION: u32 = 1;
+
+/// A crate: its version and module tree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrateModel {
+  /// Version of the schema ([`MODEL_VERSION`])
+  pub model_version: u32,
+  /// Name of the crate
+  pub name: String,
+  /// Version of the crate, when rustdoc was given one
+  pub version: Option<String>,
+  /// Root module of the crate
+  pub root: ModuleModel,
+}
+
+/// A module with its items and submodules.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleModel {
+  /// Name of the module (the crate name for the root)
+  pub name: String,
+  /// Full path of the module (e.g. `my_crate::config`)
+  pub path: String,
+  /// Docs of the module, as written
+  pub docs: Option<String>,
+  /// Items of the module other than modules and re-exports, in source order
+  pub items: Vec<ItemModel>,
+  /// Submodules, sorted by name
+  pub modules: Vec<ModuleModel>,
+}
+
+/// An item of a module.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemModel {
+  /// Name of the item
+  pub name: String,
+  /// Full path of the item (e.g. `my_crate::config::Config`)
+  pub path: String,
+  /// Kind of the item, as in rustdoc page names (`struct`, `fn`, `trait`, ...)
+  pub kind: &'static str,
+  /// `public`, `crate`, `restricted` or `private`
+  pub visibility: &'static str,
+  /// Signature on one line (e.g. `fn parse(input: &str) -> Config`)
+  pub signature: Option<String>,
+  /// Docs of the item, as written
+  pub docs: Option<String>,
+  /// Targets of the intra-doc links of the docs (item paths), keyed by link text
+  pub links: BTreeMap<String, String>,
+  /// URL of the item page in the generated site
+  pub url: Option<String>,
+  /// Deprecation note (empty when deprecated without a note)
+  pub deprecated: Option<String>,
+  /// Traits implemented by the item (explicit implementations and derives)
+  pub implements: Vec<String>,
+  /// Fields, variants, methods and associated items
+  pub members: Vec<MemberModel>,
+}
+
+/// A member of an item: field, variant, method or associated item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemberModel {
+  /// Name of the member (the position of tuple fields, e.g. `0`)
+  pub name: String,
+  /// `field`, `variant`, `method`, `assoc_const` or `assoc_type`
+  pub kind: &'static str,
+  /// Signature on one line (e.g. `fn len(self: &Self) -> usize`)
+  pub signature: Option<String>,
+  /// Docs of the member, as written
+  pub docs: Option<String>,
+}
+
+/// Build the model of a crate, as converted with `ctx`.
+pub(crate) fn crate_model(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  include_private: bool,
+) -> CrateModel {
+  let ctx = &ctx.for_conversion();
+  let root = module_model(ctx, crate_data, &crate_data.root, "", include_private);
+  CrateModel {
+    model_version: MODEL_VERSION,
+    name: root.name.clone(),
+    version: crate_data.crate_version.clone(),
+    root,
+  }
+}
+
+fn module_model(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  id: &Id,
+  parent_path: &str,
+  include_private: bool,
+) -> ModuleModel {
+  let item = &crate_data.index[id];
+  let name = item.name.clone().unwrap_or_default();
+  let path = match parent_path {
+    "" => name.clone(),
+    parent => format!("{}::{}", parent, name),
+  };
+  let mut module = ModuleModel {
+    name,
+    docs: item.docs.clone(),
+    items: Vec::new(),
+    modules: Vec::new(),
+    path,
+  };
+
+  let ItemEnum::Module(inner) = &item.inner else {
+    return module;
+  };
+  for child_id in &inner.items {
+    let Some(child) = crate_data.index.get(child_id) else {
+      continue;
+    };
+    if !include_private && !matches!(child.visibility, Visibility::Public) {
+      continue;
+    }
+    match &child.inner {
+      ItemEnum::Module(_) => module.modules.push(module_model(
+        ctx,
+        crate_data,
+        child_id,
+        &module.path,
+        include_private,
+      )),
+      ItemEnum::Use(_) | ItemEnum::Impl(_) => {}
+      _ => module
+        .items
+        .extend(item_model(ctx, crate_data, child_id, child, &module.path)),
+    }
+  }
+  module.modules.sort_by(|a, b| a.name.cmp(&b.name));
+  module
+}
+
+fn item_model(
+  ctx: &RenderContext,
+  crate_data: &Crate,
+  id: &Id,
+  item: &Item,
+  module_path: &str,
+) -> Option<ItemModel> {
+  let name = item.name.clone()?;
+  let links = item
+    .links
+    .iter()
+    .filter_map(|(text, target)| {
+      let path = crate_data.paths.get(target)?.path.join("::");
+      Some((text.clone(), path))
+    })
+    .collect();
+  let (implements, members) = members(crate_data, item);
+  Some(ItemModel {
+    path: format!("{}::{}", module_path, name),
+    kind: converter::get_item_prefix(item).trim_end_matches('.'),
+    visibility: crate::tree::visibility_label(&item.visibility),
+    signature: converter::item_signature(item, crate_data),
+    docs: item.docs.clone(),
+    links,
+    url: converter::item_url(ctx, crate_data, id),
+    deprecated: item
+      .deprecation
+      .as_ref()
+      .map(|deprecation| deprecation.note.clone().unwrap_or_default()),
+    implements,
+    members,
+    name,
+  })
+}
+
+/// Traits implemented by an item and its members.
+fn members(crate_data: &Crate, item: &Item) -> (Vec<String>, Vec<MemberModel>) {
+  let (mut member_ids, impls): (Vec<&Id>, &[Id]) = match &item.inner {
+    ItemEnum::Struct(s) => match &s.kind {
+      StructKind::Plain { fields, .. } => (fields.iter().collect(), &s.impls),
+      StructKind::Tuple(fields) => (fields.iter().flatten().collect(), &s.impls),
+      StructKind::Unit => (Vec::new(), &s.impls),
+    },
+    ItemEnum::Union(u) => (u.fields.iter().collect(), &u.impls),
+    ItemEnum::Enum(e) => (e.variants.iter().collect(), &e.impls),
+    ItemEnum::Trait(t) => (t.items.iter().collect(), &[]),
+    _ => (Vec::new(), &[]),
+  };
+
+  let mut implements = Vec::new();
+  for impl_id in impls {
+    let Some(ItemEnum::Impl(impl_block)) = crate_data.index.get(impl_id).map(|item| &item.inner)
+    else {
+      continue;
+    };
+    if impl_block.is_synthetic || impl_block.blanket_impl.is_some() {
+      continue;
+    }
+    match &impl_block.trait_ {
+      Some(trait_) if !converter::is_compiler_internal_trait(&trait_.path) => {
+        implements.push(trait_.path.clone())
+      }
+      Some(_) => {}
+      None => member_ids.extend(&impl_block.items),
+    }
+  }
+  implements.sort();
+  implements.dedup();
+
+  let members = member_ids
+    .into_iter()
+    .filter_map(|id| {
+      let member = crate_data.index.get(id)?;
+      let kind = match &member.inner {
+        ItemEnum::StructField(_) => "field",
+        ItemEnum::Variant(_) => "variant",
+        ItemEnum::Function(_) => "method",
+        ItemEnum::AssocConst { .. } => "assoc_const",
+        ItemEnum::AssocType { .. } => "assoc_type",
+        _ => return None,
+      };
+      Some(MemberModel {
+        name: member.name.clone()?,
+        kind,
+        signature: converter::item_signature(member, crate_data),
+        docs: member.docs.clone(),
+      })
+    })
+    .collect();
+  (implements, members)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_crate_model() {
+    let crate_data =
+      crate::parser::load_rustdoc_json("tests/fixtures/test_crate.json".as_ref()).unwrap();
+    let ctx = RenderContext {
+      base_path: "/docs".to_string(),
+      ..Default::default()
+    };
+    let model = crate_model(&ctx, &crate_data, false);
+    assert_eq!(model.model_version, MODEL_VERSION);
+    assert_eq!(model.name, "test_crate");
+
+    let types = model
+      .root
+      .modules
+      .iter()
+      .find(|module| module.name == "types")
+      .unwrap();
+    assert_eq!(types.path, "test_crate::types");
+    let status = types
+      .items
+      .iter()
+      .find(|item| item.name == "Status")
+      .unwrap();
+    assert_eq!(status.kind, "enum");
+    assert_eq!(
+      status.url.as_deref(),
+      Some("/docs/test_crate/types/enum.Status")
+    );
+    let running = status
+      .members
+      .iter()
+      .find(|member| member.name == "Running")
+      .unwrap();
+    assert_eq!(running.kind, "variant");
+    assert_eq!(
+      running.signature.as_deref(),
+      Some("Running { progress: f32 }")
+    );
+
+    let json = serde_json::to_value(&model).unwrap();
+    assert_eq!(json["root"]["modules"][0]["name"], "async_example");
+  }
+}
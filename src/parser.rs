@@ -1,18 +1,30 @@
 //! JSON parser for rustdoc output.
 
-use anyhow::{Context, Result};
-use rustdoc_types::Crate;
+use crate::error::{Error, Result};
+use flate2::read::MultiGzDecoder;
+use rustdoc_types::{Crate, FORMAT_VERSION};
+use ruzstd::decoding::StreamingDecoder;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::{self, Read};
 use std::path::Path;
 
+/// Path standing for stdin as input.
+pub const STDIN_PATH: &str = "-";
+
 /// Load and parse a rustdoc JSON file.
+///
+/// See [`read_rustdoc_json`] for compressed files and stdin.
 pub fn load_rustdoc_json(path: &Path) -> Result<Crate> {
-  let contents = std::fs::read_to_string(path)
-    .with_context(|| format!("Failed to read file: {}", path.display()))?;
+  let contents = read_rustdoc_json(path)?;
 
-  let crate_data: Crate = serde_json::from_str(&contents)
-    .with_context(|| format!("Failed to parse JSON from: {}", path.display()))?;
+  let crate_data = parse_rustdoc_json(&contents).map_err(|error| match error {
+    Error::SerdeJson(source) => Error::json(path)(source),
+    error => error,
+  })?;
 
-  println!(
+  log::info!(
     "Loaded crate: {} (format version: {})",
     crate_data
       .index
@@ -24,3 +36,290 @@ pub fn load_rustdoc_json(path: &Path) -> Result<Crate> {
 
   Ok(crate_data)
 }
+
+/// Read rustdoc JSON from a file, or from stdin when `path` is `-`.
+///
+/// Gzip (`.json.gz`) and zstd (`.json.zst`) compressed input is decompressed,
+/// recognized by its magic bytes rather than the file extension.
+pub fn read_rustdoc_json(path: &Path) -> Result<String> {
+  let mut bytes = Vec::new();
+  if path == Path::new(STDIN_PATH) {
+    io::stdin().lock().read_to_end(&mut bytes)
+  } else {
+    File::open(path).and_then(|mut file| file.read_to_end(&mut bytes))
+  }
+  .map_err(Error::read(path))?;
+
+  let decompressed = match bytes.as_slice() {
+    [0x1f, 0x8b, ..] => decompress(MultiGzDecoder::new(bytes.as_slice())),
+    [0x28, 0xb5, 0x2f, 0xfd, ..] => StreamingDecoder::new(bytes.as_slice())
+      .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+      .and_then(decompress),
+    _ => {
+      String::from_utf8(bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+  };
+  decompressed.map_err(Error::read(path))
+}
+
+fn decompress(mut decoder: impl Read) -> io::Result<String> {
+  let mut contents = String::new();
+  decoder.read_to_string(&mut contents)?;
+  Ok(contents)
+}
+
+/// Whether a file name is that of (possibly compressed) rustdoc JSON.
+pub fn is_rustdoc_json_path(path: &Path) -> bool {
+  path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .is_some_and(|name| {
+      [".json", ".json.gz", ".json.zst"]
+        .iter()
+        .any(|extension| name.ends_with(extension))
+    })
+}
+
+/// Oldest rustdoc JSON format version that is migrated to [`FORMAT_VERSION`].
+pub const MIN_FORMAT_VERSION: u32 = FORMAT_VERSION - 3;
+
+/// Parse rustdoc JSON.
+///
+/// JSON of format versions [`MIN_FORMAT_VERSION`] to [`FORMAT_VERSION`] is
+/// migrated to the current format when needed. JSON that fails to parse is
+/// reported as [`Error::UnsupportedFormatVersion`] when its format version is
+/// outside of that range.
+pub fn parse_rustdoc_json(json: &str) -> Result<Crate> {
+  #[derive(Deserialize)]
+  struct FormatVersion {
+    format_version: u32,
+  }
+
+  let error = match serde_json::from_str::<Crate>(json) {
+    Ok(crate_data) => {
+      if crate_data.format_version != FORMAT_VERSION {
+        log::warn!(
+          "rustdoc JSON format version {} differs from the supported version {}",
+          crate_data.format_version,
+          FORMAT_VERSION
+        );
+      }
+      return Ok(crate_data);
+    }
+    Err(error) => error,
+  };
+
+  let format_version = match serde_json::from_str::<FormatVersion>(json) {
+    Ok(FormatVersion { format_version }) if format_version != FORMAT_VERSION => format_version,
+    _ => return Err(Error::SerdeJson(error)),
+  };
+  if !(MIN_FORMAT_VERSION..FORMAT_VERSION).contains(&format_version) {
+    return Err(Error::UnsupportedFormatVersion {
+      found: format_version,
+      min: MIN_FORMAT_VERSION,
+      expected: FORMAT_VERSION,
+    });
+  }
+
+  log::info!(
+    "Migrating rustdoc JSON from format version {} to {}",
+    format_version,
+    FORMAT_VERSION
+  );
+  let mut value: Value = serde_json::from_str(json)?;
+  migrate(&mut value, format_version);
+  Ok(serde_json::from_value(value)?)
+}
+
+/// Update rustdoc JSON of an older format version to [`FORMAT_VERSION`].
+///
+/// Only changes affecting the data read by the converter are migrated.
+fn migrate(value: &mut Value, format_version: u32) {
+  // Version 54 replaced the attribute strings with structured attributes
+  if format_version < 54
+    && let Some(index) = value.get_mut("index").and_then(Value::as_object_mut)
+  {
+    for item in index.values_mut() {
+      let Some(attrs) = item.get_mut("attrs").and_then(Value::as_array_mut) else {
+        continue;
+      };
+      for attr in attrs {
+        if let Value::String(text) = attr {
+          *attr = structured_attribute(text);
+        }
+      }
+    }
+  }
+}
+
+/// Structured form (`rustdoc_types::Attribute`) of an attribute string like `#[repr(C)]`.
+fn structured_attribute(text: &str) -> Value {
+  let other = || json!({ "other": text });
+  let Some(attr) = text
+    .strip_prefix("#[")
+    .and_then(|attr| attr.strip_suffix(']'))
+    .map(str::trim)
+  else {
+    return other();
+  };
+  let attr = attr
+    .strip_prefix("unsafe(")
+    .and_then(|attr| attr.strip_suffix(')'))
+    .unwrap_or(attr);
+  let string_value = |key: &str| {
+    attr
+      .strip_prefix(key)
+      .and_then(|rest| rest.trim_start().strip_prefix('='))
+      .map(|value| value.trim().trim_matches('"').to_string())
+  };
+
+  match attr {
+    "non_exhaustive" | "macro_export" | "automatically_derived" | "no_mangle" => {
+      return json!(attr);
+    }
+    "must_use" => return json!({ "must_use": { "reason": null } }),
+    _ => {}
+  }
+  if let Some(reason) = string_value("must_use") {
+    json!({ "must_use": { "reason": reason } })
+  } else if let Some(name) = string_value("export_name") {
+    json!({ "export_name": name })
+  } else if let Some(name) = string_value("link_section") {
+    json!({ "link_section": name })
+  } else if let Some(args) = attr
+    .strip_prefix("repr(")
+    .and_then(|args| args.strip_suffix(')'))
+  {
+    repr_attribute(args).unwrap_or_else(other)
+  } else if let Some(features) = attr
+    .strip_prefix("target_feature(")
+    .and_then(|args| args.strip_suffix(')'))
+    .and_then(|args| args.trim().strip_prefix("enable"))
+    .and_then(|args| args.trim_start().strip_prefix('='))
+  {
+    let enable: Vec<&str> = features.trim().trim_matches('"').split(',').collect();
+    json!({ "target_feature": { "enable": enable } })
+  } else {
+    other()
+  }
+}
+
+/// Structured `#[repr(...)]` attribute, `None` for unknown representations.
+fn repr_attribute(args: &str) -> Option<Value> {
+  let mut kind = "rust";
+  let mut align = None;
+  let mut packed = None;
+  let mut int = None;
+  for arg in args.split(',').map(str::trim) {
+    match arg {
+      "Rust" => kind = "rust",
+      "C" => kind = "c",
+      "transparent" => kind = "transparent",
+      "simd" => kind = "simd",
+      "packed" => packed = Some(1),
+      "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+      | "isize" => int = Some(arg),
+      _ => {
+        let (name, value) = arg.strip_suffix(')')?.split_once('(')?;
+        let value: u64 = value.trim().parse().ok()?;
+        match name.trim() {
+          "align" => align = Some(value),
+          "packed" => packed = Some(value),
+          _ => return None,
+        }
+      }
+    }
+  }
+  Some(json!({ "repr": { "kind": kind, "align": align, "packed": packed, "int": int } }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_errors() {
+    assert!(matches!(
+      parse_rustdoc_json(r#"{"format_version": 30, "root": 0}"#),
+      Err(Error::UnsupportedFormatVersion {
+        found: 30,
+        min: MIN_FORMAT_VERSION,
+        expected: FORMAT_VERSION
+      })
+    ));
+    assert!(matches!(
+      parse_rustdoc_json(&format!(r#"{{"format_version": {}}}"#, FORMAT_VERSION + 1)),
+      Err(Error::UnsupportedFormatVersion { .. })
+    ));
+    assert!(matches!(
+      parse_rustdoc_json(&format!(r#"{{"format_version": {}}}"#, FORMAT_VERSION)),
+      Err(Error::SerdeJson(_))
+    ));
+    assert!(matches!(
+      load_rustdoc_json(Path::new("tests/fixtures/missing.json")),
+      Err(Error::Read { .. })
+    ));
+  }
+
+  #[test]
+  fn test_migrate_string_attributes() {
+    let json = std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap();
+    let mut value: Value = serde_json::from_str(&json).unwrap();
+    value["format_version"] = json!(53);
+    for item in value["index"].as_object_mut().unwrap().values_mut() {
+      for attr in item["attrs"].as_array_mut().unwrap() {
+        *attr = match &*attr {
+          Value::String(name) => json!(format!("#[{}]", name)),
+          other => other["other"].clone(),
+        };
+      }
+    }
+
+    let migrated = parse_rustdoc_json(&value.to_string()).unwrap();
+    let current = parse_rustdoc_json(&json).unwrap();
+    assert_eq!(migrated.format_version, 53);
+    assert_eq!(migrated.index, current.index);
+
+    assert_eq!(
+      structured_attribute("#[repr(C, align(8))]"),
+      json!({ "repr": { "kind": "c", "align": 8, "packed": null, "int": null } })
+    );
+    assert_eq!(
+      structured_attribute("#[must_use = \"use it\"]"),
+      json!({ "must_use": { "reason": "use it" } })
+    );
+    assert_eq!(
+      structured_attribute("#[doc(hidden)]"),
+      json!({ "other": "#[doc(hidden)]" })
+    );
+  }
+
+  #[test]
+  fn test_read_compressed() {
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let json = std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap();
+
+    let gz_path = dir.path().join("test_crate.json.gz");
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(json.as_bytes()).unwrap();
+    std::fs::write(&gz_path, encoder.finish().unwrap()).unwrap();
+
+    let zst_path = dir.path().join("test_crate.json.zst");
+    std::fs::write(
+      &zst_path,
+      ruzstd::encoding::compress_to_vec(
+        json.as_bytes(),
+        ruzstd::encoding::CompressionLevel::Fastest,
+      ),
+    )
+    .unwrap();
+
+    assert_eq!(read_rustdoc_json(&gz_path).unwrap(), json);
+    assert_eq!(read_rustdoc_json(&zst_path).unwrap(), json);
+    assert!(is_rustdoc_json_path(&zst_path));
+    assert!(!is_rustdoc_json_path(Path::new("test_crate.json.bak")));
+  }
+}
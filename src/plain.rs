@@ -0,0 +1,360 @@
+//! Conversion of generated Docusaurus pages to plain Markdown.
+//!
+//! Plain Markdown output is meant for tools like mdBook or Obsidian that do not
+//! understand MDX: `RustCode` components become fenced code blocks, `Link`
+//! components become Markdown links to the generated files, and the remaining
+//! JSX wrappers are dropped.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Settings needed to rewrite the links of a page.
+pub struct PlainLinkContext<'a> {
+  /// Base path of absolute links in the Docusaurus output
+  pub link_base_path: &'a str,
+  /// Extension of the generated files (without the dot)
+  pub extension: &'a str,
+}
+
+/// Convert a generated page to plain Markdown.
+///
+/// `page_path` is the path of the page relative to the output directory
+/// (including the crate directory, e.g. `my_crate/mod_a/struct.Foo.md`).
+pub fn to_plain_markdown(content: &str, page_path: &str, ctx: &PlainLinkContext) -> String {
+  let (title, body) = split_frontmatter(content);
+
+  let mut output = String::new();
+  if let Some(title) = title.filter(|_| !body.lines().any(|line| line.starts_with("# "))) {
+    output.push_str(&format!("# {}\n\n", title));
+  }
+
+  let body = replace_rust_code(body);
+  let body = replace_links(&body, page_path, ctx);
+  for line in body.lines() {
+    if line.starts_with("import ") && line.ends_with("';") {
+      continue;
+    }
+    // Admonitions become a bold title followed by their content
+    if let Some(directive) = line.strip_prefix(":::") {
+      if let Some(title) = directive
+        .split_once('[')
+        .and_then(|(_, title)| title.strip_suffix(']'))
+      {
+        output.push_str(&format!("**{}**\n", title));
+      }
+      continue;
+    }
+    // Heading IDs are a Docusaurus extension, kept as an anchor before the heading
+    if let Some((heading, id)) = split_heading_id(line) {
+      output.push_str(&format!("<a id=\"{}\"></a>\n\n{}\n", id, heading));
+      continue;
+    }
+    output.push_str(strip_wrappers(line).trim_end());
+    output.push('\n');
+  }
+
+  // Removing imports leaves runs of blank lines behind
+  while output.contains("\n\n\n") {
+    output = output.replace("\n\n\n", "\n\n");
+  }
+  output.trim_start_matches('\n').to_string()
+}
+
+/// Split a `## Heading {#id}` line into the heading and its ID.
+fn split_heading_id(line: &str) -> Option<(&str, &str)> {
+  if !line.starts_with('#') {
+    return None;
+  }
+  let (heading, id) = line.trim_end().strip_suffix('}')?.rsplit_once(" {#")?;
+  Some((heading, id))
+}
+
+/// Split off the front matter, returning its `title` and the remaining content.
+fn split_frontmatter(content: &str) -> (Option<String>, &str) {
+  let Some(rest) = content.strip_prefix("---\n") else {
+    return (None, content);
+  };
+  let Some(end) = rest.find("\n---\n") else {
+    return (None, content);
+  };
+
+  let title = rest[..end].lines().find_map(|line| {
+    line
+      .strip_prefix("title:")
+      .map(|title| title.trim().trim_matches('"').to_string())
+  });
+  (title, &rest[end + 5..])
+}
+
+/// Replace `<RustCode code={`...`} ... />` components with fenced code blocks.
+fn replace_rust_code(content: &str) -> String {
+  let mut output = String::new();
+  let mut rest = content;
+
+  while let Some(start) = rest.find("<RustCode") {
+    let Some(code_start) = rest[start..].find("code={`").map(|i| start + i + 7) else {
+      break;
+    };
+    let Some(code_end) = template_literal_end(&rest[code_start..]).map(|i| code_start + i) else {
+      break;
+    };
+    let Some(tag_end) = rest[code_end..].find("/>").map(|i| code_end + i + 2) else {
+      break;
+    };
+
+    output.push_str(&rest[..start]);
+    let code = unescape_template_literal(&rest[code_start..code_end]);
+    // Inline code within a line of text (e.g. a table cell) stays inline
+    let line_start = output.rfind('\n').map_or(0, |i| i + 1);
+    if rest[start..code_start].starts_with("<RustCode inline")
+      && !output[line_start..].trim().is_empty()
+      && !code.contains('\n')
+    {
+      output.push_str(&format!("`{}`", code.trim()));
+    } else {
+      output.push_str("```rust\n");
+      output.push_str(code.trim_end());
+      output.push_str("\n```");
+    }
+    rest = &rest[tag_end..];
+  }
+
+  output.push_str(rest);
+  output
+}
+
+/// Position of the closing backtick of a template literal, skipping escapes.
+fn template_literal_end(code: &str) -> Option<usize> {
+  let mut chars = code.char_indices();
+  while let Some((i, c)) = chars.next() {
+    match c {
+      '\\' => {
+        chars.next();
+      }
+      '`' => return Some(i),
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Undo the escaping of backslashes, backticks and `${` in a template literal.
+fn unescape_template_literal(code: &str) -> String {
+  let mut output = String::new();
+  let mut chars = code.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => output.extend(chars.next()),
+      c => output.push(c),
+    }
+  }
+  output
+}
+
+/// Replace `<Link to="...">label</Link>` components with Markdown links.
+fn replace_links(content: &str, page_path: &str, ctx: &PlainLinkContext) -> String {
+  let mut output = String::new();
+  let mut rest = content;
+
+  while let Some(start) = rest.find("<Link to=\"") {
+    let href_start = start + "<Link to=\"".len();
+    let Some(href_end) = rest[href_start..].find('"').map(|i| href_start + i) else {
+      break;
+    };
+    let Some(label_start) = rest[href_end..].find('>').map(|i| href_end + i + 1) else {
+      break;
+    };
+    let Some(label_end) = rest[label_start..].find("</Link>").map(|i| label_start + i) else {
+      break;
+    };
+
+    output.push_str(&rest[..start]);
+    output.push_str(&format!(
+      "[{}]({})",
+      &rest[label_start..label_end],
+      plain_link_target(&rest[href_start..href_end], page_path, ctx)
+    ));
+    rest = &rest[label_end + "</Link>".len()..];
+  }
+
+  output.push_str(rest);
+  output
+}
+
+/// Rewrite a Docusaurus route into a path to the generated file, relative to the page.
+fn plain_link_target(href: &str, page_path: &str, ctx: &PlainLinkContext) -> String {
+  if href.contains("://") || href.starts_with('#') {
+    return href.to_string();
+  }
+
+  let (route, anchor) = match href.find('#') {
+    Some(i) => (&href[..i], &href[i..]),
+    None => (href, ""),
+  };
+
+  let page_dir = Path::new(page_path).parent().unwrap_or(Path::new(""));
+  let target = match route.strip_prefix('/') {
+    // Absolute routes point below the base path, which maps to the output directory
+    Some(_) => {
+      let base = ctx.link_base_path.trim_end_matches('/');
+      match route.strip_prefix(base) {
+        Some(relative) => PathBuf::from(relative.trim_start_matches('/')),
+        None => return href.to_string(),
+      }
+    }
+    None => page_dir.join(route),
+  };
+
+  let mut target = normalize(&target);
+  if route.ends_with('/') || route.is_empty() {
+    target.push(format!("index.{}", ctx.extension));
+  } else {
+    target = PathBuf::from(format!("{}.{}", target.display(), ctx.extension));
+  }
+
+  format!(
+    "{}{}",
+    relative_path(page_dir, &target).to_string_lossy(),
+    anchor
+  )
+}
+
+/// Resolve `.` and `..` components without touching the file system.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::ParentDir => {
+        normalized.pop();
+      }
+      Component::Normal(part) => normalized.push(part),
+      _ => {}
+    }
+  }
+  normalized
+}
+
+/// Path of `target` relative to the directory `from` (both relative to the same root).
+pub(crate) fn relative_path(from: &Path, target: &Path) -> PathBuf {
+  let from: Vec<_> = from.components().collect();
+  let target_components: Vec<_> = target.components().collect();
+  let common = from
+    .iter()
+    .zip(&target_components)
+    .take_while(|(a, b)| a == b)
+    .count();
+
+  let mut relative = PathBuf::new();
+  for _ in common..from.len() {
+    relative.push("..");
+  }
+  for component in &target_components[common..] {
+    relative.push(component);
+  }
+  relative
+}
+
+/// Drop JSX wrapper elements the Docusaurus output uses for styling.
+fn strip_wrappers(line: &str) -> String {
+  let mut line = line.replace("<div>", "").replace("</div>", "");
+  while let Some(start) = line.find("<div className=\"") {
+    let Some(end) = line[start..].find('>').map(|i| start + i + 1) else {
+      break;
+    };
+    line.replace_range(start..end, "");
+  }
+  line = line.replace("<em>", "*").replace("</em>", "*");
+  line = line.replace("<strong>", "**").replace("</strong>", "**");
+  line = replace_code_tags(&line);
+
+  while let Some(start) = line.find("<span className=\"") {
+    let Some(end) = line[start..].find('>').map(|i| start + i + 1) else {
+      break;
+    };
+    line.replace_range(start..end, "");
+    if let Some(close) = line[start..].find("</span>").map(|i| start + i) {
+      line.replace_range(close..close + "</span>".len(), "");
+    }
+  }
+  line
+}
+
+/// Turn the `<code>` elements of doc summaries back into Markdown code spans.
+fn replace_code_tags(line: &str) -> String {
+  let mut output = String::new();
+  let mut rest = line;
+  while let Some(start) = rest.find("<code>") {
+    let Some(end) = rest[start..].find("</code>").map(|i| start + i) else {
+      break;
+    };
+    let code = rest[start + "<code>".len()..end]
+      .replace("&lt;", "<")
+      .replace("&gt;", ">")
+      .replace("&#123;", "{")
+      .replace("&#125;", "}")
+      .replace("&amp;", "&");
+    output.push_str(&rest[..start]);
+    output.push_str(&format!("`{}`", code));
+    rest = &rest[end + "</code>".len()..];
+  }
+  output.push_str(rest);
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CTX: PlainLinkContext = PlainLinkContext {
+    link_base_path: "/docs/api",
+    extension: "md",
+  };
+
+  #[test]
+  fn test_link_targets() {
+    let page = "my_crate/mod_a/struct.Foo.md";
+    assert_eq!(
+      plain_link_target("/docs/api/my_crate/mod_b/enum.Bar", page, &CTX),
+      "../mod_b/enum.Bar.md"
+    );
+    assert_eq!(
+      plain_link_target("/docs/api/other_crate/", page, &CTX),
+      "../../other_crate/index.md"
+    );
+    assert_eq!(
+      plain_link_target("struct.Baz#method.new", page, &CTX),
+      "struct.Baz.md#method.new"
+    );
+    assert_eq!(
+      plain_link_target("https://docs.rs/serde", page, &CTX),
+      "https://docs.rs/serde"
+    );
+    assert_eq!(
+      plain_link_target("inner/", "my_crate/mod_a/index.md", &CTX),
+      "inner/index.md"
+    );
+  }
+
+  #[test]
+  fn test_to_plain_markdown() {
+    let page = "---\ntitle: Foo\ndisplayed_sidebar: 'api_my_crate'\n---\n\nimport RustCode from '@site/src/components/RustCode';\nimport Link from '@docusaurus/Link';\n\n<RustCode code={`pub struct Foo;`} links={[]} />\n\n<RustCode inline code={`const S: &str = \"\\`}\\${x}\";`} links={[]} />\n\n<div><Link to=\"fn.bar\" className=\"rust-fn\">bar</Link> — Does things with <code>Vec&lt;T&gt;</code> <em>(2 items not shown)</em></div>\n\n:::danger[Safety]\n\nCall it once.\n:::\n";
+    let plain = to_plain_markdown(page, "my_crate/struct.Foo.md", &CTX);
+
+    assert_eq!(
+      plain,
+      "# Foo\n\n```rust\npub struct Foo;\n```\n\n```rust\nconst S: &str = \"`}${x}\";\n```\n\n[bar](fn.bar.md) — Does things with `Vec<T>` *(2 items not shown)*\n\n**Safety**\n\nCall it once.\n"
+    );
+  }
+
+  #[test]
+  fn test_heading_ids() {
+    let plain = to_plain_markdown(
+      "## my_crate::Foo {#struct-my_crate-foo}\n\nText\n",
+      "my_crate/index.md",
+      &CTX,
+    );
+    assert_eq!(
+      plain,
+      "<a id=\"struct-my_crate-foo\"></a>\n\n## my_crate::Foo\n\nText\n"
+    );
+  }
+}
@@ -0,0 +1,322 @@
+//! Partial output for pull request previews.
+//!
+//! With a changed-only range, pages are only generated for items whose source
+//! files changed in that git range. Links to all other pages, both in pages and
+//! in sidebars, point to the production site instead.
+
+use crate::error::{Error, Result};
+use crate::sidebar::{SidebarItem, SidebarMap};
+use rustdoc_types::{Crate, Item, ItemEnum};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Source files (relative to the current directory) changed in a git range.
+///
+/// Rustdoc spans are relative to the directory `cargo doc` ran in, so this
+/// should be called from the same directory.
+pub fn changed_files(range: &str) -> Result<BTreeSet<PathBuf>> {
+  let output = Command::new("git")
+    .args(["diff", "--name-only", "--relative", range, "--"])
+    .output()
+    .map_err(Error::GitUnavailable)?;
+  if !output.status.success() {
+    return Err(Error::GitDiff {
+      range: range.to_string(),
+      stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    });
+  }
+
+  Ok(
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter(|line| !line.is_empty())
+      .map(PathBuf::from)
+      .collect(),
+  )
+}
+
+/// Whether an item, or one of its impl blocks, is defined in a changed file.
+pub(crate) fn item_changed(item: &Item, crate_data: &Crate, changed: &BTreeSet<PathBuf>) -> bool {
+  let in_changed_file = |item: &Item| {
+    item
+      .span
+      .as_ref()
+      .is_some_and(|span| changed.iter().any(|path| span.filename.ends_with(path)))
+  };
+
+  let impls = match &item.inner {
+    ItemEnum::Struct(s) => s.impls.as_slice(),
+    ItemEnum::Enum(e) => e.impls.as_slice(),
+    ItemEnum::Union(u) => u.impls.as_slice(),
+    _ => &[],
+  };
+  in_changed_file(item)
+    || impls
+      .iter()
+      .filter_map(|id| crate_data.index.get(id))
+      .any(in_changed_file)
+}
+
+/// Settings needed to point links outside the preview to the production site.
+pub(crate) struct PreviewLinkContext<'a> {
+  /// Production site URL
+  pub production_url: &'a str,
+  /// Base path of absolute links
+  pub link_base_path: &'a str,
+  /// Doc ID prefix of the crate's pages in sidebars (e.g. `api/my_crate/`)
+  pub doc_id_prefix: &'a str,
+  /// Name of the converted crate
+  pub crate_name: &'a str,
+  /// Extension of the generated files (without the dot)
+  pub extension: &'a str,
+}
+
+impl PreviewLinkContext<'_> {
+  /// Production URL of a page, given its route below the crate.
+  fn production_link(&self, route: &str) -> String {
+    format!(
+      "{}{}/{}/{}",
+      self.production_url.trim_end_matches('/'),
+      self.link_base_path.trim_end_matches('/'),
+      self.crate_name,
+      route
+    )
+  }
+}
+
+/// Point links to pages that were not generated to the production site.
+///
+/// `page_path` is the path of the page relative to the crate directory.
+pub(crate) fn link_to_production(
+  content: &str,
+  page_path: &str,
+  files: &BTreeMap<String, String>,
+  ctx: &PreviewLinkContext,
+) -> String {
+  // `Link` components and the links of `RustCode` components
+  ["<Link to=\"", "\"href\": \""]
+    .iter()
+    .fold(content.to_string(), |content, marker| {
+      let mut output = String::new();
+      let mut rest = content.as_str();
+      while let Some(start) = rest.find(marker).map(|i| i + marker.len()) {
+        let Some(end) = rest[start..].find('"').map(|i| start + i) else {
+          break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&preview_link_target(
+          &rest[start..end],
+          page_path,
+          files,
+          ctx,
+        ));
+        rest = &rest[end..];
+      }
+      output.push_str(rest);
+      output
+    })
+}
+
+/// Rewrite a link to the production site if its target page was not generated.
+fn preview_link_target(
+  href: &str,
+  page_path: &str,
+  files: &BTreeMap<String, String>,
+  ctx: &PreviewLinkContext,
+) -> String {
+  if href.contains("://") || href.starts_with('#') {
+    return href.to_string();
+  }
+
+  let (route, anchor) = match href.find('#') {
+    Some(i) => (&href[..i], &href[i..]),
+    None => (href, ""),
+  };
+  let target = match route.strip_prefix('/') {
+    Some(_) => {
+      let crate_base = format!(
+        "{}/{}",
+        ctx.link_base_path.trim_end_matches('/'),
+        ctx.crate_name
+      );
+      match route.strip_prefix(&crate_base) {
+        Some(relative) => PathBuf::from(relative.trim_start_matches('/')),
+        // Links outside the crate are left alone
+        None => return href.to_string(),
+      }
+    }
+    None => Path::new(page_path)
+      .parent()
+      .unwrap_or(Path::new(""))
+      .join(route),
+  };
+
+  let target = crate::plain::normalize(&target)
+    .to_string_lossy()
+    .replace('\\', "/");
+  let is_index = route.is_empty() || route.ends_with('/');
+  let (file, route) = match (is_index, target.is_empty()) {
+    (true, true) => (format!("index.{}", ctx.extension), String::new()),
+    (true, false) => (
+      format!("{}/index.{}", target, ctx.extension),
+      format!("{}/", target),
+    ),
+    (false, _) => (format!("{}.{}", target, ctx.extension), target),
+  };
+  if files.contains_key(&file) {
+    return href.to_string();
+  }
+  format!("{}{}", ctx.production_link(&route), anchor)
+}
+
+/// Point sidebar entries of pages that were not generated to the production site.
+pub(crate) fn link_sidebar_to_production(
+  sidebars: &mut SidebarMap,
+  files: &BTreeMap<String, String>,
+  ctx: &PreviewLinkContext,
+) {
+  for items in sidebars.values_mut() {
+    link_sidebar_items_to_production(items, files, ctx);
+  }
+}
+
+fn link_sidebar_items_to_production(
+  items: &mut [SidebarItem],
+  files: &BTreeMap<String, String>,
+  ctx: &PreviewLinkContext,
+) {
+  // Production route of a doc ID of the crate, if its page was not generated
+  let missing_route = |id: &str| {
+    let page = id.strip_prefix(ctx.doc_id_prefix)?;
+    if files.contains_key(&format!("{}.{}", page, ctx.extension)) {
+      return None;
+    }
+    Some(match page.strip_suffix("/index") {
+      Some(dir) => format!("{}/", dir),
+      None if page == "index" => String::new(),
+      None => page.to_string(),
+    })
+  };
+
+  for item in items {
+    match item {
+      SidebarItem::Doc {
+        id,
+        label,
+        class_name,
+        custom_props,
+      } => {
+        if let Some(route) = missing_route(id) {
+          let label = label
+            .clone()
+            .unwrap_or_else(|| id.rsplit('/').next().unwrap_or(id).to_string());
+          *item = SidebarItem::Link {
+            href: ctx.production_link(&route),
+            label,
+            class_name: class_name.take(),
+            custom_props: custom_props.take(),
+          };
+        }
+      }
+      SidebarItem::Category { link, items, .. } => {
+        if link.as_deref().and_then(missing_route).is_some() {
+          *link = None;
+        }
+        link_sidebar_items_to_production(items, files, ctx);
+      }
+      SidebarItem::Link { .. } => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CTX: PreviewLinkContext = PreviewLinkContext {
+    production_url: "https://docs.example.com/",
+    link_base_path: "/docs/api",
+    doc_id_prefix: "api/my_crate/",
+    crate_name: "my_crate",
+    extension: "md",
+  };
+
+  fn files(paths: &[&str]) -> BTreeMap<String, String> {
+    paths
+      .iter()
+      .map(|path| (path.to_string(), String::new()))
+      .collect()
+  }
+
+  #[test]
+  fn test_preview_link_targets() {
+    let files = files(&["index.md", "mod_a/index.md", "mod_a/struct.Foo.md"]);
+    let page = "mod_a/struct.Foo.md";
+    assert_eq!(
+      preview_link_target("/docs/api/my_crate/mod_a/", page, &files, &CTX),
+      "/docs/api/my_crate/mod_a/"
+    );
+    assert_eq!(
+      preview_link_target(
+        "/docs/api/my_crate/mod_b/enum.Bar#variant.A",
+        page,
+        &files,
+        &CTX
+      ),
+      "https://docs.example.com/docs/api/my_crate/mod_b/enum.Bar#variant.A"
+    );
+    assert_eq!(
+      preview_link_target("../mod_b/", page, &files, &CTX),
+      "https://docs.example.com/docs/api/my_crate/mod_b/"
+    );
+    assert_eq!(
+      preview_link_target("struct.Foo", page, &files, &CTX),
+      "struct.Foo"
+    );
+    assert_eq!(
+      preview_link_target("/docs/api/other_crate/", page, &files, &CTX),
+      "/docs/api/other_crate/"
+    );
+  }
+
+  #[test]
+  fn test_link_sidebar_to_production() {
+    let files = files(&["index.md", "mod_a/struct.Foo.md"]);
+    let doc = |id: &str| SidebarItem::Doc {
+      id: id.to_string(),
+      label: Some(id.rsplit('/').next().unwrap().to_string()),
+      class_name: None,
+      custom_props: None,
+    };
+    let mut sidebars = SidebarMap::from([(
+      "api_my_crate".to_string(),
+      vec![SidebarItem::Category {
+        label: "mod_a".to_string(),
+        link: Some("api/my_crate/mod_a/index".to_string()),
+        collapsible: true,
+        collapsed: false,
+        items: vec![
+          doc("api/my_crate/mod_a/struct.Foo"),
+          doc("api/my_crate/mod_a/fn.bar"),
+        ],
+      }],
+    )]);
+    link_sidebar_to_production(&mut sidebars, &files, &CTX);
+
+    let SidebarItem::Category { link, items, .. } = &sidebars["api_my_crate"][0] else {
+      panic!("expected a category");
+    };
+    assert_eq!(link, &None);
+    assert_eq!(items[0], doc("api/my_crate/mod_a/struct.Foo"));
+    assert_eq!(
+      items[1],
+      SidebarItem::Link {
+        href: "https://docs.example.com/docs/api/my_crate/mod_a/fn.bar".to_string(),
+        label: "fn.bar".to_string(),
+        class_name: None,
+        custom_props: None,
+      }
+    );
+  }
+}
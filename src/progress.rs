@@ -0,0 +1,32 @@
+//! Progress bars for long conversions.
+//!
+//! Bars are drawn on stderr, and only once enabled (by the command line tool, when
+//! stderr is a terminal), so library users and redirected output are not disturbed.
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Fewer steps than this finish too quickly for a progress bar to be useful
+const MIN_STEPS: usize = 200;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Show progress bars for the following conversions.
+pub fn set_enabled(enabled: bool) {
+  ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Progress bar counting `steps` steps, hidden unless progress bars are enabled
+/// and there are enough steps.
+pub(crate) fn bar(steps: usize, message: &'static str) -> ProgressBar {
+  if !ENABLED.load(Ordering::Relaxed) || steps < MIN_STEPS {
+    return ProgressBar::hidden();
+  }
+  ProgressBar::new(steps as u64)
+    .with_style(
+      ProgressStyle::with_template("{msg:>14} [{bar:40}] {pos}/{len}")
+        .expect("valid progress template")
+        .progress_chars("=> "),
+    )
+    .with_message(message)
+}
@@ -0,0 +1,134 @@
+//! Custom rendering of item pages for library users.
+//!
+//! An [`ItemRenderer`] gets the Markdown of every item page body (the part after
+//! the front matter and breadcrumb) before it is written. Each item kind has its
+//! own method, which renders the page like the built-in converter unless it is
+//! overridden, so a renderer only implements the kinds it customizes.
+//!
+//! ```
+//! use cargo_doc_docusaurus::renderer::{CustomRenderer, ItemRenderer, RenderItem};
+//!
+//! struct StructCards;
+//!
+//! impl ItemRenderer for StructCards {
+//!   fn render_struct(&self, item: &RenderItem) -> Option<String> {
+//!     let markdown = item.default_markdown()?;
+//!     Some(format!("<StructCard path=\"{}\" />\n\n{}", item.path.join("::"), markdown))
+//!   }
+//! }
+//!
+//! let renderer = CustomRenderer::new(StructCards);
+//! ```
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum};
+use std::sync::Arc;
+
+/// An item being rendered, with the built-in rendering of its page.
+pub struct RenderItem<'a> {
+  /// ID of the item in the rustdoc JSON
+  pub id: &'a Id,
+  /// The item
+  pub item: &'a Item,
+  /// Full path of the item (e.g. `["my_crate", "config", "Config"]`)
+  pub path: &'a [String],
+  /// Crate the item belongs to
+  pub crate_data: &'a Crate,
+  pub(crate) default: &'a dyn Fn() -> Option<String>,
+}
+
+impl RenderItem<'_> {
+  /// Markdown of the item as the built-in converter renders it (`None` if the
+  /// item gets no page).
+  pub fn default_markdown(&self) -> Option<String> {
+    (self.default)()
+  }
+}
+
+/// Per-kind rendering of item pages. Every method defaults to
+/// [`RenderItem::default_markdown`]; returning `None` leaves the item without a page.
+pub trait ItemRenderer: Send + Sync {
+  /// Render any item, dispatching to the method of its kind.
+  fn render_item(&self, item: &RenderItem) -> Option<String> {
+    match &item.item.inner {
+      ItemEnum::Struct(_) => self.render_struct(item),
+      ItemEnum::Enum(_) => self.render_enum(item),
+      ItemEnum::Union(_) => self.render_union(item),
+      ItemEnum::Trait(_) => self.render_trait(item),
+      ItemEnum::TraitAlias(_) => self.render_trait_alias(item),
+      ItemEnum::Function(_) => self.render_function(item),
+      ItemEnum::TypeAlias(_) => self.render_type_alias(item),
+      ItemEnum::Constant { .. } => self.render_constant(item),
+      ItemEnum::Static(_) => self.render_static(item),
+      ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => self.render_macro(item),
+      ItemEnum::ExternType => self.render_foreign_type(item),
+      _ => item.default_markdown(),
+    }
+  }
+
+  fn render_struct(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_enum(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_union(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_trait(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_trait_alias(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_function(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_type_alias(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_constant(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_static(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  /// Render a declarative or procedural macro.
+  fn render_macro(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+
+  fn render_foreign_type(&self, item: &RenderItem) -> Option<String> {
+    item.default_markdown()
+  }
+}
+
+/// Shareable [`ItemRenderer`] set as the `item_renderer` of a conversion.
+#[derive(Clone)]
+pub struct CustomRenderer(Arc<dyn ItemRenderer>);
+
+impl CustomRenderer {
+  /// Wrap an item renderer.
+  pub fn new(renderer: impl ItemRenderer + 'static) -> Self {
+    Self(Arc::new(renderer))
+  }
+
+  /// Render an item with the wrapped renderer.
+  pub fn render(&self, item: &RenderItem) -> Option<String> {
+    self.0.render_item(item)
+  }
+}
+
+impl std::fmt::Debug for CustomRenderer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("CustomRenderer(..)")
+  }
+}
@@ -0,0 +1,135 @@
+//! Example Docusaurus site generator (`scaffold` subcommand)
+//!
+//! Creates a minimal Docusaurus project that is already set up for the
+//! generated documentation: the components and CSS, the generated sidebars
+//! imported into `sidebars.ts`, and a `doc-docusaurus.toml` writing the pages
+//! into the site's docs directory.
+
+use crate::components;
+use anyhow::{Context, Result, bail};
+use cargo_doc_docusaurus::config::CONFIG_FILE_NAME;
+use cargo_doc_docusaurus::converter::DocusaurusMajor;
+use cargo_doc_docusaurus::sidebar::{SidebarMap, render_typescript};
+use std::fs;
+use std::path::Path;
+
+/// Files of the site, with `{{name}}`, `{{title}}` and `{{config_path}}` placeholders
+const SITE_FILES: &[(&str, &str)] = &[
+  (
+    "package.json",
+    include_str!("../templates/scaffold/package.json.txt"),
+  ),
+  (
+    "tsconfig.json",
+    include_str!("../templates/scaffold/tsconfig.json.txt"),
+  ),
+  (
+    "docusaurus.config.ts",
+    include_str!("../templates/scaffold/docusaurus.config.ts.txt"),
+  ),
+  (
+    "sidebars.ts",
+    include_str!("../templates/scaffold/sidebars.ts.txt"),
+  ),
+  (
+    CONFIG_FILE_NAME,
+    include_str!("../templates/scaffold/doc-docusaurus.toml.txt"),
+  ),
+  (
+    "docs/intro.md",
+    include_str!("../templates/scaffold/intro.md.txt"),
+  ),
+  (
+    "src/css/custom.css",
+    include_str!("../templates/custom.css.txt"),
+  ),
+];
+
+/// Create a Docusaurus site set up for the generated documentation in `dir`.
+pub fn scaffold_site(dir: &Path) -> Result<()> {
+  if dir.join("package.json").exists() {
+    bail!(
+      "{} already contains a package.json (use `components init` to set up an existing site)",
+      dir.display()
+    );
+  }
+
+  let dir_name = dir
+    .canonicalize()
+    .unwrap_or_else(|_| dir.to_path_buf())
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let name = package_name(&dir_name);
+  let config_path = dir.join(CONFIG_FILE_NAME);
+
+  println!("🦀 Creating a Docusaurus site in {}\n", dir.display());
+  for (path, template) in SITE_FILES {
+    let content = template
+      .replace("{{name}}", &name)
+      .replace("{{title}}", &dir_name)
+      .replace("{{config_path}}", &config_path.display().to_string());
+    write_file(&dir.join(path), &content)?;
+    println!("  ✅ {}", path);
+  }
+
+  // sidebars.ts imports the generated sidebars, which need to exist before the
+  // first conversion for the site to build
+  write_file(
+    &dir.join("sidebars-rust.ts"),
+    &render_typescript(&SidebarMap::new(), DocusaurusMajor::V3),
+  )?;
+  println!("  ✅ sidebars-rust.ts (replaced by the conversion)");
+
+  let (installed, _) = components::install_templates(dir, DocusaurusMajor::V3)?;
+  println!("  ✅ {} components and styles", installed);
+
+  println!("\n📝 Next Steps:\n");
+  println!("1. Generate Rust documentation:");
+  println!("   RUSTDOCFLAGS=\"-Z unstable-options --output-format json\" \\");
+  println!("     cargo +nightly doc --no-deps\n");
+  println!("2. Convert it into the site:");
+  println!(
+    "   cargo doc-docusaurus target/doc/my_crate.json --config {}\n",
+    config_path.display()
+  );
+  println!("3. Start the site:");
+  println!("   cd {} && npm install && npm start", dir.display());
+
+  Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)
+      .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+  }
+  fs::write(path, content).with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+/// npm package name for a site directory (lowercase, no spaces or special characters).
+fn package_name(dir_name: &str) -> String {
+  let name: String = dir_name
+    .to_lowercase()
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+    .collect();
+  let name = name.trim_matches('-');
+  if name.is_empty() {
+    "rust-docs".to_string()
+  } else {
+    name.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_package_name() {
+    assert_eq!(package_name("My Docs"), "my-docs");
+    assert_eq!(package_name("api_site"), "api-site");
+    assert_eq!(package_name(""), "rust-docs");
+  }
+}
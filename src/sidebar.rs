@@ -0,0 +1,779 @@
+//! Docusaurus sidebar data model and rendering.
+//!
+//! The converter produces structured [`SidebarItem`]s; they are only rendered to
+//! TypeScript (or JSON) when written to disk. Alternatively, it describes the
+//! generated directories with [`CategoryMetadata`] for autogenerated sidebars.
+
+use crate::converter::DocusaurusMajor;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Sidebars of a crate, keyed by sidebar id (as used in `displayed_sidebar` front matter)
+pub type SidebarMap = BTreeMap<String, Vec<SidebarItem>>;
+
+/// Sidebar of a module, one of the variants the converter generates for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidebarKind {
+  /// Sidebar of a module page, or of the crate index page for the crate root
+  Module,
+  /// Contents of a module, shown on the pages of its submodules and items
+  Children,
+  /// Modules of a crate, shown on the pages of its top-level modules
+  CrateModules,
+  /// Contents of a crate root, shown on the pages of its items
+  CrateItems,
+}
+
+/// Key of a sidebar (as used in `displayed_sidebar` front matter), given the
+/// doc ID of the module's index directory (e.g. `api/my_crate/io`).
+///
+/// The doc ID is prefixed with the locale and docs version, suffixed with the
+/// sidebar kind, and `/` and `.` are replaced with `_` to make a valid id:
+/// `api/my_crate/io` is `1_2_api_my_crate_io_children` for the children sidebar
+/// of version 1.2.
+pub fn sidebar_key(
+  doc_id: &str,
+  kind: SidebarKind,
+  docs_version: Option<&str>,
+  locale: Option<&str>,
+) -> String {
+  let suffix = match kind {
+    SidebarKind::Module => "",
+    SidebarKind::Children => "_children",
+    SidebarKind::CrateModules => "_modules",
+    SidebarKind::CrateItems => "_items",
+  };
+  let path: Vec<&str> = [locale, docs_version, Some(doc_id.trim_matches('/'))]
+    .into_iter()
+    .flatten()
+    .collect();
+  format!("{}{}", path.join("/"), suffix).replace(['/', '.'], "_")
+}
+
+/// Icons shown next to sidebar entries (as `customProps.icon`), keyed by item kind
+pub type SidebarIcons = BTreeMap<String, String>;
+
+/// Item kinds that can be given a sidebar icon
+pub const SIDEBAR_ICON_KINDS: &[&str] = &[
+  "crate",
+  "module",
+  "struct",
+  "enum",
+  "trait",
+  "traitalias",
+  "fn",
+  "constant",
+  "type",
+  "macro",
+  "static",
+  "foreigntype",
+  "primitive",
+];
+
+/// Check that all sidebar icon kinds are known.
+pub fn validate_icons(icons: &SidebarIcons) -> Result<()> {
+  if let Some(kind) = icons
+    .keys()
+    .find(|kind| !SIDEBAR_ICON_KINDS.contains(&kind.as_str()))
+  {
+    return Err(Error::InvalidOptions(format!(
+      "Unknown sidebar icon kind '{}' (expected one of: {})",
+      kind,
+      SIDEBAR_ICON_KINDS.join(", ")
+    )));
+  }
+  Ok(())
+}
+
+/// Callback transforming the generated sidebars of a crate before they are written,
+/// e.g. to add, reorder or relabel entries.
+///
+/// ```
+/// use cargo_doc_docusaurus::sidebar::{SidebarHook, SidebarItem};
+///
+/// let hook = SidebarHook::new(|sidebars| {
+///   for items in sidebars.values_mut() {
+///     items.push(SidebarItem::Link {
+///       href: "https://github.com/me/my_crate".to_string(),
+///       label: "Source".to_string(),
+///       class_name: None,
+///       custom_props: None,
+///     });
+///   }
+/// });
+/// ```
+#[derive(Clone)]
+pub struct SidebarHook(Arc<dyn Fn(&mut SidebarMap) + Send + Sync>);
+
+impl SidebarHook {
+  /// Wrap a function transforming the sidebars in place.
+  pub fn new(hook: impl Fn(&mut SidebarMap) + Send + Sync + 'static) -> Self {
+    Self(Arc::new(hook))
+  }
+
+  /// Transform `sidebars` in place.
+  pub fn apply(&self, sidebars: &mut SidebarMap) {
+    (self.0)(sidebars)
+  }
+}
+
+impl std::fmt::Debug for SidebarHook {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("SidebarHook(..)")
+  }
+}
+
+/// Sidebar category levels that can start collapsed
+pub const SIDEBAR_LEVELS: &[&str] = &["crates", "modules", "items"];
+
+/// Which sidebar category levels are collapsible and start collapsed.
+///
+/// Categories of levels that are not collapsed stay open and cannot be collapsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct SidebarCollapse {
+  /// The "Crates" category listing workspace crates
+  pub crates: bool,
+  /// The `In <module>` categories wrapping the items of a module
+  pub modules: bool,
+  /// The item-type categories ("Structs", "Functions", ...)
+  pub items: bool,
+}
+
+impl SidebarCollapse {
+  /// Collapse all levels, or none.
+  pub fn all(collapsed: bool) -> Self {
+    Self {
+      crates: collapsed,
+      modules: collapsed,
+      items: collapsed,
+    }
+  }
+
+  /// Collapse the given levels (see [`SIDEBAR_LEVELS`]).
+  pub fn from_levels<S: AsRef<str>>(levels: &[S]) -> Result<Self> {
+    let mut collapse = Self::default();
+    for level in levels {
+      match level.as_ref() {
+        "crates" => collapse.crates = true,
+        "modules" => collapse.modules = true,
+        "items" => collapse.items = true,
+        other => {
+          return Err(Error::InvalidOptions(format!(
+            "Invalid sidebar level '{}', expected one of: {}",
+            other,
+            SIDEBAR_LEVELS.join(", ")
+          )));
+        }
+      }
+    }
+    Ok(collapse)
+  }
+}
+
+impl TryFrom<Vec<String>> for SidebarCollapse {
+  type Error = Error;
+
+  fn try_from(levels: Vec<String>) -> Result<Self> {
+    Self::from_levels(&levels)
+  }
+}
+
+/// Valid values for the sidebar file format
+pub const SIDEBAR_FORMATS: &[&str] = &["ts", "json", "js"];
+
+/// Format of the generated sidebar file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SidebarFormat {
+  /// TypeScript module exporting `rustSidebars` (`sidebars-rust.ts`)
+  #[default]
+  Ts,
+  /// Plain JSON data (`sidebars-rust.json`), loadable from any config flavor
+  Json,
+  /// CommonJS module exporting `rustSidebars` (`sidebars-rust.js`)
+  Js,
+}
+
+impl SidebarFormat {
+  /// Extension of sidebar files in this format.
+  pub fn extension(self) -> &'static str {
+    match self {
+      SidebarFormat::Ts => "ts",
+      SidebarFormat::Json => "json",
+      SidebarFormat::Js => "js",
+    }
+  }
+
+  /// Format of a sidebar file, from its extension (TypeScript unless `.json` or `.js`).
+  pub fn from_path(path: &std::path::Path) -> Self {
+    match path.extension().and_then(|ext| ext.to_str()) {
+      Some("json") => SidebarFormat::Json,
+      Some("js" | "cjs") => SidebarFormat::Js,
+      _ => SidebarFormat::Ts,
+    }
+  }
+}
+
+impl std::str::FromStr for SidebarFormat {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s {
+      "ts" => Ok(SidebarFormat::Ts),
+      "json" => Ok(SidebarFormat::Json),
+      "js" => Ok(SidebarFormat::Js),
+      _ => Err(Error::InvalidOptions(format!(
+        "Invalid sidebar format '{}', expected one of: {}",
+        s,
+        SIDEBAR_FORMATS.join(", ")
+      ))),
+    }
+  }
+}
+
+/// Represents a sidebar item for Docusaurus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SidebarItem {
+  /// A document reference with optional label
+  Doc {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(rename = "className", skip_serializing_if = "Option::is_none")]
+    class_name: Option<String>,
+    #[serde(rename = "customProps", skip_serializing_if = "Option::is_none")]
+    custom_props: Option<serde_json::Value>,
+  },
+  /// A link item (for dynamic sidebars)
+  Link {
+    href: String,
+    label: String,
+    #[serde(rename = "className", skip_serializing_if = "Option::is_none")]
+    class_name: Option<String>,
+    #[serde(rename = "customProps", skip_serializing_if = "Option::is_none")]
+    custom_props: Option<serde_json::Value>,
+  },
+  /// A category with sub-items
+  Category {
+    label: String,
+    /// Optional doc id to make the category clickable
+    #[serde(
+      default,
+      serialize_with = "serialize_category_link",
+      deserialize_with = "deserialize_category_link",
+      skip_serializing_if = "Option::is_none"
+    )]
+    link: Option<String>,
+    collapsible: bool,
+    collapsed: bool,
+    items: Vec<SidebarItem>,
+  },
+}
+
+impl SidebarItem {
+  /// Label shown in the sidebar (if any)
+  pub fn label(&self) -> &str {
+    match self {
+      SidebarItem::Doc { label, .. } => label.as_deref().unwrap_or(""),
+      SidebarItem::Link { label, .. } => label.as_str(),
+      SidebarItem::Category { label, .. } => label.as_str(),
+    }
+  }
+}
+
+fn serialize_category_link<S: serde::Serializer>(
+  link: &Option<String>,
+  serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+  #[derive(Serialize)]
+  struct DocLink<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    id: &'a str,
+  }
+
+  match link {
+    Some(id) => DocLink {
+      kind: "doc",
+      id: id.as_str(),
+    }
+    .serialize(serializer),
+    None => serializer.serialize_none(),
+  }
+}
+
+fn deserialize_category_link<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> std::result::Result<Option<String>, D::Error> {
+  #[derive(Deserialize)]
+  struct DocLink {
+    id: String,
+  }
+
+  Ok(Option::<DocLink>::deserialize(deserializer)?.map(|link| link.id))
+}
+
+/// Docusaurus category metadata (`_category_.json`) of a generated directory,
+/// used by autogenerated sidebars.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryMetadata {
+  pub label: String,
+  /// Position among the sibling entries (subdirectories come before pages)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub position: Option<usize>,
+  pub collapsible: bool,
+  pub collapsed: bool,
+  /// Doc id of the directory's index page, which the category links to
+  #[serde(
+    serialize_with = "serialize_category_link",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub link: Option<String>,
+  #[serde(rename = "className")]
+  pub class_name: String,
+}
+
+/// Render sidebars as JSON (an object of sidebar id -> items).
+pub fn render_json(sidebars: &SidebarMap) -> Result<String> {
+  Ok(serde_json::to_string_pretty(sidebars)?)
+}
+
+/// Start of the sidebar data in a CommonJS sidebar file
+const COMMONJS_SIDEBARS_START: &str = "const rustSidebars = ";
+
+/// Render sidebars (as JSON values, so existing files can be merged) as a
+/// CommonJS module exporting `rustSidebars`, `rootRustSidebar`,
+/// `rustApiDocumentation` and `rustApiCategory`.
+pub fn render_commonjs(sidebars: &BTreeMap<String, serde_json::Value>) -> Result<String> {
+  let mut output = String::new();
+  output.push_str("// This file is auto-generated by cargo-doc-md\n");
+  output.push_str("// Do not edit manually - this file will be regenerated\n\n");
+  output.push_str("// Rust API documentation sidebars, keyed by sidebar id\n");
+  output.push_str(&format!(
+    "{}{};\n\n",
+    COMMONJS_SIDEBARS_START,
+    serde_json::to_string_pretty(sidebars)?
+  ));
+
+  // Crate titles of all sidebars, once per crate
+  let root_items: BTreeMap<&str, &str> = sidebars
+    .values()
+    .filter_map(|items| items.as_array())
+    .flatten()
+    .filter(|item| item["customProps"]["rustCrateTitle"] == true)
+    .filter_map(|item| Some((item["id"].as_str()?, item["label"].as_str()?)))
+    .collect();
+  let root_items: Vec<serde_json::Value> = root_items
+    .into_iter()
+    .map(|(id, label)| {
+      serde_json::json!({ "type": "doc", "id": id, "label": label, "className": "rust-mod" })
+    })
+    .collect();
+  output.push_str("// Root sidebar with links to all crates (for main navigation)\n");
+  output.push_str(&format!(
+    "const rootRustSidebar = {};\n\n",
+    serde_json::to_string_pretty(&root_items)?
+  ));
+
+  let first_sidebar_key = sidebars.keys().next().map(String::as_str).unwrap_or("");
+  output.push_str(&format!(
+    "const rustApiDocumentation = rustSidebars[{}] || [];\n\n",
+    serde_json::to_string(first_sidebar_key)?
+  ));
+  output.push_str("const rustApiCategory = {\n");
+  output.push_str("  type: 'category',\n");
+  output.push_str("  label: 'API Documentation',\n");
+  output.push_str("  collapsed: false,\n");
+  output.push_str("  items: rustApiDocumentation,\n");
+  output.push_str("};\n\n");
+  output.push_str(
+    "module.exports = { rustSidebars, rootRustSidebar, rustApiDocumentation, rustApiCategory };\n",
+  );
+  Ok(output)
+}
+
+/// The sidebar data (JSON) of a CommonJS sidebar file written by [`render_commonjs`].
+pub fn parse_commonjs(content: &str) -> Option<&str> {
+  let start = content.find(COMMONJS_SIDEBARS_START)? + COMMONJS_SIDEBARS_START.len();
+  // JSON strings cannot contain raw newlines, so this is the end of the object
+  let end = start + content[start..].find(";\n")?;
+  Some(&content[start..end])
+}
+
+/// Render sidebars as a TypeScript module exporting `rustSidebars`.
+///
+/// Docusaurus 2 sites don't get the `SidebarsConfig` type import, which only
+/// the packages of recent versions export.
+pub fn render_typescript(sidebars: &SidebarMap, docusaurus_major: DocusaurusMajor) -> String {
+  let mut output = String::new();
+
+  output.push_str("// This file is auto-generated by cargo-doc-md\n");
+  output.push_str("// Do not edit manually - this file will be regenerated\n\n");
+  if docusaurus_major == DocusaurusMajor::V3 {
+    output.push_str("import type {SidebarsConfig} from '@docusaurus/plugin-content-docs';\n\n");
+  }
+  output.push_str("// Rust API documentation sidebars\n");
+  output.push_str("// Each module has its own sidebar for better navigation\n");
+  output.push_str("// Import this in your docusaurus.config.ts:\n");
+  output.push_str("// import { rustSidebars } from './sidebars-rust';\n");
+  output.push_str("//\n");
+  output.push_str("// Then configure in docs plugin:\n");
+  output.push_str("// docs: {\n");
+  output.push_str("//   sidebarPath: './sidebars.ts',\n");
+  output
+    .push_str("//   async sidebarItemsGenerator({ defaultSidebarItemsGenerator, ...args }) {\n");
+  output.push_str("//     const items = await defaultSidebarItemsGenerator(args);\n");
+  output.push_str("//     const docPath = args.item.id;\n");
+  output.push_str("//     // Use module-specific sidebar if available\n");
+  output.push_str("//     for (const [path, sidebar] of Object.entries(rustSidebars)) {\n");
+  output.push_str("//       if (docPath.startsWith(path + '/')) {\n");
+  output.push_str("//         return sidebar;\n");
+  output.push_str("//       }\n");
+  output.push_str("//     }\n");
+  output.push_str("//     return items;\n");
+  output.push_str("//   },\n");
+  output.push_str("// }\n\n");
+
+  output.push_str("export const rustSidebars: Record<string, any[]> = {\n");
+
+  // BTreeMap keys are already sorted for consistent output
+  for (sidebar_key, items) in sidebars {
+    output.push_str(&format!("  {}: [\n", js_string(sidebar_key)));
+    for item in items {
+      output.push_str(&format_sidebar_item(item, 2));
+    }
+    output.push_str("  ],\n");
+  }
+
+  output.push_str("};\n\n");
+
+  output.push_str("// Root sidebar with links to all crates (for main navigation)\n");
+  output.push_str("export const rootRustSidebar = [\n");
+  for item in root_sidebar_items(sidebars) {
+    output.push_str(&format_sidebar_item(&item, 1));
+  }
+  output.push_str("];\n\n");
+
+  // Also export the main sidebar for backward compatibility
+  if let Some(first_sidebar_key) = sidebars.keys().next() {
+    output.push_str("// Main API documentation sidebar (for backward compatibility)\n");
+    output.push_str(&format!(
+      "export const rustApiDocumentation = rustSidebars[{}];\n\n",
+      js_string(first_sidebar_key)
+    ));
+    output.push_str("// Or use as a single category:\n");
+    output.push_str("export const rustApiCategory = {\n");
+    output.push_str("  type: 'category' as const,\n");
+    output.push_str("  label: 'API Documentation',\n");
+    output.push_str("  collapsed: false,\n");
+    output.push_str("  items: rustApiDocumentation,\n");
+    output.push_str("};\n");
+  }
+
+  output
+}
+
+/// Links to the crate title page of every crate in the sidebars, once per crate.
+fn root_sidebar_items(sidebars: &SidebarMap) -> Vec<SidebarItem> {
+  let crates: BTreeMap<&str, &str> = sidebars
+    .values()
+    .flatten()
+    .filter_map(|item| match item {
+      SidebarItem::Doc {
+        id,
+        label: Some(label),
+        custom_props: Some(props),
+        ..
+      } if props["rustCrateTitle"] == true => Some((id.as_str(), label.as_str())),
+      _ => None,
+    })
+    .collect();
+  crates
+    .into_iter()
+    .map(|(id, label)| SidebarItem::Doc {
+      id: id.to_string(),
+      label: Some(label.to_string()),
+      class_name: Some("rust-mod".to_string()),
+      custom_props: None,
+    })
+    .collect()
+}
+
+/// Format a single sidebar item with proper indentation
+fn format_sidebar_item(item: &SidebarItem, indent: usize) -> String {
+  let indent_str = "  ".repeat(indent);
+
+  match item {
+    SidebarItem::Doc {
+      id,
+      label,
+      class_name,
+      custom_props,
+    } => {
+      // Remove .md extension if present and convert to doc ID
+      let doc_id = id.trim_end_matches(".md").replace(".md", "");
+
+      // If we have a label, className or customProps, create an object; otherwise a plain string reference
+      if label.is_none() && class_name.is_none() && custom_props.is_none() {
+        return format!("{}{},\n", indent_str, js_string(&doc_id));
+      }
+
+      let mut output = format!("{}{{ type: 'doc', id: {}", indent_str, js_string(&doc_id));
+      if let Some(label_text) = label {
+        output.push_str(&format!(", label: {}", js_string(label_text)));
+      }
+      output.push_str(&format_item_props(class_name, custom_props));
+      output.push_str(" },\n");
+      output
+    }
+    SidebarItem::Link {
+      href,
+      label,
+      class_name,
+      custom_props,
+    } => {
+      // Generate a link item with href
+      let mut output = format!(
+        "{}{{ type: 'link', href: {}, label: {}",
+        indent_str,
+        js_string(href),
+        js_string(label)
+      );
+      output.push_str(&format_item_props(class_name, custom_props));
+      output.push_str(" },\n");
+      output
+    }
+    SidebarItem::Category {
+      label,
+      link,
+      collapsible,
+      collapsed,
+      items,
+    } => {
+      let mut output = String::new();
+      output.push_str(&format!("{}{{\n", indent_str));
+      output.push_str(&format!("{}  type: 'category',\n", indent_str));
+      output.push_str(&format!("{}  label: {},\n", indent_str, js_string(label)));
+
+      // Add link if present (makes the category clickable)
+      if let Some(link_path) = link {
+        let doc_id = link_path.trim_end_matches(".md").replace(".md", "");
+        output.push_str(&format!("{}  link: {{\n", indent_str));
+        output.push_str(&format!("{}    type: 'doc',\n", indent_str));
+        output.push_str(&format!("{}    id: {},\n", indent_str, js_string(&doc_id)));
+        output.push_str(&format!("{}  }},\n", indent_str));
+      }
+
+      if *collapsible {
+        output.push_str(&format!("{}  collapsed: {},\n", indent_str, collapsed));
+      } else {
+        output.push_str(&format!("{}  collapsible: false,\n", indent_str));
+      }
+
+      output.push_str(&format!("{}  items: [\n", indent_str));
+
+      for sub_item in items {
+        output.push_str(&format_sidebar_item(sub_item, indent + 2));
+      }
+
+      output.push_str(&format!("{}  ],\n", indent_str));
+      output.push_str(&format!("{}}},\n", indent_str));
+      output
+    }
+  }
+}
+
+/// Format the optional `className` / `customProps` of a doc or link item
+fn format_item_props(
+  class_name: &Option<String>,
+  custom_props: &Option<serde_json::Value>,
+) -> String {
+  let mut output = String::new();
+  if let Some(class_name) = class_name {
+    output.push_str(&format!(", className: {}", js_string(class_name)));
+  }
+  if let Some(props) = custom_props {
+    output.push_str(&format!(", customProps: {}", format_js_value(props)));
+  }
+  output
+}
+
+/// Format a JSON value as a JavaScript literal (`{ key: 'value' }` style)
+fn format_js_value(value: &serde_json::Value) -> String {
+  match value {
+    serde_json::Value::Object(map) => {
+      let fields: Vec<String> = map
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, format_js_value(value)))
+        .collect();
+      format!("{{ {} }}", fields.join(", "))
+    }
+    serde_json::Value::Array(values) => {
+      let values: Vec<String> = values.iter().map(format_js_value).collect();
+      format!("[{}]", values.join(", "))
+    }
+    serde_json::Value::String(s) => js_string(s),
+    other => other.to_string(),
+  }
+}
+
+/// Format a string as a single-quoted JavaScript string literal
+fn js_string(s: &str) -> String {
+  format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sidebar_key() {
+    assert_eq!(
+      sidebar_key("my_crate", SidebarKind::Module, None, None),
+      "my_crate"
+    );
+    assert_eq!(
+      sidebar_key("api/my_crate", SidebarKind::CrateItems, None, None),
+      "api_my_crate_items"
+    );
+    assert_eq!(
+      sidebar_key(
+        "api/my_crate/io",
+        SidebarKind::Children,
+        Some("1.2"),
+        Some("fr")
+      ),
+      "fr_1_2_api_my_crate_io_children"
+    );
+    assert_eq!(
+      sidebar_key("/my-crate/v2.0/", SidebarKind::CrateModules, None, None),
+      "my-crate_v2_0_modules"
+    );
+  }
+
+  fn sample_sidebars() -> SidebarMap {
+    let mut sidebars = SidebarMap::new();
+    sidebars.insert(
+      "my_crate".to_string(),
+      vec![
+        SidebarItem::Doc {
+          id: "my_crate/index".to_string(),
+          label: Some("my_crate".to_string()),
+          class_name: None,
+          custom_props: Some(
+            serde_json::json!({ "rustCrateTitle": true, "crateName": "my_crate" }),
+          ),
+        },
+        SidebarItem::Category {
+          label: "Structs".to_string(),
+          link: Some("my_crate/index".to_string()),
+          collapsible: false,
+          collapsed: false,
+          items: vec![SidebarItem::Doc {
+            id: "my_crate/struct.Foo".to_string(),
+            label: Some("Foo".to_string()),
+            class_name: Some("rust-struct".to_string()),
+            custom_props: None,
+          }],
+        },
+      ],
+    );
+    sidebars
+  }
+
+  #[test]
+  fn test_render_typescript() {
+    let ts = render_typescript(&sample_sidebars(), DocusaurusMajor::V3);
+
+    assert!(ts.contains("export const rustSidebars: Record<string, any[]> = {\n  'my_crate': [\n"));
+    assert!(ts.contains(
+      "{ type: 'doc', id: 'my_crate/index', label: 'my_crate', customProps: { rustCrateTitle: true, crateName: 'my_crate' } },"
+    ));
+    assert!(ts.contains(
+      "{ type: 'doc', id: 'my_crate/struct.Foo', label: 'Foo', className: 'rust-struct' },"
+    ));
+    assert!(ts.contains("collapsible: false,"));
+    assert!(ts.contains("export const rustApiDocumentation = rustSidebars['my_crate'];"));
+    assert!(ts.contains(
+      "export const rootRustSidebar = [\n  { type: 'doc', id: 'my_crate/index', label: 'my_crate', className: 'rust-mod' },\n];"
+    ));
+    assert!(ts.contains("import type {SidebarsConfig}"));
+    assert!(!render_typescript(&sample_sidebars(), DocusaurusMajor::V2).contains("SidebarsConfig"));
+  }
+
+  #[test]
+  fn test_sidebar_collapse_levels() {
+    assert_eq!(
+      SidebarCollapse::from_levels(&["crates", "items"]).unwrap(),
+      SidebarCollapse {
+        crates: true,
+        modules: false,
+        items: true,
+      }
+    );
+    assert_eq!(
+      SidebarCollapse::from_levels::<&str>(&[]).unwrap(),
+      SidebarCollapse::all(false)
+    );
+    assert!(SidebarCollapse::from_levels(&["structs"]).is_err());
+  }
+
+  #[test]
+  fn test_render_commonjs() {
+    let sidebars: BTreeMap<String, serde_json::Value> = sample_sidebars()
+      .into_iter()
+      .map(|(key, items)| (key, serde_json::to_value(items).unwrap()))
+      .collect();
+    let js = render_commonjs(&sidebars).unwrap();
+
+    assert!(js.contains("module.exports = { rustSidebars, rootRustSidebar,"));
+    assert!(js.contains("const rustApiDocumentation = rustSidebars[\"my_crate\"] || [];"));
+    assert!(js.contains("\"className\": \"rust-mod\""));
+    // The sidebar data can be read back for merging
+    let data: BTreeMap<String, serde_json::Value> =
+      serde_json::from_str(parse_commonjs(&js).unwrap()).unwrap();
+    assert_eq!(data, sidebars);
+  }
+
+  #[test]
+  fn test_category_metadata() {
+    let category = CategoryMetadata {
+      label: "mod_a".to_string(),
+      position: Some(1),
+      collapsible: true,
+      collapsed: false,
+      link: Some("api/my_crate/mod_a/index".to_string()),
+      class_name: "rust-mod".to_string(),
+    };
+    assert_eq!(
+      serde_json::to_value(&category).unwrap(),
+      serde_json::json!({
+        "label": "mod_a",
+        "position": 1,
+        "collapsible": true,
+        "collapsed": false,
+        "link": { "type": "doc", "id": "api/my_crate/mod_a/index" },
+        "className": "rust-mod",
+      })
+    );
+  }
+
+  #[test]
+  fn test_render_json() {
+    let json: serde_json::Value =
+      serde_json::from_str(&render_json(&sample_sidebars()).unwrap()).unwrap();
+    let items = &json["my_crate"];
+
+    assert_eq!(items[0]["type"], "doc");
+    assert_eq!(items[0]["customProps"]["rustCrateTitle"], true);
+    assert!(items[0].get("className").is_none());
+    assert_eq!(items[1]["type"], "category");
+    assert_eq!(items[1]["link"]["type"], "doc");
+    assert_eq!(items[1]["link"]["id"], "my_crate/index");
+    assert_eq!(items[1]["items"][0]["className"], "rust-struct");
+  }
+}
@@ -0,0 +1,323 @@
+//! Wiring of the generated sidebars into a Docusaurus site (`--patch-config`).
+//!
+//! Generated pages select their sidebar from `rustSidebars` with
+//! `displayed_sidebar`, so the site's sidebars file has to spread
+//! `rustSidebars` into its sidebars and the docs plugin has to load that file.
+
+use crate::converter::DocusaurusMajor;
+use crate::error::{Error, Result};
+use crate::sidebar::SidebarFormat;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Sidebars files of a site, in the order Docusaurus' templates use them
+const SIDEBARS_FILES: &[&str] = &["sidebars.ts", "sidebars.js"];
+
+/// Docusaurus config files of a site
+const CONFIG_FILES: &[&str] = &["docusaurus.config.ts", "docusaurus.config.js"];
+
+/// Directory of the site the generated sidebar file belongs to.
+fn site_dir(sidebar_path: &Path) -> &Path {
+  match sidebar_path.parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => parent,
+    _ => Path::new("."),
+  }
+}
+
+/// The sidebars file of the site, or `sidebars.ts` when there is none yet.
+fn sidebars_file(site_dir: &Path) -> &'static str {
+  SIDEBARS_FILES
+    .iter()
+    .find(|file| site_dir.join(file).exists())
+    .copied()
+    .unwrap_or(SIDEBARS_FILES[0])
+}
+
+/// Major version of the Docusaurus site containing `dir`, from the
+/// `@docusaurus/core` dependency in the nearest `package.json` declaring it.
+pub fn detect_docusaurus_major(dir: &Path) -> Option<DocusaurusMajor> {
+  dir.ancestors().find_map(|dir| {
+    let package_json = fs::read_to_string(dir.join("package.json")).ok()?;
+    let major = docusaurus_major(&package_json)?;
+    log::debug!(
+      "Detected Docusaurus {} from {}",
+      u8::from(major),
+      dir.join("package.json").display()
+    );
+    Some(major)
+  })
+}
+
+/// Docusaurus major version required by a `package.json`, `None` when it has
+/// no supported `@docusaurus/core` dependency.
+fn docusaurus_major(package_json: &str) -> Option<DocusaurusMajor> {
+  let package: serde_json::Value = serde_json::from_str(package_json).ok()?;
+  let requirement = ["dependencies", "devDependencies"]
+    .iter()
+    .find_map(|key| package[key]["@docusaurus/core"].as_str())?;
+  // e.g. "^3.5.0", "~2.4.3" or ">=3"
+  let version = requirement.trim_start_matches(|c: char| !c.is_ascii_digit());
+  let major = version.split('.').next()?.parse::<u8>().ok()?;
+  DocusaurusMajor::try_from(major).ok()
+}
+
+/// Import of `rustSidebars` from the generated sidebar file, for a sidebars
+/// file in the same directory.
+pub fn sidebars_import(sidebar_path: &Path, sidebars_file: &str) -> String {
+  let format = SidebarFormat::from_path(sidebar_path);
+  let file = match format {
+    SidebarFormat::Json => sidebar_path.file_name(),
+    _ => sidebar_path.file_stem(),
+  }
+  .map(|name| name.to_string_lossy().into_owned())
+  .unwrap_or_default();
+
+  match (sidebars_file.ends_with(".ts"), format) {
+    (true, SidebarFormat::Json) => format!("import rustSidebars from './{}';", file),
+    (true, _) => format!("import {{rustSidebars}} from './{}';", file),
+    (false, SidebarFormat::Json) => format!("const rustSidebars = require('./{}');", file),
+    (false, _) => format!("const {{rustSidebars}} = require('./{}');", file),
+  }
+}
+
+/// Ready-to-paste snippets wiring the generated sidebar file into its site.
+pub fn wiring_snippet(sidebar_path: &Path) -> String {
+  let sidebars_file = sidebars_file(site_dir(sidebar_path));
+  let declaration = if sidebars_file.ends_with(".ts") {
+    "const sidebars: SidebarsConfig = {"
+  } else {
+    "module.exports = {"
+  };
+  let config_file = if sidebars_file.ends_with(".ts") {
+    CONFIG_FILES[0]
+  } else {
+    CONFIG_FILES[1]
+  };
+  format!(
+    "// {sidebars_file}\n\
+     {}\n\
+     \n\
+     {declaration}\n  \
+     // ...your sidebars\n  \
+     ...rustSidebars,\n\
+     }};\n\
+     \n\
+     // {config_file} (options of the classic preset's docs plugin)\n\
+     docs: {{\n  \
+     sidebarPath: './{sidebars_file}',\n\
+     }},\n",
+    sidebars_import(sidebar_path, sidebars_file),
+  )
+}
+
+/// Add the generated sidebars to the sidebars file and Docusaurus config of
+/// the site containing `sidebar_path`, keeping `.bak` copies of changed files.
+///
+/// Files that already reference `rustSidebars` or a `sidebarPath` are left
+/// unchanged. Returns the changed files.
+pub fn patch_site_config(sidebar_path: &Path) -> Result<Vec<PathBuf>> {
+  let site_dir = site_dir(sidebar_path);
+  let sidebars_file = sidebars_file(site_dir);
+  let sidebars_path = site_dir.join(sidebars_file);
+  if !sidebars_path.exists() {
+    return Err(Error::InvalidOptions(format!(
+      "--patch-config found no sidebars.ts or sidebars.js in {}",
+      site_dir.display()
+    )));
+  }
+
+  let mut patched = Vec::new();
+  let content = fs::read_to_string(&sidebars_path).map_err(Error::read(&sidebars_path))?;
+  if !content.contains("rustSidebars") {
+    let import = sidebars_import(sidebar_path, sidebars_file);
+    let new_content = patch_sidebars(&content, &import)
+      .ok_or_else(|| manual_wiring_error(&sidebars_path, sidebar_path))?;
+    write_with_backup(&sidebars_path, &content, &new_content)?;
+    patched.push(sidebars_path);
+  }
+
+  if let Some(config_path) = CONFIG_FILES
+    .iter()
+    .map(|file| site_dir.join(file))
+    .find(|path| path.exists())
+  {
+    let content = fs::read_to_string(&config_path).map_err(Error::read(&config_path))?;
+    if !content.contains("sidebarPath") {
+      let new_content = patch_config(&content, sidebars_file)
+        .ok_or_else(|| manual_wiring_error(&config_path, sidebar_path))?;
+      write_with_backup(&config_path, &content, &new_content)?;
+      patched.push(config_path);
+    }
+  }
+
+  Ok(patched)
+}
+
+fn manual_wiring_error(path: &Path, sidebar_path: &Path) -> Error {
+  Error::Sidebar(format!(
+    "Could not patch {}, add the generated sidebars manually:\n\n{}",
+    path.display(),
+    wiring_snippet(sidebar_path)
+  ))
+}
+
+fn write_with_backup(path: &Path, content: &str, new_content: &str) -> Result<()> {
+  let backup_path = PathBuf::from(format!("{}.bak", path.display()));
+  fs::write(&backup_path, content).map_err(Error::write(&backup_path))?;
+  fs::write(path, new_content).map_err(Error::write(path))?;
+  log::info!(
+    "✓ Patched {} (backup: {})",
+    path.display(),
+    backup_path.display()
+  );
+  Ok(())
+}
+
+/// Import `rustSidebars` and spread it into the sidebars object, `None` when
+/// the sidebars object is not found.
+fn patch_sidebars(content: &str, import: &str) -> Option<String> {
+  let mut lines: Vec<&str> = content.lines().collect();
+
+  let object_line = lines.iter().position(|line| {
+    let line = line.trim_end();
+    line.ends_with('{')
+      && (line.starts_with("const sidebars")
+        || line.starts_with("module.exports =")
+        || line.starts_with("export default {"))
+  })?;
+  let indent =
+    &lines[object_line][..lines[object_line].len() - lines[object_line].trim_start().len()];
+  let spread = format!("{}  ...rustSidebars,", indent);
+  lines.insert(object_line + 1, &spread);
+
+  // After the last import, including its continuation lines
+  let import_line = match lines[..object_line].iter().rposition(|line| {
+    line.starts_with("import ") || (line.starts_with("const ") && line.contains("require("))
+  }) {
+    Some(start) => {
+      let end = lines[start..object_line]
+        .iter()
+        .position(|line| line.trim_end().ends_with(';'))
+        .map_or(start, |offset| start + offset);
+      end + 1
+    }
+    None => 0,
+  };
+  lines.insert(import_line, import);
+  if import_line == 0 {
+    lines.insert(1, "");
+  }
+
+  let mut output = lines.join("\n");
+  output.push('\n');
+  Some(output)
+}
+
+/// Set the `sidebarPath` of the docs plugin options, `None` when they are not found.
+fn patch_config(content: &str, sidebars_file: &str) -> Option<String> {
+  let mut lines: Vec<&str> = content.lines().collect();
+  let docs_line = lines.iter().position(|line| line.trim() == "docs: {")?;
+  let indent = &lines[docs_line][..lines[docs_line].len() - lines[docs_line].trim_start().len()];
+  let sidebar_path = format!("{}  sidebarPath: './{}',", indent, sidebars_file);
+  lines.insert(docs_line + 1, &sidebar_path);
+
+  let mut output = lines.join("\n");
+  output.push('\n');
+  Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sidebars_import() {
+    assert_eq!(
+      sidebars_import(Path::new("website/sidebars-rust.ts"), "sidebars.ts"),
+      "import {rustSidebars} from './sidebars-rust';"
+    );
+    assert_eq!(
+      sidebars_import(Path::new("sidebars-rust.json"), "sidebars.js"),
+      "const rustSidebars = require('./sidebars-rust.json');"
+    );
+  }
+
+  #[test]
+  fn test_detect_docusaurus_major() {
+    assert_eq!(
+      docusaurus_major(r#"{"dependencies": {"@docusaurus/core": "^2.4.3"}}"#),
+      Some(DocusaurusMajor::V2)
+    );
+    assert_eq!(
+      docusaurus_major(r#"{"devDependencies": {"@docusaurus/core": "3.5.2"}}"#),
+      Some(DocusaurusMajor::V3)
+    );
+    assert_eq!(
+      docusaurus_major(r#"{"dependencies": {"@docusaurus/core": "^1.14.0"}}"#),
+      None
+    );
+    assert_eq!(docusaurus_major(r#"{"dependencies": {}}"#), None);
+
+    let site = tempfile::tempdir().unwrap();
+    fs::write(
+      site.path().join("package.json"),
+      r#"{"dependencies": {"@docusaurus/core": "~2.0.0"}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+      detect_docusaurus_major(&site.path().join("docs").join("api")),
+      Some(DocusaurusMajor::V2)
+    );
+  }
+
+  #[test]
+  fn test_patch_site_config() {
+    let site = tempfile::tempdir().unwrap();
+    let sidebars = "import type {SidebarsConfig} from '@docusaurus/plugin-content-docs';\n\
+                    \n\
+                    const sidebars: SidebarsConfig = {\n  \
+                    tutorial: [{type: 'autogenerated', dirName: '.'}],\n\
+                    };\n\
+                    \n\
+                    export default sidebars;\n";
+    let config = "export default {\n  \
+                  presets: [\n    \
+                  ['classic', {\n      \
+                  docs: {\n        \
+                  path: 'docs',\n      \
+                  },\n    \
+                  }],\n  \
+                  ],\n\
+                  };\n";
+    fs::write(site.path().join("sidebars.ts"), sidebars).unwrap();
+    fs::write(site.path().join("docusaurus.config.ts"), config).unwrap();
+    let sidebar_path = site.path().join("sidebars-rust.ts");
+
+    let patched = patch_site_config(&sidebar_path).unwrap();
+    assert_eq!(patched.len(), 2);
+    assert_eq!(
+      fs::read_to_string(site.path().join("sidebars.ts")).unwrap(),
+      "import type {SidebarsConfig} from '@docusaurus/plugin-content-docs';\n\
+       import {rustSidebars} from './sidebars-rust';\n\
+       \n\
+       const sidebars: SidebarsConfig = {\n  \
+       ...rustSidebars,\n  \
+       tutorial: [{type: 'autogenerated', dirName: '.'}],\n\
+       };\n\
+       \n\
+       export default sidebars;\n"
+    );
+    assert!(
+      fs::read_to_string(site.path().join("docusaurus.config.ts"))
+        .unwrap()
+        .contains("      docs: {\n        sidebarPath: './sidebars.ts',\n        path: 'docs',\n")
+    );
+    assert_eq!(
+      fs::read_to_string(site.path().join("sidebars.ts.bak")).unwrap(),
+      sidebars
+    );
+
+    // Patching again changes nothing
+    assert!(patch_site_config(&sidebar_path).unwrap().is_empty());
+  }
+}
@@ -0,0 +1,96 @@
+//! Statistics of a conversion (`--stats`), to track the size of the docs in CI.
+
+use crate::converter::MarkdownOutput;
+use crate::coverage::CoverageReport;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Statistics of a conversion, returned by [`crate::convert_json_file`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConversionStats {
+  /// Name of the converted crate
+  pub crate_name: String,
+  /// Public modules, the crate root included
+  pub modules: usize,
+  /// Public items by kind (e.g. `Struct`), modules and re-exports excluded
+  pub items: BTreeMap<&'static str, usize>,
+  /// Public modules and items without doc comment
+  pub undocumented: usize,
+  /// Files written to the crate's output directory (the sidebar file excluded)
+  pub files: usize,
+  /// Total size of the written files in bytes
+  pub bytes: usize,
+  /// Duration of the conversion
+  #[serde(rename = "elapsed_secs", serialize_with = "serialize_secs")]
+  pub elapsed: Duration,
+}
+
+impl ConversionStats {
+  pub(crate) fn new(coverage: &CoverageReport, output: &MarkdownOutput, elapsed: Duration) -> Self {
+    Self {
+      crate_name: output.crate_name.clone(),
+      modules: coverage.modules.len(),
+      items: coverage
+        .kinds
+        .iter()
+        .filter(|(kind, _)| **kind != "Module")
+        .map(|(kind, coverage)| (*kind, coverage.total))
+        .collect(),
+      undocumented: coverage.total.total - coverage.total.documented,
+      files: output.files.len(),
+      bytes: output.files.values().map(String::len).sum(),
+      elapsed,
+    }
+  }
+}
+
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Render the statistics of a conversion for the terminal.
+pub fn render_stats(stats: &ConversionStats) -> String {
+  let items: Vec<String> = stats
+    .items
+    .iter()
+    .map(|(kind, count)| format!("{} {}", count, kind))
+    .collect();
+  format!(
+    "{}: {} modules, {} items ({}), {} undocumented\n{} files, {} bytes, {:.2}s\n",
+    stats.crate_name,
+    stats.modules,
+    stats.items.values().sum::<usize>(),
+    items.join(", "),
+    stats.undocumented,
+    stats.files,
+    stats.bytes,
+    stats.elapsed.as_secs_f64()
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_stats() {
+    let stats = ConversionStats {
+      crate_name: "my_crate".to_string(),
+      modules: 2,
+      items: BTreeMap::from([("Function", 3), ("Struct", 1)]),
+      undocumented: 1,
+      files: 7,
+      bytes: 12345,
+      elapsed: Duration::from_millis(250),
+    };
+    assert_eq!(
+      render_stats(&stats),
+      "my_crate: 2 modules, 4 items (3 Function, 1 Struct), 1 undocumented\n\
+       7 files, 12345 bytes, 0.25s\n"
+    );
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["elapsed_secs"], 0.25);
+    assert_eq!(json["items"]["Struct"], 1);
+  }
+}
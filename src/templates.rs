@@ -0,0 +1,140 @@
+//! Page templates laying out the generated pages.
+//!
+//! Item pages, module overviews and the crate index are rendered into templates
+//! with `{{name}}` placeholders: `{{title}}`, `{{frontmatter}}` (the generated
+//! front matter fields, one per line), `{{breadcrumb}}` (item and module pages)
+//! and `{{content}}`. The built-in templates are embedded; a template directory
+//! (`template_dir`) overrides them by file name (`item.md`, `module.md`, `crate.md`).
+
+use crate::error::{Error, Result};
+use std::path::Path;
+
+/// A page laid out by a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageTemplate {
+  /// Page of an item (struct, function, macro, ...)
+  Item,
+  /// Overview page of a module
+  Module,
+  /// Index page of a crate
+  Crate,
+}
+
+impl PageTemplate {
+  /// All page templates.
+  pub const ALL: [PageTemplate; 3] = [
+    PageTemplate::Item,
+    PageTemplate::Module,
+    PageTemplate::Crate,
+  ];
+
+  /// File name of the template in a template directory.
+  pub fn file_name(self) -> &'static str {
+    match self {
+      PageTemplate::Item => "item.md",
+      PageTemplate::Module => "module.md",
+      PageTemplate::Crate => "crate.md",
+    }
+  }
+
+  /// The built-in template.
+  pub fn builtin(self) -> &'static str {
+    match self {
+      PageTemplate::Item => include_str!("../templates/pages/item.md.txt"),
+      PageTemplate::Module => include_str!("../templates/pages/module.md.txt"),
+      PageTemplate::Crate => include_str!("../templates/pages/crate.md.txt"),
+    }
+  }
+}
+
+/// Templates of the pages: the built-in ones, unless overridden.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageTemplates {
+  overrides: [Option<String>; 3],
+}
+
+impl PageTemplates {
+  /// Load the templates of `dir`, keeping the built-in ones for missing files.
+  pub fn load(dir: &Path) -> Result<Self> {
+    std::fs::read_dir(dir).map_err(Error::read(dir))?;
+    let mut templates = Self::default();
+    for template in PageTemplate::ALL {
+      let path = dir.join(template.file_name());
+      if path.is_file() {
+        templates.overrides[template as usize] =
+          Some(std::fs::read_to_string(&path).map_err(Error::read(&path))?);
+      }
+    }
+    Ok(templates)
+  }
+
+  /// Template used for a page.
+  pub fn get(&self, template: PageTemplate) -> &str {
+    self.overrides[template as usize]
+      .as_deref()
+      .unwrap_or(template.builtin())
+  }
+
+  /// Render a page, replacing the `{{name}}` placeholders with the values of `vars`.
+  ///
+  /// Placeholders are replaced in a single pass, so values are never expanded
+  /// themselves; unknown placeholders are kept as they are.
+  pub fn render(&self, template: PageTemplate, vars: &[(&str, &str)]) -> String {
+    render_template(self.get(template), vars)
+  }
+}
+
+fn render_template(template: &str, vars: &[(&str, &str)]) -> String {
+  let mut output = String::with_capacity(template.len());
+  let mut rest = template;
+  while let Some(start) = rest.find("{{") {
+    output.push_str(&rest[..start]);
+    let after = &rest[start + 2..];
+    let value = after.find("}}").and_then(|end| {
+      let name = after[..end].trim();
+      let (_, value) = vars.iter().find(|(var, _)| *var == name)?;
+      Some((*value, end))
+    });
+    match value {
+      Some((value, end)) => {
+        output.push_str(value);
+        rest = &after[end + 2..];
+      }
+      None => {
+        output.push_str("{{");
+        rest = after;
+      }
+    }
+  }
+  output.push_str(rest);
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_template() {
+    assert_eq!(
+      render_template(
+        "# {{ title }}\n{{content}} {{unknown}} {{",
+        &[("title", "{{content}}"), ("content", "Body")]
+      ),
+      "# {{content}}\nBody {{unknown}} {{"
+    );
+  }
+
+  #[test]
+  fn test_load_templates() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("item.md"), "{{content}}").unwrap();
+    let templates = PageTemplates::load(dir.path()).unwrap();
+    assert_eq!(templates.get(PageTemplate::Item), "{{content}}");
+    assert_eq!(
+      templates.get(PageTemplate::Crate),
+      PageTemplate::Crate.builtin()
+    );
+    assert!(PageTemplates::load(&dir.path().join("missing")).is_err());
+  }
+}
@@ -0,0 +1,232 @@
+//! Module tree of a crate with item counts (`tree` subcommand).
+//!
+//! Shows how many items of each kind and visibility every module contains,
+//! to help decide on filters like `--include-private` or `--max-depth`
+//! before generating a site.
+
+use rustdoc_types::{Crate, Id, ItemEnum, Visibility};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A module with the items it directly contains.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleTree {
+  /// Name of the module (the crate name for the root)
+  pub name: String,
+  /// Full path of the module (e.g. `my_crate::nested::inner`)
+  pub path: String,
+  /// Visibility of the module itself
+  pub visibility: &'static str,
+  /// Number of items per kind (e.g. `Struct`), excluding submodules
+  pub kinds: BTreeMap<&'static str, usize>,
+  /// Number of items per visibility (`public`, `crate`, `restricted`, `private`)
+  pub visibilities: BTreeMap<&'static str, usize>,
+  /// Submodules, sorted by name
+  pub modules: Vec<ModuleTree>,
+}
+
+impl ModuleTree {
+  /// Number of items directly in this module, excluding submodules.
+  pub fn item_count(&self) -> usize {
+    self.kinds.values().sum()
+  }
+
+  /// Number of items in this module and all its submodules.
+  pub fn total_item_count(&self) -> usize {
+    self.item_count()
+      + self
+        .modules
+        .iter()
+        .map(ModuleTree::total_item_count)
+        .sum::<usize>()
+  }
+}
+
+/// Build the module tree of a crate, starting at its root module.
+pub fn module_tree(crate_data: &Crate) -> ModuleTree {
+  let mut tree = build_tree(crate_data, &crate_data.root, "");
+  tree.visibility = "public";
+  tree
+}
+
+fn build_tree(crate_data: &Crate, id: &Id, parent_path: &str) -> ModuleTree {
+  let item = &crate_data.index[id];
+  let name = item.name.clone().unwrap_or_default();
+  let path = if parent_path.is_empty() {
+    name.clone()
+  } else {
+    format!("{}::{}", parent_path, name)
+  };
+
+  let mut tree = ModuleTree {
+    name,
+    path,
+    visibility: visibility_label(&item.visibility),
+    kinds: BTreeMap::new(),
+    visibilities: BTreeMap::new(),
+    modules: Vec::new(),
+  };
+
+  let ItemEnum::Module(module) = &item.inner else {
+    return tree;
+  };
+  for child_id in &module.items {
+    let Some(child) = crate_data.index.get(child_id) else {
+      continue;
+    };
+    if matches!(child.inner, ItemEnum::Module(_)) {
+      tree
+        .modules
+        .push(build_tree(crate_data, child_id, &tree.path));
+      continue;
+    }
+    let Some(kind) = kind_label(&child.inner) else {
+      continue;
+    };
+    *tree.kinds.entry(kind).or_default() += 1;
+    *tree
+      .visibilities
+      .entry(visibility_label(&child.visibility))
+      .or_default() += 1;
+  }
+  tree.modules.sort_by(|a, b| a.name.cmp(&b.name));
+  tree
+}
+
+/// Kind of a module item as counted in the tree, `None` for other items.
+pub(crate) fn kind_label(inner: &ItemEnum) -> Option<&'static str> {
+  Some(match inner {
+    ItemEnum::Function(_) => "Function",
+    ItemEnum::Struct(_) => "Struct",
+    ItemEnum::Enum(_) => "Enum",
+    ItemEnum::Union(_) => "Union",
+    ItemEnum::Trait(_) => "Trait",
+    ItemEnum::TraitAlias(_) => "Trait Alias",
+    ItemEnum::Constant { .. } => "Constant",
+    ItemEnum::TypeAlias(_) => "Type",
+    ItemEnum::Static(_) => "Static",
+    ItemEnum::ExternType => "Foreign Type",
+    ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => "Macro",
+    ItemEnum::Use(_) => "Re-export",
+    _ => return None,
+  })
+}
+
+pub(crate) fn visibility_label(visibility: &Visibility) -> &'static str {
+  match visibility {
+    Visibility::Public => "public",
+    Visibility::Crate => "crate",
+    Visibility::Restricted { .. } => "restricted",
+    Visibility::Default => "private",
+  }
+}
+
+/// Render the module tree for the terminal, one module per line.
+pub fn render_tree(tree: &ModuleTree) -> String {
+  let mut output = format!("{}: {}\n", tree.name, module_summary(tree));
+  render_children(tree, "", &mut output);
+  output.push_str(&format!(
+    "\n{} items in {} modules\n",
+    tree.total_item_count(),
+    module_count(tree)
+  ));
+  output
+}
+
+fn module_count(tree: &ModuleTree) -> usize {
+  1 + tree.modules.iter().map(module_count).sum::<usize>()
+}
+
+fn render_children(tree: &ModuleTree, prefix: &str, output: &mut String) {
+  for (i, module) in tree.modules.iter().enumerate() {
+    let last = i + 1 == tree.modules.len();
+    let visibility = match module.visibility {
+      "public" => String::new(),
+      visibility => format!(" [{}]", visibility),
+    };
+    output.push_str(&format!(
+      "{}{} {}{}: {}\n",
+      prefix,
+      if last { "└──" } else { "├──" },
+      module.name,
+      visibility,
+      module_summary(module)
+    ));
+    let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+    render_children(module, &child_prefix, output);
+  }
+}
+
+/// Item counts of a module, e.g. `3 items (2 public, 1 private): 2 Struct, 1 Function`.
+fn module_summary(tree: &ModuleTree) -> String {
+  let count = tree.item_count();
+  if count == 0 {
+    return "no items".to_string();
+  }
+  let visibilities = ["public", "crate", "restricted", "private"]
+    .iter()
+    .filter_map(|visibility| {
+      tree
+        .visibilities
+        .get(visibility)
+        .map(|count| format!("{} {}", count, visibility))
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+  let mut kinds: Vec<_> = tree.kinds.iter().collect();
+  kinds.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+  let kinds = kinds
+    .iter()
+    .map(|(kind, count)| format!("{} {}", count, kind))
+    .collect::<Vec<_>>()
+    .join(", ");
+  format!(
+    "{} item{} ({}): {}",
+    count,
+    if count == 1 { "" } else { "s" },
+    visibilities,
+    kinds
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn module(name: &str, kinds: &[(&'static str, usize)], modules: Vec<ModuleTree>) -> ModuleTree {
+    ModuleTree {
+      name: name.to_string(),
+      path: name.to_string(),
+      visibility: "public",
+      kinds: kinds.iter().copied().collect(),
+      visibilities: BTreeMap::from([("public", kinds.iter().map(|(_, n)| n).sum())]),
+      modules,
+    }
+  }
+
+  #[test]
+  fn test_render_tree() {
+    let mut private = module("internal", &[("Function", 1)], vec![]);
+    private.visibility = "crate";
+    private.visibilities = BTreeMap::from([("crate", 1)]);
+    let tree = module(
+      "my_crate",
+      &[("Function", 1), ("Struct", 2)],
+      vec![
+        module("a", &[], vec![module("b", &[("Enum", 1)], vec![])]),
+        private,
+      ],
+    );
+
+    assert_eq!(tree.total_item_count(), 5);
+    assert_eq!(
+      render_tree(&tree),
+      "my_crate: 3 items (3 public): 2 Struct, 1 Function\n\
+       ├── a: no items\n\
+       │   └── b: 1 item (1 public): 1 Enum\n\
+       └── internal [crate]: 1 item (1 crate): 1 Function\n\
+       \n\
+       5 items in 4 modules\n"
+    );
+  }
+}
@@ -1,23 +1,58 @@
 //! Markdown file writer.
 
-use crate::converter::MarkdownOutput;
-use anyhow::{Context, Result};
+use crate::converter::{DocusaurusMajor, MarkdownOutput};
+use crate::error::{Error, Result};
+use crate::progress;
+use crate::sidebar::{self, SidebarFormat, SidebarMap};
+use crate::site_config;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// File (inside the crate output directory) recording the hash of every generated file
+pub const MANIFEST_FILE_NAME: &str = ".doc-docusaurus-manifest.json";
+
+/// Contents of the manifest file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+  /// Hash of the rustdoc JSON the files were generated from (reproducible runs only)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  input_hash: Option<String>,
+  /// Hash of all generated file paths and contents (reproducible runs only)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  output_hash: Option<String>,
+  /// Hash of every generated file, keyed by path
+  files: BTreeMap<String, String>,
+}
+
+/// Manifests written before the input and output hashes were added only held the file hashes.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ManifestFormat {
+  Current(Manifest),
+  Legacy(BTreeMap<String, String>),
+}
+
+/// Summary of an incremental write.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WriteSummary {
+  /// Files that were created or whose content changed
+  pub written: usize,
+  /// Files left untouched because their content is identical
+  pub unchanged: usize,
+  /// Stale files from a previous run that were removed
+  pub removed: usize,
+}
 
 /// Write markdown content to a file in the specified directory.
 pub fn write_markdown(output_dir: &Path, content: &str) -> Result<()> {
-  fs::create_dir_all(output_dir).with_context(|| {
-    format!(
-      "Failed to create output directory: {}",
-      output_dir.display()
-    )
-  })?;
+  fs::create_dir_all(output_dir).map_err(Error::write(output_dir))?;
 
   let output_file = output_dir.join("index.md");
 
-  fs::write(&output_file, content)
-    .with_context(|| format!("Failed to write file: {}", output_file.display()))?;
+  fs::write(&output_file, content).map_err(Error::write(&output_file))?;
 
   Ok(())
 }
@@ -33,286 +68,633 @@ pub fn write_markdown_multifile_with_sidebar_path(
   output: &MarkdownOutput,
   custom_sidebar_path: Option<&Path>,
 ) -> Result<()> {
-  fs::create_dir_all(output_dir).with_context(|| {
-    format!(
-      "Failed to create output directory: {}",
-      output_dir.display()
-    )
-  })?;
+  fs::create_dir_all(output_dir).map_err(Error::write(output_dir))?;
 
+  let progress = progress::bar(output.files.len(), "Writing files");
   for (file_path, content) in &output.files {
-    let full_path = output_dir.join(file_path);
+    write_file(&output_dir.join(file_path), content)?;
+    progress.inc(1);
+  }
+  progress.finish_and_clear();
 
-    // Create parent directories if needed
-    if let Some(parent) = full_path.parent() {
-      fs::create_dir_all(parent)
-        .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+  // Write sidebar configuration if present
+  if let Some(sidebars) = &output.sidebar {
+    let sidebar_path = resolve_sidebar_path(output_dir, custom_sidebar_path);
+    for (path, content) in build_sidebar_files(&sidebar_path, sidebars, output.docusaurus_major)? {
+      write_file(&path, &content)?;
     }
 
-    fs::write(&full_path, content)
-      .with_context(|| format!("Failed to write file: {}", full_path.display()))?;
+    print_sidebar_hint(&sidebar_path);
   }
 
-  // Write sidebar configuration if present
-  if let Some(sidebar_content) = &output.sidebar {
-    let sidebar_path = if let Some(custom_path) = custom_sidebar_path {
-      custom_path.to_path_buf()
-    } else {
-      // Default behavior: Write to docs parent directory (project root for Docusaurus)
-      // If output_dir is "example-docs/docs/test_crate", parent.parent gives us "example-docs"
-      if let Some(parent) = output_dir.parent() {
-        if let Some(grandparent) = parent.parent() {
-          grandparent.join("sidebars-rust.ts")
-        } else {
-          parent.join("sidebars-rust.ts")
+  Ok(())
+}
+
+/// Write multi-file markdown output, only touching files whose content changed.
+///
+/// A manifest of content hashes is kept in the output directory. Files generated by a
+/// previous run that are no longer part of `output` are removed, so items that were
+/// deleted from the crate do not linger. Files not listed in the manifest are never removed.
+pub fn write_markdown_multifile_incremental(
+  output_dir: &Path,
+  output: &MarkdownOutput,
+  custom_sidebar_path: Option<&Path>,
+) -> Result<WriteSummary> {
+  fs::create_dir_all(output_dir).map_err(Error::write(output_dir))?;
+
+  let manifest_path = output_dir.join(MANIFEST_FILE_NAME);
+  let previous_manifest = read_manifest(&manifest_path)?;
+  let mut manifest = BTreeMap::new();
+  let mut summary = WriteSummary::default();
+
+  let progress = progress::bar(output.files.len(), "Writing files");
+  for (file_path, content) in &output.files {
+    progress.inc(1);
+    let full_path = output_dir.join(file_path);
+    let hash = content_hash(content);
+
+    // Trust the manifest only if the file is still there; otherwise hash what's on disk
+    let unchanged = full_path.exists()
+      && match previous_manifest.get(file_path) {
+        Some(previous_hash) => *previous_hash == hash,
+        None => {
+          fs::read_to_string(&full_path).is_ok_and(|existing| content_hash(&existing) == hash)
         }
-      } else {
-        output_dir.join("sidebars-rust.ts")
+      };
+
+    if unchanged {
+      summary.unchanged += 1;
+    } else {
+      write_file(&full_path, content)?;
+      summary.written += 1;
+    }
+
+    manifest.insert(file_path.clone(), hash);
+  }
+  progress.finish_and_clear();
+
+  // Prune files generated by a previous run that no longer exist
+  for stale_path in previous_manifest
+    .keys()
+    .filter(|path| !manifest.contains_key(*path))
+  {
+    let full_path = output_dir.join(stale_path);
+    if full_path.exists() {
+      fs::remove_file(&full_path).map_err(Error::write(&full_path))?;
+      remove_empty_parents(&full_path, output_dir);
+      summary.removed += 1;
+    }
+  }
+
+  write_manifest(
+    &manifest_path,
+    &Manifest {
+      files: manifest,
+      ..Default::default()
+    },
+  )?;
+
+  if let Some(sidebars) = &output.sidebar {
+    let sidebar_path = resolve_sidebar_path(output_dir, custom_sidebar_path);
+    let mut sidebar_changed = false;
+    for (path, content) in build_sidebar_files(&sidebar_path, sidebars, output.docusaurus_major)? {
+      if fs::read_to_string(&path).map_or(true, |existing| existing != content) {
+        write_file(&path, &content)?;
+        sidebar_changed = true;
       }
-    };
+    }
+    if sidebar_changed {
+      print_sidebar_hint(&sidebar_path);
+    }
+  }
 
-    // Check if sidebar already exists for append mode
-    let final_content = if sidebar_path.exists() {
-      merge_sidebar_content(&sidebar_path, sidebar_content, &output.crate_name)?
+  log::info!(
+    "✓ Incremental update: {} written, {} unchanged, {} removed",
+    summary.written,
+    summary.unchanged,
+    summary.removed
+  );
+
+  Ok(summary)
+}
+
+/// Determine where the sidebar file goes.
+fn resolve_sidebar_path(output_dir: &Path, custom_sidebar_path: Option<&Path>) -> PathBuf {
+  match custom_sidebar_path {
+    Some(custom_path) => custom_path.to_path_buf(),
+    None => default_sidebar_path(output_dir),
+  }
+}
+
+/// Default sidebar file location for a crate output directory (e.g. `docs/my_crate`).
+pub fn default_sidebar_path(output_dir: &Path) -> PathBuf {
+  // Default behavior: Write to docs parent directory (project root for Docusaurus)
+  // If output_dir is "example-docs/docs/test_crate", parent.parent gives us "example-docs"
+  if let Some(parent) = output_dir.parent() {
+    if let Some(grandparent) = parent.parent() {
+      grandparent.join("sidebars-rust.ts")
     } else {
-      sidebar_content.clone()
+      parent.join("sidebars-rust.ts")
+    }
+  } else {
+    output_dir.join("sidebars-rust.ts")
+  }
+}
+
+/// Render the sidebar file (and, for TypeScript, its data file), merging with the
+/// sidebars of other crates from an earlier run.
+///
+/// A `.json` sidebar path gets the plain JSON representation, a `.js` path a
+/// CommonJS module; anything else the TypeScript module. TypeScript is only ever
+/// generated: the sidebars are merged in an adjacent `.json` data file (e.g.
+/// `sidebars-rust.json`) and the module is rendered from it.
+fn build_sidebar_files(
+  sidebar_path: &Path,
+  sidebars: &SidebarMap,
+  docusaurus_major: DocusaurusMajor,
+) -> Result<Vec<(PathBuf, String)>> {
+  let format = SidebarFormat::from_path(sidebar_path);
+  if format != SidebarFormat::Ts {
+    let mut merged = read_sidebar_data(sidebar_path, format)?;
+    merge_sidebars(&mut merged, sidebars)?;
+    let content = match format {
+      SidebarFormat::Js => sidebar::render_commonjs(&merged)?,
+      _ => serde_json::to_string_pretty(&merged)?,
     };
+    return Ok(vec![(sidebar_path.to_path_buf(), content)]);
+  }
 
-    // Create parent directories if needed
-    if let Some(parent) = sidebar_path.parent() {
-      fs::create_dir_all(parent)
-        .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-    }
+  let data_path = sidebar_path.with_extension("json");
+  if sidebar_path.exists() && !data_path.exists() {
+    check_legacy_typescript_sidebar(sidebar_path, &data_path, sidebars)?;
+  }
+  let mut merged = read_sidebar_data(&data_path, SidebarFormat::Json)?;
+  merge_sidebars(&mut merged, sidebars)?;
+  let all_sidebars: SidebarMap =
+    serde_json::from_value(serde_json::to_value(&merged)?).map_err(|err| {
+      Error::Sidebar(format!(
+        "Invalid sidebar in {}: {}",
+        data_path.display(),
+        err
+      ))
+    })?;
+
+  Ok(vec![
+    (
+      sidebar_path.to_path_buf(),
+      sidebar::render_typescript(&all_sidebars, docusaurus_major),
+    ),
+    (data_path, serde_json::to_string_pretty(&merged)?),
+  ])
+}
 
-    fs::write(&sidebar_path, final_content)
-      .with_context(|| format!("Failed to write sidebar file: {}", sidebar_path.display()))?;
+/// Sidebars of an existing JSON or CommonJS sidebar file (empty if there is none).
+fn read_sidebar_data(
+  sidebar_path: &Path,
+  format: SidebarFormat,
+) -> Result<BTreeMap<String, serde_json::Value>> {
+  if !sidebar_path.exists() {
+    return Ok(BTreeMap::new());
+  }
+
+  let existing = fs::read_to_string(sidebar_path).map_err(Error::read(sidebar_path))?;
+  let data = match format {
+    SidebarFormat::Js => sidebar::parse_commonjs(&existing),
+    _ => Some(existing.as_str()),
+  };
+  data
+    .and_then(|data| serde_json::from_str(data).ok())
+    .ok_or_else(|| {
+      Error::Sidebar(format!(
+        "Failed to parse existing sidebar: {}",
+        sidebar_path.display()
+      ))
+    })
+}
 
-    println!(
-      "✓ Generated sidebar configuration: {}",
-      sidebar_path.display()
+/// A TypeScript sidebar written before sidebars were merged through a JSON data
+/// file cannot be merged, so it is only regenerated when it holds no sidebars of
+/// crates other than those of this run.
+fn check_legacy_typescript_sidebar(
+  sidebar_path: &Path,
+  data_path: &Path,
+  sidebars: &SidebarMap,
+) -> Result<()> {
+  let existing = fs::read_to_string(sidebar_path).map_err(Error::read(sidebar_path))?;
+  let converted: BTreeSet<&str> = sidebars
+    .values()
+    .flatten()
+    .filter_map(|item| match item {
+      sidebar::SidebarItem::Doc {
+        custom_props: Some(props),
+        ..
+      } => props["crateName"].as_str(),
+      _ => None,
+    })
+    .collect();
+  // Crate titles are rendered as `customProps: { rustCrateTitle: true, crateName: 'my_crate', ... }`
+  let other_crates: BTreeSet<&str> = existing
+    .split("crateName:")
+    .skip(1)
+    .filter_map(|rest| {
+      let rest = rest.trim_start();
+      let quote = rest.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+      rest[1..].split(quote).next()
+    })
+    .filter(|name| !converted.contains(name))
+    .collect();
+  if other_crates.is_empty() {
+    log::warn!(
+      "{} has no {} next to it; it is regenerated with the sidebars of this run",
+      sidebar_path.display(),
+      data_path.display()
     );
-    println!("  Import it in your sidebars.ts file:");
-    println!("  import {{rustApiCategory}} from './sidebars-rust';");
+    return Ok(());
   }
 
+  Err(Error::Sidebar(format!(
+    "{} holds the sidebars of other crates ({}) but has no {} to merge them from. \
+     Delete {} and convert all its crates again (once); their sidebars are then kept in {}",
+    sidebar_path.display(),
+    other_crates.into_iter().collect::<Vec<_>>().join(", "),
+    data_path.display(),
+    sidebar_path.display(),
+    data_path.display()
+  )))
+}
+
+/// Add the sidebars of this run, replacing all earlier sidebars of its crates and
+/// keeping those of other crates.
+///
+/// Sidebars belong to the crate whose title they start with, so sidebars of
+/// modules no longer converted (e.g. below a lower `--max-depth`) are dropped.
+fn merge_sidebars(
+  merged: &mut BTreeMap<String, serde_json::Value>,
+  sidebars: &SidebarMap,
+) -> Result<()> {
+  let sidebars = sidebars
+    .iter()
+    .map(|(key, items)| Ok((key.clone(), serde_json::to_value(items)?)))
+    .collect::<Result<BTreeMap<_, _>>>()?;
+  let converted: BTreeSet<String> = sidebars
+    .values()
+    .filter_map(crate_title_id)
+    .map(str::to_string)
+    .collect();
+  merged.retain(|_, items| crate_title_id(items).is_none_or(|id| !converted.contains(id)));
+  merged.extend(sidebars);
   Ok(())
 }
 
-/// Merge sidebar content when appending to existing sidebar
-fn merge_sidebar_content(
-  existing_path: &Path,
-  new_content: &str,
-  _crate_name: &str,
-) -> Result<String> {
-  // With the new multiple-sidebar format (rustSidebars object), we need to merge
-  // the new sidebars into the existing object
-  let existing_content = fs::read_to_string(existing_path).with_context(|| {
-    format!(
-      "Failed to read existing sidebar: {}",
-      existing_path.display()
-    )
-  })?;
+/// Doc id of the crate title a sidebar starts with (the crate's index page).
+fn crate_title_id(items: &serde_json::Value) -> Option<&str> {
+  items
+    .as_array()?
+    .iter()
+    .find(|item| item["customProps"]["rustCrateTitle"] == true)?["id"]
+    .as_str()
+}
 
-  // Extract rustSidebars from both files and merge them
-  merge_rust_sidebars(&existing_content, new_content)
+fn print_sidebar_hint(sidebar_path: &Path) {
+  log::info!(
+    "✓ Generated sidebar configuration: {}",
+    sidebar_path.display()
+  );
+  log::info!("  Wire it into your site (or convert with --patch-config):\n");
+  for line in site_config::wiring_snippet(sidebar_path).lines() {
+    log::info!("  {}", line);
+  }
 }
 
-/// Merge rustSidebars objects from existing and new content
-fn merge_rust_sidebars(existing: &str, new_content: &str) -> Result<String> {
-  // Find the rustSidebars object in existing content
-  let existing_start = "export const rustSidebars: Record<string, any[]> = {";
-  let existing_end = "};";
-
-  // Extract existing entries
-  let existing_entries = if let Some(start_pos) = existing.find(existing_start) {
-    let start = start_pos + existing_start.len();
-    if let Some(end_pos) = existing[start..].find(existing_end) {
-      &existing[start..start + end_pos]
-    } else {
-      ""
-    }
+/// Id of the docs plugin instance without prefixed site files.
+pub const DEFAULT_DOCS_PLUGIN_ID: &str = "default";
+
+/// Add a docs version to the `versions.json` of a Docusaurus site (or the
+/// `<ID>_versions.json` of a docs plugin instance with an id), as
+/// `docusaurus docs:version` does (newest first).
+pub fn register_docs_version(
+  site_dir: &Path,
+  docs_plugin_id: Option<&str>,
+  version: &str,
+) -> Result<()> {
+  let versions_path = match docs_plugin_id.filter(|id| *id != DEFAULT_DOCS_PLUGIN_ID) {
+    Some(id) => site_dir.join(format!("{}_versions.json", id)),
+    None => site_dir.join("versions.json"),
+  };
+  let mut versions: Vec<String> = if versions_path.exists() {
+    let content = fs::read_to_string(&versions_path).map_err(Error::read(&versions_path))?;
+    serde_json::from_str(&content).map_err(Error::json(&versions_path))?
   } else {
-    ""
+    Vec::new()
   };
+  if versions.iter().any(|existing| existing == version) {
+    return Ok(());
+  }
 
-  // Extract new entries
-  let new_start = "export const rustSidebars: Record<string, any[]> = {";
-  let new_end = "};";
+  versions.insert(0, version.to_string());
+  write_file(
+    &versions_path,
+    &format!("{}\n", serde_json::to_string_pretty(&versions)?),
+  )
+}
 
-  let new_entries = if let Some(start_pos) = new_content.find(new_start) {
-    let start = start_pos + new_start.len();
-    if let Some(end_pos) = new_content[start..].find(new_end) {
-      &new_content[start..start + end_pos]
-    } else {
-      anyhow::bail!("Could not find rustSidebars object end in new content");
-    }
-  } else {
-    anyhow::bail!("Could not find rustSidebars object in new content");
-  };
+/// Write a file, creating parent directories if needed.
+fn write_file(path: &Path, content: &str) -> Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(Error::write(parent))?;
+  }
 
-  // Extract the header and footer from new_content (everything before and after rustSidebars)
-  let header_end = new_content.find(new_start).unwrap() + new_start.len();
-  let header = &new_content[..header_end];
-
-  // Find where the footer starts (after the rustSidebars closing brace)
-  let footer_start = header_end + new_entries.len() + new_end.len();
-  let footer = &new_content[footer_start..];
-
-  // Merge: combine existing entries with new entries, avoiding duplicates
-  // Parse entries into a map to avoid duplicates
-  let mut entries_map: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-
-  // Helper function to parse sidebar entries
-  let parse_entries = |content: &str| -> Vec<(String, String)> {
-    let mut results = Vec::new();
-    let mut current_key = String::new();
-    let mut current_value = String::new();
-    let mut brace_depth = 0;
-    let mut in_value = false;
-
-    for line in content.lines() {
-      let trimmed = line.trim();
-
-      // Start of a new entry
-      if trimmed.starts_with('\'') && trimmed.contains("': [") {
-        // Save previous entry if exists
-        if !current_key.is_empty() {
-          results.push((current_key.clone(), current_value.clone()));
-        }
+  fs::write(path, content).map_err(Error::write(path))
+}
 
-        // Extract key
-        if let Some(end_quote) = trimmed[1..].find('\'') {
-          current_key = trimmed[1..=end_quote].to_string();
-          current_value = line.to_string() + "\n";
-          in_value = true;
-          brace_depth = 1; // Count the opening [
-        }
-      } else if in_value {
-        current_value.push_str(line);
-        current_value.push('\n');
-
-        // Count braces to detect end of entry
-        for ch in line.chars() {
-          match ch {
-            '[' | '{' => brace_depth += 1,
-            ']' | '}' => {
-              brace_depth -= 1;
-              if brace_depth == 0 {
-                // Entry complete
-                results.push((current_key.clone(), current_value.clone()));
-                current_key.clear();
-                current_value.clear();
-                in_value = false;
-              }
-            }
-            _ => {}
-          }
-        }
-      }
+/// Hex-encoded SHA-256 of the content.
+fn content_hash(content: &str) -> String {
+  Sha256::digest(content.as_bytes())
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
+/// Read the file hashes of the manifest of a previous run (empty if there is none).
+fn read_manifest(manifest_path: &Path) -> Result<BTreeMap<String, String>> {
+  if !manifest_path.exists() {
+    return Ok(BTreeMap::new());
+  }
+
+  let content = fs::read_to_string(manifest_path).map_err(Error::read(manifest_path))?;
+  let manifest = serde_json::from_str(&content).map_err(Error::json(manifest_path))?;
+  Ok(match manifest {
+    ManifestFormat::Current(manifest) => manifest.files,
+    ManifestFormat::Legacy(files) => files,
+  })
+}
+
+fn write_manifest(manifest_path: &Path, manifest: &Manifest) -> Result<()> {
+  let manifest_json = serde_json::to_string_pretty(manifest)?;
+  fs::write(manifest_path, manifest_json).map_err(Error::write(manifest_path))
+}
+
+/// Write a manifest attesting which output was generated from which input.
+///
+/// Besides the hash of every generated file, the manifest records the hash of the
+/// rustdoc JSON input and a hash over all generated files, so a reproducible build
+/// can be checked by comparing two manifests.
+pub fn write_attested_manifest(
+  output_dir: &Path,
+  output: &MarkdownOutput,
+  input_path: &Path,
+) -> Result<()> {
+  let input = crate::parser::read_rustdoc_json(input_path)?;
+  let files: BTreeMap<String, String> = output
+    .files
+    .iter()
+    .map(|(path, content)| (path.clone(), content_hash(content)))
+    .collect();
+  let listing: String = files
+    .iter()
+    .map(|(path, hash)| format!("{}  {}\n", hash, path))
+    .collect();
+
+  write_manifest(
+    &output_dir.join(MANIFEST_FILE_NAME),
+    &Manifest {
+      input_hash: Some(content_hash(&input)),
+      output_hash: Some(content_hash(&listing)),
+      files,
+    },
+  )
+}
+
+/// Remove directories left empty after pruning, up to (but excluding) `root`.
+fn remove_empty_parents(path: &Path, root: &Path) {
+  let mut current = path.parent();
+  while let Some(dir) = current {
+    if dir == root || !dir.starts_with(root) || fs::remove_dir(dir).is_err() {
+      break;
     }
+    current = dir.parent();
+  }
+}
 
-    // Save last entry if exists
-    if !current_key.is_empty() {
-      results.push((current_key, current_value));
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::BTreeMap;
+
+  fn output_with(files: &[(&str, &str)]) -> MarkdownOutput {
+    MarkdownOutput {
+      crate_name: "my_crate".to_string(),
+      files: files
+        .iter()
+        .map(|(path, content)| (path.to_string(), content.to_string()))
+        .collect::<BTreeMap<_, _>>(),
+      sidebar: None,
+      docusaurus_major: Default::default(),
+      warnings: Vec::new(),
+      unresolved_links: BTreeMap::new(),
     }
+  }
 
-    results
-  };
+  #[test]
+  fn test_incremental_skips_unchanged_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = output_with(&[("index.md", "root"), ("mod_a/index.md", "a")]);
+
+    let first = write_markdown_multifile_incremental(dir.path(), &output, None).unwrap();
+    assert_eq!(first.written, 2);
+    assert!(dir.path().join(MANIFEST_FILE_NAME).exists());
+
+    let changed = output_with(&[("index.md", "root v2"), ("mod_a/index.md", "a")]);
+    let second = write_markdown_multifile_incremental(dir.path(), &changed, None).unwrap();
+    assert_eq!(
+      second,
+      WriteSummary {
+        written: 1,
+        unchanged: 1,
+        removed: 0
+      }
+    );
+    assert_eq!(
+      fs::read_to_string(dir.path().join("index.md")).unwrap(),
+      "root v2"
+    );
+  }
 
-  // Parse existing entries
-  for (key, value) in parse_entries(existing_entries) {
-    entries_map.insert(key, value);
+  #[test]
+  fn test_incremental_prunes_stale_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = output_with(&[("index.md", "root"), ("mod_a/struct.Foo.md", "foo")]);
+    write_markdown_multifile_incremental(dir.path(), &output, None).unwrap();
+
+    // A file we did not generate must survive pruning
+    fs::write(dir.path().join("custom.md"), "user content").unwrap();
+
+    let output = output_with(&[("index.md", "root")]);
+    let summary = write_markdown_multifile_incremental(dir.path(), &output, None).unwrap();
+
+    assert_eq!(summary.removed, 1);
+    assert!(!dir.path().join("mod_a/struct.Foo.md").exists());
+    assert!(!dir.path().join("mod_a").exists());
+    assert!(dir.path().join("custom.md").exists());
   }
 
-  // Parse and add/overwrite with new entries
-  for (key, value) in parse_entries(new_entries) {
-    entries_map.insert(key, value); // This will overwrite duplicates
+  #[test]
+  fn test_attested_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let input_path = dir.path().join("input.json");
+    fs::write(&input_path, "{}").unwrap();
+    let output = output_with(&[("index.md", "root"), ("mod_a/index.md", "a")]);
+    write_attested_manifest(dir.path(), &output, &input_path).unwrap();
+
+    let manifest: Manifest =
+      serde_json::from_str(&fs::read_to_string(dir.path().join(MANIFEST_FILE_NAME)).unwrap())
+        .unwrap();
+    assert_eq!(manifest.input_hash, Some(content_hash("{}")));
+    let listing = format!(
+      "{}  index.md\n{}  mod_a/index.md\n",
+      content_hash("root"),
+      content_hash("a")
+    );
+    assert_eq!(manifest.output_hash, Some(content_hash(&listing)));
+
+    // Incremental runs still read the file hashes of an attested manifest
+    let summary = write_markdown_multifile_incremental(dir.path(), &output, None).unwrap();
+    assert_eq!(summary.removed, 0);
   }
 
-  // Sort keys and build merged content
-  let mut sorted_keys: Vec<_> = entries_map.keys().collect();
-  sorted_keys.sort();
-
-  let mut merged_entries = String::new();
-  for (i, key) in sorted_keys.iter().enumerate() {
-    if let Some(value) = entries_map.get(*key) {
-      merged_entries.push_str(value);
-      // Add comma between entries (but not after the last one)
-      if i < sorted_keys.len() - 1 && !value.trim_end().ends_with(',') {
-        merged_entries.push(',');
-      }
-    }
+  #[test]
+  fn test_read_legacy_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest_path = dir.path().join(MANIFEST_FILE_NAME);
+    fs::write(&manifest_path, r#"{"index.md": "abc"}"#).unwrap();
+
+    let files = read_manifest(&manifest_path).unwrap();
+    assert_eq!(files.get("index.md").map(String::as_str), Some("abc"));
   }
 
-  // Generate rootRustSidebar with all workspace crates
-  let root_sidebar = generate_root_sidebar(&merged_entries);
+  #[test]
+  fn test_incremental_rewrites_deleted_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = output_with(&[("index.md", "root")]);
+    write_markdown_multifile_incremental(dir.path(), &output, None).unwrap();
 
-  // Construct the final output
-  let mut result = String::new();
-  result.push_str(header);
-  result.push_str(&merged_entries);
-  result.push_str(new_end);
-  result.push_str(footer);
+    fs::remove_file(dir.path().join("index.md")).unwrap();
+    let summary = write_markdown_multifile_incremental(dir.path(), &output, None).unwrap();
 
-  // Append the root sidebar export
-  result.push_str("\n\n// Root sidebar with links to all crates (for main navigation)\n");
-  result.push_str("export const rootRustSidebar = [\n");
-  result.push_str(&root_sidebar);
-  result.push_str("];\n");
+    assert_eq!(summary.written, 1);
+    assert!(dir.path().join("index.md").exists());
+  }
 
-  Ok(result)
-}
+  fn crate_sidebars(crate_name: &str, label: &str) -> SidebarMap {
+    let mut sidebars = SidebarMap::new();
+    sidebars.insert(
+      crate_name.to_string(),
+      vec![
+        sidebar::SidebarItem::Doc {
+          id: format!("{}/index", crate_name),
+          label: Some(crate_name.to_string()),
+          class_name: None,
+          custom_props: Some(
+            serde_json::json!({ "rustCrateTitle": true, "crateName": crate_name }),
+          ),
+        },
+        sidebar::SidebarItem::Category {
+          label: label.to_string(),
+          link: Some(format!("{}/index", crate_name)),
+          collapsible: true,
+          collapsed: false,
+          items: Vec::new(),
+        },
+      ],
+    );
+    sidebars
+  }
 
-/// Generate a root sidebar that includes all crates with their content
-/// This function parses the merged sidebar content to extract root crate information
-fn generate_root_sidebar(merged_entries: &str) -> String {
-  let mut output = String::new();
-
-  // Find all lines with rustCrateTitle: true to identify root crates
-  // These lines have the format:
-  // { type: 'doc', id: 'runtime/rust/crate_name/index', label: 'crate_name', customProps: { rustCrateTitle: true, ... } }
-
-  use std::collections::HashSet;
-  let mut seen_crates: HashSet<String> = HashSet::new();
-  let mut crate_entries: Vec<(String, String)> = Vec::new(); // (doc_id, label)
-
-  for line in merged_entries.lines() {
-    let trimmed = line.trim();
-
-    // Look for lines containing rustCrateTitle: true
-    if trimmed.contains("rustCrateTitle: true") && trimmed.contains("type: 'doc'") {
-      // Extract the doc id and label from the line
-      // Format: { type: 'doc', id: 'path/to/crate/index', label: 'crate_name', customProps: ...
-
-      if let Some(id_start) = trimmed.find("id: '") {
-        if let Some(id_end) = trimmed[id_start + 5..].find('\'') {
-          let doc_id = &trimmed[id_start + 5..id_start + 5 + id_end];
-
-          // Extract label
-          if let Some(label_start) = trimmed.find("label: '") {
-            if let Some(label_end) = trimmed[label_start + 8..].find('\'') {
-              let label = &trimmed[label_start + 8..label_start + 8 + label_end];
-
-              // Only add each crate once (in case it appears in multiple sidebars)
-              if seen_crates.insert(doc_id.to_string()) {
-                crate_entries.push((doc_id.to_string(), label.to_string()));
-              }
-            }
-          }
-        }
-      }
+  fn write_sidebars(sidebar_path: &Path, sidebars: &SidebarMap) {
+    for (path, content) in
+      build_sidebar_files(sidebar_path, sidebars, DocusaurusMajor::default()).unwrap()
+    {
+      write_file(&path, &content).unwrap();
     }
   }
 
-  // Sort by doc_id for consistent output
-  crate_entries.sort_by(|a, b| a.0.cmp(&b.0));
-
-  for (doc_id, label) in crate_entries {
-    output.push_str(&format!(
-      "  {{ type: 'doc', id: '{}', label: '{}', className: 'rust-mod' }},\n",
-      doc_id, label
+  #[test]
+  fn test_typescript_sidebar_merge() {
+    let dir = tempfile::tempdir().unwrap();
+    let sidebar_path = dir.path().join("sidebars-rust.ts");
+    write_sidebars(
+      &sidebar_path,
+      &crate_sidebars("crate_a", "Vec<[u8; 4]> {'a'}"),
+    );
+    // The TypeScript module is never parsed, so reformatting it is harmless
+    let formatted = fs::read_to_string(&sidebar_path)
+      .unwrap()
+      .replace("  ", "    ");
+    fs::write(&sidebar_path, formatted).unwrap();
+    write_sidebars(&sidebar_path, &crate_sidebars("crate_b", "Structs"));
+    write_sidebars(&sidebar_path, &crate_sidebars("crate_b", "Traits"));
+
+    let data: BTreeMap<String, serde_json::Value> =
+      serde_json::from_str(&fs::read_to_string(dir.path().join("sidebars-rust.json")).unwrap())
+        .unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), ["crate_a", "crate_b"]);
+    assert_eq!(data["crate_a"][1]["label"], "Vec<[u8; 4]> {'a'}");
+    assert_eq!(data["crate_a"][1]["link"]["id"], "crate_a/index");
+    assert_eq!(data["crate_b"][1]["label"], "Traits");
+
+    let ts = fs::read_to_string(&sidebar_path).unwrap();
+    assert!(ts.contains("label: 'Vec<[u8; 4]> {\\'a\\'}',"));
+    assert!(!ts.contains("Structs"));
+    assert!(ts.contains(
+      "export const rootRustSidebar = [\n  { type: 'doc', id: 'crate_a/index', label: 'crate_a', className: 'rust-mod' },\n  { type: 'doc', id: 'crate_b/index', label: 'crate_b', className: 'rust-mod' },\n];"
     ));
   }
 
-  output
+  #[test]
+  fn test_sidebar_merge_drops_stale_crate_sidebars() {
+    let dir = tempfile::tempdir().unwrap();
+    let sidebar_path = dir.path().join("sidebars-rust.ts");
+    let mut sidebars = crate_sidebars("crate_a", "Modules");
+    sidebars.insert("crate_a_io".to_string(), sidebars["crate_a"].clone());
+    write_sidebars(&sidebar_path, &sidebars);
+    write_sidebars(&sidebar_path, &crate_sidebars("crate_ab", "Modules"));
+    // Converted again with a lower --max-depth, without the module sidebar
+    write_sidebars(&sidebar_path, &crate_sidebars("crate_a", "Modules"));
+
+    let data: BTreeMap<String, serde_json::Value> =
+      serde_json::from_str(&fs::read_to_string(dir.path().join("sidebars-rust.json")).unwrap())
+        .unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), ["crate_a", "crate_ab"]);
+    assert!(
+      !fs::read_to_string(&sidebar_path)
+        .unwrap()
+        .contains("crate_a_io")
+    );
+  }
+
+  #[test]
+  fn test_legacy_typescript_sidebar() {
+    let dir = tempfile::tempdir().unwrap();
+    let sidebar_path = dir.path().join("sidebars-rust.ts");
+    let data_path = dir.path().join("sidebars-rust.json");
+    let mut sidebars = crate_sidebars("crate_a", "Modules");
+    sidebars.extend(crate_sidebars("crate_b", "Modules"));
+    write_sidebars(&sidebar_path, &sidebars);
+    fs::remove_file(&data_path).unwrap();
+
+    // The sidebars of crate_b would be lost
+    let err = build_sidebar_files(
+      &sidebar_path,
+      &crate_sidebars("crate_a", "Traits"),
+      DocusaurusMajor::default(),
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("other crates (crate_b)"), "{}", err);
+    assert!(!data_path.exists());
+
+    // Converting all its crates loses nothing
+    write_sidebars(&sidebar_path, &sidebars);
+    assert!(data_path.exists());
+  }
 }
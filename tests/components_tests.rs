@@ -433,3 +433,38 @@ fn test_components_init_non_docusaurus_project() {
     "Should mention missing Docusaurus files"
   );
 }
+
+#[test]
+fn test_scaffold_creates_site() {
+  let temp_dir = TempDir::new().expect("Failed to create temp dir");
+  let site = temp_dir.path().join("My Site");
+
+  let output = std::process::Command::new("cargo")
+    .args(["run", "--", "scaffold", site.to_str().unwrap()])
+    .output()
+    .expect("Failed to run cargo run");
+  assert!(output.status.success(), "Command should succeed");
+
+  for file in [
+    "package.json",
+    "docusaurus.config.ts",
+    "sidebars.ts",
+    "sidebars-rust.ts",
+    "doc-docusaurus.toml",
+    "docs/intro.md",
+    "src/css/custom.css",
+    "src/css/rust-documentation.css",
+    "src/components/RustCode/index.tsx",
+  ] {
+    assert!(site.join(file).exists(), "File not created: {}", file);
+  }
+  let package_json = fs::read_to_string(site.join("package.json")).unwrap();
+  assert!(package_json.contains("\"name\": \"my-site\""));
+
+  // An existing site is not overwritten
+  let output = std::process::Command::new("cargo")
+    .args(["run", "--", "scaffold", site.to_str().unwrap()])
+    .output()
+    .expect("Failed to run cargo run");
+  assert!(!output.status.success(), "Command should fail");
+}
@@ -0,0 +1,24 @@
+//! Macros for building greetings.
+//!
+//! This crate only exports macros.
+
+/// Builds a greeting for `name`.
+///
+/// ```
+/// let greeting = macro_crate::greet!("world");
+/// assert_eq!(greeting, "Hello, world!");
+/// ```
+#[macro_export]
+macro_rules! greet {
+  ($name:expr) => {
+    format!("Hello, {}!", $name)
+  };
+}
+
+/// Repeats an expression `n` times into a `Vec`.
+#[macro_export]
+macro_rules! repeat {
+  ($value:expr; $n:expr) => {
+    vec![$value; $n]
+  };
+}
@@ -0,0 +1,19 @@
+//! Crate re-exporting enum variants.
+
+/// Status of a job.
+pub enum Status {
+    /// The job is running.
+    Active,
+    /// The job is done.
+    Done(u32),
+}
+
+/// Variants of [`Status`] for glob imports.
+pub mod prelude {
+    pub use crate::Status::*;
+}
+
+/// A single variant of [`Status`].
+pub mod active {
+    pub use crate::Status::Active;
+}
@@ -1,5 +1,6 @@
-use cargo_doc_docusaurus::{ConversionOptions, converter, parser};
-use std::path::Path;
+use cargo_doc_docusaurus::sidebar::{self, SidebarItem};
+use cargo_doc_docusaurus::{ConversionOptions, ConversionOptionsBuilder, converter, parser};
+use std::path::{Path, PathBuf};
 
 #[test]
 fn test_multifile_output_structure() {
@@ -82,6 +83,27 @@ fn test_nested_modules() {
   }
 }
 
+#[test]
+fn test_glob_reexport_pages_link_definition() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output =
+    converter::convert_to_markdown_multifile(&crate_data, false, "/docs", &[], false, None)
+      .expect("Failed to convert to markdown");
+
+  let definition = "/docs/test_crate/nested/reexport_test/items/struct.GlobStruct";
+  let duplicate = &output.files["nested/reexport_test/struct.GlobStruct.md"];
+  assert!(duplicate.contains(&format!("canonical_url: \"{}\"\n", definition)));
+  assert!(duplicate.contains(&format!(
+    ":::note[Re-export]\n\nDefined in <Link to=\"{}\">test_crate::nested::reexport_test::items::GlobStruct</Link>",
+    definition
+  )));
+
+  let original = &output.files["nested/reexport_test/items/struct.GlobStruct.md"];
+  assert!(!original.contains("canonical_url"));
+  assert!(!original.contains(":::note[Re-export]"));
+}
+
 #[test]
 fn test_struct_formatting() {
   let json_path = Path::new("tests/fixtures/test_crate.json");
@@ -148,6 +170,88 @@ fn test_enum_formatting() {
   assert!(complex_enum.contains("title: \"Enum ComplexEnum\""));
 }
 
+#[test]
+fn test_enum_discriminants() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // The fixture has no explicit discriminants, so add some
+  for (name, expr, value) in [("VariantA", "1", "1"), ("VariantB", "1 << 2", "4")] {
+    let variant = crate_data
+      .index
+      .values_mut()
+      .find(|item| item.name.as_deref() == Some(name))
+      .unwrap();
+    if let rustdoc_types::ItemEnum::Variant(variant) = &mut variant.inner {
+      variant.discriminant = Some(rustdoc_types::Discriminant {
+        expr: expr.to_string(),
+        value: value.to_string(),
+      });
+    }
+  }
+
+  let output = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+  let simple_enum = &output.files["enum.SimpleEnum.md"];
+
+  // The definition shows the expression, the Variants list its value
+  assert!(simple_enum.contains("\n    VariantA = 1,\n    VariantB = 1 << 2,\n    VariantC,\n}"));
+  assert!(simple_enum.contains("</span>`VariantA = 1`"));
+  assert!(simple_enum.contains("</span>`VariantB = 4`"));
+  assert!(simple_enum.contains("</span>`VariantC`"));
+}
+
+#[test]
+fn test_inlined_items_use_module_grouping() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // Make `types::Pair` look inlined from a dependency: its `paths` entry and
+  // span point to the dependency, only the module tree lists it in `types`
+  let (id, item) = crate_data
+    .index
+    .iter_mut()
+    .find(|(_, item)| item.name.as_deref() == Some("Pair"))
+    .unwrap();
+  let id = *id;
+  item.crate_id = 1;
+  item.span = Some(rustdoc_types::Span {
+    filename: "/home/user/.cargo/registry/src/dep-1.0.0/src/pair.rs".into(),
+    begin: (1, 0),
+    end: (1, 0),
+  });
+  let summary = crate_data.paths.get_mut(&id).unwrap();
+  summary.crate_id = 1;
+  summary.path = vec!["dep".to_string(), "pair".to_string(), "Pair".to_string()];
+
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = output
+    .files
+    .get("types/struct.Pair.md")
+    .expect("inlined item should get a page in the module inlining it");
+  assert!(page.contains("displayed_sidebar: 'test_crate_types'"));
+  assert!(page.contains("**<Link to=\"/docs/test_crate/\">test_crate</Link>::<Link to=\"/docs/test_crate/types/\">types</Link>::Pair**"));
+  // Links to it are local, not to the dependency's docs
+  assert!(page.contains("\"href\": \"/docs/test_crate/types/struct.Pair\""));
+  assert!(
+    !output
+      .files
+      .values()
+      .any(|content| content.contains("docs.rs/dep"))
+  );
+  assert!(!output.files.keys().any(|path| path.contains("pair/")));
+
+  let sidebar =
+    sidebar::render_typescript(output.sidebar.as_ref().unwrap(), output.docusaurus_major);
+  assert!(sidebar.contains("id: 'test_crate/types/struct.Pair'"));
+}
+
 #[test]
 fn test_function_formatting() {
   let json_path = Path::new("tests/fixtures/test_crate.json");
@@ -175,6 +279,362 @@ fn test_trait_formatting() {
   assert!(my_trait.contains("```rust"));
 }
 
+#[test]
+fn test_forwarding_impls_table() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // The fixture has no forwarding impls, so add `impl<T: MyTrait + ?Sized> MyTrait for &T`
+  // and `impl<T> MyTrait for Box<T> where T: MyTrait`
+  let trait_id = crate_data
+    .index
+    .iter()
+    .find(|(_, item)| item.name.as_deref() == Some("MyTrait"))
+    .map(|(id, _)| id.0)
+    .unwrap();
+  let bound = |path: &str, modifier: &str| {
+    serde_json::json!({"trait_bound": {
+      "trait": {"path": path, "id": trait_id, "args": null},
+      "generic_params": [],
+      "modifier": modifier,
+    }})
+  };
+  let forwarding_impls = [
+    (
+      serde_json::json!({"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "T"}}}),
+      serde_json::json!({"params": [{"name": "T", "kind": {"type": {
+        "bounds": [bound("MyTrait", "none"), bound("Sized", "maybe")],
+        "default": null,
+        "is_synthetic": false,
+      }}}], "where_predicates": []}),
+    ),
+    (
+      serde_json::json!({"resolved_path": {"path": "Box", "id": 90001, "args": {"angle_bracketed": {
+        "args": [{"type": {"generic": "T"}}],
+        "constraints": [],
+      }}}}),
+      serde_json::json!({"params": [{"name": "T", "kind": {"type": {
+        "bounds": [],
+        "default": null,
+        "is_synthetic": false,
+      }}}], "where_predicates": [{"bound_predicate": {
+        "type": {"generic": "T"},
+        "bounds": [bound("MyTrait", "none")],
+        "generic_params": [],
+      }}]}),
+    ),
+  ];
+  for (i, (for_, generics)) in forwarding_impls.into_iter().enumerate() {
+    let id = 90010 + i as u32;
+    let item: rustdoc_types::Item = serde_json::from_value(serde_json::json!({
+      "id": id, "crate_id": 0, "name": null, "span": null, "visibility": "default",
+      "docs": null, "links": {}, "attrs": [], "deprecation": null,
+      "inner": {"impl": {
+        "is_unsafe": false,
+        "generics": generics,
+        "provided_trait_methods": ["provided_method", "another_provided"],
+        "trait": {"path": "MyTrait", "id": trait_id, "args": null},
+        "for": for_,
+        "items": [],
+        "is_negative": false,
+        "is_synthetic": false,
+        "blanket_impl": null,
+      }},
+    }))
+    .unwrap();
+    crate_data.index.insert(rustdoc_types::Id(id), item);
+  }
+
+  let output = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+  let my_trait = &output.files["trait.MyTrait.md"];
+  assert!(my_trait.contains(
+    "### Forwarding Implementations\n\n| Implementor | Bounds |\n| --- | --- |\n| `&T` | `T: MyTrait + ?Sized` |\n| `Box<T>` | `T: MyTrait` |\n"
+  ));
+  // Regular impls (`impl MyTrait for String`) are not forwarding
+  assert!(!my_trait.contains("`String`"));
+}
+
+#[test]
+fn test_foreign_items() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // The fixture has no extern blocks, so add a foreign function, static and type
+  // and an `extern crate` to the crate root
+  let foreign_items = [
+    (
+      "strlen",
+      serde_json::json!({"function": {
+        "sig": {"inputs": [["s", {"raw_pointer": {"is_mutable": false, "type": {"primitive": "u8"}}}]],
+          "output": {"primitive": "usize"}, "is_c_variadic": true},
+        "generics": {"params": [], "where_predicates": []},
+        "header": {"is_const": false, "is_unsafe": true, "is_async": false, "abi": {"C": {"unwind": false}}},
+        "has_body": false,
+      }}),
+    ),
+    (
+      "ERRNO",
+      serde_json::json!({"static": {
+        "type": {"primitive": "i32"}, "is_mutable": true, "expr": "", "is_unsafe": false,
+      }}),
+    ),
+    ("Handle", serde_json::json!("extern_type")),
+    (
+      "libc",
+      serde_json::json!({"extern_crate": {"name": "libc", "rename": "c"}}),
+    ),
+  ];
+  for (i, (name, inner)) in foreign_items.into_iter().enumerate() {
+    let id = 90020 + i as u32;
+    let item: rustdoc_types::Item = serde_json::from_value(serde_json::json!({
+      "id": id, "crate_id": 0, "name": name, "span": null, "visibility": "public",
+      "docs": null, "links": {}, "attrs": [], "deprecation": null, "inner": inner,
+    }))
+    .unwrap();
+    crate_data.index.insert(rustdoc_types::Id(id), item);
+    let root = crate_data.root;
+    if let rustdoc_types::ItemEnum::Module(module) =
+      &mut crate_data.index.get_mut(&root).unwrap().inner
+    {
+      module.items.push(rustdoc_types::Id(id));
+    }
+  }
+
+  let output = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+  assert!(
+    output.files["fn.strlen.md"].contains("extern \"C\" fn strlen(s: *const u8, ...) -> usize")
+  );
+  assert!(output.files["static.ERRNO.md"].contains("pub static mut ERRNO: i32"));
+  assert!(output.files["foreigntype.Handle.md"].contains("*Foreign Type*"));
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("pub extern crate libc as c;"));
+  assert!(index.contains("## Statics"));
+  assert!(index.contains("## Foreign Types"));
+}
+
+#[test]
+fn test_trait_aliases() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // The fixture has no trait aliases, so add `pub trait Handler = MyTrait + Send;`
+  // and `pub type Handlers = impl Handler;` to the crate root
+  let trait_id = crate_data
+    .index
+    .iter()
+    .find(|(_, item)| item.name.as_deref() == Some("MyTrait"))
+    .map(|(id, _)| id.0)
+    .unwrap();
+  let bound = |path: &str, id: u32| {
+    serde_json::json!({"trait_bound": {
+      "trait": {"path": path, "id": id, "args": null},
+      "generic_params": [],
+      "modifier": "none",
+    }})
+  };
+  let no_generics = serde_json::json!({"params": [], "where_predicates": []});
+  let aliases = [
+    (
+      "Handler",
+      serde_json::json!({"trait_alias": {
+        "generics": no_generics,
+        "params": [bound("MyTrait", trait_id), bound("Send", 90001)],
+      }}),
+    ),
+    (
+      "Handlers",
+      serde_json::json!({"type_alias": {
+        "type": {"impl_trait": [bound("Handler", 90030)]},
+        "generics": no_generics,
+      }}),
+    ),
+  ];
+  for (i, (name, inner)) in aliases.into_iter().enumerate() {
+    let id = 90030 + i as u32;
+    let item: rustdoc_types::Item = serde_json::from_value(serde_json::json!({
+      "id": id, "crate_id": 0, "name": name, "span": null, "visibility": "public",
+      "docs": null, "links": {}, "attrs": [], "deprecation": null, "inner": inner,
+    }))
+    .unwrap();
+    crate_data.index.insert(rustdoc_types::Id(id), item);
+    let root = crate_data.root;
+    if let rustdoc_types::ItemEnum::Module(module) =
+      &mut crate_data.index.get_mut(&root).unwrap().inner
+    {
+      module.items.push(rustdoc_types::Id(id));
+    }
+  }
+
+  let output = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+  let handler = &output.files["traitalias.Handler.md"];
+  assert!(handler.contains("title: \"Trait Alias Handler\""));
+  assert!(handler.contains("pub trait Handler = MyTrait + Send;"));
+  assert!(handler.contains("{\"text\": \"MyTrait\", \"href\": \"/test_crate/trait.MyTrait\"}"));
+  // Links to the alias resolve to its page
+  let handlers = &output.files["type.Handlers.md"];
+  assert!(handlers.contains("pub type Handlers = impl Handler;"));
+  assert!(handlers.contains("\"href\": \"/test_crate/traitalias.Handler\""));
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("## Trait Aliases"));
+  assert!(index.contains("traitalias.Handler"));
+}
+
+#[test]
+fn test_std_wrapper_fields() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // Add `pub struct Entry<'a> { pub key: Cow<'a, str>, pub marker: PhantomData<&'a ()> }`
+  // to the crate root (the fixture paths already know `Cow` and `PhantomData`)
+  let path_id = |name: &str| {
+    crate_data
+      .paths
+      .iter()
+      .find(|(_, summary)| summary.path.last().map(String::as_str) == Some(name))
+      .map(|(id, _)| id.0)
+      .unwrap()
+  };
+  let (cow_id, phantom_id) = (path_id("Cow"), path_id("PhantomData"));
+  let fields = [
+    (
+      "key",
+      serde_json::json!({"resolved_path": {"path": "Cow", "id": cow_id, "args": {"angle_bracketed": {
+        "args": [{"lifetime": "'a"}, {"type": {"primitive": "str"}}],
+        "constraints": [],
+      }}}}),
+    ),
+    (
+      "marker",
+      serde_json::json!({"resolved_path": {"path": "PhantomData", "id": phantom_id, "args": {"angle_bracketed": {
+        "args": [{"type": {"borrowed_ref": {"lifetime": "'a", "is_mutable": false, "type": {"tuple": []}}}}],
+        "constraints": [],
+      }}}}),
+    ),
+  ];
+  let item = |id: u32, name: &str, inner: serde_json::Value| -> rustdoc_types::Item {
+    serde_json::from_value(serde_json::json!({
+      "id": id, "crate_id": 0, "name": name, "span": null, "visibility": "public",
+      "docs": null, "links": {}, "attrs": [], "deprecation": null, "inner": inner,
+    }))
+    .unwrap()
+  };
+  for (i, (name, ty)) in fields.into_iter().enumerate() {
+    let id = 90041 + i as u32;
+    crate_data.index.insert(
+      rustdoc_types::Id(id),
+      item(id, name, serde_json::json!({"struct_field": ty})),
+    );
+  }
+  let entry = item(
+    90040,
+    "Entry",
+    serde_json::json!({"struct": {
+      "kind": {"plain": {"fields": [90041, 90042], "has_stripped_fields": false}},
+      "generics": {"params": [{"name": "'a", "kind": {"lifetime": {"outlives": []}}}], "where_predicates": []},
+      "impls": [],
+    }}),
+  );
+  crate_data.index.insert(rustdoc_types::Id(90040), entry);
+  let root = crate_data.root;
+  if let rustdoc_types::ItemEnum::Module(module) =
+    &mut crate_data.index.get_mut(&root).unwrap().inner
+  {
+    module.items.push(rustdoc_types::Id(90040));
+  }
+
+  let convert = |show_phantom_data: bool| {
+    converter::Converter::new(converter::RenderContext {
+      show_phantom_data,
+      ..Default::default()
+    })
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown")
+  };
+
+  let output = convert(false);
+  let page = &output.files["struct.Entry.md"];
+  assert!(page.contains("pub struct Entry<'a> {\n    pub key: Cow<'a, str>,\n}"));
+  assert!(page.contains("<RustCode inline code={`key: Cow<'a, str>`}"));
+  assert!(page.contains("https://doc.rust-lang.org/alloc/borrow/enum.Cow.html"));
+  assert!(!page.contains("PhantomData"));
+
+  let output = convert(true);
+  let page = &output.files["struct.Entry.md"];
+  assert!(page.contains("    pub marker: PhantomData<&'a ()>,\n}"));
+  assert!(page.contains("https://doc.rust-lang.org/core/marker/struct.PhantomData.html"));
+}
+
+#[test]
+fn test_foreign_type_impls() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // Add `impl MyTrait for u64` and `impl<T> MyTrait for [T]` next to the fixture's
+  // `impl MyTrait for String`
+  let trait_id = crate_data
+    .index
+    .iter()
+    .find(|(_, item)| item.name.as_deref() == Some("MyTrait"))
+    .map(|(id, _)| id.0)
+    .unwrap();
+  let impls = [
+    (
+      serde_json::json!({"primitive": "u64"}),
+      serde_json::json!({"params": [], "where_predicates": []}),
+    ),
+    (
+      serde_json::json!({"slice": {"generic": "T"}}),
+      serde_json::json!({"params": [{"name": "T", "kind": {"type": {
+        "bounds": [], "default": null, "is_synthetic": false,
+      }}}], "where_predicates": []}),
+    ),
+  ];
+  for (i, (for_, generics)) in impls.into_iter().enumerate() {
+    let id = 90040 + i as u32;
+    let item: rustdoc_types::Item = serde_json::from_value(serde_json::json!({
+      "id": id, "crate_id": 0, "name": null, "span": null, "visibility": "default",
+      "docs": null, "links": {}, "attrs": [], "deprecation": null,
+      "inner": {"impl": {
+        "is_unsafe": false,
+        "generics": generics,
+        "provided_trait_methods": [],
+        "trait": {"path": "MyTrait", "id": trait_id, "args": null},
+        "for": for_,
+        "items": [],
+        "is_negative": false,
+        "is_synthetic": false,
+        "blanket_impl": null,
+      }},
+    }))
+    .unwrap();
+    crate_data.index.insert(rustdoc_types::Id(id), item);
+  }
+
+  let output = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+  let my_trait = &output.files["trait.MyTrait.md"];
+  let section = &my_trait[my_trait
+    .find("### Implementations on Foreign Types")
+    .expect("foreign type impls should be listed")..];
+  let impls: Vec<&str> = section
+    .lines()
+    .filter_map(|line| line.split("code={`").nth(1)?.split('`').next())
+    .collect();
+  assert_eq!(
+    impls,
+    [
+      "impl MyTrait for String",
+      "impl MyTrait for u64",
+      "impl<T> MyTrait for [T]"
+    ]
+  );
+}
+
 #[test]
 fn test_methods_and_impls() {
   let json_path = Path::new("tests/fixtures/test_crate.json");
@@ -230,16 +690,10 @@ fn test_conversion_end_to_end() {
   let _ = std::fs::remove_dir_all(&output_dir);
 
   let json_path = Path::new("tests/fixtures/test_crate.json");
-  let options = ConversionOptions {
-    input_path: json_path,
-    output_dir: &output_dir,
-    include_private: false,
-    base_path: "",
-    workspace_crates: &[],
-    sidebarconfig_collapsed: false,
-    sidebar_output: None,
-    sidebar_root_link: None,
-  };
+  let mut options = ConversionOptions::default();
+  options.input_path = json_path;
+  options.output_dir = &output_dir;
+  options.sidebarconfig_collapsed = false;
 
   cargo_doc_docusaurus::convert_json_file(&options).expect("Conversion failed");
 
@@ -250,182 +704,1326 @@ fn test_conversion_end_to_end() {
 }
 
 #[test]
-fn test_legacy_single_file_output() {
-  let json_path = Path::new("tests/fixtures/test_crate.json");
-  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
-  let markdown =
-    converter::convert_to_markdown(&crate_data, false).expect("Failed to convert to markdown");
+fn test_conversion_with_options_builder() {
+  let output_dir = tempfile::tempdir().unwrap();
+  let builder = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", output_dir.path())
+    .base_path("/docs/api")
+    .sidebar_output(Some(output_dir.path().join("sidebar.ts")));
+
+  let options = builder.options();
+  assert_eq!(options.base_path, "/docs/api");
+  assert!(!options.include_private);
+  assert_eq!(options.max_depth, None);
+  // Defaults agree with those of the CLI and the configuration file
+  let cli_defaults = cargo_doc_docusaurus::config::OptionsLayer::default().resolve();
+  assert_eq!(
+    options.sidebarconfig_collapsed,
+    cli_defaults.sidebarconfig_collapsed
+  );
+  assert!(ConversionOptions::default().sidebarconfig_collapsed);
+  assert!(
+    !builder
+      .clone()
+      .sidebarconfig_collapsed(false)
+      .options()
+      .sidebarconfig_collapsed
+  );
 
-  assert!(markdown.contains("# test_crate"));
-  assert!(markdown.contains("## Table of Contents"));
-  insta::assert_snapshot!("legacy_single_file", markdown);
+  builder.convert().expect("Conversion failed");
+
+  let index = std::fs::read_to_string(output_dir.path().join("test_crate/index.md")).unwrap();
+  assert!(index.contains("/docs/api/test_crate/"));
+  assert!(output_dir.path().join("sidebar.ts").exists());
 }
 
 #[test]
-fn test_workspace_crates_internal_links() {
-  let json_path = Path::new("tests/fixtures/test_crate.json");
-  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+fn test_sidebar_formats() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let docs_dir = site_dir.path().join("docs");
+  let convert = |format: sidebar::SidebarFormat, namespace: &str| {
+    ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &docs_dir)
+      .namespace(Some(namespace.to_string()))
+      .sidebar_format(format)
+      .convert()
+      .expect("Conversion failed");
+  };
 
-  // First, let's check what external crates are referenced
-  // We know from the JSON that there are references to external crates
+  // CommonJS sidebars of several runs are merged like the TypeScript ones
+  convert(sidebar::SidebarFormat::Js, "v1");
+  convert(sidebar::SidebarFormat::Js, "v2");
+  let js = std::fs::read_to_string(site_dir.path().join("sidebars-rust.js")).unwrap();
+  assert!(js.contains("module.exports = { rustSidebars, rootRustSidebar,"));
+  let data: serde_json::Value =
+    serde_json::from_str(sidebar::parse_commonjs(&js).unwrap()).unwrap();
+  assert!(data.get("v1_test_crate").is_some());
+  assert!(data.get("v2_test_crate").is_some());
+
+  convert(sidebar::SidebarFormat::Json, "v1");
+  let json = std::fs::read_to_string(site_dir.path().join("sidebars-rust.json")).unwrap();
+  let data: serde_json::Value = serde_json::from_str(&json).unwrap();
+  assert!(data.get("v1_test_crate").is_some());
+  assert!(!site_dir.path().join("sidebars-rust.ts").exists());
+
+  // An explicit sidebar output must match the format
+  let mismatch = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &docs_dir)
+    .sidebar_output(Some(site_dir.path().join("sidebars.ts")))
+    .sidebar_format(sidebar::SidebarFormat::Js)
+    .convert();
+  assert!(mismatch.is_err());
+}
 
-  // Test without workspace_crates parameter
-  let output_default =
-    converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
-      .expect("Failed to convert to markdown");
+#[test]
+fn test_sidebar_hook() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let docs_dir = site_dir.path().join("docs");
+  let hook = sidebar::SidebarHook::new(|sidebars| {
+    let items = sidebars.get_mut("test_crate").unwrap();
+    items.insert(
+      0,
+      SidebarItem::Link {
+        href: "/guide".to_string(),
+        label: "Guide".to_string(),
+        class_name: None,
+        custom_props: None,
+      },
+    );
+  });
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &docs_dir)
+    .sidebar_format(sidebar::SidebarFormat::Json)
+    .sidebar_hook(Some(hook))
+    .convert()
+    .expect("Conversion failed");
+
+  let json = std::fs::read_to_string(site_dir.path().join("sidebars-rust.json")).unwrap();
+  let data: serde_json::Value = serde_json::from_str(&json).unwrap();
+  assert_eq!(data["test_crate"][0]["label"], "Guide");
+  assert_eq!(data["test_crate"][0]["href"], "/guide");
+}
 
-  // Test with workspace_crates parameter
-  let workspace_crates = vec!["some_external_crate".to_string()];
-  let output_with_workspace = converter::convert_to_markdown_multifile(
-    &crate_data,
-    false,
-    "/docs/rust",
-    &workspace_crates,
-    false,
-    None,
-  )
-  .expect("Failed to convert to markdown");
+#[test]
+fn test_category_files() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let docs_dir = site_dir.path().join("docs");
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &docs_dir)
+    .base_path("/docs/api")
+    .category_files(true)
+    .convert()
+    .expect("Conversion failed");
+
+  let read_category = |dir: &str| -> serde_json::Value {
+    let path = docs_dir.join(dir).join("_category_.json");
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+  };
+  let crate_category = read_category("test_crate");
+  assert_eq!(crate_category["label"], "test_crate");
+  assert_eq!(crate_category["link"]["id"], "api/test_crate/index");
+  let module_category = read_category("test_crate/types");
+  assert_eq!(module_category["label"], "types");
+  assert_eq!(module_category["link"]["id"], "api/test_crate/types/index");
+  assert!(module_category["position"].is_u64());
+
+  // Pages use the autogenerated sidebar instead of the sidebar file
+  assert!(!site_dir.path().join("sidebars-rust.ts").exists());
+  let index = std::fs::read_to_string(docs_dir.join("test_crate/index.md")).unwrap();
+  assert!(!index.contains("displayed_sidebar"));
+  let page =
+    std::fs::read_to_string(docs_dir.join("test_crate/types/struct.Container.md")).unwrap();
+  assert!(page.contains("sidebar_label: \"Container\""));
+}
 
-  // Both should generate files successfully
-  assert!(
-    !output_default.files.is_empty(),
-    "Default should generate files"
+#[test]
+fn test_no_sidebar() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let docs_dir = site_dir.path().join("docs/api");
+  // A sidebar file of the site is left alone
+  std::fs::write(site_dir.path().join("sidebars-rust.ts"), "// mine\n").unwrap();
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &docs_dir)
+    .base_path("/docs/api")
+    .no_sidebar(true)
+    .convert()
+    .expect("Conversion failed");
+
+  assert_eq!(
+    std::fs::read_to_string(site_dir.path().join("sidebars-rust.ts")).unwrap(),
+    "// mine\n"
   );
+  assert!(!site_dir.path().join("docs/sidebars-rust.ts").exists());
+  let index = std::fs::read_to_string(docs_dir.join("test_crate/index.md")).unwrap();
+  assert!(!index.contains("displayed_sidebar"));
+  let page =
+    std::fs::read_to_string(docs_dir.join("test_crate/types/struct.Container.md")).unwrap();
+  assert!(!page.contains("displayed_sidebar"));
+
+  let conflict = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &docs_dir)
+    .no_sidebar(true)
+    .sidebar_output(Some(site_dir.path().join("sidebars-api.ts")))
+    .convert()
+    .unwrap_err();
   assert!(
-    !output_with_workspace.files.is_empty(),
-    "With workspace_crates should generate files"
+    conflict
+      .to_string()
+      .contains("--no-sidebar writes no sidebar, so it cannot be combined with --sidebar-output")
   );
+}
 
-  // Verify base_path is used correctly in the second case
-  let has_base_path_in_internal_links = output_with_workspace.files.values().any(|content| {
-    // Internal links to the current crate should use base_path
-    content.contains("/docs/rust/test_crate/")
-  });
-
-  assert!(
-    has_base_path_in_internal_links,
-    "Internal links should use the provided base_path (/docs/rust)"
-  );
+/// All files below `dir`, keyed by their path relative to `dir`
+fn read_tree(dir: &Path) -> std::collections::BTreeMap<PathBuf, Vec<u8>> {
+  let mut files = std::collections::BTreeMap::new();
+  let mut pending = vec![dir.to_path_buf()];
+  while let Some(current) = pending.pop() {
+    for entry in std::fs::read_dir(&current).unwrap() {
+      let path = entry.unwrap().path();
+      if path.is_dir() {
+        pending.push(path);
+      } else {
+        let relative = path.strip_prefix(dir).unwrap().to_path_buf();
+        files.insert(relative, std::fs::read(&path).unwrap());
+      }
+    }
+  }
+  files
+}
 
-  // Verify std library links still work (should always point to doc.rust-lang.org)
-  let has_std_links = output_with_workspace
-    .files
-    .values()
-    .any(|content| content.contains("doc.rust-lang.org"));
+#[test]
+fn test_output_is_stable_across_runs() {
+  let convert = || {
+    let site_dir = tempfile::tempdir().unwrap();
+    ConversionOptionsBuilder::new(
+      "tests/fixtures/test_crate.json",
+      site_dir.path().join("docs"),
+    )
+    .base_path("/docs/api")
+    .error_index(true)
+    .async_index(true)
+    .feature_matrix(true)
+    .glossary(true)
+    .all_items(true)
+    .convert()
+    .expect("Conversion failed");
+    read_tree(site_dir.path())
+  };
 
-  assert!(
-    has_std_links,
-    "Std library links should always point to doc.rust-lang.org"
-  );
+  let first = convert();
+  assert!(first.contains_key(Path::new("sidebars-rust.ts")));
+  for _ in 0..3 {
+    let next = convert();
+    assert_eq!(
+      first.keys().collect::<Vec<_>>(),
+      next.keys().collect::<Vec<_>>()
+    );
+    for (path, content) in &first {
+      assert!(
+        next[path] == *content,
+        "{} differs between runs",
+        path.display()
+      );
+    }
+  }
 }
 
 #[test]
-fn test_workspace_crates_with_hyphens() {
-  let json_path = Path::new("tests/fixtures/test_crate.json");
-  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+fn test_base_path_normalization() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let convert = |base_path: &str, subdir: &str| {
+    let output_dir = site_dir.path().join(subdir).join("docs/api");
+    ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &output_dir)
+      .base_path(base_path)
+      .convert()
+      .map(|_| std::fs::read_to_string(output_dir.join("test_crate/index.md")).unwrap())
+  };
 
-  // Test that workspace_crates works with hyphens (as in Cargo.toml)
-  // even though crate names in JSON use underscores
-  let workspace_crates_with_hyphens = vec!["test-crate".to_string()];
-  let workspace_crates_with_underscores = vec!["test_crate".to_string()];
+  let canonical = convert("/docs/api", "a").unwrap();
+  assert!(canonical.contains("displayed_sidebar: 'api_test_crate'"));
+  assert_eq!(convert("docs/api/", "b").unwrap(), canonical);
+  assert_eq!(convert(" /docs//api ", "c").unwrap(), canonical);
+
+  for invalid in [
+    "https://example.com/docs/api",
+    "/docs/../api",
+    "/docs/my api",
+  ] {
+    let error = convert(invalid, "d").unwrap_err();
+    assert!(matches!(
+      error,
+      cargo_doc_docusaurus::Error::InvalidOptions(_)
+    ));
+    assert!(error.to_string().contains("--base-path"));
+  }
+}
 
-  let output_hyphens = converter::convert_to_markdown_multifile(
-    &crate_data,
-    false,
-    "/docs",
-    &workspace_crates_with_hyphens,
-    false,
-    None,
-  )
-  .expect("Should work with hyphens");
+#[test]
+fn test_namespaced_versions_coexist() {
+  let docs_dir = tempfile::tempdir().unwrap();
+  let output_dir = docs_dir.path().join("docs");
+  let sidebar_path = docs_dir.path().join("sidebars-rust.ts");
+
+  for namespace in ["v1", "v2"] {
+    ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &output_dir)
+      .base_path("/docs/api")
+      .namespace(Some(namespace.to_string()))
+      .convert()
+      .expect("Conversion failed");
+  }
 
-  let output_underscores = converter::convert_to_markdown_multifile(
-    &crate_data,
-    false,
-    "/docs",
-    &workspace_crates_with_underscores,
-    false,
-    None,
-  )
-  .expect("Should work with underscores");
+  for namespace in ["v1", "v2"] {
+    let index =
+      std::fs::read_to_string(output_dir.join(namespace).join("test_crate/index.md")).unwrap();
+    assert!(index.contains(&format!(
+      "displayed_sidebar: 'api_{}_test_crate'",
+      namespace
+    )));
+    assert!(index.contains(&format!("/docs/api/{}/test_crate/", namespace)));
+  }
 
-  // Both should generate the same number of files
-  assert_eq!(
-    output_hyphens.files.len(),
-    output_underscores.files.len(),
-    "Both hyphen and underscore notation should work identically"
-  );
+  // Both versions end up in the default sidebar file without clobbering each other
+  let sidebar = std::fs::read_to_string(&sidebar_path).expect("sidebar should be written");
+  assert!(sidebar.contains("'api_v1_test_crate': ["));
+  assert!(sidebar.contains("'api_v2_test_crate': ["));
+  assert!(sidebar.contains("id: 'api/v1/test_crate/index', label: 'test_crate (v1)'"));
+  assert!(sidebar.contains("id: 'api/v2/test_crate/index', label: 'test_crate (v2)'"));
+
+  let invalid = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &output_dir)
+    .namespace(Some("../v3".to_string()))
+    .convert()
+    .unwrap_err();
+  assert!(matches!(
+    invalid,
+    cargo_doc_docusaurus::Error::InvalidOptions(_)
+  ));
+  assert!(invalid.to_string().contains("Invalid namespace"));
 }
 
 #[test]
-fn test_sidebar_collapsed_configuration() {
-  let json_path = Path::new("tests/fixtures/test_crate.json");
-  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+fn test_versioned_docs() {
+  let site_dir = tempfile::tempdir().unwrap();
 
-  // Test with collapsed = false (default)
-  let output_collapsed_false =
-    converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
-      .expect("Failed to convert to markdown");
+  ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("docs/api"),
+  )
+  .base_path("/docs/api")
+  .docs_version(Some("1.2".to_string()))
+  .convert()
+  .expect("Conversion failed");
+
+  // Output goes to the version's directory, not the current docs
+  assert!(!site_dir.path().join("docs").exists());
+  let index = std::fs::read_to_string(
+    site_dir
+      .path()
+      .join("versioned_docs/version-1.2/api/test_crate/index.md"),
+  )
+  .expect("versioned index should be written");
+  assert!(index.contains("displayed_sidebar: '1_2_api_test_crate'"));
+  assert!(index.contains("/docs/1.2/api/test_crate/"));
+  assert!(!index.contains("\"/docs/api/"));
+
+  // Doc IDs stay relative to the version directory, sidebar keys include the version
+  let sidebar = std::fs::read_to_string(
+    site_dir
+      .path()
+      .join("versioned_sidebars/version-1.2-sidebars-rust.ts"),
+  )
+  .expect("versioned sidebar should be written");
+  assert!(sidebar.contains("'1_2_api_test_crate': ["));
+  assert!(sidebar.contains("id: 'api/test_crate/index'"));
+
+  let outside_docs =
+    ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", site_dir.path())
+      .docs_version(Some("1.2".to_string()))
+      .convert()
+      .unwrap_err();
+  assert!(outside_docs.to_string().contains("Docusaurus docs folder"));
+}
 
-  // Test with collapsed = true
-  let output_collapsed_true =
-    converter::convert_to_markdown_multifile(&crate_data, false, "", &[], true, None)
-      .expect("Failed to convert to markdown");
+#[test]
+fn test_docusaurus_version() {
+  let site_dir = tempfile::tempdir().unwrap();
+  std::fs::write(site_dir.path().join("versions.json"), "[\"1.1\"]").unwrap();
+  std::fs::create_dir(site_dir.path().join("versioned_sidebars")).unwrap();
+  let sidebar_path = site_dir
+    .path()
+    .join("versioned_sidebars/version-1.2-sidebars.json");
+  std::fs::write(&sidebar_path, r#"{"tutorialSidebar": ["intro"]}"#).unwrap();
+
+  let convert = || {
+    ConversionOptionsBuilder::new(
+      "tests/fixtures/test_crate.json",
+      site_dir.path().join("docs/api"),
+    )
+    .base_path("/docs/api")
+    .docusaurus_version(Some("1.2".to_string()))
+    .convert()
+  };
+  convert().expect("Conversion failed");
+  convert().expect("Second conversion failed");
 
-  // Both should have a sidebar
   assert!(
-    output_collapsed_false.sidebar.is_some(),
-    "Should generate sidebar when collapsed=false"
+    site_dir
+      .path()
+      .join("versioned_docs/version-1.2/api/test_crate/index.md")
+      .exists()
   );
-  assert!(
-    output_collapsed_true.sidebar.is_some(),
-    "Should generate sidebar when collapsed=true"
+  // The version is registered once, newest first
+  let versions = std::fs::read_to_string(site_dir.path().join("versions.json")).unwrap();
+  assert_eq!(
+    serde_json::from_str::<Vec<String>>(&versions).unwrap(),
+    ["1.2", "1.1"]
+  );
+  // The crate's sidebars are merged into the version's sidebars
+  let sidebars: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(&sidebar_path).unwrap()).unwrap();
+  assert_eq!(sidebars["tutorialSidebar"], serde_json::json!(["intro"]));
+  assert_eq!(
+    sidebars["1_2_api_test_crate"][0]["id"],
+    "api/test_crate/index"
   );
 
-  // Note: The collapsed parameter is currently not implemented in the sidebar generation
-  // TODO: Implement collapsed functionality or remove the parameter from the API
+  let conflicting = ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("docs/api"),
+  )
+  .docs_version(Some("1.1".to_string()))
+  .docusaurus_version(Some("1.2".to_string()))
+  .convert()
+  .unwrap_err();
+  assert!(conflicting.to_string().contains("different versions"));
 }
 
 #[test]
-fn test_custom_sidebar_output() {
-  let output_dir = std::env::temp_dir().join("cargo_doc_md_test_sidebar");
-  let sidebar_path = output_dir.join("custom-sidebar.ts");
-  let _ = std::fs::remove_dir_all(&output_dir);
-  std::fs::create_dir_all(&output_dir).expect("Failed to create test directory");
-
-  let json_path = Path::new("tests/fixtures/test_crate.json");
-  let options = ConversionOptions {
-    input_path: json_path,
-    output_dir: &output_dir.join("docs"),
-    include_private: false,
-    base_path: "",
-    workspace_crates: &[],
-    sidebarconfig_collapsed: false,
-    sidebar_output: Some(&sidebar_path),
-    sidebar_root_link: None,
-  };
+fn test_docs_plugin_instance() {
+  let site_dir = tempfile::tempdir().unwrap();
 
-  cargo_doc_docusaurus::convert_json_file(&options).expect("Conversion failed");
+  // A second docs plugin instance: `id: 'rust'`, `path: 'rust'`, `routeBasePath: '/rust'`
+  ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("rust/api"),
+  )
+  .base_path("/rust/api")
+  .route_base_path(Some("/rust".to_string()))
+  .docs_plugin_id(Some("rust".to_string()))
+  .docusaurus_version(Some("1.2".to_string()))
+  .convert()
+  .expect("Conversion failed");
+
+  let index = std::fs::read_to_string(
+    site_dir
+      .path()
+      .join("rust_versioned_docs/version-1.2/api/test_crate/index.md"),
+  )
+  .expect("versioned index of the plugin instance should be written");
+  assert!(index.contains("displayed_sidebar: '1_2_api_test_crate'"));
+  assert!(index.contains("/rust/1.2/api/test_crate/"));
+  let versions = std::fs::read_to_string(site_dir.path().join("rust_versions.json")).unwrap();
+  assert_eq!(
+    serde_json::from_str::<Vec<String>>(&versions).unwrap(),
+    ["1.2"]
+  );
+  let sidebars: serde_json::Value = serde_json::from_str(
+    &std::fs::read_to_string(
+      site_dir
+        .path()
+        .join("rust_versioned_sidebars/version-1.2-sidebars.json"),
+    )
+    .unwrap(),
+  )
+  .unwrap();
+  assert_eq!(
+    sidebars["1_2_api_test_crate"][0]["id"],
+    "api/test_crate/index"
+  );
+  assert!(!site_dir.path().join("versions.json").exists());
 
-  // Check that the sidebar was created in the custom location
-  assert!(sidebar_path.exists(), "Custom sidebar should be created");
+  // Docs served at the site root get the version right below it
+  ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("docs/api"),
+  )
+  .base_path("/api")
+  .route_base_path(Some("/".to_string()))
+  .docs_version(Some("1.2".to_string()))
+  .convert()
+  .expect("Conversion failed");
+  let index = std::fs::read_to_string(
+    site_dir
+      .path()
+      .join("versioned_docs/version-1.2/api/test_crate/index.md"),
+  )
+  .unwrap();
+  assert!(index.contains("displayed_sidebar: '1_2_api_test_crate'"));
+  assert!(index.contains("\"/1.2/api/test_crate/"));
 
-  let sidebar_content = std::fs::read_to_string(&sidebar_path).expect("Failed to read sidebar");
-  assert!(
-    sidebar_content.contains("export const rustApiDocumentation"),
-    "Should contain sidebar export"
-  );
+  let outside_route = ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("docs/api"),
+  )
+  .base_path("/docs/api")
+  .route_base_path(Some("/rust".to_string()))
+  .convert()
+  .unwrap_err();
   assert!(
-    sidebar_content.contains("test_crate/index"),
-    "Should contain test_crate reference"
+    outside_route
+      .to_string()
+      .contains("--base-path /docs/api is not below --route-base-path /rust")
   );
 }
 
 #[test]
-fn test_workspace_single_crate() {
-  // Test documenting a single crate from a workspace
-  let json_path = Path::new("tests/fixtures/test_workspace/crate_a.json");
-  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load crate_a JSON");
+fn test_docusaurus_2_site() {
+  let site_dir = tempfile::tempdir().unwrap();
+  std::fs::write(
+    site_dir.path().join("package.json"),
+    r#"{"dependencies": {"@docusaurus/core": "^2.4.3"}}"#,
+  )
+  .unwrap();
+
+  ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("docs"),
+  )
+  .convert()
+  .expect("Conversion failed");
+
+  let page = std::fs::read_to_string(
+    site_dir
+      .path()
+      .join("docs/test_crate/nested/reexport_test/struct.GlobStruct.md"),
+  )
+  .unwrap();
+  assert!(page.contains(":::note Re-export\n"));
+  let sidebar = std::fs::read_to_string(site_dir.path().join("sidebars-rust.ts")).unwrap();
+  assert!(!sidebar.contains("SidebarsConfig"));
+
+  // An explicit version wins over the detected one
+  ConversionOptionsBuilder::new(
+    "tests/fixtures/test_crate.json",
+    site_dir.path().join("docs"),
+  )
+  .docusaurus_major(Some(converter::DocusaurusMajor::V3))
+  .convert()
+  .expect("Conversion failed");
+  let sidebar = std::fs::read_to_string(site_dir.path().join("sidebars-rust.ts")).unwrap();
+  assert!(sidebar.contains("import type {SidebarsConfig}"));
+}
+
+#[test]
+fn test_inline_reexports() {
+  let fixture: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string("tests/fixtures/test_crate.json").unwrap())
+      .unwrap();
+  let input_dir = tempfile::tempdir().unwrap();
+
+  // `other_crate` is a copy of the fixture under another name
+  let mut other = fixture.clone();
+  let root = other["root"].to_string();
+  other["index"][&root]["name"] = "other_crate".into();
+  for summary in other["paths"].as_object_mut().unwrap().values_mut() {
+    if summary["crate_id"] == 0 {
+      summary["path"][0] = "other_crate".into();
+    }
+  }
+  std::fs::write(input_dir.path().join("other_crate.json"), other.to_string()).unwrap();
+
+  // `pub use other_crate::GenericStruct as Pair;` in the `async_example` module
+  let mut crate_json = fixture;
+  crate_json["external_crates"]["900"] =
+    serde_json::json!({"name": "other_crate", "html_root_url": null});
+  crate_json["paths"]["90050"] = serde_json::json!({
+    "crate_id": 900, "path": ["other_crate", "GenericStruct"], "kind": "struct",
+  });
+  crate_json["index"]["90051"] = serde_json::json!({
+    "id": 90051, "crate_id": 0, "name": null, "span": null, "visibility": "public",
+    "docs": null, "links": {}, "attrs": [], "deprecation": null,
+    "inner": {"use": {
+      "source": "other_crate::GenericStruct", "name": "Pair", "id": 90050, "is_glob": false,
+    }},
+  });
+  let module = crate_json["index"]
+    .as_object_mut()
+    .unwrap()
+    .values_mut()
+    .find(|item| item["name"] == "async_example")
+    .unwrap();
+  module["inner"]["module"]["items"]
+    .as_array_mut()
+    .unwrap()
+    .push(90051.into());
+  let input = input_dir.path().join("test_crate.json");
+  std::fs::write(&input, crate_json.to_string()).unwrap();
+
+  let convert = |inline_reexports| {
+    let output_dir = tempfile::tempdir().unwrap();
+    ConversionOptionsBuilder::new(&input, output_dir.path())
+      .base_path("/docs")
+      .inline_reexports(inline_reexports)
+      .convert()
+      .expect("Conversion failed");
+    output_dir
+  };
+
+  let output_dir = convert(true);
+  let module_dir = output_dir.path().join("test_crate/async_example");
+  let page = std::fs::read_to_string(module_dir.join("struct.Pair.md"))
+    .expect("re-exported struct should be inlined");
+  assert!(page.contains("title: \"Struct Pair\""));
+  assert!(page.contains("**<Link to=\"/docs/test_crate/\">test_crate</Link>::<Link to=\"/docs/test_crate/async_example/\">async_example</Link>::Pair**"));
+  assert!(page.contains("`fn swap(self: Self)"));
+
+  let overview = std::fs::read_to_string(module_dir.join("index.md")).unwrap();
+  assert!(overview.contains(
+    "<RustCode inline code={`pub use other_crate::GenericStruct;`} links={[{\"text\": \"GenericStruct\", \"href\": \"/docs/test_crate/async_example/struct.Pair\"}]} />"
+  ));
+
+  // The option is opt-in
+  let output_dir = convert(false);
+  assert!(
+    !output_dir
+      .path()
+      .join("test_crate/async_example/struct.Pair.md")
+      .exists()
+  );
+}
+
+#[test]
+fn test_i18n_output() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let convert = |docs_version: Option<&str>| {
+    ConversionOptionsBuilder::new(
+      "tests/fixtures/test_crate.json",
+      site_dir.path().join("docs/api"),
+    )
+    .base_path("/docs/api")
+    .locale(Some("fr".to_string()))
+    .docs_version(docs_version.map(str::to_string))
+    .convert()
+    .expect("Conversion failed");
+  };
+
+  convert(None);
+  let locale_dir = site_dir.path().join("i18n/fr");
+  let index = std::fs::read_to_string(
+    locale_dir.join("docusaurus-plugin-content-docs/current/api/test_crate/index.md"),
+  )
+  .expect("translated index should be written");
+  assert!(index.contains("displayed_sidebar: 'fr_api_test_crate'"));
+  // Docusaurus adds the locale to links itself
+  assert!(index.contains("/docs/api/test_crate/"));
+
+  let sidebar = std::fs::read_to_string(locale_dir.join("sidebars-rust.ts"))
+    .expect("locale sidebar should be written");
+  assert!(sidebar.contains("'fr_api_test_crate': ["));
+  assert!(sidebar.contains("id: 'api/test_crate/index'"));
+
+  // Translations of a docs version
+  convert(Some("1.2"));
+  let index = std::fs::read_to_string(
+    locale_dir.join("docusaurus-plugin-content-docs/version-1.2/api/test_crate/index.md"),
+  )
+  .expect("translated versioned index should be written");
+  assert!(index.contains("displayed_sidebar: 'fr_1_2_api_test_crate'"));
+  let sidebar =
+    std::fs::read_to_string(locale_dir.join("versioned_sidebars/version-1.2-sidebars-rust.ts"))
+      .expect("locale versioned sidebar should be written");
+  assert!(sidebar.contains("'fr_1_2_api_test_crate': ["));
+}
+
+#[test]
+fn test_locale_dir_output() {
+  let site_dir = tempfile::tempdir().unwrap();
+  let locale_dir = site_dir
+    .path()
+    .join("i18n/de/docusaurus-plugin-content-docs-api");
+  let convert = |locale: Option<&str>| {
+    ConversionOptionsBuilder::new(
+      "tests/fixtures/test_crate.json",
+      site_dir.path().join("docs/api"),
+    )
+    .base_path("/docs/api")
+    .locale(locale.map(str::to_string))
+    .locale_dir(Some(locale_dir.clone()))
+    .convert()
+  };
+
+  // The locale is taken from the directory below i18n
+  convert(None).expect("Conversion failed");
+  let index = std::fs::read_to_string(locale_dir.join("current/api/test_crate/index.md"))
+    .expect("translated index should be written");
+  assert!(index.contains("displayed_sidebar: 'de_api_test_crate'"));
+  let sidebar = std::fs::read_to_string(site_dir.path().join("i18n/de/sidebars-rust.ts"))
+    .expect("locale sidebar should be written");
+  assert!(sidebar.contains("'de_api_test_crate': ["));
+
+  let mismatch = convert(Some("fr")).unwrap_err();
+  assert!(matches!(
+    mismatch,
+    cargo_doc_docusaurus::Error::InvalidOptions(_)
+  ));
+  assert!(
+    mismatch
+      .to_string()
+      .contains("does not match the locale 'de'")
+  );
+}
+
+#[test]
+fn test_mdx_extension() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    extension: converter::OutputExtension::Mdx,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  assert!(output.files.contains_key("index.mdx"));
+  assert!(output.files.keys().all(|path| path.ends_with(".mdx")));
+
+  // Sidebar doc IDs do not depend on the extension
+  let sidebar = sidebar::render_typescript(&output.sidebar.unwrap(), output.docusaurus_major);
+  assert!(sidebar.contains("id: 'test_crate/index'"));
+  assert!(!sidebar.contains(".mdx"));
+}
+
+#[test]
+fn test_error_index_page() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    error_index: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = output
+    .files
+    .get("error-index.md")
+    .expect("error-index.md should be generated");
+  insta::assert_snapshot!("error_index", page);
+
+  let index = &output.files["index.md"];
+  assert!(
+    index
+      .contains("<Link to=\"/docs/test_crate/error-index\" className=\"rust-mod\">Errors</Link>")
+  );
+
+  // The page is opt-in
+  let default_output =
+    converter::convert_to_markdown_multifile(&crate_data, false, "/docs", &[], false, None)
+      .unwrap();
+  assert!(!default_output.files.contains_key("error-index.md"));
+}
+
+#[test]
+fn test_async_index_page() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    async_index: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = output
+    .files
+    .get("async-index.md")
+    .expect("async-index.md should be generated");
+  assert!(page.contains("## Async Functions"));
+  assert!(page.contains("test_crate::async_example::simple_async"));
+  // Functions returning `impl Future` count as async too
+  assert!(page.contains("test_crate::async_example::returns_future"));
+  assert!(page.contains("## Async Methods"));
+  insta::assert_snapshot!("async_index", page);
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("<Link to=\"/docs/test_crate/async-index\""));
+}
+
+#[test]
+fn test_all_items_page() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    all_items: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = output
+    .files
+    .get("all.md")
+    .expect("all.md should be generated");
+  assert!(page.contains("## Structs"));
+  assert!(page.contains("## Macros"));
+  assert!(!page.contains("## Modules"));
+  // Items of glob re-exports are listed once
+  assert_eq!(
+    page
+      .matches(">test_crate::async_example::AsyncCounter<")
+      .count(),
+    1
+  );
+  insta::assert_snapshot!("all_items", page);
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("<Link to=\"/docs/test_crate/all\""));
+}
+
+#[test]
+fn test_kind_index_pages() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    kind_indexes: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let structs = &output.files["all-structs.md"];
+  assert!(structs.contains("# All Structs\n"));
+  assert!(structs.contains(">test_crate::async_example::AsyncCounter<"));
+  assert!(!structs.contains("rust-trait"));
+  assert!(output.files.contains_key("all-traits.md"));
+  // Kinds without items get no page
+  assert!(!output.files.contains_key("all-unions.md"));
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("<Link to=\"/docs/test_crate/all-structs\""));
+  assert!(!index.contains("/docs/test_crate/all\""));
+}
+
+#[test]
+fn test_feature_matrix_page() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // The fixture has no feature-gated items, so gate a few
+  let gate = |crate_data: &mut rustdoc_types::Crate, name: &str, attr: &str| {
+    let item = crate_data
+      .index
+      .values_mut()
+      .find(|item| item.name.as_deref() == Some(name))
+      .unwrap();
+    item
+      .attrs
+      .push(rustdoc_types::Attribute::Other(attr.to_string()));
+  };
+  gate(
+    &mut crate_data,
+    "async_example",
+    "#[doc(cfg(feature = \"async\"))]",
+  );
+  gate(
+    &mut crate_data,
+    "Builder",
+    "#[cfg(all(feature = \"builder\", feature=\"std\"))]",
+  );
+  gate(
+    &mut crate_data,
+    "PlainStruct",
+    "#[cfg(target_feature = \"avx2\")]",
+  );
+
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    feature_matrix: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = output
+    .files
+    .get("feature-matrix.md")
+    .expect("feature-matrix.md should be generated");
+  assert!(page.contains("| Item | `async` | `builder` | `std` |"));
+  // Items of a gated module inherit its features
+  assert!(page.contains("test_crate::async_example::simple_async</Link> | ✓ |  |  |"));
+  assert!(page.contains(
+    "| <Link to=\"/docs/test_crate/async_example/\" className=\"rust-mod\">test_crate::async_example</Link> | ✓ |"
+  ));
+  assert!(page.contains("test_crate::patterns::Builder</Link> |  | ✓ | ✓ |"));
+  assert!(!page.contains("avx2"));
+  assert!(!page.contains("PlainStruct"));
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("<Link to=\"/docs/test_crate/feature-matrix\""));
+}
+
+#[test]
+fn test_glossary_page() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // The fixture defines no glossary terms, so add a few
+  let append_docs = |crate_data: &mut rustdoc_types::Crate, name: &str, docs: &str| {
+    let item = crate_data
+      .index
+      .values_mut()
+      .find(|item| item.name.as_deref() == Some(name))
+      .unwrap();
+    let existing = item.docs.clone().unwrap_or_default();
+    item.docs = Some(format!("{}\n\n{}", existing, docs));
+  };
+  append_docs(
+    &mut crate_data,
+    "Container",
+    "<!-- glossary: Capacity -->\nThe number of items a container\ncan hold without reallocating.",
+  );
+  append_docs(
+    &mut crate_data,
+    "patterns",
+    "# Terminology\n\n- **Builder**: A type assembling another type step by step.\n- Newtype: A tuple struct wrapping a single value.\n\n# Notes\n\n- Not: a term.",
+  );
+  append_docs(
+    &mut crate_data,
+    "Builder",
+    "## Glossary\n\n- **builder** — The `Builder` type itself.",
+  );
+
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    glossary: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = output
+    .files
+    .get("glossary.md")
+    .expect("glossary.md should be generated");
+  insta::assert_snapshot!("glossary", page);
+  assert!(!page.contains("Not"));
+
+  // Markers are not rendered on item pages
+  let container = &output.files["types/struct.Container.md"];
+  assert!(container.contains("The number of items a container"));
+  assert!(!container.contains("<!--"));
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("<Link to=\"/docs/test_crate/glossary\""));
+}
+
+#[test]
+fn test_strip_prefix_from_displayed_paths() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    strip_prefix: Some("test_crate::nested".to_string()),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  // Breadcrumbs drop the prefix
+  let module_page = &output.files["nested/inner/index.md"];
+  assert!(module_page.contains("**inner**\n\n# Module inner"));
+  let item_page = &output.files["nested/inner/deep/struct.DeepStruct.md"];
+  assert!(item_page.contains("**<Link to=\"/docs/test_crate/nested/inner/\">inner</Link>::<Link to=\"/docs/test_crate/nested/inner/deep/\">deep</Link>::DeepStruct**"));
+
+  // Sidebar labels drop the prefix, paths outside it and doc IDs are unchanged
+  let sidebar =
+    sidebar::render_typescript(output.sidebar.as_ref().unwrap(), output.docusaurus_major);
+  assert!(sidebar.contains("id: 'test_crate/nested/inner/deep/index'"));
+  assert!(sidebar.contains("label: 'In inner::deep',"));
+  assert!(sidebar.contains("label: 'In nested',"));
+  assert!(sidebar.contains("label: 'In test_crate::patterns',"));
+  assert!(!sidebar.contains("In test_crate::nested"));
+}
+
+#[test]
+fn test_sidebar_icons() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let icons = sidebar::SidebarIcons::from([
+    ("module".to_string(), "M".to_string()),
+    ("struct".to_string(), "S".to_string()),
+  ]);
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    sidebar_icons: Some(icons.clone()),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let ts = sidebar::render_typescript(output.sidebar.as_ref().unwrap(), output.docusaurus_major);
+  assert!(ts.contains(
+    "{ type: 'doc', id: 'test_crate/struct.PlainStruct', label: 'PlainStruct', className: 'rust-struct', customProps: { icon: 'S' } },"
+  ));
+  assert!(ts.contains("label: 'patterns', className: 'rust-mod', customProps: { icon: 'M' } },"));
+  // Kinds without an icon are unchanged
+  assert!(ts.contains("className: 'rust-fn' },"));
+
+  let unknown = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", "unused")
+    .sidebar_icons(Some(sidebar::SidebarIcons::from([(
+      "structs".to_string(),
+      "S".to_string(),
+    )])))
+    .convert()
+    .unwrap_err();
+  assert!(
+    unknown
+      .to_string()
+      .contains("Unknown sidebar icon kind 'structs'")
+  );
+}
+
+#[test]
+fn test_doc_admonitions() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let render = |doc_admonitions| {
+    converter::Converter::new(converter::RenderContext {
+      base_path: "/docs".to_string(),
+      doc_admonitions,
+      ..Default::default()
+    })
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown")
+  };
+
+  let output = render(None);
+  let page = output
+    .files
+    .iter()
+    .find(|(path, _)| path.ends_with("fn.unsafe_function.md"))
+    .map(|(_, content)| content)
+    .unwrap();
+  assert!(page.contains(":::danger[Safety]\n\nThe caller must ensure"));
+  assert!(!page.contains("\n# Safety"));
+
+  let output = render(Some(converter::DocAdmonitions::from([(
+    "safety".to_string(),
+    "heading".to_string(),
+  )])));
+  let page = output
+    .files
+    .iter()
+    .find(|(path, _)| path.ends_with("fn.unsafe_function.md"))
+    .map(|(_, content)| content)
+    .unwrap();
+  assert!(page.contains("\n## Safety\n"));
+  assert!(!page.contains(":::"));
+
+  let invalid = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", "unused")
+    .doc_admonitions(Some(converter::DocAdmonitions::from([(
+      "Safety".to_string(),
+      "alarm".to_string(),
+    )])))
+    .convert()
+    .unwrap_err();
+  assert!(
+    invalid
+      .to_string()
+      .contains("Invalid admonition kind 'alarm' for 'Safety'")
+  );
+}
+
+#[test]
+fn test_changed_only_preview() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs/api".to_string(),
+    changed_files: Some([PathBuf::from("src/types.rs")].into()),
+    production_url: Some("https://docs.example.com".to_string()),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  // Only the crate index and pages of items defined in the changed file
+  assert!(output.files.contains_key("index.md"));
+  assert!(output.files.contains_key("types/index.md"));
+  assert!(output.files.contains_key("types/struct.Container.md"));
+  assert!(!output.files.contains_key("functions/index.md"));
+  assert!(!output.files.contains_key("functions/fn.add.md"));
+
+  // Links to other pages point to the production site
+  let index = &output.files["index.md"];
+  assert!(index.contains("<Link to=\"https://docs.example.com/docs/api/test_crate/functions/\""));
+  assert!(index.contains("<Link to=\"types/\""));
+
+  let sidebar =
+    sidebar::render_typescript(output.sidebar.as_ref().unwrap(), output.docusaurus_major);
+  assert!(sidebar.contains("id: 'api/test_crate/types/struct.Container'"));
+  assert!(
+    sidebar.contains("href: 'https://docs.example.com/docs/api/test_crate/functions/fn.add'")
+  );
+  assert!(!sidebar.contains("id: 'api/test_crate/functions/"));
+
+  let missing_url = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", "unused")
+    .changed_only(Some("HEAD~1".to_string()))
+    .convert()
+    .unwrap_err();
+  assert!(
+    missing_url
+      .to_string()
+      .contains("--changed-only needs --production-url")
+  );
+}
+
+#[test]
+fn test_plain_markdown_flavor() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs/api".to_string(),
+    flavor: converter::OutputFlavor::PlainMarkdown,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  // No Docusaurus sidebar and no MDX components
+  assert!(output.sidebar.is_none());
+  for (path, content) in &output.files {
+    assert!(!content.contains("<RustCode"), "{} contains RustCode", path);
+    assert!(!content.contains("<Link"), "{} contains Link", path);
+    assert!(!content.contains("import "), "{} contains imports", path);
+  }
+
+  insta::assert_snapshot!(
+    "plain_struct_page",
+    output.files["patterns/struct.Builder.md"]
+  );
+  let index = &output.files["index.md"];
+  assert!(index.contains("[functions](functions/index.md)"));
+}
+
+#[test]
+fn test_legacy_single_file_output() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let markdown =
+    converter::convert_to_markdown(&crate_data, false).expect("Failed to convert to markdown");
+
+  assert!(markdown.contains("# test_crate"));
+  assert!(markdown.contains("## Table of Contents"));
+  insta::assert_snapshot!("legacy_single_file", markdown);
+}
+
+#[test]
+fn test_workspace_crates_internal_links() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // First, let's check what external crates are referenced
+  // We know from the JSON that there are references to external crates
+
+  // Test without workspace_crates parameter
+  let output_default =
+    converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+      .expect("Failed to convert to markdown");
+
+  // Test with workspace_crates parameter
+  let workspace_crates = vec!["some_external_crate".to_string()];
+  let output_with_workspace = converter::convert_to_markdown_multifile(
+    &crate_data,
+    false,
+    "/docs/rust",
+    &workspace_crates,
+    false,
+    None,
+  )
+  .expect("Failed to convert to markdown");
+
+  // Both should generate files successfully
+  assert!(
+    !output_default.files.is_empty(),
+    "Default should generate files"
+  );
+  assert!(
+    !output_with_workspace.files.is_empty(),
+    "With workspace_crates should generate files"
+  );
+
+  // Verify base_path is used correctly in the second case
+  let has_base_path_in_internal_links = output_with_workspace.files.values().any(|content| {
+    // Internal links to the current crate should use base_path
+    content.contains("/docs/rust/test_crate/")
+  });
+
+  assert!(
+    has_base_path_in_internal_links,
+    "Internal links should use the provided base_path (/docs/rust)"
+  );
+
+  // Verify std library links still work (should always point to doc.rust-lang.org)
+  let has_std_links = output_with_workspace
+    .files
+    .values()
+    .any(|content| content.contains("doc.rust-lang.org"));
+
+  assert!(
+    has_std_links,
+    "Std library links should always point to doc.rust-lang.org"
+  );
+}
+
+#[test]
+fn test_workspace_crates_with_hyphens() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // Test that workspace_crates works with hyphens (as in Cargo.toml)
+  // even though crate names in JSON use underscores
+  let workspace_crates_with_hyphens = vec!["test-crate".to_string()];
+  let workspace_crates_with_underscores = vec!["test_crate".to_string()];
+
+  let output_hyphens = converter::convert_to_markdown_multifile(
+    &crate_data,
+    false,
+    "/docs",
+    &workspace_crates_with_hyphens,
+    false,
+    None,
+  )
+  .expect("Should work with hyphens");
+
+  let output_underscores = converter::convert_to_markdown_multifile(
+    &crate_data,
+    false,
+    "/docs",
+    &workspace_crates_with_underscores,
+    false,
+    None,
+  )
+  .expect("Should work with underscores");
+
+  // Both should generate the same number of files
+  assert_eq!(
+    output_hyphens.files.len(),
+    output_underscores.files.len(),
+    "Both hyphen and underscore notation should work identically"
+  );
+}
+
+#[test]
+fn test_sidebar_collapsed_configuration() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // Test with collapsed = false (default)
+  let output_collapsed_false =
+    converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+      .expect("Failed to convert to markdown");
+
+  // Test with collapsed = true
+  let output_collapsed_true =
+    converter::convert_to_markdown_multifile(&crate_data, false, "", &[], true, None)
+      .expect("Failed to convert to markdown");
+
+  // Both should have a sidebar
+  assert!(
+    output_collapsed_false.sidebar.is_some(),
+    "Should generate sidebar when collapsed=false"
+  );
+  assert!(
+    output_collapsed_true.sidebar.is_some(),
+    "Should generate sidebar when collapsed=true"
+  );
+
+  // Categories stay open unless collapsed
+  let categories = |output: &converter::MarkdownOutput, key: &str| {
+    output.sidebar.as_ref().unwrap()[key]
+      .iter()
+      .filter_map(|item| match item {
+        SidebarItem::Category {
+          label,
+          collapsible,
+          collapsed,
+          items,
+          ..
+        } => Some((label.clone(), *collapsible, *collapsed, items.clone())),
+        _ => None,
+      })
+      .collect::<Vec<_>>()
+  };
+  let open = categories(&output_collapsed_false, "test_crate_types");
+  assert!(!open.is_empty());
+  assert!(
+    open
+      .iter()
+      .all(|(_, collapsible, collapsed, _)| !collapsible && !collapsed)
+  );
+  let closed = categories(&output_collapsed_true, "test_crate_types");
+  assert!(
+    closed
+      .iter()
+      .all(|(_, collapsible, collapsed, _)| *collapsible && *collapsed)
+  );
+
+  // Levels can be collapsed separately: only the item-type categories inside
+  // the "In <module>" category of a leaf item sidebar are collapsed here
+  let output_items_collapsed = converter::Converter::new(converter::RenderContext {
+    sidebar_collapse: Some(sidebar::SidebarCollapse::from_levels(&["items"]).unwrap()),
+    ..Default::default()
+  })
+  .sidebarconfig_collapsed(false)
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+  let wrapper = categories(&output_items_collapsed, "test_crate_types");
+  let (label, collapsible, _, items) = &wrapper[0];
+  assert_eq!(label, "In test_crate::types");
+  assert!(!collapsible);
+  assert!(items.iter().all(|item| matches!(
+    item,
+    SidebarItem::Category {
+      collapsible: true,
+      collapsed: true,
+      ..
+    }
+  )));
+}
+
+#[test]
+fn test_custom_sidebar_output() {
+  let output_dir = std::env::temp_dir().join("cargo_doc_md_test_sidebar");
+  let sidebar_path = output_dir.join("custom-sidebar.ts");
+  let _ = std::fs::remove_dir_all(&output_dir);
+  std::fs::create_dir_all(&output_dir).expect("Failed to create test directory");
+
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let docs_dir = output_dir.join("docs");
+  let mut options = ConversionOptions::default();
+  options.input_path = json_path;
+  options.output_dir = &docs_dir;
+  options.sidebarconfig_collapsed = false;
+  options.sidebar_output = Some(&sidebar_path);
+
+  cargo_doc_docusaurus::convert_json_file(&options).expect("Conversion failed");
+
+  // Check that the sidebar was created in the custom location
+  assert!(sidebar_path.exists(), "Custom sidebar should be created");
+
+  let sidebar_content = std::fs::read_to_string(&sidebar_path).expect("Failed to read sidebar");
+  assert!(
+    sidebar_content.contains("export const rustApiDocumentation"),
+    "Should contain sidebar export"
+  );
+  assert!(
+    sidebar_content.contains("test_crate/index"),
+    "Should contain test_crate reference"
+  );
+}
+
+#[test]
+fn test_workspace_single_crate() {
+  // Test documenting a single crate from a workspace
+  let json_path = Path::new("tests/fixtures/test_workspace/crate_a.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load crate_a JSON");
 
   let workspace_crates = vec!["crate_a".to_string(), "crate_b".to_string()];
   let output = converter::convert_to_markdown_multifile(
@@ -467,6 +2065,42 @@ fn test_workspace_single_crate() {
   );
 }
 
+#[test]
+fn test_concurrent_conversions_keep_their_settings() {
+  let json_path = Path::new("tests/fixtures/test_workspace/crate_a.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load crate_a JSON");
+  let workspace_crates = vec!["crate_a".to_string(), "crate_b".to_string()];
+
+  // Two conversions with different base paths running at the same time
+  // must not see each other's settings.
+  let (first, second) = std::thread::scope(|scope| {
+    let convert = |base_path: &str| {
+      let converter = converter::Converter::new(converter::RenderContext {
+        base_path: base_path.to_string(),
+        workspace_crates: workspace_crates.clone(),
+        ..Default::default()
+      });
+      let crate_data = &crate_data;
+      scope.spawn(move || {
+        for _ in 0..4 {
+          converter.convert(crate_data).expect("conversion failed");
+        }
+        converter.convert(crate_data).expect("conversion failed")
+      })
+    };
+    let first = convert("/docs/one");
+    let second = convert("/docs/two");
+    (first.join().unwrap(), second.join().unwrap())
+  });
+
+  let first_struct = &first.files["struct.StructA.md"];
+  let second_struct = &second.files["struct.StructA.md"];
+  assert!(first_struct.contains("/docs/one/crate_b/"));
+  assert!(!first_struct.contains("/docs/two/"));
+  assert!(second_struct.contains("/docs/two/crate_b/"));
+  assert!(!second_struct.contains("/docs/one/"));
+}
+
 #[test]
 fn test_workspace_multiple_crates_e2e() {
   // End-to-end test: document both crates in a workspace and verify cross-references
@@ -477,31 +2111,23 @@ fn test_workspace_multiple_crates_e2e() {
 
   // Document crate_b first (dependency)
   let crate_b_json = Path::new("tests/fixtures/test_workspace/crate_b.json");
-  let options_b = ConversionOptions {
-    input_path: crate_b_json,
-    output_dir: &output_dir,
-    include_private: false,
-    base_path: "/docs/rust",
-    workspace_crates: &workspace_crates,
-    sidebarconfig_collapsed: false,
-    sidebar_output: None,
-    sidebar_root_link: None,
-  };
+  let mut options_b = ConversionOptions::default();
+  options_b.input_path = crate_b_json;
+  options_b.output_dir = &output_dir;
+  options_b.base_path = "/docs/rust";
+  options_b.workspace_crates = &workspace_crates;
+  options_b.sidebarconfig_collapsed = false;
 
   cargo_doc_docusaurus::convert_json_file(&options_b).expect("Failed to convert crate_b");
 
   // Document crate_a (depends on crate_b)
   let crate_a_json = Path::new("tests/fixtures/test_workspace/crate_a.json");
-  let options_a = ConversionOptions {
-    input_path: crate_a_json,
-    output_dir: &output_dir,
-    include_private: false,
-    base_path: "/docs/rust",
-    workspace_crates: &workspace_crates,
-    sidebarconfig_collapsed: false,
-    sidebar_output: None,
-    sidebar_root_link: None,
-  };
+  let mut options_a = ConversionOptions::default();
+  options_a.input_path = crate_a_json;
+  options_a.output_dir = &output_dir;
+  options_a.base_path = "/docs/rust";
+  options_a.workspace_crates = &workspace_crates;
+  options_a.sidebarconfig_collapsed = false;
 
   cargo_doc_docusaurus::convert_json_file(&options_a).expect("Failed to convert crate_a");
 
@@ -588,3 +2214,1061 @@ fn test_workspace_cross_crate_types() {
     "Should reference ResultB from crate_b"
   );
 }
+
+#[test]
+fn test_sidebar_structure() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+
+  let sidebar = output.sidebar.expect("sidebar should be generated");
+  let root = sidebar.get("test_crate").expect("root sidebar missing");
+
+  // The crate sidebar starts with the crate title entry
+  match &root[0] {
+    SidebarItem::Doc {
+      id, custom_props, ..
+    } => {
+      assert_eq!(id, "test_crate/index");
+      let props = custom_props.as_ref().expect("crate title props missing");
+      assert_eq!(props["rustCrateTitle"], true);
+      assert_eq!(props["crateName"], "test_crate");
+    }
+    other => panic!("Expected crate title doc, got {:?}", other),
+  }
+
+  // Item categories are never collapsible (rustdoc style)
+  assert!(root.iter().all(|item| match item {
+    SidebarItem::Category { collapsible, .. } => !collapsible,
+    _ => true,
+  }));
+
+  // Rendering happens separately from generation
+  let ts = sidebar::render_typescript(&sidebar, Default::default());
+  assert!(ts.contains("customProps: { rustCrateTitle: true, crateName: 'test_crate'"));
+}
+
+#[test]
+fn test_max_depth_hidden_root_module_visibility() {
+  let json = std::fs::read_to_string("tests/fixtures/test_crate.json")
+    .expect("Failed to read JSON")
+    .replacen(
+      r#""name":"errors","span":{"filename":"src/errors.rs","begin":[1,1],"end":[102,2]},"visibility":"public""#,
+      r#""name":"errors","span":{"filename":"src/errors.rs","begin":[1,1],"end":[102,2]},"visibility":"crate""#,
+      1,
+    );
+  let crate_data = parser::parse_rustdoc_json(&json).expect("Failed to parse JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    max_depth: Some(0),
+    ..Default::default()
+  })
+  .include_private(true)
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  // Hidden modules on the crate page show their visibility like module overviews do
+  let index = &output.files["index.md"];
+  assert!(index.contains("<span className=\"rust-mod\">errors</span> 🔒 <em>"));
+  assert!(index.contains("<span className=\"rust-mod\">types</span> — Type definitions"));
+}
+
+#[test]
+fn test_max_depth_limits_module_pages() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    max_depth: Some(2),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  // Modules up to depth 2 still get pages
+  assert!(output.files.contains_key("nested/index.md"));
+  assert!(output.files.contains_key("nested/inner/index.md"));
+
+  // Deeper modules are not rendered
+  assert!(
+    !output
+      .files
+      .keys()
+      .any(|path| path.starts_with("nested/inner/deep/")),
+    "Modules below the depth limit should not get pages"
+  );
+
+  // The parent page summarizes the hidden module
+  let inner = &output.files["nested/inner/index.md"];
+  assert!(inner.contains("<span className=\"rust-mod\">deep</span>"));
+  assert!(inner.contains("not shown)</em>"));
+  assert!(!inner.contains("<Link to=\"deep/\""));
+
+  // And the sidebar no longer references it
+  let sidebar = output.sidebar.expect("sidebar should be generated");
+  assert!(
+    !sidebar
+      .keys()
+      .any(|key| key.starts_with("nested_inner_deep"))
+  );
+  let rendered = sidebar::render_json(&sidebar).unwrap();
+  assert!(!rendered.contains("nested/inner/deep"));
+}
+
+#[test]
+fn test_parallel_generation_is_deterministic() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  let first =
+    converter::convert_to_markdown_multifile(&crate_data, false, "/docs", &[], false, None)
+      .expect("Failed to convert to markdown");
+  let second =
+    converter::convert_to_markdown_multifile(&crate_data, false, "/docs", &[], false, None)
+      .expect("Failed to convert to markdown");
+
+  assert_eq!(first.files, second.files);
+  assert_eq!(first.sidebar, second.sidebar);
+}
+
+#[test]
+fn test_sitemap_frontmatter_per_kind() {
+  use cargo_doc_docusaurus::converter::{RenderContext, SitemapEntry, SitemapOptions};
+
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  let mut sitemap = SitemapOptions {
+    changefreq: Some("monthly".to_string()),
+    priority: Some(0.3),
+    ..Default::default()
+  };
+  sitemap.kinds.insert(
+    "crate".to_string(),
+    SitemapEntry {
+      changefreq: None,
+      priority: Some(0.8),
+    },
+  );
+  let ctx = RenderContext {
+    sitemap: Some(sitemap),
+    ..Default::default()
+  };
+
+  let output =
+    converter::convert_to_markdown_multifile_with_context(&ctx, &crate_data, false, false)
+      .expect("Failed to convert to markdown");
+
+  let index = &output.files["index.md"];
+  assert!(index.contains("sitemap:\n  changefreq: monthly\n  priority: 0.8\n---"));
+
+  let nested = &output.files["nested/index.md"];
+  assert!(nested.contains("sitemap:\n  changefreq: monthly\n  priority: 0.3\n---"));
+
+  // Without sitemap options, no sitemap front matter is emitted
+  let plain = converter::convert_to_markdown_multifile(&crate_data, false, "", &[], false, None)
+    .expect("Failed to convert to markdown");
+  assert!(
+    plain
+      .files
+      .values()
+      .all(|content| !content.contains("sitemap:"))
+  );
+}
+
+#[test]
+fn test_sitemap_options_validation() {
+  use cargo_doc_docusaurus::converter::SitemapOptions;
+
+  let valid = SitemapOptions {
+    changefreq: Some("weekly".to_string()),
+    priority: Some(0.5),
+    ..Default::default()
+  };
+  assert!(valid.validate().is_ok());
+
+  let bad_freq = SitemapOptions {
+    changefreq: Some("sometimes".to_string()),
+    ..Default::default()
+  };
+  assert!(bad_freq.validate().is_err());
+
+  let bad_priority = SitemapOptions {
+    priority: Some(1.5),
+    ..Default::default()
+  };
+  assert!(bad_priority.validate().is_err());
+}
+
+#[test]
+fn test_method_order() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let render = |method_order| {
+    converter::Converter::new(converter::RenderContext {
+      method_order,
+      ..Default::default()
+    })
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown")
+    .files
+    .remove("struct.GenericStruct.md")
+    .expect("GenericStruct page should be generated")
+  };
+
+  // Impl blocks are in source order, each with its generics and bounds as a subheading
+  let page = render(converter::MethodOrder::Source);
+  let first_impl = page
+    .find("#### `impl<T, U> GenericStruct<T, U>`\n")
+    .unwrap();
+  let bounded_impl = page
+    .find("#### `impl<T, U> GenericStruct<T, U> where T: Clone, U: Clone`")
+    .unwrap();
+  assert!(first_impl < bounded_impl);
+  let position = |method: &str| page.find(&format!("`fn {}", method)).unwrap();
+  assert!(position("new") < position("swap"));
+  assert!(position("swap") < position("map_first"));
+  assert!(position("map_first") < bounded_impl && bounded_impl < position("duplicate"));
+
+  // Alphabetical order sorts methods within each impl block
+  let page = render(converter::MethodOrder::Alphabetical);
+  let position = |method: &str| page.find(&format!("`fn {}", method)).unwrap();
+  assert!(position("map_first") < position("new"));
+  assert!(position("new") < position("swap"));
+  assert!(position("swap") < position("duplicate"));
+}
+
+#[test]
+fn test_sidebar_category_links() {
+  fn category_links(items: &[SidebarItem], links: &mut Vec<String>) {
+    for item in items {
+      if let SidebarItem::Category { link, items, .. } = item {
+        links.extend(link.clone());
+        category_links(items, links);
+      }
+    }
+  }
+
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  for (base_path, doc_id_base) in [("", ""), ("/docs", ""), ("/docs/api/v2", "api/v2/")] {
+    let output = converter::Converter::new(converter::RenderContext {
+      base_path: base_path.to_string(),
+      ..Default::default()
+    })
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+
+    let mut links = Vec::new();
+    for items in output.sidebar.as_ref().unwrap().values() {
+      category_links(items, &mut links);
+    }
+    assert!(!links.is_empty());
+    // Every category links to a module index page, with the doc ID base exactly once
+    for link in links {
+      let page = link
+        .strip_prefix(doc_id_base)
+        .unwrap_or_else(|| panic!("{} should start with '{}'", link, doc_id_base));
+      assert!(page.ends_with("/index"), "{} is not a module index", link);
+      assert!(
+        !page.starts_with(doc_id_base) || doc_id_base.is_empty(),
+        "{} repeats the doc ID base",
+        link
+      );
+      // Generated files are relative to the crate directory
+      let file = format!("{}.md", page.strip_prefix("test_crate/").unwrap());
+      assert!(output.files.contains_key(&file), "{} has no page", link);
+    }
+  }
+}
+
+#[test]
+fn test_impl_block_docs() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // Document the `impl<T: Clone, U: Clone> GenericStruct<T, U>` block
+  let duplicate_id = crate_data
+    .index
+    .iter()
+    .find(|(_, item)| item.name.as_deref() == Some("duplicate"))
+    .map(|(id, _)| *id)
+    .unwrap();
+  let impl_item = crate_data
+    .index
+    .values_mut()
+    .find(|item| {
+      matches!(&item.inner, rustdoc_types::ItemEnum::Impl(impl_block)
+        if impl_block.items.contains(&duplicate_id))
+    })
+    .unwrap();
+  impl_item.docs = Some("Cloning helpers.\n\n# Examples\n\nSee below.".to_string());
+
+  let output = converter::Converter::new(Default::default())
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+  let page = &output.files["struct.GenericStruct.md"];
+
+  let heading = page
+    .find("#### `impl<T, U> GenericStruct<T, U> where T: Clone, U: Clone`")
+    .unwrap();
+  let docs = page
+    .find("Cloning helpers.\n\n##### Examples\n\nSee below.")
+    .unwrap();
+  assert!(heading < docs && docs < page.find("`fn duplicate").unwrap());
+  // Undocumented impl blocks get no docs
+  assert_eq!(page.matches("Cloning helpers.").count(), 1);
+}
+
+#[test]
+fn test_since_badges() {
+  let dir = tempfile::tempdir().unwrap();
+  let since_map = dir.path().join("since.json");
+  std::fs::write(
+    &since_map,
+    r#"{"test_crate::GenericStruct": "0.4.0", "test_crate::traits::Converter": "v0.2.1"}"#,
+  )
+  .unwrap();
+  let output_dir = dir.path().join("docs");
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &output_dir)
+    .since_map(Some(since_map))
+    .convert()
+    .expect("Conversion failed");
+
+  let read =
+    |page: &str| std::fs::read_to_string(output_dir.join("test_crate").join(page)).unwrap();
+  let badge = |version: &str| format!("<span className=\"rust-since\">Since v{}</span>", version);
+
+  // Item pages get the badge below their breadcrumb
+  assert!(read("struct.GenericStruct.md").contains(&format!(
+    "**<Link to=\"/test_crate/\">test_crate</Link>::GenericStruct**\n\n{}\n\n",
+    badge("0.4.0")
+  )));
+  assert!(read("traits/trait.Converter.md").contains(&badge("0.2.1")));
+
+  // Overview rows, in the crate root and in modules
+  assert!(read("index.md").contains(&format!(
+    "className=\"rust-struct\">GenericStruct</Link>  {}",
+    badge("0.4.0")
+  )));
+  assert!(read("traits/index.md").contains(&format!(">Converter</Link>  {}", badge("0.2.1"))));
+  // Items missing from the map get no badge
+  assert!(!read("struct.BoundedGeneric.md").contains("rust-since"));
+}
+
+#[test]
+fn test_macro_only_crate() {
+  let crate_data = parser::load_rustdoc_json(Path::new("tests/fixtures/macro_crate.json"))
+    .expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext::default())
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+
+  let file_paths: Vec<_> = output.files.keys().collect();
+  assert_eq!(
+    file_paths,
+    vec!["index.md", "macro.greet.md", "macro.repeat.md"]
+  );
+  insta::assert_snapshot!("macro_only_index", output.files["index.md"]);
+  insta::assert_snapshot!("macro_page", output.files["macro.greet.md"]);
+
+  // Only the macros are listed, without sections of other item kinds
+  let sidebars = output.sidebar.expect("sidebar not generated");
+  let labels: Vec<_> = sidebars
+    .values()
+    .flatten()
+    .filter_map(|item| match item {
+      SidebarItem::Category { label, .. } => Some(label.as_str()),
+      _ => None,
+    })
+    .collect();
+  assert!(labels.contains(&"Macros"));
+  assert!(!labels.contains(&"Modules"));
+}
+
+#[test]
+fn test_variant_reexports() {
+  let crate_data = parser::load_rustdoc_json(Path::new("tests/fixtures/variant_crate.json"))
+    .expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext::default())
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+
+  // Variants get no pages of their own, re-exports link to their anchors
+  let file_paths: Vec<_> = output.files.keys().collect();
+  assert_eq!(
+    file_paths,
+    vec![
+      "active/index.md",
+      "enum.Status.md",
+      "index.md",
+      "prelude/index.md"
+    ]
+  );
+  assert!(output.files["enum.Status.md"].contains("- <span id=\"variant.Active\"></span>`Active`"));
+  assert!(
+    output.files["active/index.md"]
+      .contains("{\"text\": \"Active\", \"href\": \"/variant_crate/enum.Status#variant.Active\"}")
+  );
+  insta::assert_snapshot!("glob_variant_reexport", output.files["prelude/index.md"]);
+}
+
+#[test]
+fn test_external_glob_reexports() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  // `pub use serde_json::value::*;` without a target, as for some external glob imports
+  crate_data.external_crates.insert(
+    u32::MAX,
+    rustdoc_types::ExternalCrate {
+      name: "serde_json".to_string(),
+      html_root_url: None,
+    },
+  );
+  let (_, reexport) = crate_data
+    .index
+    .iter()
+    .find(|(_, item)| matches!(item.inner, rustdoc_types::ItemEnum::Use(_)))
+    .unwrap();
+  let mut reexport = reexport.clone();
+  reexport.id = rustdoc_types::Id(u32::MAX);
+  reexport.inner = rustdoc_types::ItemEnum::Use(rustdoc_types::Use {
+    source: "serde_json::value".to_string(),
+    name: "value".to_string(),
+    id: None,
+    is_glob: true,
+  });
+  crate_data.index.insert(reexport.id, reexport);
+  let root = crate_data.root;
+  if let rustdoc_types::ItemEnum::Module(module) =
+    &mut crate_data.index.get_mut(&root).unwrap().inner
+  {
+    module.items.push(rustdoc_types::Id(u32::MAX));
+  }
+
+  let output = converter::Converter::new(converter::RenderContext {
+    extern_versions: Some(converter::ExternVersions::from([(
+      "serde_json".to_string(),
+      "1.0.145".to_string(),
+    )])),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+  assert!(output.files["index.md"].contains(
+    "<RustCode inline code={`pub use serde_json::value::*;`} links={[{\"text\": \"value\", \"href\": \"https://docs.rs/serde_json/1.0.145/serde_json/value/index.html\"}]} />"
+  ));
+}
+
+#[test]
+fn test_output_layouts() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let convert = |layout| {
+    converter::Converter::new(converter::RenderContext {
+      layout,
+      ..Default::default()
+    })
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown")
+  };
+
+  // Item pages become anchored sections of their module page
+  let output = convert(converter::OutputLayout::PerModule);
+  assert!(
+    !output
+      .files
+      .contains_key("nested/inner/struct.InnerStruct.md")
+  );
+  let module_page = &output.files["nested/inner/index.md"];
+  assert!(module_page.contains("<Link to=\"#struct.InnerStruct\" className=\"rust-struct\">"));
+  assert!(module_page.contains("<a id=\"struct.InnerStruct\"></a>\n\n## Struct InnerStruct\n"));
+  assert!(module_page.contains("#### Methods"));
+  assert_eq!(module_page.matches("import Link from").count(), 1);
+  let sidebar = output.sidebar.expect("sidebar should be generated");
+  assert!(sidebar.values().flatten().any(|item| {
+    fn has_section_link(item: &SidebarItem) -> bool {
+      match item {
+        SidebarItem::Link { href, .. } => href == "/test_crate/nested/inner/#struct.InnerStruct",
+        SidebarItem::Category { items, .. } => items.iter().any(has_section_link),
+        SidebarItem::Doc { .. } => false,
+      }
+    }
+    has_section_link(item)
+  }));
+
+  // The legacy converter writes the whole crate to the crate's index page
+  let output = convert(converter::OutputLayout::SingleFile);
+  assert_eq!(output.files.keys().collect::<Vec<_>>(), ["index.md"]);
+  assert!(output.sidebar.is_none());
+  assert!(output.files["index.md"].contains("## Table of Contents"));
+}
+
+#[test]
+fn test_features_page() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let features = [
+    ("default", vec!["std"]),
+    ("std", vec!["alloc", "serde?/std"]),
+    ("alloc", vec![]),
+    ("serde", vec!["dep:serde"]),
+  ]
+  .into_iter()
+  .map(|(name, values)| {
+    (
+      name.to_string(),
+      values.into_iter().map(str::to_string).collect(),
+    )
+  })
+  .collect();
+  let output = converter::Converter::new(converter::RenderContext {
+    crate_features: Some(features),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = &output.files["features.md"];
+  assert!(page.starts_with("---\ntitle: Feature Flags\n"));
+  assert!(page.contains(
+    "## `default`\n\n- feature `std`\n  - feature `alloc`\n  - feature `std` of `serde`, if that dependency is enabled\n"
+  ));
+  assert!(page.contains("## `alloc`\n\n*Enabled by default*\n\nEnables nothing else.\n"));
+  assert!(page.contains("## `serde`\n\n- optional dependency `serde`\n"));
+  assert!(output.files["index.md"].contains("<Link to=\"/test_crate/features\""));
+  let sidebar = output.sidebar.expect("sidebar should be generated");
+  assert!(
+    sidebar["test_crate"]
+      .iter()
+      .any(|item| matches!(item, SidebarItem::Doc { id, .. } if id == "test_crate/features"))
+  );
+
+  // Without a manifest path, the crate's manifest is found from the input's location
+  let output_dir = tempfile::tempdir().unwrap();
+  ConversionOptionsBuilder::new(
+    "tests/fixtures/test_workspace/crate_a.json",
+    output_dir.path(),
+  )
+  .features_page(true)
+  .convert()
+  .expect("Conversion failed");
+  let page = std::fs::read_to_string(output_dir.path().join("crate_a/features.md")).unwrap();
+  assert!(page.contains("`crate_a` has no Cargo features."));
+}
+
+#[test]
+fn test_include_readme() {
+  let dir = tempfile::tempdir().unwrap();
+  let readme_path = dir.path().join("repo/README.md");
+  std::fs::create_dir_all(readme_path.parent().unwrap()).unwrap();
+  std::fs::write(
+    &readme_path,
+    "# Getting Started\n\n![Logo](assets/logo.png \"Logo\")\n![CI](https://img.shields.io/ci.svg)\n\n<img src=\"assets/banner.png\" width=\"200\">\n",
+  )
+  .unwrap();
+
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", dir.path().join("docs"))
+    .include_readme(Some(readme_path))
+    .convert()
+    .expect("Conversion failed");
+
+  let index = std::fs::read_to_string(dir.path().join("docs/test_crate/index.md")).unwrap();
+  assert!(index.contains("## Getting Started"));
+  assert!(index.contains("![Logo](../../repo/assets/logo.png \"Logo\")"));
+  assert!(index.contains("![CI](https://img.shields.io/ci.svg)"));
+  assert!(
+    index.contains("<img src={require('../../repo/assets/banner.png').default} width=\"200\">")
+  );
+}
+
+#[test]
+fn test_copy_assets() {
+  let dir = tempfile::tempdir().unwrap();
+  let crate_dir = dir.path().join("test_crate");
+  std::fs::create_dir_all(crate_dir.join("docs")).unwrap();
+  std::fs::write(
+    crate_dir.join("Cargo.toml"),
+    "[package]\nname = \"test_crate\"\n",
+  )
+  .unwrap();
+  std::fs::write(crate_dir.join("docs/diagram.png"), "png").unwrap();
+  std::fs::write(
+    crate_dir.join("README.md"),
+    "![Diagram](docs/diagram.png)\n![Missing](docs/missing.png)\n",
+  )
+  .unwrap();
+
+  let output_dir = dir.path().join("docs");
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", &output_dir)
+    .cargo_manifest(Some(crate_dir.join("Cargo.toml")))
+    .include_readme(Some(crate_dir.join("README.md")))
+    .copy_assets(true)
+    .convert()
+    .expect("Conversion failed");
+
+  // Images found in the crate directory are copied next to the pages
+  let index = std::fs::read_to_string(output_dir.join("test_crate/index.md")).unwrap();
+  assert!(index.contains("![Diagram](./_assets/docs/diagram.png)"));
+  assert!(index.contains("![Missing](docs/missing.png)"));
+  let copied = std::fs::read_to_string(output_dir.join("test_crate/_assets/docs/diagram.png"));
+  assert_eq!(copied.unwrap(), "png");
+}
+
+#[test]
+fn test_output_has_no_mojibake() {
+  fn pages(dir: &Path, found: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+      let path = entry.unwrap().path();
+      if path.is_dir() {
+        pages(&path, found);
+      } else {
+        found.push(path);
+      }
+    }
+  }
+
+  let dir = tempfile::tempdir().unwrap();
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", dir.path())
+    .convert()
+    .expect("Conversion failed");
+
+  let mut files = Vec::new();
+  pages(dir.path(), &mut files);
+  for file in files {
+    // Reading as a string fails on invalid UTF-8
+    let content = std::fs::read_to_string(&file).unwrap();
+    for mojibake in ["â€", "ðŸ", "Ã", "\u{fffd}"] {
+      assert!(
+        !content.contains(mojibake),
+        "{} contains {:?}",
+        file.display(),
+        mojibake
+      );
+    }
+  }
+
+  let index = std::fs::read_to_string(dir.path().join("test_crate/index.md")).unwrap();
+  assert!(index.contains("</Link> — "));
+}
+
+#[test]
+fn test_summary_separator() {
+  let dir = tempfile::tempdir().unwrap();
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", dir.path())
+    .summary_separator(Some("|".to_string()))
+    .convert()
+    .expect("Conversion failed");
+
+  let index = std::fs::read_to_string(dir.path().join("test_crate/index.md")).unwrap();
+  assert!(index.contains("</Link> | "));
+  assert!(!index.contains(" — "));
+}
+
+#[test]
+fn test_item_renderer() {
+  use cargo_doc_docusaurus::renderer::{CustomRenderer, ItemRenderer, RenderItem};
+
+  struct StructCards;
+
+  impl ItemRenderer for StructCards {
+    fn render_struct(&self, item: &RenderItem) -> Option<String> {
+      let markdown = item.default_markdown()?;
+      Some(format!(
+        "<StructCard path=\"{}\" />\n\n{}",
+        item.path.join("::"),
+        markdown
+      ))
+    }
+
+    fn render_macro(&self, _item: &RenderItem) -> Option<String> {
+      None
+    }
+  }
+
+  let dir = tempfile::tempdir().unwrap();
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", dir.path())
+    .item_renderer(Some(CustomRenderer::new(StructCards)))
+    .convert()
+    .expect("Conversion failed");
+
+  let crate_dir = dir.path().join("test_crate");
+  let page = std::fs::read_to_string(crate_dir.join("types/struct.Pair.md")).unwrap();
+  assert!(page.contains("<StructCard path=\"test_crate::types::Pair\" />\n\n"));
+  assert!(page.contains("<RustCode code={`pub struct Pair"));
+  let enum_page = std::fs::read_to_string(crate_dir.join("types/enum.Status.md")).unwrap();
+  assert!(!enum_page.contains("StructCard"));
+  assert!(!crate_dir.join("macro.max.md").exists());
+}
+
+#[test]
+fn test_template_dir() {
+  let templates = tempfile::tempdir().unwrap();
+  std::fs::write(
+    templates.path().join("item.md"),
+    "---\ntitle: \"{{title}}\"\nhide_table_of_contents: true\n{{frontmatter}}---\n\nimport RustCode from '@site/src/components/RustCode';\nimport Link from '@docusaurus/Link';\nimport ApiLayout from '@site/src/components/ApiLayout';\n\n<ApiLayout>\n\n{{content}}\n</ApiLayout>\n",
+  )
+  .unwrap();
+
+  let dir = tempfile::tempdir().unwrap();
+  ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", dir.path())
+    .template_dir(Some(templates.path().to_path_buf()))
+    .convert()
+    .expect("Conversion failed");
+
+  let crate_dir = dir.path().join("test_crate");
+  let page = std::fs::read_to_string(crate_dir.join("types/struct.Pair.md")).unwrap();
+  assert!(page.starts_with("---\ntitle: \"Struct Pair\"\nhide_table_of_contents: true\n"));
+  assert!(page.contains("<ApiLayout>\n\n<RustCode code={`pub struct Pair"));
+  assert!(page.ends_with("\n</ApiLayout>\n"));
+
+  // Pages without a template in the directory keep the built-in layout
+  let module = std::fs::read_to_string(crate_dir.join("types/index.md")).unwrap();
+  assert!(module.starts_with("---\ntitle: types\nsidebar_label: types\n"));
+  assert!(!module.contains("ApiLayout"));
+}
+
+#[test]
+fn test_conversion_stats() {
+  let dir = tempfile::tempdir().unwrap();
+  let stats = ConversionOptionsBuilder::new("tests/fixtures/test_crate.json", dir.path())
+    .convert()
+    .expect("Conversion failed");
+
+  assert_eq!(stats.crate_name, "test_crate");
+  assert!(stats.modules > 1);
+  assert!(stats.items["Struct"] > 0);
+  assert!(!stats.items.contains_key("Module"));
+  let page = std::fs::read_to_string(dir.path().join("test_crate/types/struct.Pair.md")).unwrap();
+  assert!(stats.bytes > page.len());
+  assert!(stats.files > 1);
+}
+
+#[test]
+fn test_variant_field_docs() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let progress = crate_data
+    .index
+    .values_mut()
+    .find(|item| {
+      item.name.as_deref() == Some("progress")
+        && matches!(item.inner, rustdoc_types::ItemEnum::StructField(_))
+    })
+    .unwrap();
+  progress.docs = Some("Progress between 0 and 1.\n\nMore details.".to_string());
+
+  let output = converter::Converter::new(converter::RenderContext::default())
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+  let page = &output.files["types/enum.Status.md"];
+  assert!(page.contains(
+    "`Running{ progress: f32 }` - The operation is running with progress information.\n  - <span id=\"variant.Running.field.progress\"></span>`progress: f32` - Progress between 0 and 1.\n"
+  ));
+  // Variants without documented fields keep the fields on the variant line only
+  assert!(!page.contains("variant.Failed.field"));
+}
+
+#[test]
+fn test_parameter_tables() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    parameter_tables: true,
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = &output.files["functions/fn.function_with_very_long_signature.md"];
+  assert!(page.contains("### Parameters\n\n| Name | Type | Description |\n"));
+  assert!(page.contains(
+    "| `timeout_seconds` | <RustCode inline code={`u64`} links={[]} /> | The timeout duration in seconds |\n"
+  ));
+  assert!(page.contains("### Returns\n\n| Type | Description |\n"));
+  // Methods get the tables below their docs, without a row for the receiver
+  let page = &output.files["types/struct.Container.md"];
+  assert!(page.contains("**Parameters**\n"));
+  assert!(!page.contains("| `self` |"));
+
+  let output = converter::Converter::new(converter::RenderContext::default())
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+  assert!(
+    !output.files["functions/fn.function_with_very_long_signature.md"].contains("### Parameters")
+  );
+}
+
+#[test]
+fn test_item_stability() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let mut crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let mark = |crate_data: &mut rustdoc_types::Crate, name: &str, attr: &str| {
+    let item = crate_data
+      .index
+      .values_mut()
+      .find(|item| item.name.as_deref() == Some(name))
+      .unwrap();
+    item
+      .attrs
+      .push(rustdoc_types::Attribute::Other(attr.to_string()));
+  };
+  mark(
+    &mut crate_data,
+    "PlainStruct",
+    "#[unstable(feature = \"plain_api\", issue = \"42\")]",
+  );
+  mark(
+    &mut crate_data,
+    "generic_function",
+    "#[doc(cfg(feature = \"nightly\"))]",
+  );
+
+  let output = converter::Converter::new(converter::RenderContext::default())
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+
+  let page = &output.files["struct.PlainStruct.md"];
+  assert!(page.contains("sidebar_class_name: \"rust-experimental\"\n"));
+  assert!(page.contains(
+    ":::caution[Experimental]\n\nThis is an experimental API behind the unstable `plain_api` feature.\n:::\n"
+  ));
+  let page = &output.files["fn.generic_function.md"];
+  assert!(page.contains("sidebar_class_name: \"rust-nightly\"\n"));
+  assert!(page.contains(":::info[Nightly-only]\n"));
+  let sidebar =
+    sidebar::render_typescript(output.sidebar.as_ref().unwrap(), output.docusaurus_major);
+  assert!(sidebar.contains("className: 'rust-struct rust-experimental'"));
+
+  let page = &output.files["struct.TupleStruct.md"];
+  assert!(!page.contains("sidebar_class_name"));
+}
+
+#[test]
+fn test_redirects() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let redirects = [
+    ("test_crate::old::Pair", "test_crate::types::Pair"),
+    ("test_crate::kinds", "test_crate::types"),
+    ("test_crate::Gone", "test_crate::Missing"),
+  ]
+  .into_iter()
+  .map(|(from, to)| (from.to_string(), to.to_string()))
+  .collect();
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    redirects: Some(redirects),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let redirects: serde_json::Value = serde_json::from_str(&output.files["redirects.json"]).unwrap();
+  assert_eq!(
+    redirects,
+    serde_json::json!([
+      { "from": "/docs/test_crate/kinds/", "to": "/docs/test_crate/types/" },
+      { "from": "/docs/test_crate/old/struct.Pair", "to": "/docs/test_crate/types/struct.Pair" },
+    ])
+  );
+  assert_eq!(
+    output.warnings,
+    vec!["No item at `test_crate::Missing` to redirect to".to_string()]
+  );
+}
+
+#[test]
+fn test_trait_required_and_provided_methods() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+  let output = converter::Converter::new(converter::RenderContext {
+    base_path: "/docs".to_string(),
+    ..Default::default()
+  })
+  .convert(&crate_data)
+  .expect("Failed to convert to markdown");
+
+  let page = &output.files["trait.MyTrait.md"];
+  let required = page.find("### Required Methods\n\n<span id=\"tymethod.required_method\"></span>");
+  let provided = page.find("### Provided Methods\n\n<span id=\"method.provided_method\"></span>");
+  assert!(required.is_some() && provided.is_some());
+  assert!(required < provided);
+
+  // Implementations link their methods to the methods of the trait
+  let page = &output.files["struct.PlainStruct.md"];
+  assert!(page.contains(
+    "Implements <Link to=\"/docs/test_crate/trait.MyTrait#tymethod.required_method\">MyTrait::required_method</Link>"
+  ));
+  assert!(page.contains(
+    "Overrides <Link to=\"/docs/test_crate/trait.MyTrait#method.provided_method\">MyTrait::provided_method</Link>"
+  ));
+}
+
+#[test]
+fn test_stdin_to_stdout() {
+  use std::io::Write;
+  use std::process::{Command, Stdio};
+
+  let dir = tempfile::tempdir().unwrap();
+  let mut child = Command::new(env!("CARGO_BIN_EXE_cargo-doc-docusaurus"))
+    .args(["--stdin", "--stdout", "--quiet"])
+    .current_dir(dir.path())
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()
+    .expect("Failed to run cargo-doc-docusaurus");
+  let json = std::fs::read("tests/fixtures/test_crate.json").unwrap();
+  child.stdin.take().unwrap().write_all(&json).unwrap();
+  let output = child.wait_with_output().unwrap();
+
+  assert!(output.status.success());
+  let expected =
+    cargo_doc_docusaurus::convert_json_string(&String::from_utf8(json).unwrap(), false)
+      .expect("Failed to convert to markdown");
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+  // Nothing is written
+  assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_single_file_uses_options() {
+  use std::io::Write;
+  use std::process::{Command, Stdio};
+
+  let json = std::fs::read("tests/fixtures/test_crate.json").unwrap();
+  let run = |args: &[&str], dir: &Path| {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cargo-doc-docusaurus"))
+      .args(["--stdin", "--quiet", "--signature-style", "rustfmt"])
+      .args(["--signature-width", "30"])
+      .args(args)
+      .current_dir(dir)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .expect("Failed to run cargo-doc-docusaurus");
+    child.stdin.take().unwrap().write_all(&json).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+  };
+
+  let input = Path::new("tests/fixtures/test_crate.json");
+  let mut options = ConversionOptions::default();
+  options.input_path = input;
+  options.signature_style = converter::SignatureStyle::Rustfmt;
+  options.signature_width = Some(30);
+  let expected =
+    cargo_doc_docusaurus::convert_json_to_markdown(&options).expect("Failed to convert");
+  assert!(expected.contains("fn clone_data(\n    self: &Self,\n) -> T"));
+
+  let dir = tempfile::tempdir().unwrap();
+  assert_eq!(run(&["--single-file", "-"], dir.path()), expected);
+  assert_eq!(
+    run(&["--layout", "single-file", "-o", "-"], dir.path()),
+    expected
+  );
+  // Nothing is written
+  assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+  assert_eq!(run(&["--single-file", "api.md"], dir.path()), "");
+  assert_eq!(
+    std::fs::read_to_string(dir.path().join("api.md")).unwrap(),
+    expected
+  );
+}
+
+#[test]
+fn test_unresolved_links_warning() {
+  // Types rustdoc knows nothing about cannot be linked
+  let json = std::fs::read_to_string("tests/fixtures/test_crate.json")
+    .expect("Failed to read JSON")
+    .replacen(
+      r#""path":"String","id":11"#,
+      r#""path":"Missing","id":999999"#,
+      1,
+    )
+    .replacen(
+      r#""path":"String","id":11"#,
+      r#""path":"Absent","id":999998"#,
+      1,
+    );
+  let broken_crate = parser::parse_rustdoc_json(&json).expect("Failed to parse JSON");
+  let crate_data = parser::load_rustdoc_json(Path::new("tests/fixtures/test_crate.json"))
+    .expect("Failed to load JSON");
+
+  // Conversions sharing a converter on different threads keep their warnings apart
+  let converter = converter::Converter::new(converter::RenderContext::default());
+  let (output, clean_output) = std::thread::scope(|scope| {
+    let broken = scope.spawn(|| converter.convert(&broken_crate));
+    let clean = scope.spawn(|| converter.convert(&crate_data));
+    (broken.join().unwrap(), clean.join().unwrap())
+  });
+  let output = output.expect("Failed to convert to markdown");
+  let clean_output = clean_output.expect("Failed to convert to markdown");
+  assert!(clean_output.unresolved_links.is_empty());
+  assert!(clean_output.warnings.is_empty());
+
+  let warning = output
+    .warnings
+    .iter()
+    .find(|warning| warning.contains("could not be resolved"))
+    .expect("unresolved links should be reported");
+  assert!(
+    warning.starts_with(
+      "2 link targets could not be resolved and rendered without a link:\n  - `Absent` ("
+    ),
+    "{}",
+    warning
+  );
+  assert_eq!(
+    output.unresolved_links.keys().collect::<Vec<_>>(),
+    ["Absent", "Missing"]
+  );
+  // Every unresolved target counts as a warning, not their summary
+  assert_eq!(output.warning_count(), 2);
+}
+
+#[test]
+fn test_displayed_sidebars_exist() {
+  let json_path = Path::new("tests/fixtures/test_crate.json");
+  let crate_data = parser::load_rustdoc_json(json_path).expect("Failed to load JSON");
+
+  for base_path in [
+    "",
+    "/docs",
+    "/docs/api",
+    "/docs/api/nested",
+    "/docsite/api",
+    "/api",
+  ] {
+    let output = converter::Converter::new(converter::RenderContext {
+      base_path: base_path.to_string(),
+      ..Default::default()
+    })
+    .convert(&crate_data)
+    .expect("Failed to convert to markdown");
+    let sidebars = output
+      .sidebar
+      .as_ref()
+      .expect("sidebars should be generated");
+
+    // Every page is shown with a sidebar of the sidebars file
+    for (path, page) in &output.files {
+      let Some(key) = page
+        .lines()
+        .find_map(|line| line.strip_prefix("displayed_sidebar: '"))
+      else {
+        continue;
+      };
+      let key = key.trim_end_matches('\'');
+      assert!(
+        sidebars.contains_key(key),
+        "{} of {} (base path {:?}) is not in the sidebars",
+        key,
+        path,
+        base_path
+      );
+    }
+  }
+}